@@ -29,15 +29,16 @@ pub struct CardItem {
 }
 
 #[component]
-pub fn Card(children: Children) -> impl IntoView {
+pub fn Card(#[prop(optional)] links: Vec<String>, children: Children) -> impl IntoView {
     let nodes = children().nodes;
     let cols = nodes.len();
     let children = nodes
     .into_iter()
-    .map(|child| view! {
+    .enumerate()
+    .map(|(idx, child)| view! {
         <a
             class="block p-4 md:p-5 relative bg-white hover:bg-gray-50 before:absolute before:top-0 before:start-0 before:w-full before:h-px md:before:w-px md:before:h-full before:bg-gray-200 before:first:bg-transparent dark:bg-slate-900 dark:hover:bg-slate-800 dark:before:bg-gray-700 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
-            href="#"
+            href=links.get(idx).cloned().unwrap_or_else(|| "#".to_string())
         >
             <div class="flex md:grid lg:flex gap-y-3 gap-x-5">{child}</div>
         </a>
@@ -0,0 +1,155 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+
+use super::Color;
+
+const VIEW_WIDTH: f64 = 100.0;
+const VIEW_HEIGHT: f64 = 32.0;
+const PADDING: f64 = 3.0;
+
+#[component]
+pub fn Sparkline(
+    #[prop(into)] values: MaybeSignal<Vec<f64>>,
+    #[prop(default = Color::Blue)] color: Color,
+    #[prop(optional)] markers: bool,
+    #[prop(optional, into)] class: Option<String>,
+) -> impl IntoView {
+    let coordinates = create_memo(move |_| values.with(|values| sparkline_points(values)));
+    let extremes = move || {
+        coordinates.with(|points| {
+            let min = points
+                .iter()
+                .copied()
+                .reduce(|a, b| if b.1 > a.1 { b } else { a })?;
+            let max = points
+                .iter()
+                .copied()
+                .reduce(|a, b| if b.1 < a.1 { b } else { a })?;
+            Some(if min == max {
+                vec![max]
+            } else {
+                vec![min, max]
+            })
+        })
+    };
+    let color_class = match color {
+        Color::Blue => "text-blue-600 dark:text-blue-500",
+        Color::Red => "text-red-600 dark:text-red-500",
+        Color::Yellow => "text-yellow-500",
+        Color::Green => "text-teal-500",
+        Color::Gray => "text-gray-400 dark:text-gray-500",
+    };
+    let class = format!(
+        "{} {color_class}",
+        class.unwrap_or_else(|| "w-32 h-8".to_string())
+    );
+
+    view! {
+        <svg
+            class=class
+            viewBox=format!("0 0 {VIEW_WIDTH} {VIEW_HEIGHT}")
+            preserveAspectRatio="none"
+            aria-hidden="true"
+        >
+            <polyline
+                points=move || {
+                    coordinates
+                        .with(|points| {
+                            points
+                                .iter()
+                                .map(|(x, y)| format!("{x:.2},{y:.2}"))
+                                .collect::<Vec<_>>()
+                                .join(" ")
+                        })
+                }
+
+                fill="none"
+                stroke="currentColor"
+                stroke-width="2"
+                stroke-linejoin="round"
+                stroke-linecap="round"
+                vector-effect="non-scaling-stroke"
+            ></polyline>
+            {move || {
+                markers
+                    .then(extremes)
+                    .flatten()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(x, y)| {
+                        // Zero-length round-capped lines keep markers circular when stretched
+                        view! {
+                            <line
+                                x1=x
+                                y1=y
+                                x2=x
+                                y2=y
+                                stroke="currentColor"
+                                stroke-width="5"
+                                stroke-linecap="round"
+                                vector-effect="non-scaling-stroke"
+                            ></line>
+                        }
+                    })
+                    .collect_view()
+            }}
+
+        </svg>
+    }
+}
+
+fn sparkline_points(values: &[f64]) -> Vec<(f64, f64)> {
+    let values = values
+        .iter()
+        .map(|value| if value.is_finite() { *value } else { 0.0 })
+        .collect::<Vec<_>>();
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(*value), max.max(*value))
+        });
+    let range = max - min;
+    let height = VIEW_HEIGHT - PADDING * 2.0;
+    let y = |value: f64| {
+        if range > 0.0 {
+            PADDING + (1.0 - (value - min) / range) * height
+        } else {
+            VIEW_HEIGHT / 2.0
+        }
+    };
+
+    match values.as_slice() {
+        [] => vec![],
+        [value] => vec![(0.0, y(*value)), (VIEW_WIDTH, y(*value))],
+        values => {
+            let step = VIEW_WIDTH / (values.len() - 1) as f64;
+            values
+                .iter()
+                .enumerate()
+                .map(|(idx, value)| (idx as f64 * step, y(*value)))
+                .collect()
+        }
+    }
+}
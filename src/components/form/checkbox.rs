@@ -0,0 +1,96 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+
+use crate::components::next_element_id;
+
+#[component]
+pub fn Checkbox(
+    #[prop(into)] label: MaybeSignal<String>,
+    #[prop(into)] checked: MaybeSignal<bool>,
+    #[prop(into)] on_change: Callback<bool, ()>,
+    // Shown as a dash, e.g. when only some rows are selected
+    #[prop(optional, into)] indeterminate: MaybeSignal<bool>,
+    #[prop(optional, into)] disabled: MaybeSignal<bool>,
+    #[prop(optional, into)] hint: Option<MaybeSignal<String>>,
+    // The label is still announced by screen readers
+    #[prop(optional)] hide_label: bool,
+    #[prop(optional, into)] id: Option<String>,
+) -> impl IntoView {
+    let id = id.unwrap_or_else(|| next_element_id("checkbox"));
+    // Fields without help text pass an empty hint, which should not leave an empty description
+    let hint = hint.filter(|hint| hint.with_untracked(|hint| !hint.is_empty()));
+    let hint_id = hint.as_ref().map(|_| format!("{id}-hint"));
+    let toggle = move || {
+        if !disabled.get_untracked() {
+            on_change.call(!checked.get_untracked());
+        }
+    };
+
+    view! {
+        <div class="flex">
+            <div class="flex">
+                <input
+                    id=id.clone()
+                    type="checkbox"
+                    class="shrink-0 mt-0.5 border-gray-200 rounded text-blue-600 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-gray-800 dark:border-gray-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
+                    aria-describedby=hint_id.clone()
+                    aria-checked=move || {
+                        if indeterminate.get() { "mixed" } else if checked.get() { "true" } else { "false" }
+                    }
+
+                    prop:checked=move || checked.get()
+                    prop:indeterminate=move || indeterminate.get()
+                    disabled=move || disabled.get()
+                    on:change=move |ev| on_change.call(event_target_checked(&ev))
+                    on:keydown=move |ev| {
+                        // Space is handled natively, Enter would otherwise submit the form
+                        if ev.key() == "Enter" {
+                            ev.prevent_default();
+                            toggle();
+                        }
+                    }
+                />
+
+            </div>
+            <div class=if hide_label { "" } else { "ms-3" }>
+                <label
+                    for=id
+                    class=if hide_label { "sr-only" } else { "text-sm text-gray-800 dark:text-white" }
+                >
+                    {move || label.get()}
+                </label>
+                {hint
+                    .map(|hint| {
+                        view! {
+                            <p id=hint_id class="text-xs text-gray-500 dark:text-gray-400">
+                                {move || hint.get()}
+                            </p>
+                        }
+                    })}
+
+            </div>
+        </div>
+    }
+}
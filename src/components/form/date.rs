@@ -0,0 +1,230 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+use leptos::*;
+
+pub type DateRange = (DateTime<Utc>, DateTime<Utc>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateRangePreset {
+    Any,
+    Today,
+    Last7Days,
+    Last30Days,
+    Custom,
+}
+
+#[component]
+pub fn DateRangePicker(
+    #[prop(into)] value: MaybeSignal<Option<DateRange>>,
+    #[prop(into)] on_change: Callback<Option<DateRange>, ()>,
+    #[prop(optional, into)] disabled: MaybeSignal<bool>,
+) -> impl IntoView {
+    let preset = create_rw_signal(DateRangePreset::Any);
+    let start = create_rw_signal(String::new());
+    let end = create_rw_signal(String::new());
+    let error = create_rw_signal(None::<String>);
+
+    create_effect(move |_| {
+        let range = value.get();
+        if preset.get_untracked() != DateRangePreset::Custom || range.is_none() {
+            preset.set(DateRangePreset::from_range(range));
+        }
+        if let Some((range_start, range_end)) = range {
+            start.set(format_date(range_start.date_naive()));
+            end.set(format_date(range_end.date_naive()));
+        }
+    });
+
+    let emit_custom = move || {
+        let (Some(start_date), Some(end_date)) = (parse_date(&start.get()), parse_date(&end.get()))
+        else {
+            error.set(None);
+            return;
+        };
+        if start_date <= end_date {
+            error.set(None);
+            on_change.call(Some(date_range(start_date, end_date)));
+        } else {
+            error.set(Some(
+                "The start date must be before the end date".to_string(),
+            ));
+        }
+    };
+
+    view! {
+        <div class="flex flex-wrap items-center gap-2">
+            <select
+                class="py-1.5 px-3 pe-9 block border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                disabled=move || disabled.get()
+                on:change=move |ev| {
+                    let new_preset = DateRangePreset::parse(&event_target_value(&ev));
+                    preset.set(new_preset);
+                    if new_preset != DateRangePreset::Custom {
+                        error.set(None);
+                        on_change.call(new_preset.range(Utc::now().date_naive()));
+                    }
+                }
+            >
+
+                {DateRangePreset::ALL
+                    .into_iter()
+                    .map(|item| {
+                        view! {
+                            <option value=item.as_str() selected=move || preset.get() == item>
+                                {item.label()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+
+            </select>
+
+            <Show when=move || preset.get() == DateRangePreset::Custom>
+                <input
+                    type="date"
+                    class=move || date_input_class(error.get().is_some())
+                    disabled=move || disabled.get()
+                    prop:value=move || start.get()
+                    on:change=move |ev| {
+                        start.set(event_target_value(&ev));
+                        emit_custom();
+                    }
+                />
+
+                <span class="text-sm text-gray-600 dark:text-gray-400">to</span>
+                <input
+                    type="date"
+                    class=move || date_input_class(error.get().is_some())
+                    disabled=move || disabled.get()
+                    prop:value=move || end.get()
+                    on:change=move |ev| {
+                        end.set(event_target_value(&ev));
+                        emit_custom();
+                    }
+                />
+
+            </Show>
+        </div>
+        {move || {
+            error
+                .get()
+                .map(|error| {
+                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                })
+        }}
+    }
+}
+
+impl DateRangePreset {
+    pub const ALL: [DateRangePreset; 5] = [
+        DateRangePreset::Any,
+        DateRangePreset::Today,
+        DateRangePreset::Last7Days,
+        DateRangePreset::Last30Days,
+        DateRangePreset::Custom,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DateRangePreset::Any => "any",
+            DateRangePreset::Today => "today",
+            DateRangePreset::Last7Days => "7d",
+            DateRangePreset::Last30Days => "30d",
+            DateRangePreset::Custom => "custom",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateRangePreset::Any => "Any time",
+            DateRangePreset::Today => "Today",
+            DateRangePreset::Last7Days => "Last 7 days",
+            DateRangePreset::Last30Days => "Last 30 days",
+            DateRangePreset::Custom => "Custom range",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        DateRangePreset::ALL
+            .into_iter()
+            .find(|preset| preset.as_str() == value)
+            .unwrap_or(DateRangePreset::Any)
+    }
+
+    pub fn range(&self, today: NaiveDate) -> Option<DateRange> {
+        let days = match self {
+            DateRangePreset::Today => 0,
+            DateRangePreset::Last7Days => 6,
+            DateRangePreset::Last30Days => 29,
+            DateRangePreset::Any | DateRangePreset::Custom => return None,
+        };
+
+        Some(date_range(
+            today.checked_sub_days(Days::new(days)).unwrap_or(today),
+            today,
+        ))
+    }
+
+    pub fn from_range(range: Option<DateRange>) -> Self {
+        if let Some(range) = range {
+            let today = Utc::now().date_naive();
+            [
+                DateRangePreset::Today,
+                DateRangePreset::Last7Days,
+                DateRangePreset::Last30Days,
+            ]
+            .into_iter()
+            .find(|preset| preset.range(today) == Some(range))
+            .unwrap_or(DateRangePreset::Custom)
+        } else {
+            DateRangePreset::Any
+        }
+    }
+}
+
+pub fn date_range(start: NaiveDate, end: NaiveDate) -> DateRange {
+    (
+        start.and_time(NaiveTime::MIN).and_utc(),
+        end.and_hms_opt(23, 59, 59)
+            .unwrap_or(end.and_time(NaiveTime::MIN))
+            .and_utc(),
+    )
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn date_input_class(has_error: bool) -> &'static str {
+    if !has_error {
+        "py-1.5 px-3 block border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+    } else {
+        "py-1.5 px-3 block border-red-500 rounded-lg text-sm focus:border-red-500 focus:ring-red-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+    }
+}
@@ -118,6 +118,8 @@ pub fn InputExpression(
                                     <input
                                         type="text"
                                         placeholder="if"
+                                        aria-invalid=is_if_err.to_string()
+                                        aria-describedby=is_if_err.then(|| element.item_error_id(idx))
                                         prop:value=if_then.if_
                                         class=move || {
                                             if !is_if_err { ok_class } else { err_class }
@@ -142,6 +144,8 @@ pub fn InputExpression(
                                     <input
                                         type="text"
                                         placeholder="then"
+                                        aria-invalid=is_then_err.to_string()
+                                        aria-describedby=is_then_err.then(|| element.item_error_id(idx))
                                         class=move || {
                                             if !is_then_err { ok_class } else { err_class }
                                         }
@@ -180,7 +184,15 @@ pub fn InputExpression(
                             </div>
                             {error
                                 .map(|error| {
-                                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                                    view! {
+                                        <p
+                                            id=element.item_error_id(idx)
+                                            class="text-xs text-red-600 mt-2"
+                                            role="alert"
+                                        >
+                                            {error}
+                                        </p>
+                                    }
                                 })}
 
                         </div>
@@ -201,6 +213,8 @@ pub fn InputExpression(
                         }
 
                         placeholder=placeholder.clone().map(|p| move || p.get())
+                        aria-invalid=move || else_err.get().is_some().to_string()
+                        aria-describedby=move || else_err.get().map(|_| element.error_id())
                         prop:value=else_value
 
                         on:change=move |ev| {
@@ -221,7 +235,11 @@ pub fn InputExpression(
                     else_err
                         .get()
                         .map(|error| {
-                            view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                            view! {
+                                <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                                    {error}
+                                </p>
+                            }
                         })
                 }}
 
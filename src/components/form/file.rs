@@ -0,0 +1,291 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use humansize::{format_size, DECIMAL};
+use leptos::{wasm_bindgen::JsCast, *};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{
+    components::icon::{IconDocumentText, IconExclamationCircle},
+    core::{form::FileInfo, schema::Validator},
+};
+
+use super::{input::TextArea, FormElement};
+
+#[component]
+pub fn InputFile(
+    element: FormElement,
+    #[prop(optional, into)] disabled: MaybeSignal<bool>,
+) -> impl IntoView {
+    let input_ref = create_node_ref::<html::Input>();
+    let is_dragging = create_rw_signal(false);
+    // Macro references such as %{file:/path}% only make sense as text
+    let as_text = create_rw_signal(element.data.with_untracked(|data| {
+        data.value::<String>(element.id)
+            .is_some_and(|value| value.trim_start().starts_with("%{"))
+    }));
+    let value_len = create_memo(move |_| {
+        element
+            .data
+            .get()
+            .value::<String>(element.id)
+            .map_or(0, |value| value.len())
+    });
+    let file = create_memo(move |_| element.data.get().file(element.id).cloned());
+    let error = create_memo(move |_| {
+        element
+            .data
+            .get()
+            .error_string(element.id)
+            .map(|s| s.to_string())
+    });
+    let accept = element.data.with_untracked(|data| {
+        data.schema
+            .fields
+            .get(element.id)
+            .and_then(|field| field.input_check(data))
+            .and_then(|check| {
+                check
+                    .validators
+                    .iter()
+                    .find_map(|validator| match validator {
+                        Validator::FileType(types) => Some(types.join(",")),
+                        _ => None,
+                    })
+            })
+    });
+
+    let read_file = move |file: web_sys::File| {
+        let info = FileInfo {
+            name: file.name(),
+            size: file.size() as u64,
+            mime: file.type_(),
+        };
+        if let Err(err) = element
+            .data
+            .with_untracked(|data| data.check_file(element.id, &info))
+        {
+            element.data.update(|data| data.new_error(element.id, err));
+            return;
+        }
+        spawn_local(async move {
+            match JsFuture::from(file.text()).await {
+                Ok(text) => {
+                    element.data.update(|data| {
+                        data.set_file(element.id, info, text.as_string().unwrap_or_default());
+                    });
+                }
+                Err(_) => {
+                    element
+                        .data
+                        .update(|data| data.new_error(element.id, "could not be read"));
+                }
+            }
+        });
+    };
+
+    view! {
+        <Show when=move || as_text.get()>
+            <TextArea element=element disabled=disabled auto_grow=true/>
+        </Show>
+        <div
+            role="button"
+            tabindex=move || if disabled.get() { "-1" } else { "0" }
+            aria-disabled=move || disabled.get().to_string()
+            aria-invalid=move || error.get().is_some().to_string()
+            aria-describedby=move || error.get().map(|_| element.error_id())
+            class=move || {
+                if as_text.get() {
+                    return "hidden".to_string();
+                }
+                let border = if error.get().is_some() {
+                    "border-red-500"
+                } else if is_dragging.get() {
+                    "border-blue-500 bg-blue-50 dark:bg-slate-800"
+                } else {
+                    "border-gray-200 dark:border-gray-700"
+                };
+                format!(
+                    "flex items-center gap-x-3 p-4 border-2 border-dashed rounded-lg cursor-pointer text-sm text-gray-600 focus:outline-none focus:ring-2 focus:ring-blue-500 dark:text-gray-400 dark:bg-slate-900 {border}{}",
+                    if disabled.get() { " opacity-50 pointer-events-none" } else { "" },
+                )
+            }
+
+            on:click=move |_| {
+                if let Some(input) = input_ref.get_untracked() {
+                    input.click();
+                }
+            }
+
+            on:keydown=move |ev| {
+                if matches!(ev.key().as_str(), "Enter" | " ") {
+                    ev.prevent_default();
+                    if let Some(input) = input_ref.get_untracked() {
+                        input.click();
+                    }
+                }
+            }
+
+            on:dragover=move |ev| {
+                ev.prevent_default();
+                is_dragging.set(true);
+            }
+
+            on:dragleave=move |_| is_dragging.set(false)
+            on:drop=move |ev| {
+                ev.prevent_default();
+                is_dragging.set(false);
+                if disabled.get_untracked() {
+                    return;
+                }
+                if let Some(file) = ev
+                    .data_transfer()
+                    .and_then(|transfer| transfer.files())
+                    .and_then(|files| files.get(0))
+                {
+                    read_file(file);
+                }
+            }
+
+            on:paste=move |ev| {
+                if let Some(text) = ev
+                    .dyn_ref::<web_sys::ClipboardEvent>()
+                    .and_then(|ev| ev.clipboard_data())
+                    .and_then(|clipboard| clipboard.get_data("text/plain").ok())
+                    .filter(|text| !text.is_empty())
+                {
+                    ev.prevent_default();
+                    let info = FileInfo {
+                        name: "Pasted text".to_string(),
+                        size: text.len() as u64,
+                        mime: "text/plain".to_string(),
+                    };
+                    element.data.update(|data| data.set_file(element.id, info, text));
+                }
+            }
+        >
+
+            <IconDocumentText attr:class="flex-shrink-0 size-5 text-gray-400"/>
+            <div class="grow">
+                {move || match (file.get(), value_len.get()) {
+                    (Some(file), _) => {
+                        view! {
+                            <span class="font-medium text-gray-800 dark:text-gray-200">
+                                {file.name}
+                            </span>
+                            <span class="ms-2 text-gray-500">
+                                {format_size(file.size, DECIMAL)}
+                            </span>
+                        }
+                            .into_view()
+                    }
+                    (None, 0) => {
+                        view! {
+                            <span>
+                                "Drop a file here, paste its contents or "
+                                <span class="font-medium text-blue-600 dark:text-blue-500">
+                                    "browse"
+                                </span>
+                            </span>
+                        }
+                            .into_view()
+                    }
+                    (None, len) => {
+                        view! {
+                            <span>
+                                {format!("Current value ({len} bytes), drop or browse to replace")}
+                            </span>
+                        }
+                            .into_view()
+                    }
+                }}
+
+            </div>
+            <Show when=move || { value_len.get() > 0 && !disabled.get() }>
+                <button
+                    type="button"
+                    class="py-1 px-2 text-xs font-semibold rounded-lg border border-gray-200 text-gray-600 hover:bg-gray-100 dark:border-gray-700 dark:text-gray-400 dark:hover:bg-gray-800"
+                    on:click=move |ev| {
+                        ev.stop_propagation();
+                        element.data.update(|data| data.clear_file(element.id));
+                        if let Some(input) = input_ref.get_untracked() {
+                            input.set_value("");
+                        }
+                    }
+                >
+
+                    "Clear"
+                </button>
+            </Show>
+            <IconExclamationCircle attr:class=move || {
+                if error.get().is_some() {
+                    "flex-shrink-0 size-4 text-red-500"
+                } else {
+                    "hidden"
+                }
+            }/>
+            <input
+                type="file"
+                class="hidden"
+                accept=accept
+                node_ref=input_ref
+                on:click=move |ev| ev.stop_propagation()
+                on:change=move |ev| {
+                    if let Some(file) = event_target::<web_sys::HtmlInputElement>(&ev)
+                        .files()
+                        .and_then(|files| files.get(0))
+                    {
+                        read_file(file);
+                    }
+                }
+            />
+
+        </div>
+        {move || {
+            error
+                .get()
+                .filter(|_| !as_text.get())
+                .map(|error| {
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
+                })
+        }}
+        <button
+            type="button"
+            class="mt-2 text-xs font-medium text-blue-600 hover:underline focus:outline-none focus:underline dark:text-blue-500"
+            on:click=move |_| {
+                // The file name would no longer describe the edited contents
+                element.data.update(|data| {
+                    data.files.remove(element.id);
+                });
+                as_text.update(|as_text| *as_text = !*as_text);
+            }
+        >
+
+            {move || if as_text.get() { "Upload a file instead" } else { "Edit as text" }}
+        </button>
+    }
+}
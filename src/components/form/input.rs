@@ -23,9 +23,15 @@
 
 use std::str::FromStr;
 
-use leptos::*;
+use leptos::{wasm_bindgen::JsCast, *};
 
-use crate::components::icon::{IconClock, IconExclamationCircle, IconInfo};
+use crate::{
+    components::{
+        icon::{IconClock, IconExclamationCircle, IconInfo},
+        next_element_id,
+    },
+    core::schema::{InputMask, Validator},
+};
 
 use super::FormElement;
 
@@ -50,11 +56,21 @@ pub fn InputText(
             .error_string(element.id)
             .map(|s| s.to_string())
     });
+    let hint = create_memo(move |_| element.transform_hint());
+    let mask = element.input_mask();
+    let placeholder = mask_placeholder(placeholder, mask);
 
     view! {
         <div class="relative">
             <input
                 {..attrs}
+                aria-invalid=move || error.get().is_some().to_string()
+                aria-describedby=move || {
+                    error
+                        .get()
+                        .map(|_| element.error_id())
+                        .or_else(|| hint.get().map(|_| element.hint_id()))
+                }
                 type="text"
                 class=move || {
                     if error.get().is_none() {
@@ -67,6 +83,7 @@ pub fn InputText(
                 placeholder=placeholder.map(|p| move || p.get())
                 prop:value=move || value.get()
                 disabled=move || disabled.get()
+                on:input=move |ev| handle_text_input(&ev, element, mask)
                 on:change=move |ev| {
                     element
                         .data
@@ -83,11 +100,112 @@ pub fn InputText(
                 <IconExclamationCircle attr:class="flex-shrink-0 size-4 text-red-500"/>
             </div>
         </div>
+        {move || match error.get() {
+            Some(error) => {
+                Some(
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    },
+                )
+            }
+            None => {
+                hint.get()
+                    .map(|hint| {
+                        view! {
+                            <p
+                                id=element.hint_id()
+                                class="text-xs text-gray-500 mt-2 dark:text-gray-400"
+                            >
+                                {hint}
+                            </p>
+                        }
+                    })
+            }
+        }}
+    }
+}
+
+#[component]
+pub fn InputDate(
+    element: FormElement,
+    #[prop(optional, into)] disabled: MaybeSignal<bool>,
+    #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
+) -> impl IntoView {
+    let value = create_memo(move |_| {
+        element
+            .data
+            .get()
+            .value::<String>(element.id)
+            .unwrap_or_default()
+    });
+    let error = create_memo(move |_| {
+        element
+            .data
+            .get()
+            .error_string(element.id)
+            .map(|s| s.to_string())
+    });
+    let bounds = create_memo(move |_| {
+        let data = element.data.get();
+        let mut min = None;
+        let mut max = None;
+        if let Some(check) = data
+            .schema
+            .fields
+            .get(element.id)
+            .and_then(|field| field.input_check(&data))
+        {
+            for validator in &check.validators {
+                match validator {
+                    Validator::MinDate(date) => min = Some(date.format("%Y-%m-%d").to_string()),
+                    Validator::MaxDate(date) => max = Some(date.format("%Y-%m-%d").to_string()),
+                    _ => (),
+                }
+            }
+        }
+        (min, max)
+    });
+
+    view! {
+        <div class="relative">
+            <input
+                {..attrs}
+                aria-invalid=move || error.get().is_some().to_string()
+                aria-describedby=move || error.get().map(|_| element.error_id())
+                type="date"
+                class=move || {
+                    if error.get().is_none() {
+                        "py-2 px-3 block w-full border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                    } else {
+                        "py-2 px-3 block w-full border-red-500 shadow-sm text-sm rounded-lg focus:border-red-500 focus:ring-red-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                    }
+                }
+
+                min=move || bounds.get().0
+                max=move || bounds.get().1
+                prop:value=move || value.get()
+                disabled=move || disabled.get()
+                on:change=move |ev| {
+                    element
+                        .data
+                        .update(|data| {
+                            data.update(element.id, event_target_value(&ev));
+                        });
+                }
+            />
+
+        </div>
         {move || {
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -98,6 +216,8 @@ pub fn InputPassword(
     element: FormElement,
     #[prop(optional, into)] placeholder: Option<MaybeSignal<String>>,
     #[prop(optional, into)] disabled: MaybeSignal<bool>,
+    // Reveal button, opted out of for secrets that should never be displayed
+    #[prop(default = true)] show_toggle: bool,
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
 ) -> impl IntoView {
     let value = create_memo(move |_| {
@@ -115,23 +235,38 @@ pub fn InputPassword(
             .map(|s| s.to_string())
     });
     let show_password = create_rw_signal(false);
+    let check_on_input = element
+        .data
+        .with_untracked(|data| data.has_confirmation(element.id));
 
     view! {
         <div class="relative">
             <input
                 {..attrs}
+                aria-invalid=move || error.get().is_some().to_string()
+                aria-describedby=move || error.get().map(|_| element.error_id())
                 type=move || if show_password.get() { "text" } else { "password" }
                 class=move || {
+                    let padding = if show_toggle { "pe-11 " } else { "" };
                     if error.get().is_none() {
-                        "py-2 px-3 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                        format!("py-2 px-3 {padding}block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600")
                     } else {
-                        "py-2 px-3 block w-full border-red-500 rounded-lg text-sm focus:border-red-500 focus:ring-red-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                        format!("py-2 px-3 {padding}block w-full border-red-500 rounded-lg text-sm focus:border-red-500 focus:ring-red-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600")
                     }
                 }
 
                 placeholder=placeholder.map(|p| move || p.get())
                 prop:value=move || value.get()
                 disabled=move || disabled.get()
+                on:input=move |ev| {
+                    if check_on_input {
+                        element
+                            .data
+                            .update(|data| {
+                                data.update(element.id, event_target_value(&ev));
+                            });
+                    }
+                }
                 on:change=move |ev| {
                     element
                         .data
@@ -141,73 +276,91 @@ pub fn InputPassword(
                 }
             />
 
-            <button
-                type="button"
-                class="absolute top-0 end-0 p-3.5 rounded-e-md dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
-                on:click=move |_| {
-                    show_password.update(|v| *v = !*v);
-                }
-            >
+            {show_toggle
+                .then(|| {
+                    view! {
+                        <button
+                            type="button"
+                            class="absolute top-0 end-0 p-3.5 rounded-e-md dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                            aria-label="Show password"
+                            aria-pressed=move || show_password.get().to_string()
+                            on:click=move |_| {
+                                show_password.update(|v| *v = !*v);
+                            }
+                        >
 
-                <svg
-                    class=move || {
-                        let color = if error.get().is_none() { "gray-400" } else { "red-500" };
-                        format!("flex-shrink-0 size-3.5 text-{color} dark:text-neutral-600")
-                    }
+                            <svg
+                                class=move || {
+                                    let color = if error.get().is_none() {
+                                        "gray-400"
+                                    } else {
+                                        "red-500"
+                                    };
+                                    format!(
+                                        "flex-shrink-0 size-3.5 text-{color} dark:text-neutral-600",
+                                    )
+                                }
 
-                    width="24"
-                    height="24"
-                    viewBox="0 0 24 24"
-                    fill="none"
-                    stroke="currentColor"
-                    stroke-width="2"
-                    stroke-linecap="round"
-                    stroke-linejoin="round"
-                >
-                    <path
-                        class="hs-password-active:hidden"
-                        class:hidden=move || show_password.get()
-                        d="M9.88 9.88a3 3 0 1 0 4.24 4.24"
-                    ></path>
-                    <path
-                        class="hs-password-active:hidden"
-                        class:hidden=move || show_password.get()
-                        d="M10.73 5.08A10.43 10.43 0 0 1 12 5c7 0 10 7 10 7a13.16 13.16 0 0 1-1.67 2.68"
-                    ></path>
-                    <path
-                        class="hs-password-active:hidden"
-                        class:hidden=move || show_password.get()
-                        d="M6.61 6.61A13.526 13.526 0 0 0 2 12s3 7 10 7a9.74 9.74 0 0 0 5.39-1.61"
-                    ></path>
-                    <line
-                        class="hs-password-active:hidden"
-                        class:hidden=move || show_password.get()
-                        x1="2"
-                        x2="22"
-                        y1="2"
-                        y2="22"
-                    ></line>
-                    <path
-                        class:hidden=move || !show_password.get()
-                        class="hs-password-active:block"
-                        d="M2 12s3-7 10-7 10 7 10 7-3 7-10 7-10-7-10-7Z"
-                    ></path>
-                    <circle
-                        class="hs-password-active:block"
-                        class:hidden=move || !show_password.get()
-                        cx="12"
-                        cy="12"
-                        r="3"
-                    ></circle>
-                </svg>
-            </button>
+                                aria-hidden="true"
+                                width="24"
+                                height="24"
+                                viewBox="0 0 24 24"
+                                fill="none"
+                                stroke="currentColor"
+                                stroke-width="2"
+                                stroke-linecap="round"
+                                stroke-linejoin="round"
+                            >
+                                <path
+                                    class="hs-password-active:hidden"
+                                    class:hidden=move || show_password.get()
+                                    d="M9.88 9.88a3 3 0 1 0 4.24 4.24"
+                                ></path>
+                                <path
+                                    class="hs-password-active:hidden"
+                                    class:hidden=move || show_password.get()
+                                    d="M10.73 5.08A10.43 10.43 0 0 1 12 5c7 0 10 7 10 7a13.16 13.16 0 0 1-1.67 2.68"
+                                ></path>
+                                <path
+                                    class="hs-password-active:hidden"
+                                    class:hidden=move || show_password.get()
+                                    d="M6.61 6.61A13.526 13.526 0 0 0 2 12s3 7 10 7a9.74 9.74 0 0 0 5.39-1.61"
+                                ></path>
+                                <line
+                                    class="hs-password-active:hidden"
+                                    class:hidden=move || show_password.get()
+                                    x1="2"
+                                    x2="22"
+                                    y1="2"
+                                    y2="22"
+                                ></line>
+                                <path
+                                    class:hidden=move || !show_password.get()
+                                    class="hs-password-active:block"
+                                    d="M2 12s3-7 10-7 10 7 10 7-3 7-10 7-10-7-10-7Z"
+                                ></path>
+                                <circle
+                                    class="hs-password-active:block"
+                                    class:hidden=move || !show_password.get()
+                                    cx="12"
+                                    cy="12"
+                                    r="3"
+                                ></circle>
+                            </svg>
+                        </button>
+                    }
+                })}
         </div>
 
         {move || {
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -261,6 +414,8 @@ pub fn InputSize(
     view! {
         <div class="relative">
             <input
+                aria-invalid=move || error.get().is_some().to_string()
+                aria-describedby=move || error.get().map(|_| element.error_id())
                 type="text"
                 class="py-2 px-3 block w-full border-gray-200 shadow-sm rounded-lg text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
                 prop:value=move || {
@@ -294,6 +449,8 @@ pub fn InputSize(
 
             <div class="absolute inset-y-0 end-0 flex items-center text-gray-500 pe-px">
                 <select
+                    aria-invalid=move || error.get().is_some().to_string()
+                    aria-describedby=move || error.get().map(|_| element.error_id())
                     class="block text-xs w-full border-transparent rounded-lg focus:ring-blue-600 focus:border-blue-600 dark:bg-gray-800"
                     on:change=move |ev| {
                         element
@@ -335,7 +492,11 @@ pub fn InputSize(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -348,11 +509,23 @@ pub fn InputDuration(
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
 ) -> impl IntoView {
     let value = create_memo(move |_| {
-        element
-            .data
-            .get()
-            .value::<Duration>(element.id)
-            .unwrap_or_default()
+        element.data.with(|data| {
+            data.value::<Duration>(element.id)
+                .or_else(|| {
+                    data.value::<u64>(element.id)
+                        .filter(|_| {
+                            data.schema
+                                .fields
+                                .get(element.id)
+                                .is_some_and(|field| field.accepts_seconds())
+                        })
+                        .map(|secs| Duration {
+                            value: secs.to_string(),
+                            unit: "s".to_string(),
+                        })
+                })
+                .unwrap_or_default()
+        })
     });
     let error = create_memo(move |_| {
         element
@@ -365,6 +538,8 @@ pub fn InputDuration(
     view! {
         <div class="relative">
             <input
+                aria-invalid=move || error.get().is_some().to_string()
+                aria-describedby=move || error.get().map(|_| element.error_id())
                 type="text"
                 class="py-2 px-3 block w-full border-gray-200 shadow-sm rounded-lg text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
                 prop:value=move || { value.get().value }
@@ -393,6 +568,8 @@ pub fn InputDuration(
 
             <div class="absolute inset-y-0 end-0 flex items-center text-gray-500 pe-px">
                 <select
+                    aria-invalid=move || error.get().is_some().to_string()
+                    aria-describedby=move || error.get().map(|_| element.error_id())
                     class="block text-xs w-full border-transparent rounded-lg focus:ring-blue-600 focus:border-blue-600 dark:bg-gray-800"
                     on:change=move |ev| {
                         element
@@ -441,7 +618,11 @@ pub fn InputDuration(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -472,6 +653,8 @@ pub fn InputRate(
         <div class="relative">
             <div class="sm:flex rounded-lg shadow-sm">
                 <input
+                    aria-invalid=move || error.get().is_some().to_string()
+                    aria-describedby=move || error.get().map(|_| element.error_id())
                     type="text"
                     class="py-2 px-3 block w-full border-gray-200 shadow-sm rounded-lg text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
                     prop:value=move || { value.get().amount }
@@ -506,6 +689,8 @@ pub fn InputRate(
                     <IconClock attr:class="mx-auto size-4 text-gray-400"/>
                 </span>
                 <input
+                    aria-invalid=move || error.get().is_some().to_string()
+                    aria-describedby=move || error.get().map(|_| element.error_id())
                     type="text"
                     class="py-2 px-3 block w-full border-gray-200 shadow-sm rounded-lg text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
                     prop:value=move || { value.get().period.value }
@@ -534,6 +719,8 @@ pub fn InputRate(
 
                 <div class="absolute inset-y-0 end-0 flex items-center text-gray-500 pe-px">
                     <select
+                        aria-invalid=move || error.get().is_some().to_string()
+                        aria-describedby=move || error.get().map(|_| element.error_id())
                         class="block text-xs w-full border-transparent rounded-lg focus:ring-blue-600 focus:border-blue-600 dark:bg-gray-800"
                         on:change=move |ev| {
                             element
@@ -586,7 +773,11 @@ pub fn InputRate(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -607,12 +798,16 @@ pub fn InputSwitch(
             .value::<bool>(element.id)
             .unwrap_or_default()
     });
+    let id = next_element_id("switch");
 
     view! {
         <div class="flex items-center">
             <input
                 type="checkbox"
+                role="switch"
+                id=id.clone()
                 {..attrs}
+                aria-checked=move || value.get().to_string()
                 class="relative w-11 h-6 p-px bg-gray-100 border-transparent text-transparent rounded-full cursor-pointer transition-colors ease-in-out duration-200 focus:ring-blue-600 disabled:opacity-50 disabled:pointer-events-none checked:bg-none checked:text-blue-600 checked:border-blue-600 focus:checked:border-blue-600 dark:bg-gray-800 dark:border-gray-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-600 before:inline-block before:size-5 before:bg-white checked:before:bg-blue-200 before:translate-x-0 checked:before:translate-x-full before:rounded-full before:shadow before:transform before:ring-0 before:transition before:ease-in-out before:duration-200 dark:before:bg-gray-400 dark:checked:before:bg-blue-200"
                 prop:checked=move || value.get()
                 on:input=move |_| {
@@ -629,7 +824,7 @@ pub fn InputSwitch(
             {label
                 .map(|label| {
                     view! {
-                        <label class="text-sm text-gray-500 ms-3 dark:text-gray-400">
+                        <label for=id class="text-sm text-gray-500 ms-3 dark:text-gray-400">
                             {label.get()}
                         </label>
                     }
@@ -676,11 +871,16 @@ pub fn InputSwitch(
     }
 }
 
+const MAX_AUTO_GROW_ROWS: usize = 20;
+
 #[component]
 pub fn TextArea(
     element: FormElement,
     #[prop(optional, into)] placeholder: Option<MaybeSignal<String>>,
     #[prop(optional, into)] disabled: MaybeSignal<bool>,
+    #[prop(optional)] rows: Option<usize>,
+    // Grows with the content up to MAX_AUTO_GROW_ROWS lines
+    #[prop(optional)] auto_grow: bool,
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
 ) -> impl IntoView {
     let value = create_memo(move |_| {
@@ -697,11 +897,26 @@ pub fn TextArea(
             .error_string(element.id)
             .map(|s| s.to_string())
     });
+    let rows = rows.or_else(|| element.rows()).unwrap_or(5);
+    let (min_length, max_length) = element.length_limits();
+
+    // Tracks keystrokes so the counter and height follow the text before it is committed
+    let text = create_rw_signal(value.get_untracked());
+    create_effect(move |_| text.set(value.get()));
+    let num_chars = create_memo(move |_| text.with(|text| text.chars().count()));
+    let num_lines = create_memo(move |_| text.with(|text| text.split('\n').count()));
+    let out_of_bounds = move || {
+        let num_chars = num_chars.get();
+        (num_chars > 0 && min_length.is_some_and(|min| num_chars < min))
+            || max_length.is_some_and(|max| num_chars > max)
+    };
 
     view! {
         <div class="relative">
             <textarea
                 {..attrs}
+                aria-invalid=move || error.get().is_some().to_string()
+                aria-describedby=move || error.get().map(|_| element.error_id())
                 class=move || {
                     if error.get().is_none() {
                         "py-3 px-4 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -710,10 +925,23 @@ pub fn TextArea(
                     }
                 }
 
-                rows="5"
+                rows=move || {
+                    if auto_grow {
+                        (num_lines.get() + 1).clamp(rows, rows.max(MAX_AUTO_GROW_ROWS)).to_string()
+                    } else {
+                        rows.to_string()
+                    }
+                }
+
+                minlength=min_length.map(|length| length.to_string())
+                maxlength=max_length.map(|length| length.to_string())
                 placeholder=placeholder.map(|p| move || p.get())
                 prop:value=move || value.get()
                 disabled=move || disabled.get()
+                on:input=move |ev| {
+                    text.set(event_target_value(&ev));
+                }
+
                 on:change=move |ev| {
                     element
                         .data
@@ -730,11 +958,33 @@ pub fn TextArea(
                 <IconExclamationCircle attr:class="flex-shrink-0 size-4 text-red-500"/>
             </div>
         </div>
+        <p
+            class="text-xs text-end mt-1"
+            class:text-gray-500=move || !out_of_bounds()
+            class:dark:text-gray-400=move || !out_of_bounds()
+            class:text-red-600=move || out_of_bounds()
+            aria-live="polite"
+        >
+            {move || match max_length {
+                Some(max) => format!("{} / {max}", num_chars.get()),
+                None => {
+                    match num_chars.get() {
+                        1 => "1 character".to_string(),
+                        count => format!("{count} characters"),
+                    }
+                }
+            }}
+
+        </p>
         {move || {
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -886,3 +1136,45 @@ impl Rate {
         }
     }
 }
+
+pub fn mask_placeholder(
+    placeholder: Option<MaybeSignal<String>>,
+    mask: Option<InputMask>,
+) -> Option<MaybeSignal<String>> {
+    match (placeholder, mask) {
+        (Some(placeholder), Some(mask)) => Some(MaybeSignal::derive(move || {
+            Some(placeholder.get())
+                .filter(|placeholder| !placeholder.is_empty())
+                .unwrap_or_else(|| mask.hint().to_string())
+        })),
+        (None, Some(mask)) => Some(mask.hint().to_string().into()),
+        (placeholder, None) => placeholder,
+    }
+}
+
+pub fn handle_text_input(ev: &ev::Event, element: FormElement, mask: Option<InputMask>) {
+    let input = event_target::<web_sys::HtmlInputElement>(ev);
+    let value = input.value();
+    let is_paste = ev
+        .dyn_ref::<web_sys::InputEvent>()
+        .is_some_and(|ev| ev.input_type() == "insertFromPaste");
+
+    // Pasted values are normalized right away, masks only apply while typing at the end
+    let updated = if is_paste {
+        element.transform(value.clone())
+    } else if let Some(mask) = mask.filter(|_| {
+        input
+            .selection_start()
+            .ok()
+            .flatten()
+            .is_none_or(|pos| pos as usize == value.encode_utf16().count())
+    }) {
+        mask.apply(&value)
+    } else {
+        return;
+    };
+
+    if updated != value {
+        input.set_value(&updated);
+    }
+}
@@ -22,17 +22,26 @@
 */
 
 pub mod button;
+pub mod checkbox;
+pub mod date;
 pub mod expression;
+pub mod file;
 pub mod input;
 pub mod select;
+pub mod sieve;
 pub mod stacked_badge;
 pub mod stacked_input;
+pub mod undo;
 
-use leptos::*;
+use leptos::{wasm_bindgen::JsCast, *};
 
 use crate::{
-    components::{icon::IconInfo, messages::alert::Alerts},
-    core::form::FormData,
+    components::{badge::Badge, icon::IconInfo, messages::alert::Alerts, Color},
+    core::{
+        form::{ExpressionError, FormData, FormErrorType},
+        prefs::use_preferences,
+        schema::{InputMask, Validator},
+    },
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -47,9 +56,60 @@ pub type ValidateCb = Callback<Result<String, String>, ()>;
 pub fn Form(
     #[prop(optional, into)] title: MaybeSignal<String>,
     #[prop(optional, into)] subtitle: MaybeSignal<String>,
+    #[prop(optional, into)] on_save: Option<Callback<(), ()>>,
+    // Shows a sticky count of failing fields while editing
+    #[prop(optional)] data: Option<RwSignal<FormData>>,
     children: Children,
 ) -> impl IntoView {
     let title_ = title.clone();
+    let issues = data.map(|data| {
+        let count = create_memo(move |_| data.with(|data| data.errors.len()));
+        view! {
+            <Show when=move || { count.get() > 0 }>
+                <div class="sticky top-16 z-10 mb-4 flex justify-end">
+                    <button
+                        type="button"
+                        class="py-1 px-3 inline-flex items-center gap-x-1.5 text-xs font-medium rounded-full border border-red-200 bg-red-100 text-red-800 shadow-sm hover:bg-red-200 dark:bg-red-800/30 dark:border-red-900 dark:text-red-500"
+                        aria-live="polite"
+                        title=move || {
+                            data.with(|data| {
+                                data.field_errors()
+                                    .into_iter()
+                                    .map(|(_, error)| error)
+                                    .collect::<Vec<_>>()
+                                    .join("\n")
+                            })
+                        }
+
+                        on:click=move |_| scroll_to_first_error(data)
+                    >
+                        {move || match count.get() {
+                            1 => "1 issue".to_string(),
+                            count => format!("{count} issues"),
+                        }}
+
+                    </button>
+                </div>
+            </Show>
+        }
+    });
+
+    // Save with Ctrl/Cmd+S while the form is mounted instead of opening the browser's dialog
+    if let Some(on_save) = on_save {
+        let save_with_keyboard = window_event_listener(ev::keydown, move |ev| {
+            if (ev.ctrl_key() || ev.meta_key())
+                && !ev.alt_key()
+                && ev.key().eq_ignore_ascii_case("s")
+            {
+                ev.prevent_default();
+                if !ev.repeat() {
+                    commit_focused_input();
+                    on_save.call(());
+                }
+            }
+        });
+        on_cleanup(move || save_with_keyboard.remove());
+    }
 
     view! {
         <div class="max-w-4xl px-4 py-10 sm:px-6 lg:px-8 lg:py-14 mx-auto">
@@ -61,6 +121,8 @@ pub fn Form(
                     <p class="text-sm text-gray-600 dark:text-gray-400">{move || subtitle.get()}</p>
                 </div>
 
+                {issues}
+
                 <Alerts/>
 
                 <form>{children()}</form>
@@ -69,6 +131,47 @@ pub fn Form(
     }
 }
 
+// Inputs only write to FormData on change, which browsers fire synchronously when the
+// edited element loses focus. Elements that keep the focus get the event dispatched.
+fn commit_focused_input() {
+    let Some(focused) = document().active_element() else {
+        return;
+    };
+    let is_blurred = focused
+        .dyn_ref::<web_sys::HtmlElement>()
+        .is_some_and(|element| element.blur().is_ok())
+        && document().active_element().as_ref() != Some(&focused);
+    if !is_blurred {
+        let mut init = web_sys::EventInit::new();
+        init.bubbles(true);
+        if let Ok(event) = web_sys::Event::new_with_event_init_dict("change", &init) {
+            let _ = focused.dispatch_event(&event);
+        }
+    }
+}
+
+fn scroll_to_first_error(data: RwSignal<FormData>) {
+    let targets = data.with_untracked(|data| {
+        data.failing_fields().into_iter().next().map(|id| {
+            // Item errors are rendered next to the offending entry
+            match data.error(id).map(|error| error.id) {
+                Some(FormErrorType::Array(idx))
+                | Some(FormErrorType::Expression(
+                    ExpressionError::If(idx) | ExpressionError::Then(idx),
+                )) => [format!("{id}-error-{idx}"), format!("{id}-error")],
+                _ => [format!("{id}-error"), format!("{id}-error-0")],
+            }
+        })
+    });
+    if let Some(element) = targets
+        .into_iter()
+        .flatten()
+        .find_map(|id| document().get_element_by_id(&id))
+    {
+        element.scroll_into_view_with_bool(false);
+    }
+}
+
 #[component]
 pub fn FormButtonBar(children: Children) -> impl IntoView {
     view! { <div class="mt-5 flex justify-end gap-x-2">{children()}</div> }
@@ -104,53 +207,55 @@ pub fn FormSection(
     }
 }
 
+#[component]
+pub fn FormItemGroup(columns: usize, children: Children) -> impl IntoView {
+    // Collapses to a single column on narrow screens
+    let class = match columns {
+        2 => "sm:col-span-12 grid grid-cols-1 gap-2 sm:grid-cols-2 sm:gap-6",
+        3 => "sm:col-span-12 grid grid-cols-1 gap-2 sm:grid-cols-3 sm:gap-6",
+        _ => "sm:col-span-12 grid grid-cols-1 gap-2 sm:grid-cols-4 sm:gap-6",
+    };
+
+    view! { <div class=class>{children()}</div> }
+}
+
 #[component]
 pub fn FormItem(
     #[prop(into)] label: MaybeSignal<String>,
     #[prop(optional)] tooltip: Option<&'static str>,
+    #[prop(optional)] deprecated: Option<&'static str>,
     #[prop(optional, into)] hide: MaybeSignal<bool>,
     #[prop(optional, into)] is_optional: MaybeSignal<bool>,
+    #[prop(optional)] stacked: bool,
     children: Children,
 ) -> impl IntoView {
     view! {
-        <div class="sm:col-span-3" class:hidden=move || hide.get()>
+        <div class=if stacked { "" } else { "sm:col-span-3" } class:hidden=move || hide.get()>
             <label class="inline-block text-sm text-gray-800 mt-2.5 dark:text-gray-200">
                 {label}
             </label>
             {tooltip
                 .filter(|s| !s.is_empty())
                 .map(|tooltip| {
-                    let is_mouse_over = create_rw_signal(false);
                     view! {
-                        <div class="hs-tooltip inline-block">
-                            <button
-                                type="button"
-                                class="hs-tooltip-toggle ms-1"
-                                on:mouseover=move |_| {
-                                    is_mouse_over.set(true);
-                                }
-
-                                on:mouseleave=move |_| {
-                                    is_mouse_over.set(false);
-                                }
-                            >
-
-                                <IconInfo
-                                    size=16
-                                    attr:stroke-width="1"
-                                    attr:class="inline-block size-3 text-gray-400 dark:text-gray-600"
-                                />
-                            </button>
-                            <span
-                                class="hs-tooltip-content hs-tooltip-shown:opacity-100 hs-tooltip-shown:visible opacity-70 transition-opacity inline-block absolute w-40 text-center z-10 py-1 px-2 bg-gray-900 text-xs font-medium text-white rounded shadow-sm dark:bg-slate-700"
-                                role="tooltip"
-                                class:hidden=move || !is_mouse_over.get()
-                                class:show=move || is_mouse_over.get()
-                            >
-                                {tooltip}
-                            </span>
-
-                        </div>
+                        <FormTooltip text=tooltip>
+                            <IconInfo
+                                size=16
+                                attr:stroke-width="1"
+                                attr:class="inline-block size-3 text-gray-400 dark:text-gray-600"
+                            />
+                        </FormTooltip>
+                    }
+                })}
+            {deprecated
+                .filter(|s| !s.is_empty())
+                .map(|message| {
+                    view! {
+                        <FormTooltip text=message>
+                            <Badge color=Color::Yellow attr:aria-label=format!("Deprecated: {message}")>
+                                "Deprecated"
+                            </Badge>
+                        </FormTooltip>
                     }
                 })}
 
@@ -169,14 +274,121 @@ pub fn FormItem(
             }}
 
         </div>
-        <div class="sm:col-span-9" class:hidden=move || hide.get()>
+        <div class=if stacked { "mt-1" } else { "sm:col-span-9" } class:hidden=move || hide.get()>
             {children()}
         </div>
     }
 }
 
+#[component]
+fn FormTooltip(text: &'static str, children: Children) -> impl IntoView {
+    let is_mouse_over = create_rw_signal(false);
+
+    view! {
+        <div class="hs-tooltip inline-block">
+            <button
+                type="button"
+                class="hs-tooltip-toggle ms-1"
+                on:mouseover=move |_| {
+                    is_mouse_over.set(true);
+                }
+
+                on:mouseleave=move |_| {
+                    is_mouse_over.set(false);
+                }
+            >
+
+                {children()}
+            </button>
+            <span
+                class="hs-tooltip-content hs-tooltip-shown:opacity-100 hs-tooltip-shown:visible opacity-70 transition-opacity inline-block absolute w-40 text-center z-10 py-1 px-2 bg-gray-900 text-xs font-medium text-white rounded shadow-sm dark:bg-slate-700"
+                role="tooltip"
+                class:hidden=move || !is_mouse_over.get()
+                class:show=move || is_mouse_over.get()
+            >
+                {text}
+            </span>
+
+        </div>
+    }
+}
+
 impl FormElement {
     pub fn new(id: &'static str, data: RwSignal<FormData>) -> Self {
         FormElement { id, data }
     }
+
+    pub fn error_id(&self) -> String {
+        format!("{}-error", self.id)
+    }
+
+    pub fn item_error_id(&self, idx: usize) -> String {
+        format!("{}-error-{idx}", self.id)
+    }
+
+    pub fn transform(&self, value: String) -> String {
+        let data = self.data.get_untracked();
+        match data
+            .schema
+            .fields
+            .get(self.id)
+            .and_then(|field| field.input_check(&data))
+        {
+            Some(check) => check.transform(value),
+            None => value,
+        }
+    }
+
+    pub fn hint_id(&self) -> String {
+        format!("{}-hint", self.id)
+    }
+
+    pub fn transform_hint(&self) -> Option<String> {
+        self.data.with(|data| {
+            data.schema
+                .fields
+                .get(self.id)
+                .filter(|field| !field.hide_transform_hint)
+                .and_then(|field| field.input_check(data))
+                .and_then(|check| check.transform_hint())
+        })
+    }
+
+    pub fn rows(&self) -> Option<usize> {
+        self.data
+            .with_untracked(|data| data.schema.fields.get(self.id).and_then(|field| field.rows))
+    }
+
+    pub fn length_limits(&self) -> (Option<usize>, Option<usize>) {
+        self.data.with_untracked(|data| {
+            data.schema
+                .fields
+                .get(self.id)
+                .and_then(|field| field.input_check(data))
+                .map(|check| {
+                    check
+                        .validators
+                        .iter()
+                        .fold((None, None), |(min, max), validator| match validator {
+                            Validator::MinLength(length) => (Some(*length), max),
+                            Validator::MaxLength(length) => (min, Some(*length)),
+                            _ => (min, max),
+                        })
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn input_mask(&self) -> Option<InputMask> {
+        if use_preferences().get_untracked().input_masks() {
+            self.data
+                .get_untracked()
+                .schema
+                .fields
+                .get(self.id)
+                .and_then(|field| field.mask)
+        } else {
+            None
+        }
+    }
 }
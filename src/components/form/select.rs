@@ -59,6 +59,8 @@ pub fn Select(
 
     view! {
         <select
+            aria-invalid=move || error.get().is_some().to_string()
+            aria-describedby=move || error.get().map(|_| element.error_id())
             class=move || {
                 if error.get().is_none() {
                     "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -97,7 +99,11 @@ pub fn Select(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -148,6 +154,8 @@ pub fn CheckboxGroup(
                             view! {
                                 <label class="max-w-xs flex p-3 w-full bg-white border border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400">
                                     <input
+                                        aria-invalid=move || error.get().is_some().to_string()
+                                        aria-describedby=move || error.get().map(|_| element.error_id())
                                         type="checkbox"
                                         class="shrink-0 mt-0.5 border-gray-200 rounded text-blue-600 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-gray-800 dark:border-gray-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
                                         prop:checked=move || values.get().contains(&id_)
@@ -184,7 +192,11 @@ pub fn CheckboxGroup(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -215,6 +227,8 @@ pub fn SelectCron(
             <div class="relative">
                 <div class="sm:flex rounded-lg shadow-sm">
                     <select
+                        aria-invalid=move || error.get().is_some().to_string()
+                        aria-describedby=move || error.get().map(|_| element.error_id())
                         class=move || {
                             if error.get().is_none() {
                                 "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -268,6 +282,8 @@ pub fn SelectCron(
 
                     </select>
                     <select
+                        aria-invalid=move || error.get().is_some().to_string()
+                        aria-describedby=move || error.get().map(|_| element.error_id())
                         class=move || {
                             if error.get().is_none() {
                                 "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -319,6 +335,8 @@ pub fn SelectCron(
 
                     </select>
                     <select
+                        aria-invalid=move || error.get().is_some().to_string()
+                        aria-describedby=move || error.get().map(|_| element.error_id())
                         class=move || {
                             if error.get().is_none() {
                                 "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -370,7 +388,11 @@ pub fn SelectCron(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -0,0 +1,183 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::{html::Div, html::Pre, *};
+
+use crate::{
+    components::icon::IconExclamationCircle,
+    core::sieve::{is_control, tokenize, TokenKind},
+};
+
+use super::FormElement;
+
+const LINE_CLASS: &str = "font-mono text-sm leading-6 whitespace-pre";
+
+#[component]
+pub fn SieveEditor(
+    element: FormElement,
+    #[prop(optional, into)] disabled: MaybeSignal<bool>,
+) -> impl IntoView {
+    let value = create_memo(move |_| {
+        element
+            .data
+            .get()
+            .value::<String>(element.id)
+            .unwrap_or_default()
+    });
+    let error = create_memo(move |_| {
+        element
+            .data
+            .get()
+            .error_string(element.id)
+            .map(|s| s.to_string())
+    });
+
+    // Tracks keystrokes so highlighting follows the text before it is committed to the form
+    let text = create_rw_signal(value.get_untracked());
+    create_effect(move |_| text.set(value.get()));
+    let num_lines = create_memo(move |_| text.with(|text| text.split('\n').count()));
+
+    let gutter = create_node_ref::<Div>();
+    let highlighted = create_node_ref::<Pre>();
+
+    view! {
+        <div
+            class="relative flex overflow-hidden rounded-lg border bg-white dark:bg-slate-900"
+            class:border-gray-200=move || error.get().is_none()
+            class:dark:border-gray-700=move || error.get().is_none()
+            class:border-red-500=move || error.get().is_some()
+        >
+            <div
+                node_ref=gutter
+                aria-hidden="true"
+                class=format!(
+                    "{LINE_CLASS} select-none overflow-hidden py-3 px-2 text-right text-gray-400 bg-gray-50 border-e border-gray-200 dark:bg-slate-800 dark:border-gray-700",
+                )
+            >
+
+                {move || {
+                    (1..=num_lines.get()).map(|line| view! { <div>{line}</div> }).collect_view()
+                }}
+
+            </div>
+            <div class="relative grow">
+                <pre
+                    node_ref=highlighted
+                    aria-hidden="true"
+                    class=format!(
+                        "{LINE_CLASS} absolute inset-0 m-0 overflow-hidden py-3 px-3 pointer-events-none text-gray-800 dark:text-gray-300",
+                    )
+                >
+                    {move || text.with(|text| highlight(text))}
+                    // Keeps the last line visible when the text ends with a new line
+                    "\n"
+                </pre>
+                <textarea
+                    class=format!(
+                        "{LINE_CLASS} relative block w-full min-h-60 py-3 px-3 border-0 bg-transparent text-transparent caret-gray-800 resize-y focus:ring-0 disabled:opacity-50 dark:caret-gray-200",
+                    )
+
+                    rows=move || (num_lines.get() + 1).clamp(10, 30).to_string()
+                    wrap="off"
+                    spellcheck="false"
+                    autocomplete="off"
+                    aria-invalid=move || error.get().is_some().to_string()
+                    aria-describedby=move || error.get().map(|_| element.error_id())
+                    prop:value=move || value.get()
+                    disabled=move || disabled.get()
+                    on:input=move |ev| {
+                        text.set(event_target_value(&ev));
+                    }
+
+                    on:change=move |ev| {
+                        element
+                            .data
+                            .update(|data| {
+                                data.update(element.id, event_target_value(&ev));
+                            });
+                    }
+
+                    on:scroll=move |ev| {
+                        let textarea = event_target::<web_sys::HtmlTextAreaElement>(&ev);
+                        if let Some(pre) = highlighted.get_untracked() {
+                            pre.set_scroll_top(textarea.scroll_top());
+                            pre.set_scroll_left(textarea.scroll_left());
+                        }
+                        if let Some(gutter) = gutter.get_untracked() {
+                            gutter.set_scroll_top(textarea.scroll_top());
+                        }
+                    }
+                >
+                </textarea>
+            </div>
+            <div
+                class="absolute top-0 end-0 flex items-center pointer-events-none p-3"
+                class:hidden=move || error.get().is_none()
+            >
+                <IconExclamationCircle attr:class="flex-shrink-0 size-4 text-red-500"/>
+            </div>
+        </div>
+        {move || {
+            error
+                .get()
+                .map(|error| {
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
+                })
+        }}
+    }
+}
+
+fn highlight(text: &str) -> View {
+    let (tokens, _) = tokenize(text);
+    let mut pos = 0;
+    let mut parts = Vec::with_capacity(tokens.len() * 2 + 1);
+
+    for token in tokens {
+        if token.start > pos {
+            parts.push(text[pos..token.start].to_string().into_view());
+        }
+        let token_text = &text[token.start..token.end];
+        let class = match token.kind {
+            TokenKind::Comment => "text-gray-400 italic",
+            TokenKind::String => "text-green-700 dark:text-green-400",
+            TokenKind::Number => "text-purple-700 dark:text-purple-400",
+            TokenKind::Tag => "text-blue-600 dark:text-blue-400",
+            TokenKind::Identifier if is_control(token_text) => {
+                "font-semibold text-pink-700 dark:text-pink-400"
+            }
+            TokenKind::Identifier => "text-cyan-700 dark:text-cyan-400",
+            TokenKind::Punctuation(_) => "text-gray-500",
+        };
+        parts.push(view! { <span class=class>{token_text.to_string()}</span> }.into_view());
+        pos = token.end;
+    }
+    if pos < text.len() {
+        parts.push(text[pos..].to_string().into_view());
+    }
+
+    parts.collect_view()
+}
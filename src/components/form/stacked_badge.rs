@@ -159,6 +159,10 @@ pub fn StackedBadge(
                         }
 
                         prop:value=add_value
+                        aria-invalid=move || validation_error.get().is_some().to_string()
+                        aria-describedby=move || {
+                            validation_error.get().map(|_| element.error_id())
+                        }
                         on:change=move |ev| {
                             add_value.set(event_target_value(&ev));
                         }
@@ -195,8 +199,10 @@ pub fn StackedBadge(
 
                 </div>
                 <p
+                    id=element.error_id()
                     class="text-xs text-red-600 mt-2"
                     class:hidden=move || validation_error.get().is_none()
+                    role="alert"
                 >
                     {move || validation_error.get().unwrap_or_default()}
                 </p>
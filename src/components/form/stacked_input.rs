@@ -28,7 +28,10 @@ use crate::{
     core::form::FormErrorType,
 };
 
-use super::FormElement;
+use super::{
+    input::{handle_text_input, mask_placeholder},
+    FormElement,
+};
 
 #[component]
 pub fn StackedInput(
@@ -36,6 +39,8 @@ pub fn StackedInput(
     element: FormElement,
     #[prop(optional, into)] placeholder: Option<MaybeSignal<String>>,
 ) -> impl IntoView {
+    let mask = element.input_mask();
+    let placeholder = mask_placeholder(placeholder, mask);
     let values = create_memo(move |_| {
         let data = element.data.get();
         let error = data.error(element.id);
@@ -88,6 +93,8 @@ pub fn StackedInput(
                             <div class="relative">
                                 <input
                                     type="text"
+                                    aria-invalid=is_err.to_string()
+                                    aria-describedby=is_err.then(|| element.item_error_id(idx))
                                     class=move || {
                                         if !is_err {
                                             "py-2 px-3 pe-11 block w-full border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -98,6 +105,7 @@ pub fn StackedInput(
 
                                     prop:value=item
                                     placeholder=placeholder.clone().map(|p| move || p.get())
+                                    on:input=move |ev| handle_text_input(&ev, element, mask)
                                     on:change=move |ev| {
                                         element
                                             .data
@@ -123,7 +131,12 @@ pub fn StackedInput(
 
                                 </button>
                             </div>
-                            <p class="text-xs text-red-600 mt-2" class:hidden=!is_err>
+                            <p
+                                id=element.item_error_id(idx)
+                                class="text-xs text-red-600 mt-2"
+                                class:hidden=!is_err
+                                role="alert"
+                            >
                                 {error}
                             </p>
                         </div>
@@ -157,7 +170,11 @@ pub fn StackedInput(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=element.error_id() class="text-xs text-red-600 mt-2" role="alert">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
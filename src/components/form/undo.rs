@@ -0,0 +1,118 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::{wasm_bindgen::JsCast, *};
+
+use crate::components::icon::{IconArrowUturnLeft, IconArrowUturnRight};
+
+use super::FormElement;
+
+#[component]
+pub fn FieldUndo(element: FormElement, children: Children) -> impl IntoView {
+    let can_undo = create_memo(move |_| element.data.with(|data| data.can_undo(element.id)));
+    let can_redo = create_memo(move |_| element.data.with(|data| data.can_redo(element.id)));
+    let apply = move |redo: bool| {
+        element.data.update(|data| {
+            if redo {
+                data.redo(element.id);
+            } else {
+                data.undo(element.id);
+            }
+        });
+    };
+
+    view! {
+        <div on:keydown=move |ev| {
+            if !(ev.ctrl_key() || ev.meta_key()) || ev.alt_key() {
+                return;
+            }
+            let redo = match ev.key().to_ascii_lowercase().as_str() {
+                "z" => ev.shift_key(),
+                "y" => true,
+                _ => return,
+            };
+            if has_uncommitted_input(&ev, element)
+                || !(if redo { can_redo } else { can_undo }).get_untracked()
+            {
+                return;
+            }
+            ev.prevent_default();
+            apply(redo);
+        }>
+            {children()}
+            <Show when=move || can_undo.get() || can_redo.get()>
+                <div class="flex gap-x-3 mt-1">
+                    <button
+                        type="button"
+                        class="inline-flex items-center gap-x-1 text-xs text-gray-400 hover:text-gray-600 disabled:opacity-50 disabled:pointer-events-none dark:text-gray-500 dark:hover:text-gray-300"
+                        title="Undo (Ctrl+Z)"
+                        aria-keyshortcuts="Control+Z"
+                        disabled=move || !can_undo.get()
+                        on:click=move |_| apply(false)
+                    >
+                        <IconArrowUturnLeft attr:class="flex-shrink-0 size-3"/>
+                        "Undo"
+                    </button>
+                    <button
+                        type="button"
+                        class="inline-flex items-center gap-x-1 text-xs text-gray-400 hover:text-gray-600 disabled:opacity-50 disabled:pointer-events-none dark:text-gray-500 dark:hover:text-gray-300"
+                        title="Redo (Ctrl+Y)"
+                        aria-keyshortcuts="Control+Y"
+                        disabled=move || !can_redo.get()
+                        on:click=move |_| apply(true)
+                    >
+                        <IconArrowUturnRight attr:class="flex-shrink-0 size-3"/>
+                        "Redo"
+                    </button>
+                </div>
+            </Show>
+        </div>
+    }
+}
+
+// Typing that hasn't reached the form yet is left to the browser's own undo
+fn has_uncommitted_input(ev: &ev::KeyboardEvent, element: FormElement) -> bool {
+    let Some(typed) = ev.target().and_then(|target| {
+        if let Some(input) = target.dyn_ref::<web_sys::HtmlInputElement>() {
+            (!matches!(input.type_().as_str(), "checkbox" | "radio" | "file"))
+                .then(|| input.value())
+        } else {
+            target
+                .dyn_ref::<web_sys::HtmlTextAreaElement>()
+                .map(|textarea| textarea.value())
+        }
+    }) else {
+        return false;
+    };
+
+    element.data.with_untracked(|data| {
+        let id = element.id;
+        let is_committed = (typed.is_empty() && data.value_is_empty(id))
+            || data.array_value(id).any(|value| value == typed)
+            || data.expr_else(id) == Some(typed.as_str())
+            || data
+                .expr_if_thens(id)
+                .any(|if_then| if_then.if_ == typed || if_then.then_ == typed);
+        !is_committed
+    })
+}
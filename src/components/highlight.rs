@@ -0,0 +1,126 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+
+use crate::core::fuzzy::fuzzy_ranges;
+
+#[component]
+pub fn Highlight(
+    #[prop(into)] text: String,
+    #[prop(into)] query: MaybeSignal<Vec<String>>,
+    #[prop(into, default = false.into())] fuzzy: MaybeSignal<bool>,
+) -> impl IntoView {
+    move || {
+        let ranges = query.with(|query| {
+            if fuzzy.get() {
+                fuzzy_ranges(&text, query)
+            } else {
+                match_ranges(&text, query)
+            }
+        });
+        let mut pos = 0;
+        let mut parts = Vec::with_capacity(ranges.len() * 2 + 1);
+        for (start, end) in ranges {
+            if start > pos {
+                parts.push(text[pos..start].to_string().into_view());
+            }
+            parts.push(
+                view! {
+                    <mark class="bg-yellow-100 text-inherit rounded-sm dark:bg-yellow-800/40">
+                        {text[start..end].to_string()}
+                    </mark>
+                }
+                .into_view(),
+            );
+            pos = end;
+        }
+        if pos < text.len() {
+            parts.push(text[pos..].to_string().into_view());
+        }
+        parts.collect_view()
+    }
+}
+
+pub fn filter_terms(filter: Signal<Option<String>>) -> Signal<Vec<String>> {
+    Signal::derive(move || {
+        filter
+            .get()
+            .map(|filter| search_terms(&filter))
+            .unwrap_or_default()
+    })
+}
+
+pub fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .map(|term| term.to_lowercase())
+        .collect()
+}
+
+pub fn matches_terms(text: &str, terms: &[String]) -> bool {
+    !match_ranges(text, terms).is_empty()
+}
+
+// Byte ranges of all case-insensitive matches, merged and sorted
+fn match_ranges(text: &str, terms: &[String]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    for term in terms.iter().filter(|term| !term.is_empty()) {
+        for (start, _) in text.char_indices() {
+            if let Some(len) = match_len(&text[start..], term) {
+                ranges.push((start, start + len));
+            }
+        }
+    }
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+fn match_len(text: &str, term: &str) -> Option<usize> {
+    let mut term = term.chars().peekable();
+    let mut len = 0;
+    for ch in text.chars() {
+        if term.peek().is_none() {
+            break;
+        }
+        for lower in ch.to_lowercase() {
+            if term.next() != Some(lower) {
+                return None;
+            }
+        }
+        len += ch.len_utf8();
+    }
+    if term.peek().is_none() {
+        Some(len)
+    } else {
+        None
+    }
+}
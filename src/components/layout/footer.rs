@@ -0,0 +1,99 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+
+use crate::{
+    components::icon::IconClipboard,
+    core::{
+        clipboard::copy_to_clipboard,
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+    },
+    version_name,
+};
+
+#[component]
+pub fn Footer() -> impl IntoView {
+    let auth = use_authorization();
+    let is_logged_in = create_memo(move |_| auth.get().is_logged_in());
+    let copied = create_rw_signal(false);
+
+    let server_version = create_local_resource(
+        move || is_logged_in.get(),
+        move |is_logged_in| {
+            let auth = auth.get_untracked();
+
+            async move {
+                if is_logged_in {
+                    match HttpRequest::get("/api/server/version")
+                        .with_authorization(&auth)
+                        .send::<String>()
+                        .await
+                    {
+                        Ok(version) => Some(version),
+                        Err(http::Error::Unauthorized) => None,
+                        Err(err) => {
+                            log::debug!("Failed to fetch server version: {err:?}");
+                            None
+                        }
+                    }
+                } else {
+                    None
+                }
+            }
+        },
+    );
+    let version = move || {
+        let mut version = version_name();
+        if let Some(server_version) = server_version.get().flatten() {
+            version.push_str(&format!(" / Server v{server_version}"));
+        }
+        version
+    };
+
+    view! {
+        <footer class="flex justify-center items-center gap-x-2 py-5 text-xs text-gray-500 dark:text-gray-400">
+            <span>{version}</span>
+            <button
+                type="button"
+                class="inline-flex items-center gap-x-1 text-gray-500 hover:text-blue-600 dark:text-gray-400 dark:hover:text-blue-500"
+                title="Copy version"
+                on:click=move |_| {
+                    let version = version();
+                    spawn_local(async move {
+                        copied.set(copy_to_clipboard(&version).await);
+                        set_timeout(move || copied.set(false), std::time::Duration::from_secs(2));
+                    });
+                }
+            >
+
+                <IconClipboard attr:class="flex-shrink-0 size-3.5"/>
+                <span class="sr-only">Copy version</span>
+                <Show when=move || copied.get()>
+                    <span>Copied</span>
+                </Show>
+            </button>
+        </footer>
+    }
+}
@@ -21,31 +21,89 @@
  * for more details.
 */
 
-use gloo_storage::{SessionStorage, Storage};
-use leptos::*;
-use leptos_router::use_navigate;
+use std::time::Duration;
+
+use leptos::{leptos_dom::helpers::TimeoutHandle, *};
+use leptos_router::{use_location, use_navigate, NavigateOptions};
+use leptos_use::{use_debounce_fn_with_arg, use_document_visibility};
+use web_sys::VisibilityState;
 
 use crate::{
-    components::icon::{
-        IconAdjustmentsHorizontal, IconHeart, IconPower, IconServer, IconUserCircle,
+    components::{
+        badge::Badge,
+        icon::{IconAdjustmentsHorizontal, IconHeart, IconPower, IconServer, IconUserCircle},
+        list::toolbar::SEARCH_DEBOUNCE_MS,
+        messages::modal::{use_modals, Modal},
+        Color,
     },
     core::{
-        oauth::{use_authorization, AuthToken},
+        oauth::{use_authorization, use_refresh_timer},
+        prefs::use_preferences,
+        recent::use_recently_viewed,
         url::UrlBuilder,
     },
-    pages::config::edit::DEFAULT_SETTINGS_URL,
-    STATE_STORAGE_KEY, VERSION_NAME,
+    pages::{
+        config::edit::DEFAULT_SETTINGS_URL,
+        format_count,
+        login::{forget_saved_login, sign_out},
+        queue::messages::fetch_queue_size,
+    },
+    version_name, LOGO_URL, PRODUCT_NAME,
 };
 use web_sys::wasm_bindgen::JsCast;
 
 #[component]
 pub fn Header(is_admin: MaybeSignal<bool>) -> impl IntoView {
+    let modal = use_modals();
+    let auth_token = use_authorization();
+    let refresh_timer = use_refresh_timer();
+    let recent = use_recently_viewed();
+    let navigate = use_navigate();
+    let location = use_location();
+    let logout = create_action(move |forget_login: &bool| {
+        let forget_login = *forget_login;
+        let base_url = auth_token.with_untracked(|auth| auth.base_url.clone());
+        let navigate = use_navigate();
+
+        async move {
+            if forget_login {
+                forget_saved_login(&base_url);
+                recent.clear();
+            }
+            sign_out(auth_token, refresh_timer).await;
+            navigate("/login", Default::default());
+        }
+    });
+    let search_settings = Callback::new(move |query: String| {
+        let query = query.trim();
+        if !query.is_empty() {
+            // Update the results in place while typing instead of stacking history entries
+            let replace = location.pathname.get_untracked() == "/settings/search";
+            navigate(
+                &UrlBuilder::new("/settings/search")
+                    .with_parameter("query", query)
+                    .finish(),
+                NavigateOptions {
+                    replace,
+                    ..Default::default()
+                },
+            );
+        }
+    });
+    let search_debounced = use_debounce_fn_with_arg(
+        move |query: String| search_settings.call(query),
+        SEARCH_DEBOUNCE_MS,
+    );
+    let search_debounced = Callback::new(move |query: String| {
+        search_debounced(query);
+    });
+
     view! {
         <header class="sticky top-0 inset-x-0 flex flex-wrap sm:justify-start sm:flex-nowrap z-[48] w-full bg-white border-b text-sm py-2.5 sm:py-4 lg:ps-64 dark:bg-gray-800 dark:border-gray-700">
             <nav class="flex basis-full items-center w-full mx-auto px-4 sm:px-6 md:px-8">
 
                 <div class="me-5 lg:me-0 lg:hidden">
-                    <img src="/logo.svg" title=VERSION_NAME/>
+                    <img src=LOGO_URL alt=PRODUCT_NAME title=version_name()/>
                 </div>
 
                 <div class="w-full flex items-center justify-end ms-auto sm:justify-between sm:gap-x-3 sm:order-3">
@@ -97,21 +155,16 @@ pub fn Header(is_admin: MaybeSignal<bool>) -> impl IntoView {
                                     type="text"
                                     class="py-2 px-4 ps-11 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
                                     placeholder="Search settings"
+                                    on:input=move |ev| {
+                                        search_debounced.call(event_target_value(&ev));
+                                    }
+
                                     on:keyup=move |ev| {
                                         let key_code = ev
                                             .unchecked_ref::<web_sys::KeyboardEvent>()
                                             .key_code();
                                         if key_code == 13 {
-                                            let filter = event_target_value(&ev);
-                                            let query = filter.trim();
-                                            if !query.is_empty() {
-                                                use_navigate()(
-                                                    &UrlBuilder::new("/settings/search")
-                                                        .with_parameter("query", query)
-                                                        .finish(),
-                                                    Default::default(),
-                                                );
-                                            }
+                                            search_settings.call(event_target_value(&ev));
                                         }
                                     }
                                 />
@@ -122,6 +175,9 @@ pub fn Header(is_admin: MaybeSignal<bool>) -> impl IntoView {
                     </Show>
 
                     <div class="flex flex-row items-center justify-end gap-2">
+                        <Show when=move || is_admin.get()>
+                            <QueueSizeBadge/>
+                        </Show>
                         <a
                             href="/manage/directory/accounts"
                             class="w-[2.375rem] h-[2.375rem] inline-flex justify-center items-center gap-x-2 text-sm font-semibold rounded-full border border-transparent text-gray-800 hover:bg-gray-100 disabled:opacity-50 disabled:pointer-events-none dark:text-white dark:hover:bg-gray-700 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
@@ -160,10 +216,27 @@ pub fn Header(is_admin: MaybeSignal<bool>) -> impl IntoView {
                         <a
                             class="w-[2.375rem] h-[2.375rem] inline-flex justify-center items-center gap-x-2 text-sm font-semibold rounded-full border border-transparent text-gray-800 hover:bg-gray-100 disabled:opacity-50 disabled:pointer-events-none dark:text-white dark:hover:bg-gray-700 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
                             title="Logout"
+                            class:opacity-50=move || logout.pending().get()
+                            class:pointer-events-none=move || logout.pending().get()
+                            aria-disabled=move || logout.pending().get().to_string()
                             on:click=move |_| {
-                                SessionStorage::delete(STATE_STORAGE_KEY);
-                                use_authorization().set(AuthToken::default());
-                                use_navigate()("/login", Default::default());
+                                if logout.pending().get_untracked() {
+                                    return;
+                                }
+                                let forget_login = create_rw_signal(true);
+                                modal
+                                    .set(
+                                        Modal::with_title("Sign out")
+                                            .with_message("Are you sure you want to sign out?")
+                                            .with_button("Sign out")
+                                            .with_checkbox(
+                                                "Also forget saved login and recently viewed items on this device",
+                                                forget_login,
+                                            )
+                                            .with_callback(move || {
+                                                logout.dispatch(forget_login.get_untracked());
+                                            }),
+                                    );
                             }
                         >
 
@@ -176,3 +249,71 @@ pub fn Header(is_admin: MaybeSignal<bool>) -> impl IntoView {
         </header>
     }
 }
+
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+const QUEUE_POLL_INTERVAL_HIDDEN: Duration = Duration::from_secs(300);
+
+#[component]
+fn QueueSizeBadge() -> impl IntoView {
+    let auth = use_authorization();
+    let prefs = use_preferences();
+    let visibility = use_document_visibility();
+    let queue_size = create_rw_signal(None::<u64>);
+    let handle = store_value(None::<TimeoutHandle>);
+
+    let poll = create_action(move |_: &()| {
+        let auth = auth.get_untracked();
+
+        async move {
+            match fetch_queue_size(&auth).await {
+                Ok(size) => queue_size.set(Some(size)),
+                Err(err) => log::debug!("Failed to fetch queue size: {err:?}"),
+            }
+        }
+    });
+    let clear = move || {
+        if let Some(handle) = handle.try_update_value(|handle| handle.take()).flatten() {
+            handle.clear();
+        }
+    };
+
+    // Schedule the next poll after each completed one, backing off while the tab is hidden
+    create_effect(move |_| {
+        poll.version().get();
+        clear();
+        let delay = if visibility.get_untracked() == VisibilityState::Hidden {
+            QUEUE_POLL_INTERVAL_HIDDEN
+        } else {
+            QUEUE_POLL_INTERVAL
+        };
+        handle.set_value(set_timeout_with_handle(move || poll.dispatch(()), delay).ok());
+    });
+    create_effect(move |prev: Option<VisibilityState>| {
+        let state = visibility.get();
+        if prev == Some(VisibilityState::Hidden) && state == VisibilityState::Visible {
+            poll.dispatch(());
+        }
+        state
+    });
+    on_cleanup(clear);
+    poll.dispatch(());
+
+    move || {
+        queue_size.get().map(|size| {
+            let prefs = prefs.get();
+            let color = if size >= prefs.queue_critical_threshold() {
+                Color::Red
+            } else if size >= prefs.queue_warning_threshold() {
+                Color::Yellow
+            } else {
+                Color::Gray
+            };
+
+            view! {
+                <a href="/manage/queue/messages" title="Messages in queue">
+                    <Badge color=color>{format!("{} queued", format_count(size))}</Badge>
+                </a>
+            }
+        })
+    }
+}
@@ -21,6 +21,7 @@
  * for more details.
 */
 
+pub mod footer;
 pub mod header;
 pub mod sidebar;
 pub mod toggle;
@@ -28,15 +29,23 @@ pub mod toggle;
 use std::hash::{DefaultHasher, Hash, Hasher};
 
 use leptos::*;
-use leptos_meta::Body;
-use leptos_router::Outlet;
+use leptos_meta::{Body, Title};
+use leptos_router::{use_location, Outlet, Redirect};
 
 use crate::{
     components::{
-        layout::{header::Header, sidebar::SideBar, toggle::ToggleNavigation},
-        messages::modal::Modal,
+        layout::{footer::Footer, header::Header, sidebar::SideBar, toggle::ToggleNavigation},
+        messages::{
+            alert::{defer_alert, Alert},
+            error::PageErrorBoundary,
+            modal::Modal,
+        },
+    },
+    core::{
+        a11y::use_reduced_motion,
+        oauth::{login_redirect_url, use_authorization, use_session_check},
+        schema::{Schema, SchemaType},
     },
-    core::schema::{Schema, SchemaType},
 };
 
 pub struct LayoutBuilder {
@@ -59,22 +68,73 @@ pub struct MenuItem {
     pub children: Vec<MenuItem>,
 }
 
+#[component]
+pub fn GuardedLayout(
+    menu_items: fn() -> Vec<MenuItem>,
+    #[prop(optional)] admin_only: bool,
+) -> impl IntoView {
+    let auth = use_authorization();
+    let is_logged_in = create_memo(move |_| auth.get().is_logged_in());
+    let is_admin = create_memo(move |_| auth.get().is_admin());
+    let session_check = use_session_check();
+
+    move || {
+        if session_check.is_pending() {
+            view! { <SessionSplash/> }.into_view()
+        } else if !is_logged_in.get() {
+            view! { <Redirect path=login_redirect_url()/> }.into_view()
+        } else if admin_only && !is_admin.get() {
+            defer_alert(Alert::warning(
+                "You do not have permission to access that page. Administrator privileges are required.",
+            ));
+            view! { <Redirect path=auth.get_untracked().default_route()/> }.into_view()
+        } else {
+            view! { <Layout menu_items=menu_items() is_admin=is_admin/> }.into_view()
+        }
+    }
+}
+
+#[component]
+fn SessionSplash() -> impl IntoView {
+    let reduced_motion = use_reduced_motion();
+
+    view! {
+        <Body class="bg-gray-50 dark:bg-slate-900"/>
+        <div class="flex min-h-screen flex-col items-center justify-center gap-y-4" role="status">
+            <div
+                class="size-8 rounded-full border-4 border-gray-200 border-t-blue-600 dark:border-gray-700 dark:border-t-blue-500"
+                class:animate-spin=move || !reduced_motion.get()
+            ></div>
+            <p class="text-sm text-gray-500 dark:text-gray-400">Restoring session...</p>
+        </div>
+    }
+}
+
 #[component]
 pub fn Layout(
     menu_items: Vec<MenuItem>,
     #[prop(into)] is_admin: MaybeSignal<bool>,
 ) -> impl IntoView {
     let menu_items_toggle = menu_items.clone();
+    let menu_items_title = store_value(menu_items.clone());
     let show_sidebar = create_rw_signal(false);
+    let location = use_location();
+    let title = move || {
+        menu_items_title.with_value(|items| MenuItem::page_title(items, &location.pathname.get()))
+    };
 
     view! {
+        <Title text=title/>
         <Body class="bg-gray-50 dark:bg-slate-900"/>
         <Modal/>
         <Header is_admin/>
         <ToggleNavigation menu_items show_sidebar/>
         <SideBar menu_items=menu_items_toggle show_sidebar/>
         <div class="w-full pt-10 px-4 sm:px-6 md:px-8 lg:ps-72">
-            <Outlet/>
+            <PageErrorBoundary>
+                <Outlet/>
+            </PageErrorBoundary>
+            <Footer/>
         </div>
     }
 }
@@ -143,6 +203,44 @@ impl LayoutBuilder {
 }
 
 impl MenuItem {
+    pub fn page_title(items: &[MenuItem], path: &str) -> String {
+        let path = path.trim_end_matches('/');
+        let mut best_match: Option<(&MenuItem, usize)> = None;
+        let mut stack = items.iter().collect::<Vec<_>>();
+        while let Some(item) = stack.pop() {
+            stack.extend(item.children.iter());
+            let Some(route) = item.route.as_deref() else {
+                continue;
+            };
+            // Detail routes are usually the singular form of their list route
+            if [route, route.trim_end_matches('s')]
+                .into_iter()
+                .any(|route| {
+                    path.strip_prefix(route)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                })
+                && best_match.is_none_or(|(_, len)| route.len() > len)
+            {
+                best_match = Some((item, route.len()));
+            }
+        }
+
+        if let Some((item, _)) = best_match {
+            item.name.clone()
+        } else {
+            path.rsplit('/')
+                .find(|segment| !segment.is_empty() && *segment != "edit")
+                .map(|segment| {
+                    let mut title = segment.replace('-', " ");
+                    if let Some(first) = title.get_mut(..1) {
+                        first.make_ascii_uppercase();
+                    }
+                    title
+                })
+                .unwrap_or_default()
+        }
+    }
+
     pub fn id(&self) -> String {
         let mut hasher = DefaultHasher::new();
         self.name.hash(&mut hasher);
@@ -24,13 +24,15 @@
 use leptos::*;
 use leptos_router::use_location;
 
-use crate::VERSION_NAME;
+use crate::{core::recent::use_recently_viewed, version_name, LOGO_URL, PRODUCT_NAME};
 
 use super::MenuItem;
 
 #[component]
 pub fn SideBar(menu_items: Vec<MenuItem>, show_sidebar: RwSignal<bool>) -> impl IntoView {
     let current_route = create_memo(move |_| use_location().pathname.get());
+    let recent = use_recently_viewed();
+    let recent_items = recent.items();
 
     view! {
         <div
@@ -39,7 +41,7 @@ pub fn SideBar(menu_items: Vec<MenuItem>, show_sidebar: RwSignal<bool>) -> impl
             class:open=move || show_sidebar.get()
         >
             <div class="px-8">
-                <img src="/logo.svg" style="height: 25px;" title=VERSION_NAME/>
+                <img src=LOGO_URL alt=PRODUCT_NAME style="height: 25px;" title=version_name()/>
             </div>
 
             <nav
@@ -309,6 +311,58 @@ pub fn SideBar(menu_items: Vec<MenuItem>, show_sidebar: RwSignal<bool>) -> impl
                     </For>
 
                 </ul>
+
+                <Show when=move || recent_items.with(|items| !items.is_empty())>
+                    <div class="mt-6 mb-2 px-2.5 flex items-center justify-between">
+                        <span class="text-xs font-semibold uppercase text-gray-500 dark:text-gray-400">
+                            Recently viewed
+                        </span>
+                        <button
+                            type="button"
+                            class="text-xs text-gray-500 hover:text-blue-600 dark:text-gray-400 dark:hover:text-blue-500"
+                            title="Clear recently viewed items"
+                            on:click=move |_| recent.clear()
+                        >
+                            Clear
+                        </button>
+                    </div>
+                    <ul class="space-y-1">
+                        <For
+                            each=move || recent_items.get()
+                            key=|item| (item.typ.clone(), item.id.clone())
+                            let:item
+                        >
+
+                            {
+                                let route = item.route.clone();
+                                view! {
+                                    <li>
+                                        <a
+                                            class=move || {
+                                                format!(
+                                                    "flex flex-col py-1.5 px-2.5 text-sm text-slate-700 rounded-lg hover:bg-gray-100 dark:hover:bg-gray-900 dark:text-slate-400 dark:hover:text-slate-300 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600{}",
+                                                    if route == current_route.get() {
+                                                        " bg-gray-100"
+                                                    } else {
+                                                        ""
+                                                    },
+                                                )
+                                            }
+
+                                            href=item.route.clone()
+                                        >
+                                            <span class="truncate">{item.label}</span>
+                                            <span class="text-xs capitalize text-gray-500 dark:text-gray-500">
+                                                {item.typ}
+                                            </span>
+                                        </a>
+                                    </li>
+                                }
+                            }
+
+                        </For>
+                    </ul>
+                </Show>
             </nav>
         </div>
     }
@@ -25,10 +25,15 @@ use std::collections::HashSet;
 
 use leptos::*;
 
+use crate::{components::form::checkbox::Checkbox, core::prefs::ListSort};
+
 #[component]
 pub fn ColumnList(
     #[prop(into)] headers: MaybeSignal<Vec<String>>,
     #[prop(into, optional)] select_all: Option<Callback<(), Vec<String>>>,
+    #[prop(into, optional)] sort: Option<Signal<Option<ListSort>>>,
+    // Headers are only clickable when set
+    #[prop(optional_no_strip)] on_sort: Option<Callback<ListSort, ()>>,
     children: Children,
 ) -> impl IntoView {
     let headers_ = headers.clone();
@@ -43,27 +48,41 @@ pub fn ColumnList(
                         let selected = use_context::<RwSignal<HashSet<String>>>().unwrap();
                         view! {
                             <th scope="col" class="ps-6 py-3 text-start">
-                                <label for="hs-at-with-checkboxes-main" class="flex">
-                                    <input
-                                        type="checkbox"
-                                        class="shrink-0 border-gray-300 rounded text-blue-600 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-600 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
-                                        on:change=move |ev| {
-                                            selected
-                                                .update(|t| {
-                                                    let items = select_all.call(());
-                                                    if event_target_checked(&ev) {
-                                                        t.extend(items);
-                                                    } else {
-                                                        for item in items {
-                                                            t.remove(&item);
-                                                        }
-                                                    }
-                                                });
-                                        }
-                                    />
+                                <Checkbox
+                                    label="Select all"
+                                    hide_label=true
+                                    checked=Signal::derive(move || {
+                                        let items = select_all.call(());
+                                        !items.is_empty()
+                                            && selected.with(|t| items.iter().all(|item| t.contains(item)))
+                                    })
+
+                                    indeterminate=Signal::derive(move || {
+                                        let items = select_all.call(());
+                                        selected
+                                            .with(|t| {
+                                                let count = items
+                                                    .iter()
+                                                    .filter(|item| t.contains(*item))
+                                                    .count();
+                                                count > 0 && count < items.len()
+                                            })
+                                    })
 
-                                    <span class="sr-only">Checkbox</span>
-                                </label>
+                                    on_change=move |checked| {
+                                        selected
+                                            .update(|t| {
+                                                let items = select_all.call(());
+                                                if checked {
+                                                    t.extend(items);
+                                                } else {
+                                                    for item in items {
+                                                        t.remove(&item);
+                                                    }
+                                                }
+                                            });
+                                    }
+                                />
                             </th>
                         }
                     })}
@@ -79,12 +98,39 @@ pub fn ColumnList(
                         } else {
                             "px-6 py-3 text-start"
                         };
+                        let sortable = on_sort.is_some() && !header.is_empty();
+                        let indicator = move || {
+                            sort.and_then(|sort| sort.get())
+                                .filter(|sort| sortable && sort.column == idx)
+                                .map(|sort| if sort.ascending { "▲" } else { "▼" })
+                        };
                         view! {
-                            <th scope="col" class=class>
-                                <div class="flex items-center gap-x-2">
+                            <th
+                                scope="col"
+                                class=class
+                                aria-sort=move || {
+                                    sort.and_then(|sort| sort.get())
+                                        .filter(|sort| sortable && sort.column == idx)
+                                        .map(|sort| if sort.ascending { "ascending" } else { "descending" })
+                                }
+                            >
+                                <div
+                                    class="flex items-center gap-x-2"
+                                    class=("cursor-pointer", sortable)
+                                    class=("select-none", sortable)
+                                    on:click=move |_| {
+                                        if let Some(on_sort) = on_sort.filter(|_| sortable) {
+                                            on_sort
+                                                .call(
+                                                    ListSort::toggle(sort.and_then(|sort| sort.get_untracked()), idx),
+                                                );
+                                        }
+                                    }
+                                >
                                     <span class="text-xs font-semibold uppercase tracking-wide text-gray-800 dark:text-gray-200">
                                         {header}
                                     </span>
+                                    <span class="text-xs text-gray-500">{indicator}</span>
                                 </div>
                             </th>
                         }
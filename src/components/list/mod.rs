@@ -31,6 +31,8 @@ use leptos::*;
 
 use crate::components::{icon::IconPlus, messages::alert::Alerts};
 
+use self::row::provide_row_menu;
+
 #[slot]
 pub struct Toolbar {
     children: Children,
@@ -41,6 +43,11 @@ pub struct Footer {
     children: Children,
 }
 
+#[slot]
+pub struct Filters {
+    children: Children,
+}
+
 #[component]
 pub fn ListTable(
     #[prop(optional, into)] title: MaybeSignal<String>,
@@ -48,7 +55,10 @@ pub fn ListTable(
     children: Children,
     toolbar: Toolbar,
     footer: Footer,
+    #[prop(optional)] filters: Option<Filters>,
 ) -> impl IntoView {
+    provide_row_menu();
+
     view! {
         <div class="flex flex-col">
             <div class="-m-1.5 overflow-x-auto">
@@ -81,6 +91,15 @@ pub fn ListTable(
 
                         </div>
 
+                        {filters
+                            .map(|filters| {
+                                view! {
+                                    <div class="px-6 py-3 flex flex-wrap items-center gap-3 border-b border-gray-200 dark:border-gray-700">
+                                        {(filters.children)()}
+                                    </div>
+                                }
+                            })}
+
                         <table class="min-w-full divide-y divide-gray-200 dark:divide-gray-700">
                             {children()}
                         </table>
@@ -23,18 +23,39 @@
 
 use leptos::*;
 
-use crate::components::icon::{IconArrowLeft, IconArrowRight};
+use crate::{
+    components::icon::{IconArrowFirst, IconArrowLast, IconArrowLeft, IconArrowRight},
+    core::prefs::use_preferences,
+};
+
+pub const PAGE_SIZES: &[u32] = &[10, 25, 50, 100];
 
 #[component]
 pub fn Pagination(
     #[prop(into)] current_page: MaybeSignal<u32>,
     #[prop(into)] total_results: MaybeSignal<Option<u32>>,
-    #[prop(into)] page_size: MaybeSignal<u32>,
+    // Defaults to the page size set in the preferences
+    #[prop(into, optional)] page_size: Option<MaybeSignal<u32>>,
     #[prop(into)] on_page_change: Callback<u32, ()>,
+    #[prop(into, optional)] on_page_size_change: Option<Callback<u32, ()>>,
+    // The total is a lower bound, keep moving forward until an empty page is returned
+    #[prop(into, default = false.into())] has_more: MaybeSignal<bool>,
 ) -> impl IntoView {
+    let page_size = page_size.unwrap_or_else(|| {
+        let prefs = use_preferences();
+        Signal::derive(move || prefs.with(|prefs| prefs.page_size())).into()
+    });
     let total_pages = create_memo(move |_| {
-        (total_results.get().unwrap_or(0) as f64 / page_size.get() as f64).ceil() as u32
+        let pages =
+            (total_results.get().unwrap_or(0) as f64 / page_size.get() as f64).ceil() as u32;
+        if has_more.get() {
+            pages.max(current_page.get())
+        } else {
+            pages
+        }
     });
+    let is_last_page =
+        create_memo(move |_| !has_more.get() && current_page.get() >= total_pages.get());
 
     view! {
         <div class="px-6 py-4 grid gap-3 md:flex md:justify-between md:items-center border-t border-gray-200 dark:border-gray-700">
@@ -44,6 +65,7 @@ pub fn Pagination(
                     <p class="text-sm text-gray-600 dark:text-gray-400">
                         <span class="font-semibold text-gray-800 dark:text-gray-200">
                             {move || { total_results.get().map_or(0, |r| r) }}
+                            {move || has_more.get().then_some("+")}
                         </span>
                         " results. Page"
 
@@ -64,7 +86,43 @@ pub fn Pagination(
                         </select>
                     </div>
 
-                    <p class="text-sm text-gray-600 dark:text-gray-400">of {total_pages}</p>
+                    <p class="text-sm text-gray-600 dark:text-gray-400">
+                        "of "
+                        {total_pages}
+                        {move || has_more.get().then_some("+")}
+                    </p>
+                    {on_page_size_change
+                        .map(|on_page_size_change| {
+                            view! {
+                                <select
+                                    class="py-2 px-3 pe-9 block border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400"
+                                    title="Results per page"
+                                    on:change=move |ev| {
+                                        if let Ok(size) = event_target_value(&ev).parse() {
+                                            on_page_size_change.call(size);
+                                        }
+                                    }
+                                >
+
+                                    {PAGE_SIZES
+                                        .iter()
+                                        .map(|size| {
+                                            let size = *size;
+                                            view! {
+                                                <option
+                                                    value=size.to_string()
+                                                    selected=move || page_size.get() == size
+                                                >
+                                                    {format!("{size} per page")}
+                                                </option>
+                                            }
+                                        })
+                                        .collect_view()}
+
+                                </select>
+                            }
+                        })}
+
                 </div>
 
             </Show>
@@ -74,9 +132,21 @@ pub fn Pagination(
                     <button
                         type="button"
                         class="py-2 px-3 inline-flex items-center gap-x-2 text-sm font-medium rounded-lg border border-gray-200 bg-white text-gray-800 shadow-sm hover:bg-gray-50 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-white dark:hover:bg-gray-800 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
-                        disabled=move || {
-                            total_results.get().map_or(true, |r| r == 0) || current_page.get() <= 1
+                        title="First page"
+                        disabled=move || { current_page.get() <= 1 }
+                        on:click=move |_| {
+                            on_page_change.call(1);
                         }
+                    >
+
+                        <IconArrowFirst attr:class="flex-shrink-0 size-4"/>
+                        <span class="sr-only">First</span>
+                    </button>
+
+                    <button
+                        type="button"
+                        class="py-2 px-3 inline-flex items-center gap-x-2 text-sm font-medium rounded-lg border border-gray-200 bg-white text-gray-800 shadow-sm hover:bg-gray-50 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-white dark:hover:bg-gray-800 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                        disabled=move || { current_page.get() <= 1 }
 
                         on:click=move |_| {
                             on_page_change.call(current_page.get() - 1);
@@ -96,13 +166,30 @@ pub fn Pagination(
                                 on_page_change.call(current_page.get() + 1);
                             }
 
-                            disabled=move || { current_page.get() >= total_pages.get() }
+                            disabled=move || is_last_page.get()
                         >
 
                             Next
                             <IconArrowRight attr:class="flex-shrink-0 size-4"/>
                         </button>
 
+                        <button
+                            type="button"
+                            class="py-2 px-3 inline-flex items-center gap-x-2 text-sm font-medium rounded-lg border border-gray-200 bg-white text-gray-800 shadow-sm hover:bg-gray-50 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-white dark:hover:bg-gray-800 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                            title="Last page"
+                            on:click=move |_| {
+                                on_page_change.call(total_pages.get());
+                            }
+
+                            disabled=move || {
+                                has_more.get() || current_page.get() >= total_pages.get()
+                            }
+                        >
+
+                            <IconArrowLast attr:class="flex-shrink-0 size-4"/>
+                            <span class="sr-only">Last</span>
+                        </button>
+
                     </Suspense>
 
                 </div>
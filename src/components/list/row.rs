@@ -23,7 +23,30 @@
 
 use std::collections::HashSet;
 
-use leptos::*;
+use leptos::{html::Button, html::Div, wasm_bindgen::JsCast, *};
+use leptos_router::use_navigate;
+
+use crate::components::{
+    icon::IconEllipsisVertical,
+    messages::modal::{use_modals, Modal},
+};
+
+#[derive(Clone)]
+pub struct RowAction {
+    label: String,
+    kind: RowActionKind,
+}
+
+#[derive(Clone)]
+enum RowActionKind {
+    Link(String),
+    Callback(Callback<(), ()>),
+    Dangerous(Modal),
+}
+
+// Identifies the row whose actions menu is open, so that opening one closes the others
+#[derive(Clone, Copy)]
+pub struct RowMenu(RwSignal<Option<String>>);
 
 #[component]
 pub fn SelectItem(item_id: String) -> impl IntoView {
@@ -46,3 +69,245 @@ pub fn SelectItem(item_id: String) -> impl IntoView {
         />
     }
 }
+
+#[component]
+pub fn RowActions(#[prop(into)] item_id: String, actions: Vec<RowAction>) -> impl IntoView {
+    let menu = use_row_menu();
+    let modal = use_modals();
+    let item_id = store_value(item_id);
+    let actions = store_value(actions);
+    let is_open = create_memo(move |_| item_id.with_value(|id| menu.is_open(id)));
+    let trigger = create_node_ref::<Button>();
+    let list = create_node_ref::<Div>();
+    let position = create_rw_signal((0.0, 0.0));
+
+    // Fixed positioning keeps the menu from being clipped by the table container
+    create_effect(move |_| {
+        if is_open.get() {
+            if let Some(trigger) = trigger.get_untracked() {
+                let rect = trigger.get_bounding_client_rect();
+                let width = window()
+                    .inner_width()
+                    .ok()
+                    .and_then(|width| width.as_f64())
+                    .unwrap_or_default();
+                position.set((rect.bottom(), width - rect.right()));
+            }
+            request_animation_frame(move || focus_item(list, 0));
+        }
+    });
+
+    let close = move || {
+        menu.close();
+        if let Some(trigger) = trigger.get_untracked() {
+            let _ = trigger.focus();
+        }
+    };
+    let run = move |idx: usize| {
+        close();
+        let Some(action) = actions.with_value(|actions| actions.get(idx).cloned()) else {
+            return;
+        };
+        match action.kind {
+            RowActionKind::Link(url) => use_navigate()(&url, Default::default()),
+            RowActionKind::Callback(callback) => callback.call(()),
+            RowActionKind::Dangerous(confirm) => modal.set(confirm),
+        }
+    };
+    let items = actions.with_value(|actions| {
+        actions
+            .iter()
+            .enumerate()
+            .map(|(idx, action)| {
+                let is_dangerous = matches!(action.kind, RowActionKind::Dangerous(_));
+                view! {
+                    <button
+                        type="button"
+                        role="menuitem"
+                        tabindex="-1"
+                        class="flex w-full items-center gap-x-3.5 py-2 px-3 rounded-lg text-sm text-start hover:bg-gray-100 focus:outline-none focus:bg-gray-100 dark:hover:bg-gray-700 dark:focus:bg-gray-700"
+                        class:text-gray-800=!is_dangerous
+                        class:dark:text-gray-300=!is_dangerous
+                        class:text-red-600=is_dangerous
+                        class:dark:text-red-500=is_dangerous
+                        on:click=move |_| run(idx)
+                    >
+                        {action.label.clone()}
+                    </button>
+                }
+            })
+            .collect_view()
+    });
+
+    view! {
+        <div class="relative inline-block" data-row-menu="">
+            <button
+                node_ref=trigger
+                type="button"
+                class="inline-flex size-8 items-center justify-center rounded-lg text-gray-500 hover:bg-gray-100 focus:outline-none focus:ring-2 focus:ring-blue-500 dark:text-gray-400 dark:hover:bg-gray-700"
+                aria-label="Actions"
+                aria-haspopup="menu"
+                aria-expanded=move || is_open.get().to_string()
+                on:click=move |_| {
+                    if is_open.get_untracked() {
+                        menu.close();
+                    } else {
+                        menu.open(item_id.get_value());
+                    }
+                }
+            >
+
+                <IconEllipsisVertical/>
+            </button>
+            <Show when=move || is_open.get()>
+                <div
+                    node_ref=list
+                    role="menu"
+                    class="fixed z-20 min-w-40 mt-1 p-1 bg-white border border-gray-200 rounded-lg shadow-md dark:bg-slate-800 dark:border-gray-700"
+                    style=move || {
+                        let (top, right) = position.get();
+                        format!("top: {top}px; right: {right}px;")
+                    }
+
+                    on:keydown=move |ev| {
+                        let count = actions.with_value(|actions| actions.len());
+                        let current = focused_item(list);
+                        match ev.key().as_str() {
+                            "Escape" => {
+                                ev.prevent_default();
+                                close();
+                            }
+                            "Tab" => menu.close(),
+                            "ArrowDown" => {
+                                ev.prevent_default();
+                                focus_item(list, current.map_or(0, |idx| (idx + 1) % count));
+                            }
+                            "ArrowUp" => {
+                                ev.prevent_default();
+                                focus_item(
+                                    list,
+                                    current.map_or(count.saturating_sub(1), |idx| (idx + count - 1) % count),
+                                );
+                            }
+                            "Home" => {
+                                ev.prevent_default();
+                                focus_item(list, 0);
+                            }
+                            "End" => {
+                                ev.prevent_default();
+                                focus_item(list, count.saturating_sub(1));
+                            }
+                            _ => {}
+                        }
+                    }
+                >
+
+                    {items.clone()}
+                </div>
+            </Show>
+        </div>
+    }
+}
+
+impl RowAction {
+    pub fn link(label: impl Into<String>, url: impl Into<String>) -> Self {
+        RowAction {
+            label: label.into(),
+            kind: RowActionKind::Link(url.into()),
+        }
+    }
+
+    pub fn new(label: impl Into<String>, callback: impl Into<Callback<(), ()>>) -> Self {
+        RowAction {
+            label: label.into(),
+            kind: RowActionKind::Callback(callback.into()),
+        }
+    }
+
+    // Destructive actions only run once the user accepts the confirmation modal
+    pub fn dangerous(label: impl Into<String>, confirm: Modal) -> Self {
+        RowAction {
+            label: label.into(),
+            kind: RowActionKind::Dangerous(confirm),
+        }
+    }
+}
+
+pub fn provide_row_menu() {
+    let menu = RowMenu(create_rw_signal(None));
+    provide_context(menu);
+
+    let click_handle = window_event_listener(ev::click, move |ev| {
+        let is_inside = ev
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .and_then(|element| element.closest("[data-row-menu]").ok().flatten())
+            .is_some();
+        if !is_inside && menu.0.with_untracked(Option::is_some) {
+            menu.close();
+        }
+    });
+    let scroll_handle = window_event_listener(ev::scroll, move |_| {
+        if menu.0.with_untracked(Option::is_some) {
+            menu.close();
+        }
+    });
+    on_cleanup(move || {
+        click_handle.remove();
+        scroll_handle.remove();
+    });
+}
+
+pub fn use_row_menu() -> RowMenu {
+    expect_context::<RowMenu>()
+}
+
+impl RowMenu {
+    pub fn open(&self, item_id: String) {
+        self.0.set(Some(item_id));
+    }
+
+    pub fn close(&self) {
+        self.0.set(None);
+    }
+
+    pub fn is_open(&self, item_id: &str) -> bool {
+        self.0.with(|open| open.as_deref() == Some(item_id))
+    }
+
+    // Opens the menu of a focused row with the context menu key or Shift+F10
+    pub fn on_row_keydown(&self, item_id: String) -> impl Fn(ev::KeyboardEvent) + 'static {
+        let menu = *self;
+        move |ev| {
+            let is_row = ev.target() == ev.current_target();
+            if is_row && (ev.key() == "ContextMenu" || (ev.shift_key() && ev.key() == "F10")) {
+                ev.prevent_default();
+                menu.open(item_id.clone());
+            }
+        }
+    }
+}
+
+fn menu_items(list: NodeRef<Div>) -> Vec<web_sys::HtmlElement> {
+    list.get_untracked()
+        .and_then(|list| list.query_selector_all("[role=menuitem]").ok())
+        .map(|items| {
+            (0..items.length())
+                .filter_map(|idx| items.item(idx)?.dyn_into::<web_sys::HtmlElement>().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn focus_item(list: NodeRef<Div>, idx: usize) {
+    if let Some(item) = menu_items(list).get(idx) {
+        let _ = item.focus();
+    }
+}
+
+fn focused_item(list: NodeRef<Div>) -> Option<usize> {
+    let active = document().active_element()?;
+    menu_items(list)
+        .iter()
+        .position(|item| item.unchecked_ref::<web_sys::Element>() == &active)
+}
@@ -21,11 +21,16 @@
  * for more details.
 */
 
-use leptos::*;
+use std::time::Duration;
+
+use leptos::{leptos_dom::helpers::IntervalHandle, *};
+use leptos_use::use_debounce_fn_with_arg;
 use web_sys::wasm_bindgen::JsCast;
 
 use crate::components::Color;
 
+pub const SEARCH_DEBOUNCE_MS: f64 = 200.0;
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum ButtonIcon {
     Add,
@@ -56,12 +61,85 @@ pub fn ToolbarButton(
     }
 }
 
+const REFRESH_INTERVALS: &[(u64, &str)] = &[(0, "Off"), (10, "10s"), (30, "30s"), (60, "60s")];
+
+#[component]
+pub fn AutoRefresh(
+    #[prop(into)] on_refresh: Callback<(), ()>,
+    #[prop(into, optional)] paused: MaybeSignal<bool>,
+) -> impl IntoView {
+    let interval = create_rw_signal(0u64);
+    let handle = store_value(None::<IntervalHandle>);
+    let clear = move || {
+        if let Some(handle) = handle.try_update_value(|handle| handle.take()).flatten() {
+            handle.clear();
+        }
+    };
+
+    create_effect(move |_| {
+        let secs = interval.get();
+        clear();
+        if secs > 0 {
+            match set_interval_with_handle(
+                move || {
+                    if !paused.get_untracked() {
+                        on_refresh.call(());
+                    }
+                },
+                Duration::from_secs(secs),
+            ) {
+                Ok(new_handle) => handle.set_value(Some(new_handle)),
+                Err(err) => log::error!("Failed to start auto-refresh: {err:?}"),
+            }
+        }
+    });
+    on_cleanup(clear);
+
+    view! {
+        <label class="inline-flex items-center gap-x-2 text-sm text-gray-800 dark:text-white">
+            <span class="whitespace-nowrap">Auto-refresh</span>
+            <select
+                class="py-2 px-3 pe-9 block border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                on:change=move |ev| {
+                    interval.set(event_target_value(&ev).parse().unwrap_or_default());
+                }
+            >
+
+                {REFRESH_INTERVALS
+                    .iter()
+                    .map(|(secs, label)| {
+                        view! {
+                            <option value=secs.to_string() selected=move || interval.get() == *secs>
+                                {*label}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+
+            </select>
+        </label>
+    }
+}
+
 #[component]
 pub fn SearchBox(
     #[prop(into)] value: MaybeSignal<Option<String>>,
     #[prop(into)] on_search: Callback<String, ()>,
+    #[prop(optional, into)] placeholder: Option<MaybeSignal<String>>,
 ) -> impl IntoView {
+    let placeholder = placeholder.unwrap_or_else(|| "Search".to_string().into());
     let value_ = value.clone();
+    let owner = Owner::current();
+    let search = move |filter: String| {
+        if filter.trim() != value.get_untracked().unwrap_or_default() {
+            match owner {
+                Some(owner) => with_owner(owner, || on_search.call(filter)),
+                None => on_search.call(filter),
+            }
+        }
+    };
+    let search_debounced = use_debounce_fn_with_arg(search.clone(), SEARCH_DEBOUNCE_MS);
+
     view! {
         <div class="sm:col-span-1">
             <label for="hs-as-table-product-review-search" class="sr-only">
@@ -76,15 +154,16 @@ pub fn SearchBox(
                         "dark:text-gray-400 dark:focus:ring-gray-600",
                     )
 
-                    placeholder="Search"
+                    placeholder=placeholder
                     prop:value=move || value_.get().unwrap_or_default()
+                    on:input=move |ev| {
+                        search_debounced(event_target_value(&ev));
+                    }
+
                     on:keyup=move |ev| {
                         let key_code = ev.unchecked_ref::<web_sys::KeyboardEvent>().key_code();
                         if key_code == 13 {
-                            let filter = event_target_value(&ev);
-                            if filter != value.get().unwrap_or_default() {
-                                on_search.call(filter);
-                            }
+                            search(event_target_value(&ev));
                         }
                     }
                 />
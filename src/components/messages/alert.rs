@@ -30,7 +30,10 @@ use crate::{
         IconCheckCircle, IconExclamationCircle, IconExclamationTriangle, IconXMark,
     },
     core::http::{self, ManagementApiError},
-    pages::config::{ConfigError, ConfigWarning, ReloadSettings},
+    pages::{
+        config::{ConfigError, ConfigWarning, ReloadSettings},
+        maybe_plural,
+    },
 };
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -47,18 +50,42 @@ pub struct Alert {
     pub message: String,
     pub details: Option<View>,
     pub timeout: Option<Duration>,
+    pub action: Option<AlertAction>,
+    // Set when the server throttled the request, so callers can hold off retrying
+    pub retry_after: Option<Duration>,
+}
+
+#[derive(Clone)]
+pub struct AlertAction {
+    pub label: String,
+    pub callback: Callback<(), ()>,
 }
 
+pub const UNDO_TIMEOUT: Duration = Duration::from_secs(8);
+
+#[derive(Clone, Copy)]
+struct PendingAlert(StoredValue<Option<Alert>>);
+
 pub fn init_alerts() {
     provide_context(create_rw_signal(Alert::disabled()));
+    provide_context(PendingAlert(store_value(None)));
 }
 
 pub fn use_alerts() -> RwSignal<Alert> {
     let signal = expect_context::<RwSignal<Alert>>();
-    signal.set(Alert::disabled());
+    let pending = expect_context::<PendingAlert>()
+        .0
+        .try_update_value(|alert| alert.take())
+        .flatten();
+    signal.set(pending.unwrap_or_else(Alert::disabled));
     signal
 }
 
+// Shows an alert on the next page that calls `use_alerts`, used when redirecting
+pub fn defer_alert(alert: Alert) {
+    expect_context::<PendingAlert>().0.set_value(Some(alert));
+}
+
 #[component]
 pub fn Alerts() -> impl IntoView {
     let alert = expect_context::<RwSignal<Alert>>();
@@ -79,13 +106,25 @@ pub fn Alerts() -> impl IntoView {
         }
     });
 
+    // The live region stays mounted so that newly shown alerts are announced
     view! {
-        <div class=move || {
-            match alert.get().typ {
-                AlertType::None => "hidden",
-                _ => "pb-5",
+        <div
+            class=move || {
+                match alert.get().typ {
+                    AlertType::None => "",
+                    _ => "pb-5",
+                }
             }
-        }>
+
+            aria-live=move || {
+                match alert.get().typ {
+                    AlertType::Error | AlertType::Warning => "assertive",
+                    _ => "polite",
+                }
+            }
+
+            aria-atomic="true"
+        >
             <div
                 class=move || {
                     match alert.get().typ {
@@ -102,8 +141,14 @@ pub fn Alerts() -> impl IntoView {
                     }
                 }
 
-                role="alert"
+                role=move || {
+                    match alert.get().typ {
+                        AlertType::Error | AlertType::Warning => "alert",
+                        _ => "status",
+                    }
+                }
             >
+
                 <div class="flex">
                     <div class="flex-shrink-0">
                         {move || {
@@ -144,6 +189,30 @@ pub fn Alerts() -> impl IntoView {
                             {move || { alert.get().message.clone() }}
                         </h3>
                         <div class="mt-1 text-sm">{move || { alert.get().details }}</div>
+                        {move || {
+                            alert
+                                .get()
+                                .action
+                                .map(|action| {
+                                    view! {
+                                        <button
+                                            type="button"
+                                            class="mt-2 text-sm font-semibold underline decoration-2 hover:opacity-80"
+                                            on:click=move |_| {
+                                                alert
+                                                    .update(|alert| {
+                                                        alert.close();
+                                                    });
+                                                action.callback.call(());
+                                            }
+                                        >
+
+                                            {action.label}
+                                        </button>
+                                    }
+                                })
+                        }}
+
                     </div>
 
                     <div class="ps-3 ms-auto">
@@ -185,6 +254,29 @@ pub fn Alerts() -> impl IntoView {
     }
 }
 
+// Counts `remaining` down to zero one second at a time. Restarting a countdown on the
+// same signal leaves a single chain running, a tick only proceeds while the value is
+// still the one it last wrote.
+pub fn start_countdown(remaining: RwSignal<u64>, duration: Duration) {
+    fn tick(remaining: RwSignal<u64>, expected: u64) {
+        if expected > 0 {
+            set_timeout(
+                move || {
+                    if remaining.get_untracked() == expected {
+                        remaining.set(expected - 1);
+                        tick(remaining, expected - 1);
+                    }
+                },
+                Duration::from_secs(1),
+            );
+        }
+    }
+
+    let secs = duration.as_secs_f64().ceil() as u64;
+    remaining.set(secs);
+    tick(remaining, secs);
+}
+
 impl Alert {
     pub fn disabled() -> Self {
         Self::new(AlertType::None, "")
@@ -208,6 +300,8 @@ impl Alert {
             message: message.into(),
             details: None,
             timeout: None,
+            action: None,
+            retry_after: None,
         }
     }
 
@@ -216,6 +310,18 @@ impl Alert {
         self
     }
 
+    pub fn with_action(
+        mut self,
+        label: impl Into<String>,
+        callback: impl Into<Callback<(), ()>>,
+    ) -> Self {
+        self.action = Some(AlertAction {
+            label: label.into(),
+            callback: callback.into(),
+        });
+        self
+    }
+
     pub fn without_timeout(mut self) -> Self {
         self.timeout = None;
         self
@@ -227,6 +333,27 @@ impl Alert {
         self
     }
 
+    pub fn with_retry_countdown(mut self, retry_after: Duration) -> Self {
+        let remaining = create_rw_signal(0);
+        start_countdown(remaining, retry_after);
+        self.retry_after = Some(retry_after);
+        self.details = Some(
+            view! {
+                {move || match remaining.get() {
+                    0 => "You can try again now.".to_string(),
+                    secs => {
+                        format!(
+                            "The server is limiting requests, try again in {}.",
+                            maybe_plural(secs as usize, "second", "seconds"),
+                        )
+                    }
+                }}
+            }
+            .into_view(),
+        );
+        self
+    }
+
     pub fn with_details_list<T, I>(mut self, details: T) -> Self
     where
         T: IntoIterator<Item = I>,
@@ -269,41 +396,39 @@ impl From<http::Error> for Alert {
                 Alert::error("Failed to deserialize response").with_details(error)
             }
             http::Error::Server(error) => {
-                let (title, details) = match error {
-                    ManagementApiError::FieldAlreadyExists { field, value } => (
-                        "Field already exists".to_string(),
-                        format!(
-                            "Another record exists with with value {value:?} in field {field:?}."
-                        ),
-                    ),
-                    ManagementApiError::FieldMissing { field } => (
-                        "Missing required field".to_string(),
-                        format!("Field {} is missing", field),
-                    ),
-                    ManagementApiError::NotFound { item } => {
-                        ("Not found".to_string(), format!("{item} was not found"))
-                    }
-                    ManagementApiError::Unsupported { details } => {
-                        ("Operation not allowed".to_string(), details)
+                let title = match &error {
+                    ManagementApiError::FieldAlreadyExists { .. } => "Field already exists",
+                    ManagementApiError::FieldMissing { .. } => "Missing required field",
+                    ManagementApiError::NotFound { .. } => "Not found",
+                    ManagementApiError::Unsupported { .. } => "Operation not allowed",
+                    ManagementApiError::AssertFailed => "Record already exists",
+                    ManagementApiError::Other { .. } => "Operation failed",
+                    ManagementApiError::UnsupportedDirectoryOperation { .. } => {
+                        return Alert::error(error.to_string()).with_details(
+                            "Only internal directories support inserts and update operations.",
+                        );
                     }
-                    ManagementApiError::AssertFailed => (
-                        "Record already exists".to_string(),
-                        "Another record with the same ID already exists".to_string(),
-                    ),
-                    ManagementApiError::Other { details } => {
-                        ("Operation failed".to_string(), details)
-                    }
-                    ManagementApiError::UnsupportedDirectoryOperation { class } => (
-                        format!("{class} directory cannot be managed"),
-                        "Only internal directories support inserts and update operations."
-                            .to_string(),
-                    ),
                 };
 
-                Alert::error(title).with_details(details)
+                Alert::error(title).with_details(error.to_string())
             }
             http::Error::NotFound => Alert::error("Not found"),
             http::Error::Unauthorized => Alert::error("Unauthorized"),
+            http::Error::BadRequest { response } => Alert::error("Operation failed").with_details(
+                http::bad_request_details(&response)
+                    .unwrap_or_else(|| "The server rejected the request.".to_string()),
+            ),
+            http::Error::Forbidden => Alert::warning("Permission denied")
+                .with_details("Your account does not have permission to perform this operation."),
+            http::Error::TooManyRequests {
+                retry_after: Some(retry_after),
+            } => Alert::warning("Too many requests").with_retry_countdown(retry_after),
+            http::Error::TooManyRequests { retry_after: None } => {
+                Alert::warning("Too many requests")
+                    .with_details("The server is limiting requests, try again in a moment.")
+            }
+            http::Error::TotpRequired => Alert::warning("Verification code required")
+                .with_details("This account requires a code from an authenticator app."),
         }
     }
 }
@@ -360,6 +485,8 @@ impl From<ReloadSettings> for Alert {
                     view! { <ul class="list-disc space-y-1 ps-5">{messages}</ul> }.into_view(),
                 ),
                 timeout: None,
+                action: None,
+                retry_after: None,
             }
         }
     }
@@ -0,0 +1,57 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+
+use crate::{
+    components::icon::{IconInfo, IconXMark},
+    core::storage::is_storage_available,
+};
+
+#[component]
+pub fn StorageBanner() -> impl IntoView {
+    // Checked once on startup, a dismissal can't be remembered without storage anyway
+    let show = create_rw_signal(!is_storage_available());
+
+    view! {
+        <Show when=move || show.get()>
+            <div
+                class="flex items-center gap-x-3 px-4 py-2 bg-blue-50 border-b border-blue-200 text-sm text-blue-800 dark:bg-blue-800/10 dark:border-blue-900 dark:text-blue-500"
+                role="status"
+            >
+                <IconInfo attr:class="flex-shrink-0 size-4"/>
+                <p class="grow">
+                    "This browser is blocking site storage. Preferences, recently viewed items and \"Remember me\" will not be kept, and reloading the page signs you out."
+                </p>
+                <button
+                    type="button"
+                    class="inline-flex rounded-lg p-1.5 text-blue-500 hover:bg-blue-100 focus:outline-none focus:ring-2 focus:ring-blue-600 dark:hover:bg-blue-800/50"
+                    on:click=move |_| show.set(false)
+                >
+                    <span class="sr-only">Dismiss</span>
+                    <IconXMark/>
+                </button>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,109 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+use log::Level;
+
+use crate::{
+    components::icon::{IconTrash, IconXMark},
+    core::logging::use_log_state,
+};
+
+#[component]
+pub fn LogConsole() -> impl IntoView {
+    let state = use_log_state();
+
+    view! {
+        <Show when=move || state.settings.get().console>
+            <div class="fixed bottom-0 inset-x-0 z-[70] flex flex-col h-64 bg-gray-900 border-t border-gray-700 shadow-lg">
+                <div class="flex justify-between items-center py-1.5 px-4 border-b border-gray-700">
+                    <h3 class="text-xs font-semibold uppercase text-gray-400">
+                        {move || {
+                            format!(
+                                "Log console ({}, {} entries)",
+                                state.settings.get().level,
+                                state.entries.get().len(),
+                            )
+                        }}
+
+                    </h3>
+                    <div class="flex items-center gap-x-1">
+                        <button
+                            type="button"
+                            class="flex justify-center items-center size-7 rounded-full text-gray-400 hover:bg-gray-700"
+                            on:click=move |_| state.entries.set(Vec::new())
+                        >
+                            <span class="sr-only">Clear</span>
+                            <IconTrash attr:class="flex-shrink-0 size-4"/>
+                        </button>
+                        <button
+                            type="button"
+                            class="flex justify-center items-center size-7 rounded-full text-gray-400 hover:bg-gray-700"
+                            on:click=move |_| {
+                                state
+                                    .settings
+                                    .update(|settings| {
+                                        settings.console = false;
+                                        settings.save();
+                                    });
+                            }
+                        >
+
+                            <span class="sr-only">Close</span>
+                            <IconXMark/>
+                        </button>
+                    </div>
+                </div>
+                <div class="flex-1 overflow-y-auto py-1 px-4 font-mono text-xs">
+                    <For
+                        each=move || state.entries.get().into_iter().rev()
+                        key=|entry| entry.id
+                        let:entry
+                    >
+                        <div class="flex gap-x-2 whitespace-pre-wrap break-all">
+                            <span class="shrink-0 text-gray-500">
+                                {entry.timestamp.format("%H:%M:%S%.3f").to_string()}
+                            </span>
+                            <span class=format!(
+                                "shrink-0 w-12 {}",
+                                level_class(entry.level),
+                            )>{entry.level.to_string()}</span>
+                            <span class="shrink-0 text-gray-500">{entry.target}</span>
+                            <span class="text-gray-200">{entry.message}</span>
+                        </div>
+                    </For>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+fn level_class(level: Level) -> &'static str {
+    match level {
+        Level::Error => "text-red-400",
+        Level::Warn => "text-yellow-400",
+        Level::Info => "text-blue-400",
+        Level::Debug => "text-gray-300",
+        Level::Trace => "text-gray-500",
+    }
+}
@@ -0,0 +1,179 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::panic;
+
+use leptos::*;
+
+use crate::components::{form::button::Button, icon::IconExclamationTriangle, Color};
+
+// Catches errors returned while rendering a page, the navigation around it keeps working
+// so the user can move on to another page
+#[component]
+pub fn PageErrorBoundary(children: Children) -> impl IntoView {
+    view! {
+        <ErrorBoundary fallback=|errors| {
+            view! { <ErrorCard errors/> }
+        }>{children()}</ErrorBoundary>
+    }
+}
+
+#[component]
+fn ErrorCard(errors: RwSignal<Errors>) -> impl IntoView {
+    let details = create_memo(move |_| {
+        errors.with(|errors| {
+            errors
+                .iter()
+                .map(|(_, error)| error.to_string())
+                .collect::<Vec<_>>()
+        })
+    });
+    create_effect(move |_| {
+        for error in details.get() {
+            log::error!("Failed to render page: {error}");
+        }
+    });
+
+    view! {
+        <div class="max-w-2xl px-4 py-10 sm:px-6 lg:px-8 lg:py-14 mx-auto">
+            <div
+                class="bg-white rounded-xl shadow p-4 sm:p-7 dark:bg-slate-900"
+                role="alert"
+            >
+                <div class="flex gap-x-3">
+                    <IconExclamationTriangle attr:class="flex-shrink-0 size-5 mt-0.5 text-red-500"/>
+                    <div class="grow">
+                        <h2 class="text-lg font-semibold text-gray-800 dark:text-gray-200">
+                            Something went wrong
+                        </h2>
+                        <p class="mt-1 text-sm text-gray-600 dark:text-gray-400">
+                            This page could not be displayed. Reload it to try again, or pick another page from the menu.
+                        </p>
+                        <details class="mt-3 text-sm text-gray-600 dark:text-gray-400">
+                            <summary class="cursor-pointer">Error details</summary>
+                            <ul class="mt-2 list-disc space-y-1 ps-5 font-mono text-xs break-all">
+                                {move || {
+                                    details
+                                        .get()
+                                        .into_iter()
+                                        .map(|error| view! { <li>{error}</li> })
+                                        .collect_view()
+                                }}
+
+                            </ul>
+                        </details>
+                        <div class="mt-5 flex gap-x-2">
+                            <Button
+                                text="Reload"
+                                color=Color::Blue
+                                on_click=move |_| {
+                                    let _ = window().location().reload();
+                                }
+                            />
+                            <Button
+                                text="Go back"
+                                color=Color::Gray
+                                on_click=move |_| {
+                                    if let Ok(history) = window().history() {
+                                        let _ = history.back();
+                                    }
+                                }
+                            />
+
+                        </div>
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+// A panic leaves the WASM module unusable, so instead of a blank page a static notice
+// is added to the document with a way to reload
+pub fn init_panic_handler() {
+    panic::set_hook(Box::new(|info| {
+        console_error_panic_hook::hook(info);
+        log::error!("Unrecoverable error: {info}");
+        let _ = show_crash_notice(&info.to_string());
+    }));
+}
+
+fn show_crash_notice(details: &str) -> Option<()> {
+    let document = document();
+    let element = |tag: &str, class: &str, text: &str| {
+        let element = document.create_element(tag).ok()?;
+        element.set_class_name(class);
+        if !text.is_empty() {
+            element.set_text_content(Some(text));
+        }
+        Some(element)
+    };
+
+    let overlay = element(
+        "div",
+        "fixed inset-0 z-[100] flex items-center justify-center p-4 bg-gray-900/50",
+        "",
+    )?;
+    let card = element(
+        "div",
+        "max-w-lg w-full bg-white rounded-xl shadow p-4 sm:p-7 dark:bg-slate-900",
+        "",
+    )?;
+    let title = element(
+        "h2",
+        "text-lg font-semibold text-gray-800 dark:text-gray-200",
+        "Something went wrong",
+    )?;
+    let message = element(
+        "p",
+        "mt-1 text-sm text-gray-600 dark:text-gray-400",
+        "The web admin stopped working because of an unexpected error. Reload the page to continue.",
+    )?;
+    let collapsible = element(
+        "details",
+        "mt-3 text-sm text-gray-600 dark:text-gray-400",
+        "",
+    )?;
+    let summary = element("summary", "cursor-pointer", "Error details")?;
+    let detail = element(
+        "pre",
+        "mt-2 whitespace-pre-wrap break-all font-mono text-xs",
+        details,
+    )?;
+    let reload = element(
+        "button",
+        "mt-5 py-2 px-3 inline-flex items-center text-sm font-semibold rounded-lg border border-transparent bg-blue-600 text-white hover:bg-blue-700",
+        "Reload",
+    )?;
+
+    overlay.set_attribute("role", "alertdialog").ok()?;
+    reload.set_attribute("type", "button").ok()?;
+    // The WASM module can no longer run event handlers
+    reload.set_attribute("onclick", "location.reload()").ok()?;
+    collapsible.append_with_node_2(&summary, &detail).ok()?;
+    card.append_with_node_4(&title, &message, &collapsible, &reload)
+        .ok()?;
+    overlay.append_child(&card).ok()?;
+    document.body()?.append_child(&overlay).ok()?;
+    Some(())
+}
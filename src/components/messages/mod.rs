@@ -22,4 +22,7 @@
 */
 
 pub mod alert;
+pub mod banner;
+pub mod console;
+pub mod error;
 pub mod modal;
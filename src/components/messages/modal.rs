@@ -36,9 +36,24 @@ pub struct Modal {
     pub message: String,
     pub button_text: String,
     pub danger: bool,
+    pub checkbox: Option<ModalCheckbox>,
+    pub secondary: Option<ModalAction>,
+    pub countdown: Option<RwSignal<u64>>,
     pub on_confirm: Arc<dyn Fn()>,
 }
 
+#[derive(Clone)]
+pub struct ModalAction {
+    pub label: String,
+    pub on_click: Arc<dyn Fn()>,
+}
+
+#[derive(Clone)]
+pub struct ModalCheckbox {
+    pub label: String,
+    pub checked: RwSignal<bool>,
+}
+
 pub fn init_modals() {
     provide_context(create_rw_signal(Modal::default()));
 }
@@ -111,6 +126,48 @@ pub fn Modal() -> impl IntoView {
                                     <p class="text-gray-800 dark:text-gray-400">
                                         {move || { modal.get().message }}
                                     </p>
+                                    {move || {
+                                        modal
+                                            .get()
+                                            .countdown
+                                            .map(|remaining| {
+                                                view! {
+                                                    <p
+                                                        class="mt-2 text-sm font-semibold text-gray-800 dark:text-gray-200"
+                                                        role="timer"
+                                                    >
+                                                        {move || {
+                                                            format!("{} seconds remaining", remaining.get())
+                                                        }}
+
+                                                    </p>
+                                                }
+                                            })
+                                    }}
+
+                                    {move || {
+                                        modal
+                                            .get()
+                                            .checkbox
+                                            .map(|checkbox| {
+                                                let checked = checkbox.checked;
+                                                view! {
+                                                    <label class="flex items-center gap-x-3 mt-4 text-sm text-gray-800 dark:text-gray-400">
+                                                        <input
+                                                            type="checkbox"
+                                                            class="shrink-0 border-gray-200 rounded text-blue-600 focus:ring-blue-500 dark:bg-gray-800 dark:border-gray-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
+                                                            prop:checked=checked
+                                                            on:input=move |_| {
+                                                                checked.update(|v| *v = !*v);
+                                                            }
+                                                        />
+
+                                                        {checkbox.label}
+                                                    </label>
+                                                }
+                                            })
+                                    }}
+
                                 </div>
                                 <div class="flex justify-end items-center gap-x-2 py-3 px-4 border-t dark:border-gray-700">
                                     <button
@@ -126,6 +183,32 @@ pub fn Modal() -> impl IntoView {
 
                                         Close
                                     </button>
+                                    {move || {
+                                        modal
+                                            .get()
+                                            .secondary
+                                            .map(|action| {
+                                                view! {
+                                                    <button
+                                                        type="button"
+                                                        class="py-3 px-4 inline-flex items-center gap-x-2 text-sm font-semibold rounded-lg border border-gray-200 bg-white text-gray-800 shadow-sm hover:bg-gray-50 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-white dark:hover:bg-gray-800 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                                                        on:click=move |_| {
+                                                            (action.on_click)();
+                                                            modal
+                                                                .update(|modal| {
+                                                                    modal.is_open = false;
+                                                                });
+                                                        }
+
+                                                        disabled=move || processing.get()
+                                                    >
+
+                                                        {action.label.clone()}
+                                                    </button>
+                                                }
+                                            })
+                                    }}
+
                                     <button
                                         type="button"
                                         class=move || {
@@ -169,6 +252,9 @@ impl Modal {
             message: String::new(),
             button_text: "Confirm".to_string(),
             danger: false,
+            checkbox: None,
+            secondary: None,
+            countdown: None,
             on_confirm: Arc::new(|| {}),
         }
     }
@@ -182,6 +268,32 @@ impl Modal {
         self
     }
 
+    pub fn with_checkbox(mut self, label: impl Into<String>, checked: RwSignal<bool>) -> Self {
+        self.checkbox = Some(ModalCheckbox {
+            label: label.into(),
+            checked,
+        });
+        self
+    }
+
+    pub fn with_secondary_button(
+        mut self,
+        label: impl Into<String>,
+        on_click: impl Fn() + 'static,
+    ) -> Self {
+        self.secondary = Some(ModalAction {
+            label: label.into(),
+            on_click: Arc::new(on_click),
+        });
+        self
+    }
+
+    // Seconds left before the action stops being available, driven by the caller
+    pub fn with_countdown(mut self, remaining: RwSignal<u64>) -> Self {
+        self.countdown = Some(remaining);
+        self
+    }
+
     pub fn with_dangerous_callback(mut self, on_confirm: impl Fn() + 'static) -> Self {
         self.danger = true;
         self.on_confirm = Arc::new(on_confirm);
@@ -203,6 +315,9 @@ impl Default for Modal {
             message: String::new(),
             button_text: "Confirm".to_string(),
             danger: false,
+            checkbox: None,
+            secondary: None,
+            countdown: None,
             on_confirm: Arc::new(|| {}),
         }
     }
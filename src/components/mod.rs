@@ -23,13 +23,24 @@
 
 pub mod badge;
 pub mod card;
+pub mod chart;
 pub mod form;
+pub mod highlight;
 pub mod icon;
 pub mod layout;
 pub mod list;
 pub mod messages;
+pub mod progress;
+pub mod qr;
 pub mod report;
 pub mod skeleton;
+pub mod tooltip;
+
+use std::cell::Cell;
+
+thread_local! {
+    static NEXT_ELEMENT_ID: Cell<usize> = const { Cell::new(0) };
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Color {
@@ -39,3 +50,12 @@ pub enum Color {
     Yellow,
     Green,
 }
+
+// Unique DOM id for tying labels and descriptions to a control, e.g. "checkbox-3"
+pub fn next_element_id(prefix: &str) -> String {
+    NEXT_ELEMENT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        format!("{prefix}-{id}")
+    })
+}
@@ -0,0 +1,93 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+
+use crate::core::loading::use_loading_indicator;
+
+// Shows the completed percentage when `value` is set, otherwise an indeterminate
+// animation that falls back to a static bar when reduced motion is requested
+#[component]
+pub fn ProgressBar(
+    #[prop(optional, into)] value: Option<MaybeSignal<f64>>,
+    #[prop(optional, into)] label: Option<MaybeSignal<String>>,
+) -> impl IntoView {
+    let percent = move || value.as_ref().map(|value| value.get().clamp(0.0, 100.0));
+    let label_ = label.clone();
+
+    view! {
+        <div class="w-full">
+            {label
+                .map(|label| {
+                    view! {
+                        <div class="mb-2 text-sm text-gray-800 dark:text-white">
+                            {move || label.get()}
+                        </div>
+                    }
+                })}
+            <div
+                class="relative flex w-full h-2 bg-gray-200 rounded-full overflow-hidden dark:bg-gray-700"
+                role="progressbar"
+                aria-label=move || label_.as_ref().map(|label| label.get())
+                aria-valuemin="0"
+                aria-valuemax="100"
+                aria-valuenow=move || percent().map(|percent| format!("{percent:.0}"))
+            >
+                {move || match percent() {
+                    Some(percent) => {
+                        view! {
+                            <div
+                                class="flex flex-col justify-center rounded-full overflow-hidden bg-blue-600 transition-[width] duration-500 motion-reduce:transition-none dark:bg-blue-500"
+                                style=format!("width: {percent}%")
+                            ></div>
+                        }
+                    }
+                    None => {
+                        view! {
+                            <div class="absolute inset-y-0 w-2/5 rounded-full bg-blue-600 animate-progress motion-reduce:animate-none motion-reduce:w-full motion-reduce:opacity-50 dark:bg-blue-500"></div>
+                        }
+                    }
+                }}
+
+            </div>
+        </div>
+    }
+}
+
+// Thin bar along the top of the page shown while routes load or requests are in flight
+#[component]
+pub fn LoadingBar() -> impl IntoView {
+    let indicator = use_loading_indicator();
+
+    view! {
+        <Show when=move || indicator.is_loading()>
+            <div
+                class="fixed top-0 inset-x-0 z-[90] h-0.5 overflow-hidden pointer-events-none"
+                role="progressbar"
+                aria-label="Loading"
+            >
+                <div class="absolute inset-y-0 w-2/5 bg-blue-600 animate-progress motion-reduce:animate-none motion-reduce:w-full motion-reduce:opacity-50 dark:bg-blue-500"></div>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,58 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+use qrcode::{render::svg, EcLevel, QrCode as Encoder};
+
+#[component]
+pub fn QrCode(
+    #[prop(into)] data: MaybeSignal<String>,
+    // Announced instead of the image, the encoded data is usually meaningless when read out
+    #[prop(into)] label: MaybeSignal<String>,
+    #[prop(default = 200)] size: u32,
+) -> impl IntoView {
+    let image = create_memo(move |_| {
+        data.with(|data| {
+            Encoder::with_error_correction_level(data.as_bytes(), EcLevel::M)
+                .map(|code| {
+                    code.render::<svg::Color>()
+                        .min_dimensions(size, size)
+                        .quiet_zone(true)
+                        .dark_color(svg::Color("#000000"))
+                        .light_color(svg::Color("#ffffff"))
+                        .build()
+                })
+                .map_err(|err| log::warn!("Failed to encode QR code: {err}"))
+                .ok()
+        })
+    });
+
+    view! {
+        <div
+            role="img"
+            aria-label=move || label.get()
+            class="inline-block rounded-lg border border-gray-200 bg-white p-2 dark:border-gray-700"
+            inner_html=move || image.get().unwrap_or_default()
+        ></div>
+    }
+}
@@ -21,7 +21,31 @@
  * for more details.
 */
 
-use leptos::*;
+use std::{net::IpAddr, time::Duration};
+
+use ahash::AHashSet;
+use leptos::{html::Div, *};
+use leptos_router::use_navigate;
+
+use serde_json::Value;
+
+use crate::{
+    core::{clipboard::copy_to_clipboard, download::download_file, rdns::use_reverse_dns},
+    pages::maybe_plural,
+};
+
+use super::{form::button::Button, tooltip::Tooltip, Color};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReportNavigation {
+    pub previous: Option<String>,
+    pub next: Option<String>,
+}
+
+// Anchors of the sections currently rendered, so repeated titles get numbered suffixes.
+// Sections release theirs on cleanup, a section rendered again gets the same anchor back.
+#[derive(Clone, Copy)]
+struct SectionIds(StoredValue<AHashSet<String>>);
 
 #[component]
 pub fn ReportView(
@@ -29,6 +53,8 @@ pub fn ReportView(
     #[prop(into, optional)] hide: MaybeSignal<bool>,
     children: Children,
 ) -> impl IntoView {
+    provide_context(SectionIds(store_value(AHashSet::new())));
+
     view! {
         <div
             {..attrs}
@@ -50,14 +76,70 @@ pub fn ReportSection(
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
     children: Children,
 ) -> impl IntoView {
+    let slug = slugify(&title);
+    let id = match use_context::<SectionIds>() {
+        Some(SectionIds(ids)) => {
+            let id = ids
+                .try_update_value(|ids| {
+                    let id = unique_section_id(&slug, ids);
+                    ids.insert(id.clone());
+                    id
+                })
+                .unwrap_or(slug);
+            let id_ = id.clone();
+            on_cleanup(move || {
+                ids.try_update_value(|ids| ids.remove(&id_));
+            });
+            id
+        }
+        None => slug,
+    };
+    let copied = create_rw_signal(false);
+    let section = create_node_ref::<Div>();
+
+    // Scrolls to the section named in the URL fragment once it has been rendered
+    let id_ = id.clone();
+    section.on_load(move |section| {
+        if window().location().hash().ok().as_deref() == Some(format!("#{id_}").as_str()) {
+            request_animation_frame(move || section.scroll_into_view());
+        }
+    });
+
+    let id_ = id.clone();
+    let copy_link = move |_| {
+        let location = window().location();
+        let Ok(href) = location.href() else {
+            return;
+        };
+        let base = href.split_once('#').map_or(href.as_str(), |(base, _)| base);
+        let link = format!("{base}#{id_}");
+        spawn_local(async move {
+            if copy_to_clipboard(&link).await {
+                copied.set(true);
+                set_timeout(move || copied.set(false), Duration::from_secs(2));
+            }
+        });
+    };
+
     view! {
         <div
             {..attrs}
+            id=id
+            node_ref=section
             class="grid sm:grid-cols-12 gap-2 sm:gap-4 py-8 first:pt-0 last:pb-0 border-t first:border-transparent border-gray-200 dark:border-gray-700 dark:first:border-transparent"
         >
 
-            <div class="sm:col-span-12">
+            <div class="sm:col-span-12 group flex items-center gap-x-2">
                 <h2 class="text-lg font-semibold text-gray-800 dark:text-gray-200">{title}</h2>
+                <button
+                    type="button"
+                    class="text-sm font-semibold text-gray-400 opacity-0 group-hover:opacity-100 focus:opacity-100 hover:text-blue-600 dark:text-gray-500 dark:hover:text-blue-500"
+                    class:opacity-100=move || copied.get()
+                    aria-label="Copy link to section"
+                    on:click=copy_link
+                >
+                    {move || if copied.get() { "Link copied" } else { "#" }}
+                </button>
             </div>
 
             {children()}
@@ -84,11 +166,295 @@ pub fn ReportItem(
     }
 }
 
+// Values past this many bytes are never rendered in full, whatever `truncate_at` says
+const MAX_TEXT_VALUE_LEN: usize = 16 * 1024;
+
 #[component]
-pub fn ReportTextValue(#[prop(into)] value: MaybeSignal<String>) -> impl IntoView {
+pub fn ReportTextValue(
+    #[prop(into)] value: MaybeSignal<String>,
+    #[prop(optional)] truncate_at: Option<usize>,
+) -> impl IntoView {
+    let value_ = value.clone();
+    let is_too_large = create_memo(move |_| value_.with(|value| value.len() > MAX_TEXT_VALUE_LEN));
+    // Without a limit only values past the cap get truncated, which is decided reactively
+    // as the value may grow after mount
+    let has_limit = truncate_at.is_some();
+    let truncate_at = truncate_at.unwrap_or(MAX_TEXT_VALUE_LEN);
+
+    let expanded = create_rw_signal(false);
+    let value_ = value.clone();
+    let is_long = create_memo(move |_| {
+        value_.with(|value| is_too_large.get() || value.chars().nth(truncate_at).is_some())
+    });
+    // Built once per value so toggling never re-walks or lays out the full text
+    let value_ = value.clone();
+    let display = create_memo(move |_| {
+        value_.with(|value| {
+            let limit = if expanded.get() {
+                MAX_TEXT_VALUE_LEN
+            } else {
+                truncate_at.min(MAX_TEXT_VALUE_LEN)
+            };
+            match value.char_indices().nth(limit) {
+                Some((end, _)) => format!("{}…", &value[..end]),
+                None if value.len() > MAX_TEXT_VALUE_LEN => {
+                    let mut end = MAX_TEXT_VALUE_LEN;
+                    while !value.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    format!("{}…", &value[..end])
+                }
+                None => value.clone(),
+            }
+        })
+    });
+    let value_ = value.clone();
+
+    view! {
+        <label
+            class="inline-block text-sm font-semibold text-gray-500 mt-2.5"
+            class:break-all=move || has_limit || is_long.get()
+        >
+            {move || display.get()}
+        </label>
+        <Show when=move || is_long.get()>
+            <Show when=move || { truncate_at < MAX_TEXT_VALUE_LEN }>
+                <button
+                    type="button"
+                    class="ms-2 text-xs font-semibold text-blue-600 hover:underline dark:text-blue-500"
+                    on:click=move |_| expanded.update(|v| *v = !*v)
+                >
+                    {move || if expanded.get() { "show less" } else { "show more" }}
+                </button>
+            </Show>
+            <Show when=move || is_too_large.get()>
+                <button
+                    type="button"
+                    class="ms-2 text-xs font-semibold text-blue-600 hover:underline dark:text-blue-500"
+                    on:click={
+                        let value = value_.clone();
+                        move |_| {
+                            value.with_untracked(|value| download_file("value.txt", "text/plain", value));
+                        }
+                    }
+                >
+
+                    value too large, download to view full
+                </button>
+            </Show>
+            <button
+                type="button"
+                class="ms-2 text-xs font-semibold text-blue-600 hover:underline dark:text-blue-500"
+                on:click={
+                    let value = value_.clone();
+                    move |_| {
+                        let value = value.get_untracked();
+                        spawn_local(async move {
+                            copy_to_clipboard(&value).await;
+                        });
+                    }
+                }
+            >
+
+                copy
+            </button>
+        </Show>
+    }
+    .into_view()
+}
+
+#[component]
+pub fn ReportLabelValue(
+    #[prop(into)] label: String,
+    #[prop(into)] description: String,
+) -> impl IntoView {
+    view! {
+        <Tooltip text=description>
+            <label class="inline-block text-sm font-semibold text-gray-500 mt-2.5 cursor-help">
+                {label}
+            </label>
+        </Tooltip>
+    }
+}
+
+#[component]
+pub fn ReportIpValue(ip: IpAddr) -> impl IntoView {
     view! {
         <label class="inline-block text-sm font-semibold text-gray-500 mt-2.5">
-            {move || value.get()}
+            {ip.to_string()} <ReverseDnsName ip=ip/>
         </label>
     }
 }
+
+#[component]
+pub fn ReverseDnsName(ip: IpAddr) -> impl IntoView {
+    let name = use_reverse_dns().resolve(ip);
+
+    move || {
+        name.get().map(|name| {
+            view! { <span class="block text-xs font-normal text-gray-400">{name}</span> }
+        })
+    }
+}
+
+#[component]
+pub fn ReportExtraValue(value: String) -> impl IntoView {
+    let trimmed = value.trim();
+    let json = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        serde_json::from_str::<Value>(trimmed).ok()
+    } else {
+        None
+    };
+
+    match json {
+        Some(json) => view! { <ReportJsonValue value=json/> }.into_view(),
+        None => view! { <ReportTextValue value=value/> }.into_view(),
+    }
+}
+
+#[component]
+pub fn ReportJsonValue(value: Value) -> impl IntoView {
+    view! {
+        <div class="mt-2.5 p-3 overflow-x-auto text-xs font-mono bg-gray-50 border border-gray-200 rounded-lg dark:bg-slate-800 dark:border-gray-700">
+            {json_node(None, value, 0)}
+        </div>
+    }
+}
+
+fn json_node(key: Option<String>, value: Value, depth: usize) -> View {
+    let key = key.map(|key| {
+        view! {
+            <span class="text-gray-800 dark:text-gray-200">{format!("\"{key}\"")}</span>
+            <span class="text-gray-500">": "</span>
+        }
+    });
+    let ((open, close, len), children) = match value {
+        Value::Object(map) => (
+            ("{", "}", map.len()),
+            map.into_iter()
+                .map(|(key, value)| json_node(Some(key), value, depth + 1))
+                .collect_view(),
+        ),
+        Value::Array(items) => (
+            ("[", "]", items.len()),
+            items
+                .into_iter()
+                .map(|value| json_node(None, value, depth + 1))
+                .collect_view(),
+        ),
+        value => {
+            let (class, text) = match &value {
+                Value::String(text) => (
+                    "text-teal-700 dark:text-teal-500 break-all",
+                    format!("{:?}", text),
+                ),
+                Value::Number(number) => ("text-blue-600 dark:text-blue-500", number.to_string()),
+                _ => ("text-purple-600 dark:text-purple-400", value.to_string()),
+            };
+            return view! {
+                <div class="ps-4">{key} <span class=class>{text}</span></div>
+            }
+            .into_view();
+        }
+    };
+
+    view! {
+        <details class="ps-4" open=depth < 2>
+            <summary class="cursor-pointer -ms-4">
+                {key}
+                <span class="text-gray-500">
+                    {format!("{open} {} {close}", maybe_plural(len, "item", "items"))}
+                </span>
+            </summary>
+            {children}
+        </details>
+    }
+    .into_view()
+}
+
+#[component]
+pub fn ReportNavigationButtons(navigation: ReportNavigation) -> impl IntoView {
+    let has_previous = navigation.previous.is_some();
+    let has_next = navigation.next.is_some();
+    let previous = navigation.previous.unwrap_or_default();
+    let next = navigation.next.unwrap_or_default();
+
+    view! {
+        <Button
+            text="Previous"
+            color=Color::Gray
+            disabled=!has_previous
+            on_click=move |_| {
+                use_navigate()(&previous, Default::default());
+            }
+        />
+        <Button
+            text="Next"
+            color=Color::Gray
+            disabled=!has_next
+            on_click=move |_| {
+                use_navigate()(&next, Default::default());
+            }
+        />
+    }
+}
+
+// The first section with a title keeps the plain slug, later ones are numbered from 2
+fn unique_section_id(slug: &str, taken: &AHashSet<String>) -> String {
+    if !taken.contains(slug) {
+        return slug.to_string();
+    }
+    (2..)
+        .map(|count| format!("{slug}-{count}"))
+        .find(|id| !taken.contains(id))
+        .unwrap_or_default()
+}
+
+// Lowercase words joined by dashes, e.g. "Report Details" becomes "report-details"
+fn slugify(title: &str) -> String {
+    title
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use ahash::AHashSet;
+
+    use super::{slugify, unique_section_id};
+
+    #[test]
+    fn slugify_titles() {
+        for (title, expected) in [
+            ("Report Details", "report-details"),
+            ("DKIM Results", "dkim-results"),
+            ("  Source / IP address: ", "source-ip-address"),
+            ("Policy (p=reject)", "policy-p-reject"),
+            ("Überprüfung 2", "überprüfung-2"),
+            ("---", ""),
+            ("", ""),
+        ] {
+            assert_eq!(slugify(title), expected, "{title:?}");
+        }
+    }
+
+    #[test]
+    fn unique_section_ids() {
+        let mut taken = AHashSet::new();
+        for expected in ["record", "record-2", "record-3"] {
+            let id = unique_section_id("record", &taken);
+            assert_eq!(id, expected);
+            taken.insert(id);
+        }
+
+        // A section rendered again after releasing its anchor gets the same one back
+        taken.remove("record-2");
+        assert_eq!(unique_section_id("record", &taken), "record-2");
+        taken.remove("record");
+        assert_eq!(unique_section_id("record", &taken), "record");
+        assert_eq!(unique_section_id("details", &taken), "details");
+    }
+}
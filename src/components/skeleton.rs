@@ -23,10 +23,14 @@
 
 use leptos::*;
 
+use crate::core::a11y::use_reduced_motion;
+
 #[component]
 pub fn Skeleton() -> impl IntoView {
+    let reduced_motion = use_reduced_motion();
+
     view! {
-        <div class="flex animate-pulse">
+        <div class="flex" class:animate-pulse=move || !reduced_motion.get()>
             <div class="ms-4 mt-2 w-full">
                 <h3 class="h-4 bg-gray-200 rounded-full dark:bg-gray-700" style="width: 40%;"></h3>
 
@@ -0,0 +1,173 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::{
+    html::{Div, Span},
+    wasm_bindgen::{JsCast, JsValue},
+    *,
+};
+
+use super::next_element_id;
+
+const VIEWPORT_MARGIN: f64 = 8.0;
+const ANCHOR_GAP: f64 = 6.0;
+
+#[component]
+pub fn Tooltip(#[prop(into)] text: MaybeSignal<String>, children: Children) -> impl IntoView {
+    let id = next_element_id("tooltip");
+    let is_open = create_rw_signal(false);
+    let is_touch = store_value(false);
+    let anchor = create_node_ref::<Span>();
+    let content = create_node_ref::<Div>();
+    let position = create_rw_signal(None::<(f64, f64)>);
+    let text = Signal::derive(move || text.get());
+
+    // Describes the wrapped control, the wrapper itself takes focus when there is none
+    let id_ = id.clone();
+    anchor.on_load(move |anchor| {
+        let target = anchor
+            .first_element_child()
+            .filter(|child| {
+                child
+                    .matches("a[href], button, input, select, textarea, [tabindex]")
+                    .unwrap_or_default()
+            })
+            .unwrap_or_else(|| {
+                let _ = anchor.set_attribute("tabindex", "0");
+                anchor.unchecked_ref::<web_sys::Element>().clone()
+            });
+        let _ = target.set_attribute("aria-describedby", &id_);
+    });
+
+    // Measured once rendered so the tooltip can flip below the anchor or shift sideways
+    // when it would otherwise leave the viewport
+    create_effect(move |_| {
+        if !is_open.get() {
+            position.set(None);
+            return;
+        }
+        request_animation_frame(move || {
+            let (Some(anchor), Some(content)) = (anchor.get_untracked(), content.get_untracked())
+            else {
+                return;
+            };
+            let target = anchor.get_bounding_client_rect();
+            let tooltip = content.get_bounding_client_rect();
+            let viewport = |size: Result<JsValue, JsValue>| {
+                size.ok().and_then(|size| size.as_f64()).unwrap_or_default()
+            };
+            let width = viewport(window().inner_width());
+            let height = viewport(window().inner_height());
+
+            let above = target.top() - tooltip.height() - ANCHOR_GAP;
+            let below = target.bottom() + ANCHOR_GAP;
+            let top = if above >= VIEWPORT_MARGIN
+                || below + tooltip.height() > height - VIEWPORT_MARGIN
+            {
+                above
+            } else {
+                below
+            };
+            let left = (target.left() + (target.width() - tooltip.width()) / 2.0)
+                .min(width - tooltip.width() - VIEWPORT_MARGIN)
+                .max(VIEWPORT_MARGIN);
+            position.set(Some((top, left)));
+        });
+    });
+
+    let keydown_handle = window_event_listener(ev::keydown, move |ev| {
+        is_touch.set_value(false);
+        if ev.key() == "Escape" && is_open.get_untracked() {
+            is_open.set(false);
+        }
+    });
+    // Taps outside an open tooltip dismiss it on touch devices
+    let click_handle = window_event_listener(ev::click, move |ev| {
+        if !is_open.get_untracked() {
+            return;
+        }
+        let is_inside = anchor.get_untracked().is_some_and(|anchor| {
+            ev.target()
+                .and_then(|target| target.dyn_into::<web_sys::Node>().ok())
+                .is_some_and(|target| anchor.contains(Some(&target)))
+        });
+        if !is_inside {
+            is_open.set(false);
+        }
+    });
+    on_cleanup(move || {
+        keydown_handle.remove();
+        click_handle.remove();
+    });
+
+    view! {
+        <span
+            node_ref=anchor
+            class="inline-flex"
+            on:pointerdown=move |ev| is_touch.set_value(ev.pointer_type() == "touch")
+            on:pointerenter=move |ev| {
+                if ev.pointer_type() != "touch" {
+                    is_open.set(true);
+                }
+            }
+
+            on:pointerleave=move |ev| {
+                if ev.pointer_type() != "touch" {
+                    is_open.set(false);
+                }
+            }
+
+            on:click=move |_| {
+                if is_touch.get_value() {
+                    is_open.update(|open| *open = !*open);
+                }
+            }
+
+            on:focusin=move |_| {
+                if !is_touch.get_value() {
+                    is_open.set(true);
+                }
+            }
+
+            on:focusout=move |_| is_open.set(false)
+        >
+            {children()}
+        </span>
+        <Show when=move || is_open.get()>
+            <div
+                node_ref=content
+                id=id.clone()
+                role="tooltip"
+                class="fixed z-[80] max-w-xs py-1.5 px-2.5 rounded-lg bg-gray-900 text-xs font-medium text-white shadow-sm pointer-events-none dark:bg-slate-700"
+                class:invisible=move || position.get().is_none()
+                style=move || {
+                    let (top, left) = position.get().unwrap_or_default();
+                    format!("top: {top}px; left: {left}px;")
+                }
+            >
+
+                {move || text.get()}
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,53 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+use leptos_use::use_media_query;
+
+use super::prefs::use_preferences;
+
+pub const HIGH_CONTRAST_OPTIONS: &[(&str, &str)] =
+    &[("", "System default"), ("true", "On"), ("false", "Off")];
+
+pub fn use_reduced_motion() -> Signal<bool> {
+    use_media_query("(prefers-reduced-motion: reduce)")
+}
+
+pub fn init_high_contrast() {
+    let prefs = use_preferences();
+    let system = use_media_query("(prefers-contrast: more)");
+
+    create_effect(move |_| {
+        let enabled = prefs
+            .with(|prefs| prefs.high_contrast)
+            .unwrap_or_else(|| system.get());
+        if let Some(root) = document().document_element() {
+            if let Err(err) = root
+                .class_list()
+                .toggle_with_force("high-contrast", enabled)
+            {
+                log::error!("Failed to toggle high contrast mode: {err:?}");
+            }
+        }
+    });
+}
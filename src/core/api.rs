@@ -0,0 +1,145 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::sync::Arc;
+
+use gloo_net::http::Method;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::pages::List;
+
+use super::{
+    http::{HttpRequest, IntoUrlBuilder},
+    oauth::AuthToken,
+    resource::ListQuery,
+    secret::SecretString,
+};
+
+pub use super::http::Error as ApiError;
+
+pub type ApiResult<T> = std::result::Result<T, ApiError>;
+
+#[derive(Clone, Debug)]
+pub struct ApiClient {
+    auth: AuthToken,
+    credentials: Option<(String, SecretString)>,
+}
+
+impl ApiClient {
+    pub fn new(auth: impl AsRef<AuthToken>) -> Self {
+        Self {
+            auth: auth.as_ref().clone(),
+            credentials: None,
+        }
+    }
+
+    pub fn with_credentials(
+        base_url: impl Into<String>,
+        username: impl Into<String>,
+        password: impl Into<SecretString>,
+    ) -> Self {
+        Self {
+            auth: AuthToken {
+                base_url: Arc::new(base_url.into()),
+                ..Default::default()
+            },
+            credentials: Some((username.into(), password.into())),
+        }
+    }
+
+    pub fn request(&self, method: Method, url: impl IntoUrlBuilder) -> HttpRequest {
+        let request = HttpRequest::new(method, url);
+        match &self.credentials {
+            Some((username, password)) => request
+                .with_base_url(&self.auth)
+                .with_basic_authorization(username, password),
+            None => request.with_authorization(&self.auth),
+        }
+    }
+
+    pub fn curl<B>(
+        &self,
+        method: Method,
+        url: impl IntoUrlBuilder,
+        body: Option<B>,
+    ) -> ApiResult<String>
+    where
+        B: Serialize,
+    {
+        let request = self.request(method, url);
+        match body {
+            Some(body) => request.with_body(body).map(|request| request.to_curl()),
+            None => Ok(request.to_curl()),
+        }
+    }
+
+    pub async fn get<T>(&self, url: impl IntoUrlBuilder) -> ApiResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.request(Method::GET, url).send::<T>().await
+    }
+
+    pub async fn list<T>(&self, url: impl IntoUrlBuilder, query: &ListQuery) -> ApiResult<List<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let mut request = self
+            .request(Method::GET, url)
+            .with_parameter("page", query.page.to_string())
+            .with_parameter("limit", query.limit.to_string());
+        for (key, value) in &query.params {
+            request = request.with_parameter(key, value.as_str());
+        }
+        request.send::<List<T>>().await
+    }
+
+    pub async fn create<B, T>(&self, url: impl IntoUrlBuilder, body: B) -> ApiResult<T>
+    where
+        B: Serialize,
+        T: DeserializeOwned,
+    {
+        self.request(Method::POST, url)
+            .with_body(body)?
+            .send::<T>()
+            .await
+    }
+
+    pub async fn update<B, T>(&self, url: impl IntoUrlBuilder, body: B) -> ApiResult<T>
+    where
+        B: Serialize,
+        T: DeserializeOwned,
+    {
+        self.request(Method::PATCH, url)
+            .with_body(body)?
+            .send::<T>()
+            .await
+    }
+
+    pub async fn delete<T>(&self, url: impl IntoUrlBuilder) -> ApiResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.request(Method::DELETE, url).send::<T>().await
+    }
+}
@@ -0,0 +1,49 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::window;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::{Function, Promise, Reflect},
+    wasm_bindgen::{JsCast, JsValue},
+};
+
+// Calls navigator.clipboard.writeText, which web-sys only exposes behind unstable APIs.
+// The returned promise rejects when the page lacks focus or permission, so it is awaited.
+pub async fn copy_to_clipboard(text: &str) -> bool {
+    let clipboard = match Reflect::get(&window().navigator(), &JsValue::from_str("clipboard")) {
+        Ok(clipboard) if !clipboard.is_undefined() => clipboard,
+        _ => return false,
+    };
+
+    let promise = Reflect::get(&clipboard, &JsValue::from_str("writeText"))
+        .ok()
+        .and_then(|write_text| write_text.dyn_into::<Function>().ok())
+        .and_then(|write_text| write_text.call1(&clipboard, &JsValue::from_str(text)).ok())
+        .and_then(|promise| promise.dyn_into::<Promise>().ok());
+
+    match promise {
+        Some(promise) => JsFuture::from(promise).await.is_ok(),
+        None => false,
+    }
+}
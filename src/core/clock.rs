@@ -0,0 +1,89 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use chrono::{DateTime, Duration, Utc};
+use chrono_humanize::{Accuracy, HumanTime, Tense};
+use leptos::{create_effect, create_memo, store_value, RwSignal, SignalGet, SignalWith};
+
+use crate::components::messages::alert::{use_alerts, Alert};
+
+use super::oauth::AuthToken;
+
+// Differences below this are ignored, token issue times only have second precision
+const MAX_CLOCK_SKEW_MS: i64 = 2 * 60 * 1000;
+
+static CLOCK_SKEW_MS: AtomicI64 = AtomicI64::new(0);
+
+// Current time according to the server clock, when the local clock is known to be skewed
+pub fn server_now() -> DateTime<Utc> {
+    Utc::now() + Duration::milliseconds(CLOCK_SKEW_MS.load(Ordering::Relaxed))
+}
+
+// Cross-origin responses hide the Date header, so the server clock is read from the issue
+// time of each new access token instead. Signing in and every refresh measure it again.
+pub fn init_clock_check(auth: RwSignal<AuthToken>) {
+    let alert = use_alerts();
+    let warned = store_value(false);
+    let issued_at = create_memo(move |_| {
+        auth.with(|auth| {
+            auth.claims
+                .as_ref()
+                .filter(|_| auth.is_valid)
+                .and_then(|claims| claims.issued_at)
+        })
+    });
+
+    create_effect(move |_| {
+        let Some(issued_at) = issued_at.get() else {
+            return;
+        };
+        let skew = clock_skew(issued_at, Utc::now());
+        if skew.abs() <= MAX_CLOCK_SKEW_MS {
+            CLOCK_SKEW_MS.store(0, Ordering::Relaxed);
+            return;
+        }
+
+        CLOCK_SKEW_MS.store(skew, Ordering::Relaxed);
+        if warned.get_value() {
+            return;
+        }
+        warned.set_value(true);
+        alert.set(
+            Alert::warning("Clock out of sync")
+                .with_details(format!(
+                    "This device's clock is {} the server clock by {}. Relative times are shown using the server clock.",
+                    if skew > 0 { "behind" } else { "ahead of" },
+                    HumanTime::from(Duration::milliseconds(skew.abs()))
+                        .to_text_en(Accuracy::Rough, Tense::Present)
+                ))
+                .without_timeout(),
+        );
+    });
+}
+
+// The token was issued moments before it reached the browser
+fn clock_skew(issued_at: i64, now: DateTime<Utc>) -> i64 {
+    issued_at * 1000 - now.timestamp_millis()
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::time::Duration;
+
+use leptos::{document, set_timeout};
+use web_sys::{
+    js_sys::Array,
+    wasm_bindgen::{JsCast, JsValue},
+    Blob, BlobPropertyBag, HtmlAnchorElement, Url,
+};
+
+// Saves `contents` through a temporary object URL, returns false if the browser refused
+pub fn download_file(filename: &str, content_type: &str, contents: &str) -> bool {
+    let parts = Array::of1(&JsValue::from_str(contents));
+    let mut options = BlobPropertyBag::new();
+    options.type_(content_type);
+    let Ok(url) = Blob::new_with_str_sequence_and_options(&parts, &options)
+        .and_then(|blob| Url::create_object_url_with_blob(&blob))
+    else {
+        return false;
+    };
+
+    let result = document()
+        .create_element("a")
+        .ok()
+        .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok())
+        .map(|anchor| {
+            anchor.set_href(&url);
+            anchor.set_download(filename);
+            anchor.click();
+        })
+        .is_some();
+    set_timeout(
+        move || {
+            let _ = Url::revoke_object_url(&url);
+        },
+        Duration::from_secs(1),
+    );
+    result
+}
@@ -23,12 +23,14 @@
 
 use std::borrow::Cow;
 use std::hash::{DefaultHasher, Hash, Hasher};
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
 use ahash::AHashMap;
+use chrono::NaiveDate;
+use humansize::{format_size, DECIMAL};
 use leptos::RwSignal;
 
 use crate::pages::config::{Settings, SettingsValues};
@@ -39,6 +41,10 @@ use super::expr::{Constant, ParseValue, Token};
 use super::schema::{NumberType, SchemaType, Type};
 
 use super::schema::{InputCheck, Schema, Transformer, Validator};
+use super::secret::SecretString;
+use super::sieve::check_script;
+
+const MAX_FIELD_HISTORY: usize = 50;
 
 pub type ExternalSources = AHashMap<String, Vec<(String, String)>>;
 
@@ -49,6 +55,21 @@ pub struct FormData {
     pub external_sources: Arc<ExternalSources>,
     pub schema: Arc<Schema>,
     pub is_update: bool,
+    pub files: AHashMap<String, FileInfo>,
+    pub history: AHashMap<String, FieldHistory>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FieldHistory {
+    undo: Vec<Option<FormValue>>,
+    redo: Vec<Option<FormValue>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileInfo {
+    pub name: String,
+    pub size: u64,
+    pub mime: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -56,6 +77,7 @@ pub enum FormValue {
     Value(String),
     Array(Vec<String>),
     Expression(Expression),
+    Secret(SecretString),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -99,34 +121,72 @@ impl FormData {
     }
 
     pub fn with_value(mut self, id: impl Into<String>, value: impl Into<FormValue>) -> Self {
-        self.values.insert(id.into(), value.into());
+        self.set(id, value);
         self
     }
 
     pub fn get(&self, key: &str) -> Option<&str> {
         self.values.get(key).and_then(|v| match v {
             FormValue::Value(s) => Some(s.as_str()),
+            FormValue::Secret(s) => Some(s.expose()),
             _ => None,
         })
     }
 
     pub fn set(&mut self, id: impl Into<String>, value: impl Into<FormValue>) {
-        self.values.insert(id.into(), value.into());
+        let id = id.into();
+        let value = self.protect(&id, value.into());
+        self.values.insert(id, value);
+    }
+
+    // Values of secret fields are never kept as plain strings
+    fn protect(&self, id: &str, value: FormValue) -> FormValue {
+        match value {
+            FormValue::Value(value)
+                if self
+                    .schema
+                    .fields
+                    .get(id)
+                    .is_some_and(|field| matches!(field.typ_, Type::Secret)) =>
+            {
+                FormValue::Secret(value.into())
+            }
+            value => value,
+        }
+    }
+
+    pub fn secret(&self, id: &str) -> Option<SecretString> {
+        self.values.get(id).and_then(|v| match v {
+            FormValue::Secret(v) => Some(v.clone()),
+            FormValue::Value(v) => Some(v.as_str().into()),
+            _ => None,
+        })
     }
 
     pub fn new_error(&mut self, id: impl Into<String>, error: impl Into<String>) {
+        let id = id.into();
+        let error = self.field_error(&id, error.into());
         self.errors.insert(
-            id.into(),
+            id,
             FormError {
                 id: FormErrorType::None,
-                error: error.into(),
+                error,
             },
         );
     }
 
+    // Prefixes errors with the field label, e.g. "Bind addresses: required"
+    fn field_error(&self, id: &str, error: impl std::fmt::Display) -> String {
+        match self.schema.fields.get(id).map(|field| field.label_form) {
+            Some(label) if !label.is_empty() => format!("{label}: {error}"),
+            _ => error.to_string(),
+        }
+    }
+
     pub fn value<T: FromStr>(&self, id: &str) -> Option<T> {
         self.values.get(id).and_then(|v| match v {
             FormValue::Value(v) => T::from_str(v.as_str()).ok(),
+            FormValue::Secret(v) => T::from_str(v.expose()).ok(),
             _ => None,
         })
     }
@@ -134,6 +194,7 @@ impl FormData {
     pub fn value_as_str(&self, id: &str) -> Option<&str> {
         self.values.get(id).and_then(|v| match v {
             FormValue::Value(v) => Some(v.as_str()),
+            FormValue::Secret(v) => Some(v.expose()),
             _ => None,
         })
     }
@@ -143,6 +204,7 @@ impl FormData {
             FormValue::Value(v) => v.is_empty(),
             FormValue::Array(v) => v.is_empty(),
             FormValue::Expression(v) => v.is_empty(),
+            FormValue::Secret(v) => v.is_empty(),
         })
     }
 
@@ -150,13 +212,206 @@ impl FormData {
         self.values.contains_key(id)
     }
 
+    pub fn file(&self, id: &str) -> Option<&FileInfo> {
+        self.files.get(id)
+    }
+
+    pub fn check_file(&self, id: &str, file: &FileInfo) -> Result<(), Cow<'static, str>> {
+        match self
+            .schema
+            .fields
+            .get(id)
+            .and_then(|field| field.input_check(self))
+        {
+            Some(check) => check.check_file(file),
+            None => Ok(()),
+        }
+    }
+
+    // The contents become the field value, the metadata is kept for display
+    pub fn set_file(&mut self, id: &str, file: FileInfo, contents: String) {
+        match self.check_file(id, &file) {
+            Ok(()) => {
+                self.update(id, contents);
+                self.files.insert(id.to_string(), file);
+            }
+            Err(err) => self.new_error(id, err),
+        }
+    }
+
+    pub fn clear_file(&mut self, id: &str) {
+        self.files.remove(id);
+        self.update(id, String::new());
+    }
+
     pub fn update(&mut self, id: &str, value: impl Into<FormValue>) {
-        let value = value.into();
+        let value = self.protect(id, value.into());
+        let snapshot = self.values.get(id).cloned();
+        let previous = self.value::<String>(id);
         self.cascading_reset(id);
         //let c = log::debug!("Updating field {id:?} with value {value:?}");
         self.values.insert(id.to_string(), value);
         self.update_defaults(id);
+        self.update_linked_values(id, previous.as_deref());
+        self.record_change(id, snapshot);
+        self.update_confirmations(id);
+    }
+
+    // Per-field undo history for the current editing session, secrets are not kept
+    fn record_change(&mut self, id: &str, previous: Option<FormValue>) {
+        self.errors.remove(id);
+        if self.values.get(id) == previous.as_ref()
+            || self
+                .schema
+                .fields
+                .get(id)
+                .is_some_and(|field| matches!(field.typ_, Type::Secret))
+        {
+            return;
+        }
+
+        let history = self.history.entry(id.to_string()).or_default();
+        if history.undo.len() == MAX_FIELD_HISTORY {
+            history.undo.remove(0);
+        }
+        history.undo.push(previous);
+        history.redo.clear();
+    }
+
+    pub fn undo(&mut self, id: &str) -> bool {
+        let Some(previous) = self
+            .history
+            .get_mut(id)
+            .and_then(|history| history.undo.pop())
+        else {
+            return false;
+        };
+        let current = self.restore(id, previous);
+        self.history
+            .entry(id.to_string())
+            .or_default()
+            .redo
+            .push(current);
+        true
+    }
+
+    pub fn redo(&mut self, id: &str) -> bool {
+        let Some(next) = self
+            .history
+            .get_mut(id)
+            .and_then(|history| history.redo.pop())
+        else {
+            return false;
+        };
+        let current = self.restore(id, next);
+        self.history
+            .entry(id.to_string())
+            .or_default()
+            .undo
+            .push(current);
+        true
+    }
+
+    fn restore(&mut self, id: &str, value: Option<FormValue>) -> Option<FormValue> {
         self.errors.remove(id);
+        self.files.remove(id);
+        match value {
+            Some(value) => self.values.insert(id.to_string(), value),
+            None => self.values.remove(id),
+        }
+    }
+
+    pub fn can_undo(&self, id: &str) -> bool {
+        self.history
+            .get(id)
+            .is_some_and(|history| !history.undo.is_empty())
+    }
+
+    pub fn can_redo(&self, id: &str) -> bool {
+        self.history
+            .get(id)
+            .is_some_and(|history| !history.redo.is_empty())
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    // Re-checks confirmation fields while either side is being edited,
+    // leaving an empty confirmation alone until the form is submitted
+    fn update_confirmations(&mut self, id: &str) {
+        let schema = self.schema.clone();
+        for field in schema.fields.values() {
+            let Some(target) = field.confirms else {
+                continue;
+            };
+            if field.id != id && target != id {
+                continue;
+            }
+
+            let confirmation = self.value::<String>(field.id).unwrap_or_default();
+            if confirmation.is_empty()
+                || confirmation == self.value::<String>(target).unwrap_or_default()
+            {
+                self.errors.remove(field.id);
+            } else {
+                self.new_error(field.id, "values do not match");
+            }
+        }
+    }
+
+    pub fn has_confirmation(&self, id: &str) -> bool {
+        self.schema
+            .fields
+            .values()
+            .any(|field| field.id == id && field.confirms.is_some() || field.confirms == Some(id))
+    }
+
+    // Auto-fills linked fields unless the user has already changed them
+    fn update_linked_values(&mut self, id: &str, previous: Option<&str>) {
+        let schema = self.schema.clone();
+        let Some(field) = schema.fields.get(id) else {
+            return;
+        };
+        let current = self.value::<String>(id).unwrap_or_default();
+
+        for linked in &field.on_change {
+            let (Some(target), Some(value)) =
+                (schema.fields.get(linked.target), linked.lookup(&current))
+            else {
+                continue;
+            };
+            let auto_filled = previous.and_then(|previous| linked.lookup(previous));
+            let default = target.default(self).and_then(|default| match default {
+                FormValue::Value(value) => Some(value.as_str()),
+                FormValue::Array(values) if values.len() == 1 => Some(values[0].as_str()),
+                _ => None,
+            });
+            let is_untouched = match self.values.get(target.id) {
+                None => true,
+                Some(FormValue::Value(current)) => {
+                    current.is_empty() || [auto_filled, default].contains(&Some(current))
+                }
+                Some(FormValue::Array(current)) => match current.as_slice() {
+                    [] => true,
+                    [current] => {
+                        current.is_empty() || [auto_filled, default].contains(&Some(current))
+                    }
+                    _ => false,
+                },
+                _ => false,
+            };
+
+            if is_untouched {
+                let value = if target.is_multivalue() {
+                    FormValue::Array(vec![value.to_string()])
+                } else {
+                    FormValue::Value(value.to_string())
+                };
+                self.set(target.id, value);
+                self.errors.remove(target.id);
+            }
+        }
     }
 
     pub fn remove(&mut self, id: &str) {
@@ -174,19 +429,22 @@ impl FormData {
         match self.values.get(id) {
             Some(FormValue::Array(values)) => Box::new(values.iter().map(|v| v.as_str())),
             Some(FormValue::Value(v)) => Box::new(std::iter::once(v.as_str())),
+            Some(FormValue::Secret(v)) => Box::new(std::iter::once(v.expose())),
             _ => Box::new([].into_iter()),
         }
     }
 
     pub fn array_set(&mut self, id: &str, values: impl IntoIterator<Item = impl Into<String>>) {
+        let previous = self.values.get(id).cloned();
         self.values.insert(
             id.to_string(),
             FormValue::Array(values.into_iter().map(Into::into).collect()),
         );
-        self.errors.remove(id);
+        self.record_change(id, previous);
     }
 
     pub fn array_update(&mut self, id: &str, idx: usize, value: impl Into<String>) {
+        let previous = self.values.get(id).cloned();
         match self
             .values
             .entry(id.to_string())
@@ -202,10 +460,11 @@ impl FormData {
             }
             _ => (),
         }
-        self.errors.remove(id);
+        self.record_change(id, previous);
     }
 
     pub fn array_delete(&mut self, id: &str, idx: usize) {
+        let previous = self.values.get(id).cloned();
         let left = self.values.get_mut(id).and_then(|v| match v {
             FormValue::Array(values) => {
                 values.remove(idx);
@@ -217,10 +476,11 @@ impl FormData {
         if left == Some(0) {
             self.values.remove(id);
         }
-        self.errors.remove(id);
+        self.record_change(id, previous);
     }
 
     pub fn array_push(&mut self, id: &str, value: impl Into<String>) {
+        let previous = self.values.get(id).cloned();
         let v = self
             .values
             .entry(id.to_string())
@@ -235,7 +495,7 @@ impl FormData {
             }
             _ => unreachable!(),
         };
-        self.errors.remove(id);
+        self.record_change(id, previous);
     }
 
     pub fn expr_if_thens<'x>(
@@ -257,6 +517,7 @@ impl FormData {
     }
 
     pub fn expr_update_else(&mut self, id: &str, value: impl Into<String>) {
+        let previous = self.values.get(id).cloned();
         match self
             .values
             .entry(id.to_string())
@@ -276,7 +537,7 @@ impl FormData {
             }
             _ => (),
         }
-        self.errors.remove(id);
+        self.record_change(id, previous);
     }
 
     pub fn expr_push_if_then(
@@ -285,6 +546,7 @@ impl FormData {
         if_: impl Into<String>,
         then_: impl Into<String>,
     ) {
+        let previous = self.values.get(id).cloned();
         let if_then = ExpressionIfThen {
             if_: if_.into(),
             then_: then_.into(),
@@ -310,32 +572,35 @@ impl FormData {
             }
             _ => (),
         }
-        self.errors.remove(id);
+        self.record_change(id, previous);
     }
 
     pub fn expr_delete_if_then(&mut self, id: &str, idx: usize) {
+        let previous = self.values.get(id).cloned();
         if let Some(FormValue::Expression(expr)) = self.values.get_mut(id) {
             expr.if_thens.remove(idx);
         }
-        self.errors.remove(id);
+        self.record_change(id, previous);
     }
 
     pub fn expr_update_if(&mut self, id: &str, idx: usize, if_: impl Into<String>) {
+        let previous = self.values.get(id).cloned();
         if let Some(FormValue::Expression(expr)) = self.values.get_mut(id) {
             if let Some(if_then) = expr.if_thens.get_mut(idx) {
                 if_then.if_ = if_.into();
             }
         }
-        self.errors.remove(id);
+        self.record_change(id, previous);
     }
 
     pub fn expr_update_then(&mut self, id: &str, idx: usize, then_: impl Into<String>) {
+        let previous = self.values.get(id).cloned();
         if let Some(FormValue::Expression(expr)) = self.values.get_mut(id) {
             if let Some(if_then) = expr.if_thens.get_mut(idx) {
                 if_then.then_ = then_.into();
             }
         }
-        self.errors.remove(id);
+        self.record_change(id, previous);
     }
 
     fn cascading_reset(&mut self, id: &str) {
@@ -459,6 +724,36 @@ impl FormData {
         self.errors.contains_key(id)
     }
 
+    // Fields currently failing validation, in the order they appear on the form
+    pub fn failing_fields(&self) -> Vec<&str> {
+        let mut fields = self
+            .schema
+            .form
+            .sections
+            .iter()
+            .flat_map(|section| section.fields.iter())
+            .map(|field| field.id)
+            .filter(|id| self.errors.contains_key(*id))
+            .collect::<Vec<_>>();
+        let mut others = self
+            .errors
+            .keys()
+            .map(|id| id.as_str())
+            .filter(|id| !fields.contains(id))
+            .collect::<Vec<_>>();
+        others.sort_unstable();
+        fields.extend(others);
+        fields
+    }
+
+    // Current errors as (field, message) pairs, in the same order as failing_fields
+    pub fn field_errors(&self) -> Vec<(&str, &str)> {
+        self.failing_fields()
+            .into_iter()
+            .filter_map(|id| self.error_string(id).map(|error| (id, error)))
+            .collect()
+    }
+
     pub fn validate_form(&mut self) -> bool {
         if !self.errors.is_empty() {
             log::debug!("Skipping validation, form has errors: {:#?}", self.errors);
@@ -471,6 +766,15 @@ impl FormData {
                 continue;
             }
 
+            if let Some(target) = field.confirms {
+                if self.value::<String>(field.id).unwrap_or_default()
+                    != self.value::<String>(target).unwrap_or_default()
+                {
+                    self.new_error(field.id, "values do not match");
+                }
+                continue;
+            }
+
             if let Some(check) = field.input_check(self) {
                 match field.typ_ {
                     Type::Input
@@ -481,12 +785,15 @@ impl FormData {
                     | Type::Duration
                     | Type::Rate
                     | Type::Cron
+                    | Type::Date
+                    | Type::File
                     | Type::Select { multi: false, .. } => {
                         match check.check_value(self.value::<String>(field.id).unwrap_or_default())
                         {
                             Ok(value) => {
                                 if !value.is_empty() {
-                                    self.values.insert(field.id.into(), value.into());
+                                    let value = self.protect(field.id, value.into());
+                                    self.values.insert(field.id.into(), value);
                                 } else {
                                     self.values.remove(field.id);
                                 }
@@ -518,7 +825,7 @@ impl FormData {
                                         field.id.to_string(),
                                         FormError {
                                             id: FormErrorType::Array(idx),
-                                            error: err.to_string(),
+                                            error: self.field_error(field.id, err),
                                         },
                                     );
                                 }
@@ -529,14 +836,14 @@ impl FormData {
                             match validator {
                                 Validator::Required => {
                                     if total_values == 0 {
-                                        self.new_error(field.id, "This field is required");
+                                        self.new_error(field.id, "required");
                                     }
                                 }
                                 Validator::MinItems(min) => {
                                     if total_values < *min {
                                         self.new_error(
                                             field.id,
-                                            format!("At least {min} items are required"),
+                                            format!("at least {min} items are required"),
                                         );
                                     }
                                 }
@@ -544,7 +851,7 @@ impl FormData {
                                     if total_values > *max {
                                         self.new_error(
                                             field.id,
-                                            format!("At most {max} items are allowed"),
+                                            format!("at most {max} items are allowed"),
                                         );
                                     }
                                 }
@@ -608,8 +915,10 @@ impl FormData {
                                                 field.id.to_string(),
                                                 FormError {
                                                     id: FormErrorType::Expression(expr_item),
-                                                    error: "This expression cannot be empty"
-                                                        .to_string(),
+                                                    error: self.field_error(
+                                                        field.id,
+                                                        "expression cannot be empty",
+                                                    ),
                                                 },
                                             );
                                             has_expression = true;
@@ -621,7 +930,7 @@ impl FormData {
                                             field.id.to_string(),
                                             FormError {
                                                 id: FormErrorType::Expression(expr_item),
-                                                error,
+                                                error: self.field_error(field.id, error),
                                             },
                                         );
                                         has_expression = true;
@@ -636,7 +945,7 @@ impl FormData {
                                 field.id.to_string(),
                                 FormError {
                                     id: FormErrorType::Expression(ExpressionError::Else),
-                                    error: "This field is required".to_string(),
+                                    error: self.field_error(field.id, "required"),
                                 },
                             );
                         }
@@ -663,6 +972,8 @@ impl FormData {
                     | Type::Duration
                     | Type::Rate
                     | Type::Cron
+                    | Type::Date
+                    | Type::File
                     | Type::Size => {
                         if let Some(value) = settings.remove(field.id) {
                             data.set(field.id, value);
@@ -776,22 +1087,35 @@ impl FormData {
 }
 
 impl InputCheck {
-    pub fn check_value(&self, mut value: String) -> Result<String, Cow<'static, str>> {
+    pub fn transform(&self, mut value: String) -> String {
         for transformer in &self.transformers {
             value = match transformer {
                 Transformer::Trim => value.trim().to_string(),
                 Transformer::RemoveSpaces => value.replace(' ', ""),
                 Transformer::Lowercase => value.to_lowercase(),
                 Transformer::Uppercase => value.to_uppercase(),
+                Transformer::NormalizeSocketAddr => normalize_socket_addr(&value),
+                Transformer::SecondsToDuration => {
+                    if !value.is_empty() && value.chars().all(|ch| ch.is_ascii_digit()) {
+                        format!("{value}s")
+                    } else {
+                        value
+                    }
+                }
             };
         }
+        value
+    }
+
+    pub fn check_value(&self, value: String) -> Result<String, Cow<'static, str>> {
+        let value = self.transform(value);
 
         if !value.is_empty() {
             for validator in &self.validators {
                 match validator {
                     Validator::IsEmail => {
                         if !value.contains('@') {
-                            return Err("This field must be a valid email address".into());
+                            return Err("must be a valid email address".into());
                         }
                     }
                     Validator::IsId => {
@@ -799,107 +1123,156 @@ impl InputCheck {
                             .chars()
                             .find(|c| !c.is_alphanumeric() && !['_', '-', '.'].contains(c))
                         {
-                            return Err(format!("Invalid character '{ch}' in this field").into());
+                            return Err(format!("invalid character '{ch}'").into());
                         }
                     }
                     Validator::IsHost => {
                         if value.contains('/') || value.contains(':') {
-                            return Err("This field must be a valid hostname".into());
+                            return Err("must be a valid hostname".into());
                         }
                     }
                     Validator::IsPort => {
                         if value.parse::<u16>().is_err() {
-                            return Err("This field must be a valid port number".into());
+                            return Err("must be a valid port number".into());
                         }
                     }
                     Validator::IsSocketAddr => {
                         if value.parse::<SocketAddr>().is_err() {
-                            return Err("This field must be a valid socket address".into());
+                            return Err("must be a valid socket address".into());
                         }
                     }
                     Validator::IsUrl => {
                         if !value.contains("://") {
-                            return Err("This field must be a valid URL".into());
+                            return Err("must be a valid URL".into());
                         }
                     }
                     Validator::IsDomain => {
                         if !value.contains('.') || value.starts_with('.') || value.ends_with('.') {
-                            return Err("This field must be a valid domain name".into());
+                            return Err("must be a valid domain name".into());
                         }
                     }
                     Validator::IsRegex => {
                         if regex::Regex::new(&value).is_err() {
-                            return Err("This field must be a valid regular expression".into());
+                            return Err("must be a valid regular expression".into());
                         }
                     }
                     Validator::MinLength(length) => {
-                        if value.len() < *length {
-                            return Err(format!(
-                                "This field must be at least {} characters",
-                                length
-                            )
-                            .into());
+                        if value.chars().count() < *length {
+                            return Err(format!("must be at least {} characters", length).into());
                         }
                     }
                     Validator::MaxLength(length) => {
-                        if value.len() > *length {
-                            return Err(format!(
-                                "This field must be at most {} characters",
-                                length
-                            )
-                            .into());
+                        if value.chars().count() > *length {
+                            return Err(format!("must be at most {} characters", length).into());
                         }
                     }
                     Validator::MinValue(val) => match val {
                         NumberType::Integer(val) => {
                             if value.parse::<i64>().ok().filter(|v| v >= val).is_none() {
-                                return Err(format!("This field must be at least {val}").into());
+                                return Err(format!("must be at least {val}").into());
                             }
                         }
                         NumberType::Float(val) => {
                             if value.parse::<f64>().ok().filter(|v| v >= val).is_none() {
-                                return Err(format!("This field must be at least {val}").into());
+                                return Err(format!("must be at least {val}").into());
                             }
                         }
                     },
                     Validator::MaxValue(val) => match val {
                         NumberType::Integer(val) => {
                             if value.parse::<i64>().ok().filter(|v| v <= val).is_none() {
-                                return Err(format!("This field must be at most {}", val).into());
+                                return Err(format!("must be at most {}", val).into());
                             }
                         }
                         NumberType::Float(val) => {
                             if value.parse::<f64>().ok().filter(|v| v <= val).is_none() {
-                                return Err(format!("This field must be at most {}", val).into());
+                                return Err(format!("must be at most {}", val).into());
                             }
                         }
                     },
                     Validator::IsIpOrMask => {
-                        let value = if let Some((ip, mask)) = value.rsplit_once('/') {
-                            if mask.parse::<u8>().is_err() {
-                                return Err("Invalid IP address mask".into());
-                            }
-                            ip
-                        } else {
-                            value.as_str()
-                        };
-
-                        if value.parse::<std::net::IpAddr>().is_err() {
-                            return Err("This field must be a valid IP address or network".into());
+                        parse_ip_or_mask(&value)?;
+                    }
+                    Validator::IsDate => {
+                        if NaiveDate::parse_from_str(&value, "%Y-%m-%d").is_err() {
+                            return Err("must be a valid date".into());
+                        }
+                    }
+                    Validator::MinDate(min) => {
+                        if NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                            .ok()
+                            .filter(|date| date >= min)
+                            .is_none()
+                        {
+                            return Err(
+                                format!("must be on or after {}", min.format("%Y-%m-%d")).into()
+                            );
+                        }
+                    }
+                    Validator::MaxDate(max) => {
+                        if NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                            .ok()
+                            .filter(|date| date <= max)
+                            .is_none()
+                        {
+                            return Err(
+                                format!("must be on or before {}", max.format("%Y-%m-%d")).into()
+                            );
+                        }
+                    }
+                    Validator::IsSieveScript => {
+                        if let Err(err) = check_script(&value) {
+                            return Err(err.to_string().into());
+                        }
+                    }
+                    Validator::IsDuration => {
+                        if !value.ends_with(|ch: char| ch.is_ascii_alphabetic())
+                            || Duration::parse_value(&value).is_none()
+                        {
+                            return Err(
+                                "must be a number followed by a unit, e.g. 30s or 500ms".into()
+                            );
+                        }
+                    }
+                    Validator::IsOtpCode => {
+                        if value.len() != 6 || !value.chars().all(|ch| ch.is_ascii_digit()) {
+                            return Err("must be a 6 digit code".into());
+                        }
+                    }
+                    Validator::MaxFileSize(max) => {
+                        if value.len() as u64 > *max {
+                            return Err(max_file_size_error(*max));
                         }
                     }
                     Validator::IsValidExpression { .. }
                     | Validator::MinItems(_)
                     | Validator::MaxItems(_)
+                    | Validator::FileType(_)
                     | Validator::Required => (),
                 }
             }
         } else if self.validators.contains(&Validator::Required) {
-            return Err("This field is required".into());
+            return Err("required".into());
         }
 
         Ok(value)
     }
+
+    // Checked before reading a selected file, so oversized files are never loaded
+    pub fn check_file(&self, file: &FileInfo) -> Result<(), Cow<'static, str>> {
+        for validator in &self.validators {
+            match validator {
+                Validator::MaxFileSize(max) if file.size > *max => {
+                    return Err(max_file_size_error(*max));
+                }
+                Validator::FileType(types) if !types.iter().any(|typ| file.is_type(typ)) => {
+                    return Err(format!("must be a {} file", types.join(", ")).into());
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
 }
 
 impl From<String> for FormValue {
@@ -974,3 +1347,120 @@ impl Default for FormValue {
         FormValue::Value("".to_string())
     }
 }
+
+fn max_file_size_error(max: u64) -> Cow<'static, str> {
+    format!("must not be larger than {}", format_size(max, DECIMAL)).into()
+}
+
+impl FileInfo {
+    fn is_type(&self, typ: &str) -> bool {
+        if typ.starts_with('.') {
+            self.name.to_ascii_lowercase().ends_with(typ)
+        } else if let Some(prefix) = typ.strip_suffix('*') {
+            self.mime.starts_with(prefix)
+        } else {
+            self.mime == typ
+        }
+    }
+}
+
+pub fn parse_ip_or_mask(value: &str) -> Result<(IpAddr, Option<u8>), &'static str> {
+    let (ip, mask) = match value.rsplit_once('/') {
+        Some((ip, mask)) => (
+            ip,
+            Some(mask.parse::<u8>().map_err(|_| "invalid IP address mask")?),
+        ),
+        None => (value, None),
+    };
+
+    ip.parse::<IpAddr>()
+        .map(|ip| (ip, mask))
+        .map_err(|_| "must be a valid IP address or network")
+}
+
+// Cleans up addresses copied from configuration files, such as "127.0.0.1:25", or [127.0.0.1]:25
+fn normalize_socket_addr(value: &str) -> String {
+    let mut value = value.trim().trim_end_matches([',', ';']).trim();
+    while let Some(unquoted) = ['"', '\'', '`'].iter().find_map(|quote| {
+        value
+            .strip_prefix(*quote)
+            .and_then(|value| value.strip_suffix(*quote))
+    }) {
+        value = unquoted.trim();
+    }
+    let value = value
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect::<String>();
+
+    // Brackets are only valid around IPv6 addresses
+    if let Some((ip, port)) = value
+        .strip_prefix('[')
+        .and_then(|value| value.split_once("]:"))
+    {
+        if ip.parse::<std::net::Ipv4Addr>().is_ok() {
+            return format!("{ip}:{port}");
+        }
+    }
+
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::schema::{InputCheck, Transformer, Validator};
+
+    #[test]
+    fn normalize_socket_addr() {
+        let check = InputCheck {
+            transformers: vec![Transformer::NormalizeSocketAddr],
+            validators: vec![Validator::Required, Validator::IsSocketAddr],
+        };
+
+        for (value, expected) in [
+            ("127.0.0.1:25", "127.0.0.1:25"),
+            ("[::1]:465", "[::1]:465"),
+            (" 127.0.0.1 : 25 ", "127.0.0.1:25"),
+            ("[ ::1 ] : 465", "[::1]:465"),
+            ("\t0.0.0.0:587\n", "0.0.0.0:587"),
+            ("[127.0.0.1]:25", "127.0.0.1:25"),
+            ("\"127.0.0.1:25\",", "127.0.0.1:25"),
+        ] {
+            assert_eq!(
+                check.check_value(value.to_string()).as_deref(),
+                Ok(expected),
+                "{value:?}"
+            );
+        }
+
+        for value in ["127.0.0.1", "[127.0.0.1:25", "::1:465", "localhost:25"] {
+            assert!(check.check_value(value.to_string()).is_err(), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn seconds_to_duration() {
+        let check = InputCheck {
+            transformers: vec![Transformer::SecondsToDuration],
+            validators: vec![Validator::IsDuration],
+        };
+
+        for (value, expected) in [
+            ("30s", "30s"),
+            ("500ms", "500ms"),
+            ("5m", "5m"),
+            ("1d", "1d"),
+            ("30", "30s"),
+        ] {
+            assert_eq!(
+                check.check_value(value.to_string()).as_deref(),
+                Ok(expected),
+                "{value:?}"
+            );
+        }
+
+        for value in ["0s", "ms", "30x", "thirty", "-5s", "1.5h"] {
+            assert!(check.check_value(value.to_string()).is_err(), "{value:?}");
+        }
+    }
+}
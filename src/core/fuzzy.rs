@@ -0,0 +1,137 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+// Subsequence matcher used by client-side searches. Terms are expected in lowercase,
+// as produced by `search_terms`, and matching never allocates.
+
+const MATCH_SCORE: i32 = 1;
+const CONSECUTIVE_BONUS: i32 = 5;
+const WORD_START_BONUS: i32 = 3;
+const PREFIX_BONUS: i32 = 2;
+const MAX_GAP_PENALTY: i32 = 3;
+
+// Best score of `text` against all terms, `None` unless every term matches
+pub fn fuzzy_score(text: &str, terms: &[String]) -> Option<i32> {
+    terms
+        .iter()
+        .filter(|term| !term.is_empty())
+        .try_fold(0, |score, term| {
+            fuzzy_match(text, term, |_, _| {}).map(|s| score + s)
+        })
+}
+
+// Byte ranges of the matched characters of every term, merged and sorted
+pub fn fuzzy_ranges(text: &str, terms: &[String]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for term in terms.iter().filter(|term| !term.is_empty()) {
+        fuzzy_match(text, term, |start, end| ranges.push((start, end)));
+    }
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+// Tries every occurrence of the first character as a starting point and reports the
+// matched characters of the best alignment through `on_match`
+pub fn fuzzy_match(text: &str, term: &str, mut on_match: impl FnMut(usize, usize)) -> Option<i32> {
+    let first = term.chars().next()?;
+    let mut best: Option<(usize, i32)> = None;
+    for (start, ch) in text.char_indices() {
+        if lower(ch) == first {
+            if let Some(score) = match_from(text, start, term, |_, _| {}) {
+                if best.is_none_or(|(_, best)| score > best) {
+                    best = Some((start, score));
+                }
+            }
+        }
+    }
+
+    let (start, score) = best?;
+    match_from(text, start, term, &mut on_match);
+    Some(score)
+}
+
+fn match_from(
+    text: &str,
+    start: usize,
+    term: &str,
+    mut on_match: impl FnMut(usize, usize),
+) -> Option<i32> {
+    let mut wanted = term.chars().peekable();
+    let mut score = 0;
+    let mut gap = 0;
+    let mut scattered = 0;
+    let mut last_match = None;
+    let mut prev = text[..start].chars().next_back();
+
+    for (offset, ch) in text[start..].char_indices() {
+        let Some(&next) = wanted.peek() else {
+            break;
+        };
+        let pos = start + offset;
+        if lower(ch) == next {
+            wanted.next();
+            let word_start = prev.is_none_or(|prev| {
+                !prev.is_alphanumeric() || (prev.is_lowercase() && ch.is_uppercase())
+            });
+            score += MATCH_SCORE;
+            if last_match == Some(pos) {
+                score += CONSECUTIVE_BONUS;
+            } else {
+                if word_start {
+                    score += WORD_START_BONUS;
+                } else if last_match.is_some() {
+                    scattered += 1;
+                }
+                score -= gap.min(MAX_GAP_PENALTY);
+            }
+            if pos == 0 {
+                score += PREFIX_BONUS;
+            }
+            last_match = Some(pos + ch.len_utf8());
+            on_match(pos, pos + ch.len_utf8());
+            gap = 0;
+        } else if last_match.is_some() {
+            gap += 1;
+        }
+        prev = Some(ch);
+    }
+
+    // Characters spread across unrelated words are noise rather than a near-match
+    if wanted.peek().is_none() && scattered <= 1 + term.chars().count() as i32 / 4 {
+        Some(score)
+    } else {
+        None
+    }
+}
+
+fn lower(ch: char) -> char {
+    ch.to_lowercase().next().unwrap_or(ch)
+}
@@ -0,0 +1,119 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::cell::RefCell;
+
+use gloo_net::http::Headers;
+use serde::{Deserialize, Serialize};
+
+use crate::STATE_CUSTOM_HEADERS_KEY;
+
+use super::storage::{LocalStorage, Storage};
+
+// Headers attached to every request, e.g. for an authenticating gateway in front of
+// the server. They are kept on this device only as they usually carry credentials.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CustomHeaders(pub Vec<(String, String)>);
+
+thread_local! {
+    static CUSTOM_HEADERS: RefCell<CustomHeaders> = RefCell::new(CustomHeaders::load());
+}
+
+pub fn apply_custom_headers(headers: &Headers) {
+    CUSTOM_HEADERS.with_borrow(|custom| {
+        for (name, value) in &custom.0 {
+            headers.set(name, value);
+        }
+    });
+}
+
+pub fn is_custom_header(name: &str) -> bool {
+    CUSTOM_HEADERS.with_borrow(|custom| {
+        custom
+            .0
+            .iter()
+            .any(|(custom, _)| custom.eq_ignore_ascii_case(name))
+    })
+}
+
+impl CustomHeaders {
+    pub fn load() -> Self {
+        LocalStorage::get(STATE_CUSTOM_HEADERS_KEY).unwrap_or_default()
+    }
+
+    pub fn current() -> Self {
+        CUSTOM_HEADERS.with_borrow(|custom| custom.clone())
+    }
+
+    pub fn save(&self) {
+        let result = if self.0.is_empty() {
+            LocalStorage::delete(STATE_CUSTOM_HEADERS_KEY);
+            Ok(())
+        } else {
+            LocalStorage::set(STATE_CUSTOM_HEADERS_KEY, self)
+        };
+        if let Err(err) = result {
+            log::error!("Failed to save custom headers to local storage: {}", err);
+        }
+    }
+
+    pub fn apply(&self) {
+        CUSTOM_HEADERS.with_borrow_mut(|custom| *custom = self.clone());
+    }
+
+    // One "Name: Value" pair per line
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut headers = Vec::new();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let Some((name, value)) = line.split_once(':') else {
+                return Err(format!("Expected \"Name: Value\" in {line:?}"));
+            };
+            let name = name.trim();
+            let value = value.trim();
+            if name.is_empty() || !name.chars().all(is_token_char) {
+                return Err(format!("Invalid header name {name:?}"));
+            } else if name.eq_ignore_ascii_case("authorization") {
+                return Err(
+                    "The Authorization header is reserved for the signed in session".to_string(),
+                );
+            } else if value.chars().any(|ch| ch.is_control()) {
+                return Err(format!("Invalid value for header {name:?}"));
+            }
+            headers.push((name.to_string(), value.to_string()));
+        }
+        Ok(CustomHeaders(headers))
+    }
+
+    pub fn to_text(&self) -> String {
+        self.0
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn is_token_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(ch)
+}
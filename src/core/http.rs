@@ -21,18 +21,36 @@
  * for more details.
 */
 
+use std::{fmt::Display, time::Duration};
+
 use ahash::AHashMap;
 use base64::{engine::general_purpose::STANDARD, Engine};
 use gloo_net::http::{Headers, Method, RequestBuilder};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-
-use super::{oauth::AuthToken, url::UrlBuilder};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::{Date, Promise},
+    wasm_bindgen::JsValue,
+};
+
+use super::{
+    headers::{apply_custom_headers, is_custom_header},
+    loading::LoadingIndicator,
+    oauth::AuthToken,
+    url::UrlBuilder,
+};
+
+// Reads are retried transparently when the server asks for a short enough pause
+const MAX_RATE_LIMIT_RETRIES: usize = 2;
+const MAX_AUTO_RETRY_DELAY: Duration = Duration::from_secs(10);
 
 pub struct HttpRequest {
     method: Method,
     url: UrlBuilder,
     headers: Headers,
     body: Option<String>,
+    timeout: Option<Duration>,
+    background: bool,
 }
 
 #[derive(Deserialize)]
@@ -57,10 +75,18 @@ pub enum ManagementApiError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Error {
     Unauthorized,
+    // OAuth endpoints describe what was wrong in the body of 400 responses
+    BadRequest { response: String },
+    // The session is valid but lacks the permissions for the request
+    Forbidden,
     NotFound,
     Network(String),
     Serializer { error: String, response: String },
     Server(ManagementApiError),
+    // Throttled by the server, with the delay it asked for when one was sent
+    TooManyRequests { retry_after: Option<Duration> },
+    // The credentials were accepted but a one-time code has to be appended
+    TotpRequired,
 }
 
 pub trait IntoUrlBuilder {
@@ -71,11 +97,15 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 impl<'x> HttpRequest {
     pub fn new(method: Method, url: impl IntoUrlBuilder) -> Self {
+        let headers = Headers::new();
+        apply_custom_headers(&headers);
         Self {
             method,
             url: url.into_url_builder(),
-            headers: Headers::new(),
+            headers,
             body: None,
+            timeout: None,
+            background: false,
         }
     }
 
@@ -175,6 +205,76 @@ impl<'x> HttpRequest {
         self
     }
 
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    // Polling requests should not flash the page loading bar
+    pub fn in_background(mut self) -> Self {
+        self.background = true;
+        self
+    }
+
+    // Equivalent command line request, credentials and custom headers are replaced with
+    // shell variables
+    pub fn to_curl(&self) -> String {
+        let mut url = self.url.clone().finish();
+        if url.starts_with('/') {
+            if let Ok(origin) = leptos::window().location().origin() {
+                url = format!("{origin}{url}");
+            }
+        }
+
+        let mut command = format!("curl -X {} {}", self.method, shell_quote(&url));
+        let mut headers = self.headers.entries().collect::<Vec<_>>();
+        if self.body.is_some()
+            && !headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        {
+            headers.push(("content-type".to_string(), "application/json".to_string()));
+        }
+        for (name, value) in headers {
+            // Double quotes so the shell expands the token placeholder
+            let value = if name.eq_ignore_ascii_case("authorization") {
+                match value.split_once(' ') {
+                    Some((scheme, _)) => format!("{scheme} $TOKEN"),
+                    None => "$TOKEN".to_string(),
+                }
+            } else if is_custom_header(&name) {
+                name.chars()
+                    .map(|ch| {
+                        if ch.is_ascii_alphanumeric() {
+                            ch.to_ascii_uppercase()
+                        } else {
+                            '_'
+                        }
+                    })
+                    .fold("$".to_string(), |mut var, ch| {
+                        var.push(ch);
+                        var
+                    })
+            } else {
+                value
+                    .chars()
+                    .flat_map(|ch| {
+                        matches!(ch, '\\' | '"' | '$' | '`')
+                            .then_some('\\')
+                            .into_iter()
+                            .chain([ch])
+                    })
+                    .collect()
+            };
+            command.push_str(&format!(" \\\n  -H \"{name}: {value}\""));
+        }
+        if let Some(body) = &self.body {
+            command.push_str(&format!(" \\\n  --data-raw {}", shell_quote(body)));
+        }
+
+        command
+    }
+
     pub async fn send<T>(self) -> Result<T>
     where
         T: DeserializeOwned,
@@ -202,9 +302,14 @@ impl<'x> HttpRequest {
     }
 
     pub async fn send_raw(self) -> Result<Vec<u8>> {
+        let _pending = (!self.background).then(LoadingIndicator::track).flatten();
         let abort_controller = web_sys::AbortController::new().ok();
         let abort_signal = abort_controller.as_ref().map(|a| a.signal());
 
+        if let (Some(timeout), Some(abort_controller)) = (self.timeout, abort_controller.clone()) {
+            leptos::set_timeout(move || abort_controller.abort(), timeout);
+        }
+
         // abort in-flight requests if, e.g., we've navigated away from this page
         leptos::on_cleanup(move || {
             if let Some(abort_controller) = abort_controller {
@@ -212,29 +317,91 @@ impl<'x> HttpRequest {
             }
         });
 
-        let builder = RequestBuilder::new(&self.url.finish())
-            .method(self.method)
-            .headers(self.headers)
-            .abort_signal(abort_signal.as_ref());
-        let req = if let Some(body) = self.body {
-            builder.body(body)
-        } else {
-            builder.build()
-        }?;
-
-        let response = req.send().await?;
-
-        match response.status() {
-            200..=299 => response.binary().await.map_err(Into::into),
-            401 => Err(Error::Unauthorized),
-            404 => Err(Error::NotFound),
-            code => Err(Error::Server(ManagementApiError::Other {
-                details: format!("Invalid response code {code}: {}", response.status_text()),
-            })),
+        let url = self.url.finish();
+        let mut headers = self.headers;
+        let mut retries = 0;
+        loop {
+            let builder = RequestBuilder::new(&url)
+                .method(self.method.clone())
+                .headers(headers)
+                .abort_signal(abort_signal.as_ref());
+            let req = if let Some(body) = &self.body {
+                builder.body(body.as_str())
+            } else {
+                builder.build()
+            }?;
+            headers = req.headers();
+
+            let response = req.send().await?;
+
+            return match response.status() {
+                200..=299 => response.binary().await.map_err(Into::into),
+                400 => Err(Error::BadRequest {
+                    response: response.text().await.unwrap_or_default(),
+                }),
+                401 => Err(Error::Unauthorized),
+                402 => Err(Error::TotpRequired),
+                403 => Err(Error::Forbidden),
+                404 => Err(Error::NotFound),
+                429 => {
+                    let retry_after = response
+                        .headers()
+                        .get("retry-after")
+                        .and_then(|value| parse_retry_after(&value));
+                    match retry_after {
+                        Some(delay)
+                            if self.method == Method::GET
+                                && retries < MAX_RATE_LIMIT_RETRIES
+                                && delay <= MAX_AUTO_RETRY_DELAY =>
+                        {
+                            log::debug!("Rate limited, retrying {url} in {delay:?}");
+                            retries += 1;
+                            sleep(delay).await;
+                            continue;
+                        }
+                        _ => Err(Error::TooManyRequests { retry_after }),
+                    }
+                }
+                code => Err(Error::Server(ManagementApiError::Other {
+                    details: format!("Invalid response code {code}: {}", response.status_text()),
+                })),
+            };
         }
     }
 }
 
+// Retry-After is either a number of seconds or an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = Date::parse(value);
+    (!at.is_nan()).then(|| Duration::from_millis((at - Date::now()).max(0.0) as u64))
+}
+
+async fn sleep(delay: Duration) {
+    let promise = Promise::new(&mut |resolve, _| {
+        leptos::set_timeout(
+            move || {
+                let _ = resolve.call0(&JsValue::NULL);
+            },
+            delay,
+        );
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+impl IntoUrlBuilder for UrlBuilder {
+    fn into_url_builder(self) -> UrlBuilder {
+        self
+    }
+}
+
 impl IntoUrlBuilder for String {
     fn into_url_builder(self) -> UrlBuilder {
         UrlBuilder::new(self)
@@ -279,3 +446,65 @@ impl From<serde_json::Error> for Error {
         }
     }
 }
+
+// Rejected requests are explained in the body, as problem details (RFC 7807) or plain text
+pub fn bad_request_details(response: &str) -> Option<String> {
+    const MAX_TEXT_LEN: usize = 500;
+
+    let response = response.trim();
+    match serde_json::from_str::<serde_json::Value>(response) {
+        Ok(response) => ["detail", "title", "error_description", "error"]
+            .iter()
+            .filter_map(|key| response.get(key)?.as_str())
+            .map(str::trim)
+            .find(|details| !details.is_empty())
+            .map(ToString::to_string),
+        // Skip error pages served by proxies
+        Err(_) if !response.is_empty() && !response.starts_with('<') => {
+            Some(response.chars().take(MAX_TEXT_LEN).collect())
+        }
+        Err(_) => None,
+    }
+}
+
+// Plain text descriptions for places where an alert does not fit, such as table cells
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Unauthorized => f.write_str("Unauthorized"),
+            Error::BadRequest { response } => match bad_request_details(response) {
+                Some(details) => write!(f, "The server rejected the request: {details}"),
+                None => f.write_str("The server rejected the request"),
+            },
+            Error::Forbidden => f.write_str("Permission denied"),
+            Error::NotFound => f.write_str("Not found"),
+            Error::Network(details) => f.write_str(details),
+            Error::Serializer { error, .. } => f.write_str(error),
+            Error::Server(error) => Display::fmt(error, f),
+            Error::TooManyRequests { .. } => f.write_str("Too many requests"),
+            Error::TotpRequired => f.write_str("Verification code required"),
+        }
+    }
+}
+
+impl Display for ManagementApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManagementApiError::FieldAlreadyExists { field, value } => write!(
+                f,
+                "Another record exists with value {value:?} in field {field:?}"
+            ),
+            ManagementApiError::FieldMissing { field } => write!(f, "Field {field} is missing"),
+            ManagementApiError::NotFound { item } => write!(f, "{item} was not found"),
+            ManagementApiError::Unsupported { details } | ManagementApiError::Other { details } => {
+                f.write_str(details)
+            }
+            ManagementApiError::AssertFailed => {
+                f.write_str("Another record with the same ID already exists")
+            }
+            ManagementApiError::UnsupportedDirectoryOperation { class } => {
+                write!(f, "{class} directory cannot be managed")
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::cell::Cell;
+
+use leptos::{
+    create_rw_signal, expect_context, provide_context, RwSignal, SignalGet, SignalSet, SignalUpdate,
+};
+
+// Drives the loading bar at the top of the page, requests register themselves
+// so that every fetch is covered without each page having to report it
+#[derive(Clone, Copy)]
+pub struct LoadingIndicator {
+    pending: RwSignal<usize>,
+    routing: RwSignal<bool>,
+}
+
+pub struct PendingRequest(LoadingIndicator);
+
+thread_local! {
+    static LOADING_INDICATOR: Cell<Option<LoadingIndicator>> = const { Cell::new(None) };
+}
+
+pub fn init_loading_indicator() -> LoadingIndicator {
+    let indicator = LoadingIndicator {
+        pending: create_rw_signal(0),
+        routing: create_rw_signal(false),
+    };
+    LOADING_INDICATOR.set(Some(indicator));
+    provide_context(indicator);
+    indicator
+}
+
+pub fn use_loading_indicator() -> LoadingIndicator {
+    expect_context::<LoadingIndicator>()
+}
+
+impl LoadingIndicator {
+    pub fn is_loading(&self) -> bool {
+        self.pending.get() > 0 || self.routing.get()
+    }
+
+    pub fn set_routing(&self, routing: bool) {
+        self.routing.set(routing);
+    }
+
+    // The request counts as pending until the guard is dropped, which also
+    // covers requests aborted by navigating away
+    pub fn track() -> Option<PendingRequest> {
+        LOADING_INDICATOR.get().map(|indicator| {
+            indicator.pending.update(|pending| *pending += 1);
+            PendingRequest(indicator)
+        })
+    }
+}
+
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        self.0
+            .pending
+            .try_update(|pending| *pending = pending.saturating_sub(1));
+    }
+}
@@ -0,0 +1,166 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::{cell::Cell, str::FromStr};
+
+use chrono::{DateTime, Local};
+use leptos::{
+    create_rw_signal, expect_context, provide_context, queue_microtask, RwSignal, SignalUpdate,
+};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+
+use crate::STATE_LOG_SETTINGS_KEY;
+
+use super::storage::{SessionStorage, Storage};
+
+pub const LOG_LEVELS: &[(&str, &str)] = &[
+    ("off", "Off"),
+    ("error", "Error"),
+    ("warn", "Warning"),
+    ("info", "Info"),
+    ("debug", "Debug"),
+    ("trace", "Trace"),
+];
+
+pub const MAX_CONSOLE_ENTRIES: usize = 500;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogSettings {
+    pub level: String,
+    pub console: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogEntry {
+    pub id: u64,
+    pub timestamp: DateTime<Local>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+#[derive(Clone, Copy)]
+pub struct LogState {
+    pub settings: RwSignal<LogSettings>,
+    pub entries: RwSignal<Vec<LogEntry>>,
+}
+
+struct WebLogger;
+
+static LOGGER: WebLogger = WebLogger;
+
+thread_local! {
+    static CONSOLE: Cell<Option<RwSignal<Vec<LogEntry>>>> = const { Cell::new(None) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+pub fn init_logging() {
+    if log::set_logger(&LOGGER).is_ok() {
+        LogSettings::load().apply();
+    }
+}
+
+pub fn init_log_console() {
+    let state = LogState {
+        settings: create_rw_signal(LogSettings::load()),
+        entries: create_rw_signal(Vec::new()),
+    };
+    CONSOLE.with(|console| console.set(Some(state.entries)));
+    provide_context(state);
+}
+
+pub fn use_log_state() -> LogState {
+    expect_context::<LogState>()
+}
+
+impl LogSettings {
+    pub fn load() -> Self {
+        SessionStorage::get(STATE_LOG_SETTINGS_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = SessionStorage::set(STATE_LOG_SETTINGS_KEY, self) {
+            log::error!("Failed to save log settings to session storage: {}", err);
+        }
+    }
+
+    pub fn level_filter(&self) -> LevelFilter {
+        LevelFilter::from_str(&self.level).unwrap_or(LevelFilter::Info)
+    }
+
+    pub fn apply(&self) {
+        log::set_max_level(self.level_filter());
+    }
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        LogSettings {
+            level: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "info"
+            }
+            .to_string(),
+            console: false,
+        }
+    }
+}
+
+impl Log for WebLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        console_log::log(record);
+
+        if let Some(entries) = CONSOLE.with(Cell::get) {
+            let entry = LogEntry {
+                id: NEXT_ID.with(|id| id.replace(id.get() + 1)),
+                timestamp: Local::now(),
+                level: record.level(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            };
+
+            // Defer the update, records can be emitted while signals are being updated
+            queue_microtask(move || {
+                entries.try_update(|entries| {
+                    if entries.len() >= MAX_CONSOLE_ENTRIES {
+                        entries.remove(0);
+                    }
+                    entries.push(entry);
+                });
+            });
+        }
+    }
+
+    fn flush(&self) {}
+}
@@ -21,9 +21,28 @@
  * for more details.
 */
 
+pub mod a11y;
+pub mod api;
+pub mod cert;
+pub mod clipboard;
+pub mod clock;
+pub mod download;
 pub mod expr;
 pub mod form;
+pub mod fuzzy;
+pub mod headers;
 pub mod http;
+pub mod loading;
+pub mod logging;
 pub mod oauth;
+pub mod otp;
+pub mod prefs;
+pub mod query;
+pub mod rdns;
+pub mod recent;
+pub mod resource;
 pub mod schema;
+pub mod secret;
+pub mod sieve;
+pub mod storage;
 pub mod url;
@@ -23,12 +23,24 @@
 
 use std::{sync::Arc, time::Duration};
 
-use leptos::{expect_context, RwSignal};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use leptos::{
+    create_rw_signal, expect_context, leptos_dom::helpers::TimeoutHandle, on_cleanup,
+    provide_context, set_timeout_with_handle, store_value, window, RwSignal, SignalGet,
+    SignalGetUntracked, SignalSet, SignalUpdate, StoredValue,
+};
 use serde::{Deserialize, Serialize};
+use web_sys::js_sys::Date;
 
 use crate::components::messages::alert::Alert;
 
-use super::http::{self, HttpRequest};
+use super::{
+    api::{ApiClient, ApiError},
+    http::HttpRequest,
+    prefs::Preferences,
+    secret::SecretString,
+    url::UrlBuilder,
+};
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AuthToken {
@@ -38,8 +50,42 @@ pub struct AuthToken {
     pub username: Arc<String>,
     pub is_valid: bool,
     pub is_admin: bool,
+    #[serde(default)]
+    pub claims: Option<TokenClaims>,
 }
 
+// Permissions carried by the access token, as far as the server discloses them
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenClaims {
+    pub scopes: Vec<String>,
+    pub roles: Vec<String>,
+    pub expires_at: Option<i64>,
+    // Server time when the token was issued, in seconds since the epoch
+    pub issued_at: Option<i64>,
+    pub source: ClaimsSource,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClaimsSource {
+    #[default]
+    Jwt,
+    Introspection,
+    Grant,
+}
+
+const REFRESH_MARGIN_SECS: u64 = 60;
+
+#[derive(Clone, Copy)]
+pub struct RefreshTimer {
+    handle: StoredValue<Option<TimeoutHandle>>,
+    // When the current access token stops being accepted, in seconds since the epoch
+    expires_at: StoredValue<u64>,
+}
+
+// Pending while a session restored from storage is being validated
+#[derive(Clone, Copy)]
+pub struct SessionCheck(RwSignal<bool>);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum OAuthCodeRequest {
@@ -74,6 +120,12 @@ pub struct OAuthGrant {
     pub scope: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct OAuthMetadata {
+    #[serde(default)]
+    token_endpoint: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ErrorType {
     #[serde(rename = "invalid_grant")]
@@ -98,13 +150,33 @@ pub enum ErrorType {
     ExpiredToken,
 }
 
+pub enum OAuthLogin {
+    Granted { grant: OAuthGrant, is_admin: bool },
+    // The password was accepted but the account also requires a one-time code
+    MfaRequired,
+}
+
 pub async fn oauth_authenticate(
     base_url: &str,
     username: &str,
-    password: &str,
-) -> Result<(OAuthGrant, bool), Alert> {
+    password: &SecretString,
+    totp_code: Option<&str>,
+) -> Result<OAuthLogin, Alert> {
+    // The server expects the one-time code appended to the password
+    let password = match totp_code {
+        Some(code) => password.join('$', code),
+        None => password.clone(),
+    };
     let response =
-        oauth_user_authentication(base_url, username, password, "webadmin", None).await?;
+        match request_authorization_code(base_url, username, &password, "webadmin", None).await {
+            Ok(response) => response,
+            Err(ApiError::TotpRequired) if totp_code.is_some() => {
+                return Err(Alert::warning("Invalid verification code")
+                    .with_details("Enter the current code from your authenticator app."));
+            }
+            Err(ApiError::TotpRequired) => return Ok(OAuthLogin::MfaRequired),
+            Err(err) => return Err(authentication_error(err, totp_code.is_some())),
+        };
     let is_admin = response.is_admin;
     match HttpRequest::post(format!("{base_url}/auth/token"))
         .with_raw_body(
@@ -121,52 +193,187 @@ pub async fn oauth_authenticate(
         .and_then(|response| {
             serde_json::from_slice::<OAuthResponse>(response.as_slice()).map_err(Into::into)
         }) {
-        Ok(OAuthResponse::Granted(grant)) => Ok((grant, is_admin)),
+        Ok(OAuthResponse::Granted(grant)) => Ok(OAuthLogin::Granted { grant, is_admin }),
         Ok(OAuthResponse::Error { error }) => Err(Alert::error("OAuth failure")
             .with_details(format!("Server returned error code {error:?}"))),
         Err(err) => Err(Alert::from(err)),
     }
 }
 
+// Self-contained tokens are decoded locally, opaque ones are looked up on the
+// introspection endpoint and, failing that, only the granted scope is known
+pub async fn oauth_token_claims(base_url: &str, grant: &OAuthGrant) -> Option<TokenClaims> {
+    let claims = match decode_jwt_claims(&grant.access_token) {
+        Some(claims) => TokenClaims::from_json(&claims, ClaimsSource::Jwt),
+        None => oauth_introspect(base_url, &grant.access_token)
+            .await
+            .map(|claims| TokenClaims::from_json(&claims, ClaimsSource::Introspection)),
+    };
+
+    match (claims, grant.scope.as_deref()) {
+        (Some(mut claims), Some(scope)) if claims.scopes.is_empty() => {
+            claims.scopes = split_scope(scope);
+            Some(claims)
+        }
+        (Some(claims), _) => Some(claims),
+        (None, Some(scope)) if !scope.trim().is_empty() => Some(TokenClaims {
+            scopes: split_scope(scope),
+            source: ClaimsSource::Grant,
+            ..Default::default()
+        }),
+        (None, _) => None,
+    }
+}
+
+// Looking up the claims can take a round trip, so the token is stored and used first and
+// the claims are filled in afterwards, unless the token was replaced in the meantime
+pub async fn update_token_claims(auth_token: RwSignal<AuthToken>, grant: &OAuthGrant) {
+    let base_url = auth_token.get_untracked().base_url;
+    if let Some(claims) = oauth_token_claims(&base_url, grant).await {
+        auth_token.update(|auth_token| {
+            if *auth_token.access_token == grant.access_token {
+                auth_token.claims = Some(claims);
+            }
+        });
+    }
+}
+
+async fn oauth_introspect(base_url: &str, access_token: &str) -> Option<serde_json::Value> {
+    match HttpRequest::post(format!("{base_url}/auth/introspect"))
+        .with_header("Authorization", format!("Bearer {access_token}"))
+        .with_raw_body(serde_urlencoded::to_string([("token", access_token)]).unwrap())
+        .with_timeout(Duration::from_secs(5))
+        .in_background()
+        .send_raw()
+        .await
+        .and_then(|response| {
+            serde_json::from_slice::<serde_json::Value>(&response).map_err(Into::into)
+        }) {
+        Ok(response) if response["active"].as_bool() == Some(true) => Some(response),
+        Ok(_) => None,
+        Err(err) => {
+            log::debug!("Token introspection failed: {err:?}");
+            None
+        }
+    }
+}
+
+fn decode_jwt_claims(token: &str) -> Option<serde_json::Value> {
+    let mut parts = token.split('.');
+    let (_, payload, _) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+
+    URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()
+        .and_then(|payload| serde_json::from_slice::<serde_json::Value>(&payload).ok())
+        .filter(|claims| claims.is_object())
+}
+
+fn split_scope(scope: &str) -> Vec<String> {
+    scope.split_whitespace().map(ToString::to_string).collect()
+}
+
+impl TokenClaims {
+    fn from_json(claims: &serde_json::Value, source: ClaimsSource) -> Self {
+        // Claims may hold either a space separated string or an array
+        let list = |names: &[&str]| {
+            names
+                .iter()
+                .filter_map(|name| claims.get(name))
+                .flat_map(|value| match value {
+                    serde_json::Value::String(value) => split_scope(value),
+                    serde_json::Value::Array(values) => values
+                        .iter()
+                        .filter_map(|value| value.as_str().map(ToString::to_string))
+                        .collect(),
+                    _ => vec![],
+                })
+                .collect::<Vec<_>>()
+        };
+
+        TokenClaims {
+            scopes: list(&["scope", "scp"]),
+            roles: list(&["roles", "role"]),
+            expires_at: claims["exp"].as_i64(),
+            issued_at: claims["iat"].as_i64(),
+            source,
+        }
+    }
+}
+
+pub async fn oauth_probe_server(base_url: &str) -> bool {
+    match HttpRequest::get(format!("{base_url}/.well-known/oauth-authorization-server"))
+        .with_timeout(Duration::from_secs(3))
+        .send_raw()
+        .await
+    {
+        Ok(response) => serde_json::from_slice::<OAuthMetadata>(&response)
+            .is_ok_and(|metadata| !metadata.token_endpoint.is_empty()),
+        Err(err) => {
+            log::debug!("Server probe for {base_url} failed: {err:?}");
+            false
+        }
+    }
+}
+
 pub async fn oauth_user_authentication(
     base_url: &str,
     username: &str,
-    password: &str,
+    password: &SecretString,
     client_id: &str,
     redirect_uri: Option<&str>,
 ) -> Result<OAuthCodeResponse, Alert> {
-    match HttpRequest::post(format!("{base_url}/api/oauth"))
-        .with_basic_authorization(username, password)
-        .with_body(OAuthCodeRequest::Code {
-            client_id: client_id.to_string(),
-            redirect_uri: redirect_uri.map(ToOwned::to_owned),
-        })
-        .unwrap()
-        .send::<OAuthCodeResponse>()
+    request_authorization_code(base_url, username, password, client_id, redirect_uri)
         .await
-    {
-        Ok(response) => Ok(response),
-        Err(http::Error::Unauthorized) => {
-            Err(Alert::warning("Incorrect username or password")
-                .with_timeout(Duration::from_secs(3)))
-        }
-        Err(err) => Err(Alert::from(err)),
+        .map_err(|err| authentication_error(err, false))
+}
+
+async fn request_authorization_code(
+    base_url: &str,
+    username: &str,
+    password: &SecretString,
+    client_id: &str,
+    redirect_uri: Option<&str>,
+) -> Result<OAuthCodeResponse, ApiError> {
+    ApiClient::with_credentials(base_url, username, password.clone())
+        .create::<_, OAuthCodeResponse>(
+            "/api/oauth",
+            OAuthCodeRequest::Code {
+                client_id: client_id.to_string(),
+                redirect_uri: redirect_uri.map(ToOwned::to_owned),
+            },
+        )
+        .await
+}
+
+fn authentication_error(err: ApiError, has_totp_code: bool) -> Alert {
+    match err {
+        ApiError::Unauthorized => Alert::warning(if has_totp_code {
+            "Incorrect password or verification code"
+        } else {
+            "Incorrect username or password"
+        })
+        .with_timeout(Duration::from_secs(3)),
+        err => Alert::from(err),
     }
 }
 
 pub async fn oauth_device_authentication(
     base_url: &str,
     username: &str,
-    password: &str,
+    password: &SecretString,
     code: &str,
 ) -> Alert {
-    match HttpRequest::post(format!("{base_url}/api/oauth"))
-        .with_basic_authorization(username, password)
-        .with_body(OAuthCodeRequest::Device {
-            code: code.to_string(),
-        })
-        .unwrap()
-        .send::<bool>()
+    match ApiClient::with_credentials(base_url, username, password.clone())
+        .create::<_, bool>(
+            "/api/oauth",
+            OAuthCodeRequest::Device {
+                code: code.to_string(),
+            },
+        )
         .await
     {
         Ok(is_valid) => {
@@ -179,14 +386,25 @@ pub async fn oauth_device_authentication(
                     .with_details("The code you entered is invalid or has expired")
             }
         }
-        Err(http::Error::Unauthorized) => {
+        Err(ApiError::Unauthorized) => {
             Alert::warning("Incorrect username or password").with_timeout(Duration::from_secs(3))
         }
         Err(err) => Alert::from(err),
     }
 }
 
-pub async fn oauth_refresh_token(base_url: &str, refresh_token: &str) -> Option<OAuthGrant> {
+// Network errors and throttling leave the refresh token usable, so only an explicit
+// rejection means the session cannot be renewed anymore
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshError {
+    Rejected,
+    Failed,
+}
+
+pub async fn oauth_refresh_token(
+    base_url: &str,
+    refresh_token: &str,
+) -> Result<OAuthGrant, RefreshError> {
     log::debug!("Refreshing OAuth token");
 
     match HttpRequest::post(format!("{base_url}/auth/token"))
@@ -201,23 +419,173 @@ pub async fn oauth_refresh_token(base_url: &str, refresh_token: &str) -> Option<
         .await
         .and_then(|response| {
             serde_json::from_slice::<OAuthResponse>(response.as_slice()).map_err(Into::into)
+        })
+        .or_else(|err| match err {
+            ApiError::BadRequest { response } => {
+                serde_json::from_str(&response).map_err(Into::into)
+            }
+            err => Err(err),
         }) {
-        Ok(OAuthResponse::Granted(grant)) => Some(grant),
+        Ok(OAuthResponse::Granted(grant)) => Ok(grant),
         Ok(OAuthResponse::Error { error }) => {
             log::error!("OAuth failure: Server returned error code {error:?}");
-            None
+            Err(if error == ErrorType::InvalidGrant {
+                RefreshError::Rejected
+            } else {
+                RefreshError::Failed
+            })
+        }
+        Err(ApiError::Unauthorized) => {
+            log::error!("OAuth failure: Refresh token was not accepted");
+            Err(RefreshError::Rejected)
         }
         Err(err) => {
             log::error!("OAuth failure: {err:?}");
-            None
+            Err(RefreshError::Failed)
         }
     }
 }
 
+// Asks the server to invalidate a token (RFC 7009), revoking the refresh token ends
+// the whole grant while the access token alone only stops that token
+// The request is authenticated with the access token, the token being revoked is only
+// sent in the form body
+pub async fn oauth_revoke(
+    base_url: &str,
+    access_token: &str,
+    token: &str,
+    token_type: &str,
+) -> bool {
+    match HttpRequest::post(format!("{base_url}/auth/revoke"))
+        .with_header("Authorization", format!("Bearer {access_token}"))
+        .with_raw_body(
+            serde_urlencoded::to_string([("token", token), ("token_type_hint", token_type)])
+                .unwrap(),
+        )
+        .with_timeout(Duration::from_secs(3))
+        .send_raw()
+        .await
+    {
+        Ok(_) => true,
+        Err(err) => {
+            log::warn!("Failed to revoke OAuth {token_type}: {err:?}");
+            false
+        }
+    }
+}
+
+// Uses the cheapest endpoint available to every authenticated user, only an explicit
+// rejection invalidates the session so that network errors do not log the user out
+pub async fn validate_session(auth: &AuthToken) -> bool {
+    !matches!(
+        ApiClient::new(auth)
+            .get::<serde_json::Value>("/api/crypto")
+            .await,
+        Err(ApiError::Unauthorized)
+    )
+}
+
+pub fn init_session_check(pending: bool) -> SessionCheck {
+    let check = SessionCheck(create_rw_signal(pending));
+    provide_context(check);
+    check
+}
+
+pub fn use_session_check() -> SessionCheck {
+    expect_context::<SessionCheck>()
+}
+
+impl SessionCheck {
+    pub fn is_pending(&self) -> bool {
+        self.0.get()
+    }
+
+    pub fn is_pending_untracked(&self) -> bool {
+        self.0.get_untracked()
+    }
+
+    pub fn finish(&self) {
+        self.0.set(false);
+    }
+}
+
 pub fn use_authorization() -> RwSignal<AuthToken> {
     expect_context::<RwSignal<AuthToken>>()
 }
 
+pub fn init_refresh_timer() {
+    let timer = RefreshTimer {
+        handle: store_value(None),
+        expires_at: store_value(0),
+    };
+    provide_context(timer);
+    on_cleanup(move || timer.cancel());
+}
+
+pub fn use_refresh_timer() -> RefreshTimer {
+    expect_context::<RefreshTimer>()
+}
+
+impl RefreshTimer {
+    pub fn schedule(&self, auth_token: RwSignal<AuthToken>, expires_in: u64) {
+        self.cancel();
+        self.expires_at.set_value(now_secs() + expires_in);
+
+        // Refresh ahead of expiry so that requests in flight never carry a stale token
+        let expires_in = expires_in - (expires_in / 10).min(REFRESH_MARGIN_SECS);
+        log::debug!("Next OAuth token refresh in {} seconds.", expires_in);
+        let timer = *self;
+        match set_timeout_with_handle(
+            move || {
+                timer.handle.set_value(None);
+                // Picked up by the refresh resource, which exchanges the refresh token
+                auth_token.update(|auth_token| {
+                    auth_token.is_valid = false;
+                });
+            },
+            Duration::from_secs(expires_in),
+        ) {
+            Ok(handle) => self.handle.set_value(Some(handle)),
+            Err(err) => log::error!("Failed to schedule OAuth token refresh: {err:?}"),
+        }
+    }
+
+    pub fn cancel(&self) {
+        if let Some(handle) = self
+            .handle
+            .try_update_value(|handle| handle.take())
+            .flatten()
+        {
+            handle.clear();
+        }
+    }
+
+    // Time the current access token remains usable if it can't be renewed
+    pub fn seconds_left(&self) -> u64 {
+        self.expires_at.get_value().saturating_sub(now_secs())
+    }
+}
+
+pub fn now_secs() -> u64 {
+    (Date::now() / 1000.0) as u64
+}
+
+pub fn login_redirect_url() -> String {
+    let location = window().location();
+    let route = format!(
+        "{}{}",
+        location.pathname().unwrap_or_default(),
+        location.search().unwrap_or_default()
+    );
+
+    UrlBuilder::new("/login")
+        .with_optional_parameter(
+            "redirect",
+            Some(route).filter(|route| route != "/" && !route.starts_with("/login")),
+        )
+        .finish()
+}
+
 impl AuthToken {
     pub fn is_logged_in(&self) -> bool {
         !self.access_token.is_empty()
@@ -226,6 +594,41 @@ impl AuthToken {
     pub fn is_admin(&self) -> bool {
         self.is_admin && self.is_logged_in()
     }
+
+    pub fn default_route(&self) -> &'static str {
+        if self.is_admin {
+            "/manage/directory/accounts"
+        } else {
+            "/account/crypto"
+        }
+    }
+
+    pub fn is_allowed_route(&self, route: &str) -> bool {
+        let path = route.split(['?', '#']).next().unwrap_or_default();
+        if !path.starts_with('/')
+            || path.starts_with("//")
+            || path.contains('\\')
+            || path.split('/').any(|part| part == "..")
+        {
+            return false;
+        }
+
+        let prefixes: &[&str] = if self.is_admin {
+            &["/manage/", "/settings/", "/account/"]
+        } else {
+            &["/account/"]
+        };
+        prefixes.iter().any(|prefix| path.starts_with(prefix))
+    }
+
+    pub fn landing_route(&self, redirect: Option<&str>, prefs: &Preferences) -> String {
+        redirect
+            .into_iter()
+            .chain(prefs.landing_page.as_deref())
+            .find(|route| self.is_allowed_route(route))
+            .unwrap_or_else(|| self.default_route())
+            .to_string()
+    }
 }
 
 impl AsRef<AuthToken> for AuthToken {
@@ -233,3 +636,113 @@ impl AsRef<AuthToken> for AuthToken {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use base64::{
+        engine::general_purpose::{URL_SAFE, URL_SAFE_NO_PAD},
+        Engine,
+    };
+    use serde_json::json;
+
+    use super::{decode_jwt_claims, split_scope, ClaimsSource, TokenClaims};
+
+    fn jwt(payload: &str) -> String {
+        format!(
+            "{}.{}.signature",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+            URL_SAFE_NO_PAD.encode(payload)
+        )
+    }
+
+    #[test]
+    fn decode_claims() {
+        assert_eq!(
+            decode_jwt_claims(&jwt(r#"{"sub":"jane","exp":1700000000}"#)),
+            Some(json!({"sub": "jane", "exp": 1700000000}))
+        );
+
+        // Padding is not allowed by the spec but some issuers add it anyway
+        let padded = format!("header.{}.signature", URL_SAFE.encode(r#"{"sub":"j"}"#));
+        assert!(padded.split('.').nth(1).unwrap().ends_with('='));
+        assert_eq!(decode_jwt_claims(&padded), Some(json!({"sub": "j"})));
+
+        for token in [
+            jwt("[1, 2]"),
+            jwt(r#""jane""#),
+            jwt("null"),
+            jwt("not json"),
+            "header.!!!.signature".to_string(),
+            format!("{}.extra", jwt("{}")),
+            "header.payload".to_string(),
+            "opaque-token".to_string(),
+            String::new(),
+        ] {
+            assert_eq!(decode_jwt_claims(&token), None, "{token}");
+        }
+    }
+
+    #[test]
+    fn split_scopes() {
+        for (scope, expected) in [
+            ("openid email", vec!["openid", "email"]),
+            ("  openid\temail\n ", vec!["openid", "email"]),
+            ("admin", vec!["admin"]),
+            ("", vec![]),
+            ("   ", vec![]),
+        ] {
+            assert_eq!(split_scope(scope), expected, "{scope:?}");
+        }
+    }
+
+    #[test]
+    fn claims_from_json() {
+        for (claims, scopes, roles) in [
+            (
+                json!({"scope": "openid email", "roles": ["admin", "user"]}),
+                vec!["openid", "email"],
+                vec!["admin", "user"],
+            ),
+            (
+                json!({"scp": ["openid", "email"], "role": "admin user"}),
+                vec!["openid", "email"],
+                vec!["admin", "user"],
+            ),
+            // Both spellings are merged, in the order they are looked up
+            (
+                json!({"scope": "a", "scp": ["b"], "roles": "x", "role": ["y"]}),
+                vec!["a", "b"],
+                vec!["x", "y"],
+            ),
+            // Values of other types are ignored
+            (
+                json!({"scope": 1, "scp": ["a", 2, null], "roles": {"admin": true}}),
+                vec!["a"],
+                vec![],
+            ),
+            (json!({}), vec![], vec![]),
+        ] {
+            let parsed = TokenClaims::from_json(&claims, ClaimsSource::Jwt);
+            assert_eq!(parsed.scopes, scopes, "{claims}");
+            assert_eq!(parsed.roles, roles, "{claims}");
+        }
+
+        assert_eq!(
+            TokenClaims::from_json(
+                &json!({"exp": 1700000600, "iat": 1700000000}),
+                ClaimsSource::Introspection
+            ),
+            TokenClaims {
+                scopes: vec![],
+                roles: vec![],
+                expires_at: Some(1700000600),
+                issued_at: Some(1700000000),
+                source: ClaimsSource::Introspection,
+            }
+        );
+        assert_eq!(
+            TokenClaims::from_json(&json!({"exp": "soon"}), ClaimsSource::Jwt).expires_at,
+            None
+        );
+    }
+}
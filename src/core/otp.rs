@@ -0,0 +1,126 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+// Unpadded RFC 4648 base32, the encoding authenticator apps expect for secrets
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 8).div_ceil(5));
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    encoded
+}
+
+// Builds the otpauth:// URI understood by authenticator apps, see
+// https://github.com/google/google-authenticator/wiki/Key-Uri-Format
+pub fn otpauth_url(issuer: &str, account: &str, secret: &str) -> String {
+    let secret = secret
+        .chars()
+        .filter(|ch| !ch.is_whitespace() && *ch != '=')
+        .collect::<String>()
+        .to_ascii_uppercase();
+    let query = form_urlencoded::Serializer::new(String::new())
+        .append_pair("secret", &secret)
+        .append_pair("issuer", issuer)
+        .finish();
+
+    format!(
+        "otpauth://totp/{}:{}?{query}",
+        encode_label(issuer),
+        encode_label(account)
+    )
+}
+
+// Labels are part of the path, where '+' would not be read as a space
+fn encode_label(label: &str) -> String {
+    form_urlencoded::byte_serialize(label.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base32() {
+        for (bytes, expected) in [
+            (&b""[..], ""),
+            (b"f", "MY"),
+            (b"fo", "MZXQ"),
+            (b"foo", "MZXW6"),
+            (b"foob", "MZXW6YQ"),
+            (b"fooba", "MZXW6YTB"),
+            (b"foobar", "MZXW6YTBOI"),
+        ] {
+            assert_eq!(base32_encode(bytes), expected, "{bytes:?}");
+        }
+    }
+
+    #[test]
+    fn otpauth_urls() {
+        for (issuer, account, secret, expected) in [
+            (
+                "Example",
+                "jane@example.org",
+                "JBSWY3DPEHPK3PXP",
+                "otpauth://totp/Example:jane%40example.org?secret=JBSWY3DPEHPK3PXP&issuer=Example",
+            ),
+            // Spaces are %20 in the label but + in the query, as apps expect
+            (
+                "Mail Server",
+                "jane doe",
+                "jbsw y3dp ehpk 3pxp==",
+                "otpauth://totp/Mail%20Server:jane%20doe?secret=JBSWY3DPEHPK3PXP&issuer=Mail+Server",
+            ),
+            // A colon in either part would otherwise split the label in the wrong place
+            (
+                "a:b&c",
+                "user:1/2?x",
+                "MZXW6",
+                "otpauth://totp/a%3Ab%26c:user%3A1%2F2%3Fx?secret=MZXW6&issuer=a%3Ab%26c",
+            ),
+            (
+                "Sérvidor",
+                "josé",
+                "MZXW6",
+                "otpauth://totp/S%C3%A9rvidor:jos%C3%A9?secret=MZXW6&issuer=S%C3%A9rvidor",
+            ),
+        ] {
+            assert_eq!(otpauth_url(issuer, account, secret), expected);
+        }
+    }
+}
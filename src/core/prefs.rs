@@ -0,0 +1,319 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use ahash::AHashMap;
+use leptos::{
+    create_effect, create_rw_signal, expect_context, provide_context, spawn_local, store_value,
+    RwSignal, SignalGet, SignalGetUntracked, SignalSet,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{pages::config::UpdateSettings, STATE_PREFERENCES_KEY, STATE_SLIDING_SESSION_PREF_KEY};
+
+use super::{
+    api::{ApiClient, ApiResult},
+    oauth::AuthToken,
+    storage::{LocalStorage, Storage},
+    url::UrlBuilder,
+};
+
+pub const LANDING_PAGES: &[(&str, &str)] = &[
+    ("", "Default"),
+    ("/manage/dashboard", "Dashboard"),
+    ("/manage/directory/accounts", "Accounts"),
+    ("/manage/directory/domains", "Domains"),
+    ("/manage/queue/messages", "Message Queue"),
+    ("/manage/reports/dmarc", "DMARC Reports"),
+    ("/manage/logs", "Logs"),
+    ("/settings/network/edit", "Settings"),
+    ("/account/crypto", "Encryption-at-rest"),
+    ("/account/password", "Change Password"),
+];
+
+const SERVER_PREFERENCES_KEY: &str = "webadmin.preferences";
+
+pub const DEFAULT_CERT_EXPIRY_DAYS: u32 = 30;
+pub const DEFAULT_QUEUE_WARNING_THRESHOLD: u64 = 100;
+pub const DEFAULT_QUEUE_CRITICAL_THRESHOLD: u64 = 1000;
+pub const DEFAULT_PAGE_SIZE: u32 = 10;
+
+// How long a session may sit unused in storage once its tab has been closed
+pub const SLIDING_SESSION_IDLE_SECS: u64 = 8 * 60 * 60;
+
+pub const SESSION_SCOPE_OPTIONS: &[(&str, &str)] = &[
+    ("false", "Until the tab is closed"),
+    ("true", "Up to 8 hours after closing the tab"),
+];
+
+pub const PAGE_SIZE_OPTIONS: &[(&str, &str)] = &[
+    ("10", "10 per page"),
+    ("25", "25 per page"),
+    ("50", "50 per page"),
+    ("100", "100 per page"),
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub landing_page: Option<String>,
+    pub cert_expiry_days: Option<u32>,
+    pub queue_warning_threshold: Option<u64>,
+    pub queue_critical_threshold: Option<u64>,
+    pub input_masks: Option<bool>,
+    pub strict_search: Option<bool>,
+    pub high_contrast: Option<bool>,
+    pub page_size: Option<u32>,
+    // Whether this browser may keep a session is not for other devices to decide, so it
+    // is stored under its own key and never synced
+    #[serde(skip)]
+    pub sliding_session: Option<bool>,
+    pub list_views: AHashMap<String, ListView>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListView {
+    pub sort: Option<ListSort>,
+    pub page_size: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListSort {
+    pub column: usize,
+    pub ascending: bool,
+}
+
+pub fn init_preferences() {
+    provide_context(create_rw_signal(Preferences::load()));
+}
+
+pub fn use_preferences() -> RwSignal<Preferences> {
+    expect_context::<RwSignal<Preferences>>()
+}
+
+// Mirrors preferences to the server so they follow the user across devices, local storage
+// remains the cache and the only store when the server rejects the request
+pub fn init_preferences_sync(auth: RwSignal<AuthToken>) {
+    let prefs = use_preferences();
+    let synced_user = store_value(None::<String>);
+    let synced_prefs = store_value(None::<Preferences>);
+
+    create_effect(move |previous_user: Option<String>| {
+        let auth = auth.get();
+        let username = if auth.is_valid {
+            auth.username.to_string()
+        } else {
+            String::new()
+        };
+
+        if username.is_empty() {
+            synced_user.set_value(None);
+        } else if previous_user.as_deref() != Some(username.as_str()) {
+            synced_user.set_value(None);
+            let username = username.clone();
+            spawn_local(async move {
+                match Preferences::fetch(&auth).await {
+                    Ok(Some(mut server_prefs)) => {
+                        server_prefs.sliding_session = prefs.get_untracked().sliding_session;
+                        server_prefs.save();
+                        synced_prefs.set_value(Some(server_prefs.clone()));
+                        synced_user.set_value(Some(username));
+                        prefs.set(server_prefs);
+                    }
+                    Ok(None) => {
+                        let local_prefs = prefs.get_untracked();
+                        match local_prefs.store(&auth).await {
+                            Ok(_) => {
+                                synced_prefs.set_value(Some(local_prefs));
+                                synced_user.set_value(Some(username));
+                            }
+                            Err(err) => {
+                                log::debug!("Server-side preferences unavailable: {err:?}");
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        log::debug!("Server-side preferences unavailable: {err:?}");
+                    }
+                }
+            });
+        }
+
+        username
+    });
+
+    create_effect(move |_| {
+        // Device-local settings never reach the server, changing them needs no sync
+        let current = Preferences {
+            sliding_session: None,
+            ..prefs.get()
+        };
+        if synced_user.get_value().is_none()
+            || synced_prefs.with_value(|synced| synced.as_ref() == Some(&current))
+        {
+            return;
+        }
+
+        synced_prefs.set_value(Some(current.clone()));
+        let auth = auth.get_untracked();
+        spawn_local(async move {
+            if let Err(err) = current.store(&auth).await {
+                log::warn!("Failed to save preferences to the server: {err:?}");
+            }
+        });
+    });
+}
+
+impl Preferences {
+    pub fn load() -> Self {
+        Preferences {
+            sliding_session: LocalStorage::get(STATE_SLIDING_SESSION_PREF_KEY).ok(),
+            ..LocalStorage::get(STATE_PREFERENCES_KEY).unwrap_or_default()
+        }
+    }
+
+    pub fn cert_expiry_days(&self) -> u32 {
+        self.cert_expiry_days.unwrap_or(DEFAULT_CERT_EXPIRY_DAYS)
+    }
+
+    pub fn queue_warning_threshold(&self) -> u64 {
+        self.queue_warning_threshold
+            .unwrap_or(DEFAULT_QUEUE_WARNING_THRESHOLD)
+    }
+
+    pub fn queue_critical_threshold(&self) -> u64 {
+        self.queue_critical_threshold
+            .unwrap_or(DEFAULT_QUEUE_CRITICAL_THRESHOLD)
+    }
+
+    pub fn input_masks(&self) -> bool {
+        self.input_masks.unwrap_or(true)
+    }
+
+    pub fn fuzzy_search(&self) -> bool {
+        !self.strict_search.unwrap_or(false)
+    }
+
+    pub fn page_size(&self) -> u32 {
+        self.page_size.unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+
+    // Sessions are tab-scoped unless the user opted into outliving the tab
+    pub fn sliding_session(&self) -> bool {
+        self.sliding_session.unwrap_or(false)
+    }
+
+    // A page size chosen on the list itself wins over the app-wide default
+    pub fn list_page_size(&self, list: &str) -> u32 {
+        self.list_view(list)
+            .page_size
+            .unwrap_or_else(|| self.page_size())
+    }
+
+    pub fn list_view(&self, list: &str) -> ListView {
+        self.list_views.get(list).cloned().unwrap_or_default()
+    }
+
+    pub fn set_list_view(&mut self, list: &str, view: ListView) {
+        if view == ListView::default() {
+            self.list_views.remove(list);
+        } else {
+            self.list_views.insert(list.to_string(), view);
+        }
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = LocalStorage::set(STATE_PREFERENCES_KEY, self) {
+            log::error!("Failed to save preferences to local storage: {}", err);
+        }
+        let result = match self.sliding_session {
+            Some(sliding_session) => {
+                LocalStorage::set(STATE_SLIDING_SESSION_PREF_KEY, sliding_session)
+            }
+            None => {
+                LocalStorage::delete(STATE_SLIDING_SESSION_PREF_KEY);
+                Ok(())
+            }
+        };
+        if let Err(err) = result {
+            log::error!(
+                "Failed to save session preference to local storage: {}",
+                err
+            );
+        }
+    }
+
+    async fn fetch(auth: &AuthToken) -> ApiResult<Option<Self>> {
+        let key = server_key(auth);
+        let mut values = ApiClient::new(auth)
+            .get::<AHashMap<String, Option<String>>>(
+                UrlBuilder::new("/api/settings/keys").with_parameter("keys", key.clone()),
+            )
+            .await?;
+
+        Ok(values
+            .remove(&key)
+            .flatten()
+            .and_then(|value| match serde_json::from_str(&value) {
+                Ok(prefs) => Some(prefs),
+                Err(err) => {
+                    log::warn!("Ignoring invalid server-side preferences: {err}");
+                    None
+                }
+            }))
+    }
+
+    async fn store(&self, auth: &AuthToken) -> ApiResult<()> {
+        let value = serde_json::to_string(self)?;
+        ApiClient::new(auth)
+            .create::<_, Option<String>>(
+                "/api/settings",
+                vec![UpdateSettings::Insert {
+                    prefix: None,
+                    values: vec![(server_key(auth), value)],
+                    assert_empty: false,
+                }],
+            )
+            .await
+            .map(|_| ())
+    }
+}
+
+fn server_key(auth: &AuthToken) -> String {
+    format!("{SERVER_PREFERENCES_KEY}.{}", auth.username)
+}
+
+impl ListSort {
+    pub fn toggle(current: Option<ListSort>, column: usize) -> ListSort {
+        match current {
+            Some(sort) if sort.column == column => ListSort {
+                column,
+                ascending: !sort.ascending,
+            },
+            _ => ListSort {
+                column,
+                ascending: true,
+            },
+        }
+    }
+}
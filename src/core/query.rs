@@ -0,0 +1,129 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use leptos::{leptos_dom::helpers::TimeoutHandle, *};
+use leptos_router::{use_location, use_navigate, NavigateOptions};
+
+const QUERY_DEBOUNCE_MS: u64 = 300;
+
+// A typed value kept in sync with a query parameter so it is shareable and survives
+// a refresh. Reads follow the URL, writes replace the current history entry once
+// they settle instead of adding one per keystroke.
+pub struct QueryParam<T: 'static> {
+    value: Memo<Option<T>>,
+    set: Callback<Option<T>>,
+}
+
+// Derived impls would require T: Copy, the signals themselves always are
+impl<T> Clone for QueryParam<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for QueryParam<T> {}
+
+pub fn use_query_param<T>(key: &'static str) -> QueryParam<T>
+where
+    T: FromStr + Display + Clone + PartialEq + 'static,
+{
+    let location = use_location();
+    let value = create_memo(move |_| {
+        location
+            .query
+            .with(|query| query.get(key).and_then(|value| value.parse::<T>().ok()))
+    });
+
+    let navigate = use_navigate();
+    let update_url = Callback::new(move |value: Option<String>| {
+        let mut query = location.query.get_untracked();
+        match value {
+            Some(value) => {
+                query.insert(key.to_string(), value);
+            }
+            None => {
+                query.remove(key);
+            }
+        }
+        navigate(
+            &format!(
+                "{}{}",
+                location.pathname.get_untracked(),
+                query.to_query_string()
+            ),
+            NavigateOptions {
+                replace: true,
+                scroll: false,
+                ..Default::default()
+            },
+        );
+    });
+
+    // A write still pending when the page is left would navigate back to it
+    let pending = store_value(None::<TimeoutHandle>);
+    let cancel = move || {
+        if let Some(handle) = pending.try_update_value(Option::take).flatten() {
+            handle.clear();
+        }
+    };
+    on_cleanup(cancel);
+
+    let set = Callback::new(move |new_value: Option<T>| {
+        cancel();
+        if value.get_untracked() != new_value {
+            let new_value = new_value.map(|value| value.to_string());
+            match set_timeout_with_handle(
+                move || {
+                    pending.set_value(None);
+                    update_url.call(new_value);
+                },
+                Duration::from_millis(QUERY_DEBOUNCE_MS),
+            ) {
+                Ok(handle) => pending.set_value(Some(handle)),
+                Err(err) => log::warn!("Failed to schedule query update: {err:?}"),
+            }
+        }
+    });
+
+    QueryParam { value, set }
+}
+
+impl<T: Clone + PartialEq + 'static> QueryParam<T> {
+    pub fn get(&self) -> Option<T> {
+        self.value.get()
+    }
+
+    pub fn get_untracked(&self) -> Option<T> {
+        self.value.get_untracked()
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.value.with(Option::is_some)
+    }
+
+    pub fn set(&self, value: Option<T>) {
+        self.set.call(value);
+    }
+}
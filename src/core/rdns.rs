@@ -0,0 +1,160 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::{net::IpAddr, time::Duration};
+
+use ahash::AHashMap;
+use leptos::{
+    create_rw_signal, expect_context, provide_context, set_timeout, spawn_local, store_value,
+    RwSignal, Signal, SignalGetUntracked, SignalUpdate, SignalUpdateUntracked, SignalWith,
+    SignalWithUntracked, StoredValue,
+};
+use web_sys::js_sys::Date;
+
+use super::{
+    api::{ApiClient, ApiError},
+    oauth::AuthToken,
+};
+
+// Lookups requested within this window are sent to the server together
+const BATCH_DELAY: Duration = Duration::from_millis(50);
+const CACHE_TTL_MS: f64 = 10.0 * 60.0 * 1000.0;
+const FAILURE_TTL_MS: f64 = 60.0 * 1000.0;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Lookup {
+    Pending,
+    Resolved { names: Vec<String>, expires: u64 },
+}
+
+#[derive(Clone, Copy)]
+pub struct ReverseDns {
+    auth: RwSignal<AuthToken>,
+    cache: RwSignal<AHashMap<IpAddr, Lookup>>,
+    queue: StoredValue<Vec<IpAddr>>,
+    is_supported: StoredValue<bool>,
+}
+
+pub fn init_reverse_dns(auth: RwSignal<AuthToken>) {
+    provide_context(ReverseDns {
+        auth,
+        cache: create_rw_signal(AHashMap::new()),
+        queue: store_value(Vec::new()),
+        is_supported: store_value(true),
+    });
+}
+
+pub fn use_reverse_dns() -> ReverseDns {
+    expect_context::<ReverseDns>()
+}
+
+impl ReverseDns {
+    pub fn resolve(&self, ip: IpAddr) -> Signal<Option<String>> {
+        self.request(ip);
+
+        let cache = self.cache;
+        Signal::derive(move || {
+            cache.with(|cache| match cache.get(&ip) {
+                Some(Lookup::Resolved { names, .. }) => names.first().cloned(),
+                _ => None,
+            })
+        })
+    }
+
+    fn request(&self, ip: IpAddr) {
+        if !self.is_supported.get_value() {
+            return;
+        }
+
+        // Skip addresses that are already cached or part of an in-flight batch
+        let now = Date::now() as u64;
+        let is_known = self.cache.with_untracked(|cache| match cache.get(&ip) {
+            Some(Lookup::Pending) => true,
+            Some(Lookup::Resolved { expires, .. }) => *expires > now,
+            None => false,
+        });
+        if is_known {
+            return;
+        }
+        self.cache.update_untracked(|cache| {
+            cache.insert(ip, Lookup::Pending);
+        });
+
+        let schedule_flush = self.queue.with_value(|queue| queue.is_empty());
+        self.queue.update_value(|queue| queue.push(ip));
+        if schedule_flush {
+            let reverse_dns = *self;
+            set_timeout(move || reverse_dns.flush(), BATCH_DELAY);
+        }
+    }
+
+    fn flush(&self) {
+        let ips = self
+            .queue
+            .try_update_value(std::mem::take)
+            .unwrap_or_default();
+        if ips.is_empty() {
+            return;
+        }
+
+        let reverse_dns = *self;
+        let auth = self.auth.get_untracked();
+        spawn_local(async move {
+            let result = ApiClient::new(auth)
+                .create::<_, AHashMap<String, Vec<String>>>("/api/dns/ptr", &ips)
+                .await;
+            let mut resolved = match result {
+                Ok(resolved) => resolved
+                    .into_iter()
+                    .filter_map(|(ip, names)| Some((ip.parse::<IpAddr>().ok()?, names)))
+                    .collect::<AHashMap<_, _>>(),
+                Err(ApiError::NotFound) => {
+                    log::debug!("Reverse DNS lookups are not supported by the server");
+                    reverse_dns.is_supported.set_value(false);
+                    AHashMap::new()
+                }
+                Err(err) => {
+                    log::debug!("Reverse DNS lookup failed: {err:?}");
+                    AHashMap::new()
+                }
+            };
+
+            let now = Date::now();
+            reverse_dns.cache.update(|cache| {
+                for ip in ips {
+                    let (names, ttl) = match resolved.remove(&ip) {
+                        Some(names) => (names, CACHE_TTL_MS),
+                        None => (Vec::new(), FAILURE_TTL_MS),
+                    };
+                    cache.insert(
+                        ip,
+                        Lookup::Resolved {
+                            names,
+                            expires: (now + ttl) as u64,
+                        },
+                    );
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,167 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use ahash::AHashMap;
+use leptos::{
+    create_effect, create_rw_signal, expect_context, provide_context, RwSignal, SignalGetUntracked,
+    SignalSet, SignalUpdate, SignalWith, SignalWithUntracked,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::STATE_RECENTLY_VIEWED_KEY;
+
+use super::{
+    oauth::AuthToken,
+    storage::{LocalStorage, Storage},
+};
+
+pub const MAX_RECENTLY_VIEWED: usize = 8;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentItem {
+    pub id: String,
+    pub typ: String,
+    pub label: String,
+    pub route: String,
+}
+
+// Items are kept per user so that shared devices do not mix up histories
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct RecentlyViewedStore(AHashMap<String, Vec<RecentItem>>);
+
+#[derive(Clone, Copy)]
+pub struct RecentlyViewed {
+    username: RwSignal<String>,
+    items: RwSignal<Vec<RecentItem>>,
+}
+
+pub fn init_recently_viewed(auth: RwSignal<AuthToken>) {
+    let recent = RecentlyViewed {
+        username: create_rw_signal(String::new()),
+        items: create_rw_signal(Vec::new()),
+    };
+    provide_context(recent);
+
+    // Token refreshes update the session without changing who is signed in, the list is
+    // only reloaded when the user does
+    create_effect(move |previous_user: Option<String>| {
+        let username = auth.with(|auth| {
+            if auth.is_logged_in() {
+                auth.username.to_string()
+            } else {
+                String::new()
+            }
+        });
+        if previous_user.as_deref() != Some(username.as_str()) {
+            recent.items.set(if !username.is_empty() {
+                RecentlyViewedStore::load()
+                    .0
+                    .remove(&username)
+                    .unwrap_or_default()
+            } else {
+                Vec::new()
+            });
+            recent.username.set(username.clone());
+        }
+
+        username
+    });
+}
+
+pub fn use_recently_viewed() -> RecentlyViewed {
+    expect_context::<RecentlyViewed>()
+}
+
+impl RecentlyViewed {
+    pub fn items(&self) -> RwSignal<Vec<RecentItem>> {
+        self.items
+    }
+
+    pub fn record(
+        &self,
+        typ: impl Into<String>,
+        id: impl Into<String>,
+        label: impl Into<String>,
+        route: impl Into<String>,
+    ) {
+        let item = RecentItem {
+            id: id.into(),
+            typ: typ.into(),
+            label: label.into(),
+            route: route.into(),
+        };
+        if self
+            .items
+            .with_untracked(|items| items.first() == Some(&item))
+        {
+            return;
+        }
+
+        self.items.update(|items| {
+            items.retain(|i| i.typ != item.typ || i.id != item.id);
+            items.insert(0, item);
+            items.truncate(MAX_RECENTLY_VIEWED);
+        });
+        self.save();
+    }
+
+    pub fn clear(&self) {
+        self.items.set(Vec::new());
+        self.save();
+    }
+
+    fn save(&self) {
+        let username = self.username.get_untracked();
+        if username.is_empty() {
+            return;
+        }
+
+        let mut store = RecentlyViewedStore::load();
+        let items = self.items.get_untracked();
+        if items.is_empty() {
+            store.0.remove(&username);
+        } else {
+            store.0.insert(username, items);
+        }
+
+        let result = if store.0.is_empty() {
+            LocalStorage::delete(STATE_RECENTLY_VIEWED_KEY);
+            Ok(())
+        } else {
+            LocalStorage::set(STATE_RECENTLY_VIEWED_KEY, &store)
+        };
+        if let Err(err) = result {
+            log::error!(
+                "Failed to save recently viewed items to local storage: {}",
+                err
+            );
+        }
+    }
+}
+
+impl RecentlyViewedStore {
+    fn load() -> Self {
+        LocalStorage::get(STATE_RECENTLY_VIEWED_KEY).unwrap_or_default()
+    }
+}
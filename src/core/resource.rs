@@ -0,0 +1,114 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+use leptos_router::use_navigate;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::pages::List;
+
+use super::{
+    api::{ApiClient, ApiError, ApiResult},
+    oauth::{login_redirect_url, use_authorization},
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ListQuery {
+    pub page: u32,
+    pub limit: u32,
+    pub params: Vec<(&'static str, String)>,
+}
+
+impl ListQuery {
+    pub fn new(page: u32, limit: u32) -> Self {
+        Self {
+            page,
+            limit,
+            params: Vec::new(),
+        }
+    }
+
+    pub fn with_parameter(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.params.push((key, value.into()));
+        self
+    }
+
+    pub fn with_optional_parameter(
+        self,
+        key: &'static str,
+        value: Option<impl Into<String>>,
+    ) -> Self {
+        match value {
+            Some(value) => self.with_parameter(key, value),
+            None => self,
+        }
+    }
+}
+
+pub fn use_list_resource<T>(
+    endpoint: &'static str,
+    query: impl Fn() -> ListQuery + 'static,
+) -> Resource<ListQuery, ApiResult<List<T>>>
+where
+    T: Serialize + DeserializeOwned + Clone + 'static,
+{
+    let auth = use_authorization();
+    let navigate = use_navigate();
+
+    create_resource(query, move |query| {
+        let auth = auth.get_untracked();
+        let navigate = navigate.clone();
+
+        async move {
+            let result = ApiClient::new(&auth).list::<T>(endpoint, &query).await;
+            if matches!(result, Err(ApiError::Unauthorized)) {
+                navigate(&login_redirect_url(), Default::default());
+            }
+            result
+        }
+    })
+}
+
+pub fn use_item_resource<T>(
+    endpoint: &'static str,
+    id: impl Fn() -> String + 'static,
+) -> Resource<String, ApiResult<T>>
+where
+    T: Serialize + DeserializeOwned + Clone + 'static,
+{
+    let auth = use_authorization();
+    let navigate = use_navigate();
+
+    create_resource(id, move |id| {
+        let auth = auth.get_untracked();
+        let navigate = navigate.clone();
+
+        async move {
+            let result = ApiClient::new(&auth).get::<T>((endpoint, &id)).await;
+            if matches!(result, Err(ApiError::Unauthorized)) {
+                navigate(&login_redirect_url(), Default::default());
+            }
+            result
+        }
+    })
+}
@@ -24,6 +24,7 @@
 use std::{hash::Hasher, sync::Arc};
 
 use ahash::AHashMap;
+use chrono::NaiveDate;
 
 use super::form::{FormData, FormValue};
 
@@ -54,6 +55,8 @@ pub enum Type<S, F> {
     Rate,
     Size,
     Cron,
+    Date,
+    File,
 }
 
 #[derive(Clone, Default, Debug)]
@@ -68,6 +71,28 @@ pub struct Field {
     pub placeholder: Value<&'static str>,
     pub display: Vec<Eval>,
     pub readonly: bool,
+    pub inline_edit: bool,
+    pub mask: Option<InputMask>,
+    pub on_change: Vec<LinkedValues>,
+    pub deprecated: Option<Deprecation>,
+    pub references: Option<Arc<Schema>>,
+    pub hide_transform_hint: bool,
+    pub sensitive: bool,
+    pub confirms: Option<&'static str>,
+    pub checkbox: bool,
+    pub rows: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Deprecation {
+    pub message: &'static str,
+    pub hide_if_unset: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinkedValues {
+    pub target: &'static str,
+    pub mapping: &'static [(&'static str, &'static str)],
 }
 
 #[derive(Clone, Default, Debug)]
@@ -80,6 +105,7 @@ pub struct Schema {
     pub reload_prefix: Option<&'static str>,
     pub list: List,
     pub form: Form,
+    pub advisory: Option<fn(&FormData) -> Vec<String>>,
 }
 
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
@@ -115,7 +141,7 @@ pub struct List {
     pub subtitle: &'static str,
     pub fields: Vec<Arc<Field>>,
     pub actions: Vec<Action>,
-    pub page_size: u32,
+    pub page_size: Option<u32>,
 }
 
 #[derive(Clone, Default, Debug)]
@@ -142,6 +168,7 @@ pub struct Section {
     pub title: Option<&'static str>,
     pub display: Vec<Eval>,
     pub fields: Vec<Arc<Field>>,
+    pub groups: Vec<Vec<&'static str>>,
 }
 
 #[derive(Clone, Debug)]
@@ -191,6 +218,15 @@ pub enum Transformer {
     RemoveSpaces,
     Lowercase,
     Uppercase,
+    NormalizeSocketAddr,
+    SecondsToDuration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputMask {
+    Numeric,
+    IpOrMask,
+    SocketAddr,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -211,7 +247,17 @@ pub enum Validator {
     MaxValue(NumberType),
     MinItems(usize),
     MaxItems(usize),
+    IsDate,
+    MinDate(NaiveDate),
+    MaxDate(NaiveDate),
     IsValidExpression(ExpressionValidator),
+    IsSieveScript,
+    IsDuration,
+    // Six digit code from an authenticator app
+    IsOtpCode,
+    MaxFileSize(u64),
+    // MIME types such as "text/plain" or "image/*", or extensions such as ".pem"
+    FileType(&'static [&'static str]),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -246,6 +292,8 @@ impl From<Arc<Schema>> for FormData {
             values: Default::default(),
             errors: Default::default(),
             external_sources: Default::default(),
+            files: Default::default(),
+            history: Default::default(),
             schema,
             is_update: false,
         }
@@ -300,6 +348,101 @@ impl Schema {
     }
 }
 
+impl Schema {
+    // Fields whose values must match the id of a record in another schema
+    pub fn references(&self) -> impl Iterator<Item = (Arc<Field>, Arc<Schema>)> + '_ {
+        self.fields.values().filter_map(|field| match &field.typ_ {
+            Type::Select {
+                source: Source::Dynamic { schema, .. },
+                ..
+            } => Some((field.clone(), schema.clone())),
+            _ => field
+                .references
+                .as_ref()
+                .map(|schema| (field.clone(), schema.clone())),
+        })
+    }
+}
+
+impl LinkedValues {
+    pub fn lookup(&self, value: &str) -> Option<&'static str> {
+        self.mapping
+            .iter()
+            .find_map(|(key, target)| (*key == value).then_some(*target))
+    }
+}
+
+impl InputMask {
+    pub fn hint(&self) -> &'static str {
+        match self {
+            InputMask::Numeric => "0",
+            InputMask::IpOrMask => "192.168.0.0/24",
+            InputMask::SocketAddr => "0.0.0.0:25",
+        }
+    }
+
+    pub fn apply(&self, value: &str) -> String {
+        match self {
+            InputMask::Numeric => value.chars().filter(|ch| ch.is_ascii_digit()).collect(),
+            InputMask::IpOrMask => {
+                let value = value
+                    .chars()
+                    .filter(|ch| ch.is_ascii_hexdigit() || matches!(ch, '.' | ':' | '/'))
+                    .collect::<String>();
+                insert_ipv4_separators(&value, '/').unwrap_or(value)
+            }
+            InputMask::SocketAddr => {
+                let value = value
+                    .chars()
+                    .filter(|ch| ch.is_ascii_hexdigit() || matches!(ch, '.' | ':' | '[' | ']'))
+                    .collect::<String>();
+                insert_ipv4_separators(&value, ':').unwrap_or(value)
+            }
+        }
+    }
+}
+
+// Splits dotted quads when an octet would exceed three digits, leaving IPv6 input untouched
+fn insert_ipv4_separators(value: &str, terminator: char) -> Option<String> {
+    let (address, suffix) = value.split_once(terminator).unwrap_or((value, ""));
+    if !address.chars().all(|ch| ch.is_ascii_digit() || ch == '.') {
+        return None;
+    }
+
+    let mut result = String::with_capacity(value.len() + 4);
+    let mut octets = 1;
+    let mut digits = 0;
+    let mut chars = address.chars();
+    for ch in chars.by_ref() {
+        if ch == '.' {
+            octets += 1;
+            digits = 0;
+        } else if digits == 3 {
+            if octets == 4 {
+                if value.len() > address.len() {
+                    return None;
+                }
+                result.push(terminator);
+                result.push(ch);
+                break;
+            }
+            result.push('.');
+            octets += 1;
+            digits = 1;
+        } else {
+            digits += 1;
+        }
+        result.push(ch);
+    }
+    result.extend(chars);
+    if value.len() > address.len() {
+        result.push(terminator);
+        result.push_str(suffix);
+    }
+
+    Some(result)
+}
+
 impl Field {
     pub fn value(&self, settings: &FormData) -> String {
         settings
@@ -309,7 +452,13 @@ impl Field {
     }
 
     pub fn display(&self, settings: &FormData) -> bool {
-        self.display.is_empty() || self.display.iter().any(|eval| eval.eval(settings))
+        (self.display.is_empty() || self.display.iter().any(|eval| eval.eval(settings)))
+            && !(self.deprecated.is_some_and(|d| d.hide_if_unset)
+                && settings.value_is_empty(self.id))
+    }
+
+    pub fn is_sensitive(&self) -> bool {
+        self.sensitive || matches!(self.typ_, Type::Secret)
     }
 
     pub fn placeholder(&self, settings: &FormData) -> Option<&str> {
@@ -332,6 +481,23 @@ impl Field {
                 .unwrap_or_default()
     }
 
+    // Older versions stored some durations as plain seconds
+    pub fn accepts_seconds(&self) -> bool {
+        self.checks
+            .default
+            .iter()
+            .chain(self.checks.if_thens.iter().map(|if_then| &if_then.value))
+            .any(|check| check.transformers.contains(&Transformer::SecondsToDuration))
+    }
+
+    pub fn is_sieve_script(&self) -> bool {
+        self.checks
+            .default
+            .iter()
+            .chain(self.checks.if_thens.iter().map(|if_then| &if_then.value))
+            .any(|check| check.validators.contains(&Validator::IsSieveScript))
+    }
+
     pub fn is_multivalue(&self) -> bool {
         matches!(
             self.typ_,
@@ -366,6 +532,13 @@ impl Section {
     pub fn display(&self, settings: &FormData) -> bool {
         self.display.is_empty() || self.display.iter().any(|eval| eval.eval(settings))
     }
+
+    pub fn group(&self, field: &str) -> Option<&[&'static str]> {
+        self.groups
+            .iter()
+            .find(|group| group.contains(&field))
+            .map(|group| group.as_slice())
+    }
 }
 
 impl Schemas {
@@ -375,6 +548,41 @@ impl Schemas {
             item: (),
         }
     }
+
+    pub fn is_known_key(&self, key: &str) -> bool {
+        self.fields_for_key(key).next().is_some()
+            || self.schemas.values().any(|schema| match &schema.typ {
+                SchemaType::Entry { prefix } => key
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('.')),
+                _ => false,
+            })
+    }
+
+    pub fn is_sensitive_key(&self, key: &str) -> bool {
+        self.fields_for_key(key).any(|field| field.is_sensitive())
+    }
+
+    // Resolves a flat settings key, e.g. "server.listener.smtp.bind", to its schema fields
+    fn fields_for_key<'x>(&'x self, key: &'x str) -> impl Iterator<Item = &'x Arc<Field>> + 'x {
+        self.schemas.values().flat_map(move |schema| {
+            let field_key = match &schema.typ {
+                SchemaType::Record { prefix, .. } => key
+                    .strip_prefix(prefix)
+                    .and_then(|key| key.strip_prefix('.'))
+                    .and_then(|key| key.split_once('.'))
+                    .map(|(_, field_key)| field_key),
+                SchemaType::List => Some(key),
+                SchemaType::Entry { .. } => None,
+            };
+
+            schema.fields.values().filter(move |field| {
+                field_key
+                    .and_then(|field_key| field_key.strip_prefix(field.id))
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+            })
+        })
+    }
 }
 
 impl Builder<Schemas, ()> {
@@ -391,7 +599,6 @@ impl Builder<Schemas, ()> {
                         Action::Modify,
                         Action::Reload,
                     ],
-                    page_size: 10,
                     ..Default::default()
                 },
                 form: Form {
@@ -476,6 +683,12 @@ impl Builder<Schemas, Schema> {
         self
     }
 
+    // Non-blocking warnings about likely-wrong combinations of field values
+    pub fn advisory(mut self, advisory: fn(&FormData) -> Vec<String>) -> Self {
+        self.item.advisory = Some(advisory);
+        self
+    }
+
     pub fn list_title(mut self, title: &'static str) -> Self {
         self.item.list.title = title;
         self
@@ -614,6 +827,19 @@ impl Builder<(Schemas, Schema), Field> {
         self
     }
 
+    pub fn inline_edit(mut self) -> Self {
+        if self.item.readonly
+            || !matches!(
+                self.item.typ_,
+                Type::Input | Type::Boolean | Type::Duration | Type::Rate | Type::Size
+            )
+        {
+            panic!("Field {:?} does not support inline editing.", self.item.id);
+        }
+        self.item.inline_edit = true;
+        self
+    }
+
     pub fn typ(mut self, typ_: Type<&'static str, &'static str>) -> Self {
         self.item.typ_ = match typ_ {
             Type::Select {
@@ -705,6 +931,76 @@ impl Builder<(Schemas, Schema), Field> {
         self
     }
 
+    pub fn sensitive(mut self) -> Self {
+        self.item.sensitive = true;
+        self
+    }
+
+    pub fn hide_transform_hint(mut self) -> Self {
+        self.item.hide_transform_hint = true;
+        self
+    }
+
+    pub fn references(mut self, schema: &'static str) -> Self {
+        if !matches!(self.item.typ_, Type::Input | Type::Array) {
+            panic!("Field {:?} cannot reference other records.", self.item.id);
+        }
+        self.item.references = Some(self.schema(schema));
+        self
+    }
+
+    pub fn deprecated(mut self, message: &'static str) -> Self {
+        self.item.deprecated = Some(Deprecation {
+            message,
+            hide_if_unset: false,
+        });
+        self
+    }
+
+    // Keeps the field out of the form unless an existing config still sets it
+    pub fn deprecated_hidden(mut self, message: &'static str) -> Self {
+        self.item.deprecated = Some(Deprecation {
+            message,
+            hide_if_unset: true,
+        });
+        self
+    }
+
+    pub fn on_change_set(
+        mut self,
+        target: &'static str,
+        mapping: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        self.item.on_change.push(LinkedValues { target, mapping });
+        self
+    }
+
+    // Renders a boolean as a checkbox rather than an on/off switch, for options that
+    // read as a list of choices
+    pub fn checkbox(mut self) -> Self {
+        if !matches!(self.item.typ_, Type::Boolean) {
+            panic!("Field {:?} cannot be rendered as a checkbox.", self.item.id);
+        }
+        self.item.checkbox = true;
+        self
+    }
+
+    pub fn rows(mut self, rows: usize) -> Self {
+        if !matches!(self.item.typ_, Type::Text) {
+            panic!("Field {:?} does not support rows.", self.item.id);
+        }
+        self.item.rows = Some(rows);
+        self
+    }
+
+    pub fn mask(mut self, mask: InputMask) -> Self {
+        if !matches!(self.item.typ_, Type::Input | Type::Array) {
+            panic!("Field {:?} does not support input masks.", self.item.id);
+        }
+        self.item.mask = Some(mask);
+        self
+    }
+
     pub fn placeholder(mut self, placeholder: &'static str) -> Self {
         self.item.placeholder.push_else(placeholder);
         self
@@ -771,6 +1067,14 @@ impl Builder<(Schemas, Schema), Field> {
         }
     }
 
+    // Adds a secret field that must repeat the value of `target`, e.g. a password confirmation
+    pub fn new_confirm_field(self, id: &'static str, target: &'static str) -> Self {
+        let mut builder = self.new_field(id).typ(Type::Secret);
+        builder.item.checks = Default::default();
+        builder.item.confirms = Some(target);
+        builder
+    }
+
     pub fn new_field(mut self, id: &'static str) -> Self {
         let cloned_field = Field {
             id,
@@ -821,6 +1125,24 @@ impl Builder<(Schemas, Schema), Section> {
         builder
     }
 
+    // Lays out the given fields side by side on a single row
+    pub fn group(mut self, fields: impl IntoIterator<Item = &'static str>) -> Self {
+        let group = fields.into_iter().collect::<Vec<_>>();
+        if !(2..=4).contains(&group.len()) {
+            panic!("Field groups must have between 2 and 4 fields.");
+        }
+        for field in &group {
+            if !self.item.fields.iter().any(|f| f.id == *field) {
+                panic!("Field {field:?} not found in section.");
+            }
+            if self.item.group(field).is_some() {
+                panic!("Field {field:?} already belongs to a group.");
+            }
+        }
+        self.item.groups.push(group);
+        self
+    }
+
     fn display_if(
         mut self,
         field: &'static str,
@@ -919,6 +1241,33 @@ impl InputCheck {
             validators: validators.into_iter().collect(),
         }
     }
+
+    // Describes how the value will be rewritten, e.g. "Input will be lowercased"
+    pub fn transform_hint(&self) -> Option<String> {
+        let hints = self
+            .transformers
+            .iter()
+            .filter_map(|transformer| transformer.hint())
+            .collect::<Vec<_>>();
+        match hints.as_slice() {
+            [] => None,
+            [hint] => Some(format!("Input will be {hint}")),
+            [hints @ .., last] => Some(format!("Input will be {} and {last}", hints.join(", "))),
+        }
+    }
+}
+
+impl Transformer {
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Transformer::Trim => None,
+            Transformer::RemoveSpaces => Some("stripped of spaces"),
+            Transformer::Lowercase => Some("lowercased"),
+            Transformer::Uppercase => Some("uppercased"),
+            Transformer::NormalizeSocketAddr => Some("normalized"),
+            Transformer::SecondsToDuration => Some("read as seconds when no unit is given"),
+        }
+    }
 }
 
 impl ExpressionValidator {
@@ -952,6 +1301,8 @@ impl From<Type<&'static str, &'static str>> for Type<Arc<Schema>, Arc<Field>> {
             Type::Text => Type::Text,
             Type::Size => Type::Size,
             Type::Cron => Type::Cron,
+            Type::Date => Type::Date,
+            Type::File => Type::File,
             Type::Rate => Type::Rate,
             Type::Select {
                 source: Source::Static(items),
@@ -0,0 +1,636 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Comment,
+    String,
+    Number,
+    Tag,
+    Identifier,
+    Punctuation(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SieveError {
+    pub line: usize,
+    pub message: String,
+}
+
+const CONTROL_COMMANDS: &[&str] = &["require", "if", "elsif", "else", "stop"];
+
+pub fn is_control(name: &str) -> bool {
+    CONTROL_COMMANDS
+        .iter()
+        .any(|command| command.eq_ignore_ascii_case(name))
+}
+
+// Splits a script into tokens, stopping at the first lexical error. Whitespace is not
+// returned so callers rendering the script should emit the gaps between tokens verbatim.
+pub fn tokenize(script: &str) -> (Vec<Token>, Option<SieveError>) {
+    let bytes = script.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut line = 1;
+
+    while pos < bytes.len() {
+        let start = pos;
+        let start_line = line;
+        let mut error = None;
+
+        let kind = match bytes[pos] {
+            b'\n' => {
+                line += 1;
+                pos += 1;
+                continue;
+            }
+            b' ' | b'\t' | b'\r' => {
+                pos += 1;
+                continue;
+            }
+            b'#' => {
+                while pos < bytes.len() && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+                TokenKind::Comment
+            }
+            b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                match script[pos + 2..].find("*/") {
+                    Some(end) => pos += end + 4,
+                    None => {
+                        pos = bytes.len();
+                        error = Some("unterminated comment");
+                    }
+                }
+                line += count_lines(&script[start..pos]);
+                TokenKind::Comment
+            }
+            b'"' => {
+                pos += 1;
+                loop {
+                    match bytes.get(pos) {
+                        Some(b'"') => {
+                            pos += 1;
+                            break;
+                        }
+                        Some(b'\\') => {
+                            if bytes.get(pos + 1) == Some(&b'\n') {
+                                line += 1;
+                            }
+                            pos = (pos + 2).min(bytes.len());
+                        }
+                        Some(ch) => {
+                            if *ch == b'\n' {
+                                line += 1;
+                            }
+                            pos += 1;
+                        }
+                        None => {
+                            error = Some("unterminated string");
+                            break;
+                        }
+                    }
+                }
+                TokenKind::String
+            }
+            b'0'..=b'9' => {
+                while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+                if matches!(
+                    bytes.get(pos),
+                    Some(b'K' | b'M' | b'G' | b'k' | b'm' | b'g')
+                ) {
+                    pos += 1;
+                }
+                TokenKind::Number
+            }
+            b':' => {
+                pos += 1;
+                if bytes.get(pos).is_some_and(|ch| is_identifier_start(*ch)) {
+                    pos = skip_identifier(bytes, pos);
+                } else {
+                    error = Some("expected a tag name after ':'");
+                }
+                TokenKind::Tag
+            }
+            ch if is_identifier_start(ch) => {
+                pos = skip_identifier(bytes, pos);
+                if script[start..pos].eq_ignore_ascii_case("text") && bytes.get(pos) == Some(&b':')
+                {
+                    pos += 1;
+                    if let Err(err) = skip_multiline(script, &mut pos, &mut line) {
+                        error = Some(err);
+                    }
+                    TokenKind::String
+                } else {
+                    TokenKind::Identifier
+                }
+            }
+            ch @ (b';' | b',' | b'{' | b'}' | b'[' | b']' | b'(' | b')') => {
+                pos += 1;
+                TokenKind::Punctuation(ch)
+            }
+            _ => {
+                let ch = script[pos..].chars().next().unwrap_or_default();
+                return (
+                    tokens,
+                    Some(SieveError::new(
+                        line,
+                        format!("unexpected character '{ch}'"),
+                    )),
+                );
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            start,
+            end: pos,
+            line: start_line,
+        });
+
+        if let Some(error) = error {
+            return (tokens, Some(SieveError::new(start_line, error)));
+        }
+    }
+
+    (tokens, None)
+}
+
+// Structural validation of a script: lexical errors, balanced blocks and lists,
+// statement terminators and the placement of control commands
+pub fn check_script(script: &str) -> Result<(), SieveError> {
+    let (tokens, error) = tokenize(script);
+    if let Some(error) = error {
+        return Err(error);
+    }
+    let tokens = tokens
+        .into_iter()
+        .filter(|token| token.kind != TokenKind::Comment)
+        .collect::<Vec<_>>();
+
+    Parser {
+        script,
+        tokens: &tokens,
+        pos: 0,
+    }
+    .commands(false)
+}
+
+struct Parser<'x> {
+    script: &'x str,
+    tokens: &'x [Token],
+    pos: usize,
+}
+
+impl<'x> Parser<'x> {
+    fn commands(&mut self, nested: bool) -> Result<(), SieveError> {
+        let mut allow_require = !nested;
+        let mut previous = String::new();
+
+        loop {
+            let token = match self.next() {
+                Some(token) => token,
+                None if nested => return Err(self.error_at(None, "missing '}'")),
+                None => return Ok(()),
+            };
+
+            match token.kind {
+                TokenKind::Identifier => {
+                    let name = self.text(&token).to_ascii_lowercase();
+                    match name.as_str() {
+                        "require" if !allow_require => {
+                            return Err(self.error_at(
+                                Some(&token),
+                                "'require' must appear before any other command",
+                            ));
+                        }
+                        "elsif" | "else" if !matches!(previous.as_str(), "if" | "elsif") => {
+                            return Err(self.error_at(
+                                Some(&token),
+                                format!("'{name}' without a preceding 'if'"),
+                            ));
+                        }
+                        _ => {}
+                    }
+                    if name != "require" {
+                        allow_require = false;
+                    }
+
+                    self.command(&name, &token)?;
+                    previous = name;
+                }
+                TokenKind::Punctuation(b'}') if nested => return Ok(()),
+                _ => {
+                    return Err(self.error_at(
+                        Some(&token),
+                        format!("expected a command, found '{}'", self.text(&token)),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn command(&mut self, name: &str, token: &Token) -> Result<(), SieveError> {
+        let (has_arguments, has_test) = self.arguments()?;
+
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::Punctuation(b';'),
+                ..
+            }) => {
+                if matches!(name, "if" | "elsif" | "else") {
+                    return Err(
+                        self.error_at(Some(token), format!("'{name}' must be followed by a block"))
+                    );
+                }
+            }
+            Some(Token {
+                kind: TokenKind::Punctuation(b'{'),
+                ..
+            }) => {
+                self.commands(true)?;
+            }
+            next => {
+                return Err(self.error_at(
+                    next.as_ref().or(Some(token)),
+                    format!("expected ';' or '{{' after '{name}'"),
+                ));
+            }
+        }
+
+        match name {
+            "if" | "elsif" if !has_test => {
+                Err(self.error_at(Some(token), format!("'{name}' requires a test")))
+            }
+            "else" if has_arguments => {
+                Err(self.error_at(Some(token), "'else' does not take arguments"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Returns whether any arguments were present and whether they ended with a test
+    fn arguments(&mut self) -> Result<(bool, bool), SieveError> {
+        let mut has_arguments = false;
+
+        loop {
+            match self.peek_kind() {
+                Some(TokenKind::String | TokenKind::Number | TokenKind::Tag) => {
+                    self.pos += 1;
+                }
+                Some(TokenKind::Punctuation(b'[')) => {
+                    self.pos += 1;
+                    self.string_list()?;
+                }
+                _ => break,
+            }
+            has_arguments = true;
+        }
+
+        match self.peek_kind() {
+            // Tests never follow positional arguments, this is usually a missing ';'
+            Some(TokenKind::Identifier) if has_arguments => {
+                let token = self.tokens[self.pos];
+                Err(self.error_at(
+                    Some(&token),
+                    format!("unexpected '{}' after arguments", self.text(&token)),
+                ))
+            }
+            Some(TokenKind::Identifier) => {
+                self.pos += 1;
+                self.arguments()?;
+                Ok((true, true))
+            }
+            Some(TokenKind::Punctuation(b'(')) => {
+                self.pos += 1;
+                self.test_list()?;
+                Ok((true, true))
+            }
+            _ => Ok((has_arguments, false)),
+        }
+    }
+
+    fn test_list(&mut self) -> Result<(), SieveError> {
+        loop {
+            match self.next() {
+                Some(Token {
+                    kind: TokenKind::Identifier,
+                    ..
+                }) => {
+                    self.arguments()?;
+                }
+                next => return Err(self.error_at(next.as_ref(), "expected a test")),
+            }
+            match self.next() {
+                Some(Token {
+                    kind: TokenKind::Punctuation(b','),
+                    ..
+                }) => {}
+                Some(Token {
+                    kind: TokenKind::Punctuation(b')'),
+                    ..
+                }) => return Ok(()),
+                next => {
+                    return Err(self.error_at(next.as_ref(), "expected ',' or ')' in test list"))
+                }
+            }
+        }
+    }
+
+    fn string_list(&mut self) -> Result<(), SieveError> {
+        loop {
+            match self.next() {
+                Some(Token {
+                    kind: TokenKind::String,
+                    ..
+                }) => {}
+                next => return Err(self.error_at(next.as_ref(), "expected a string in list")),
+            }
+            match self.next() {
+                Some(Token {
+                    kind: TokenKind::Punctuation(b','),
+                    ..
+                }) => {}
+                Some(Token {
+                    kind: TokenKind::Punctuation(b']'),
+                    ..
+                }) => return Ok(()),
+                next => {
+                    return Err(self.error_at(next.as_ref(), "expected ',' or ']' in string list"))
+                }
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).copied();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn peek_kind(&self) -> Option<TokenKind> {
+        self.tokens.get(self.pos).map(|token| token.kind)
+    }
+
+    fn text(&self, token: &Token) -> &'x str {
+        &self.script[token.start..token.end]
+    }
+
+    fn error_at(&self, token: Option<&Token>, message: impl Into<String>) -> SieveError {
+        let line = token
+            .or(self.tokens.last())
+            .map(|token| token.line)
+            .unwrap_or(1);
+        let message = message.into();
+        if token.is_none() {
+            SieveError::new(line, format!("unexpected end of script, {message}"))
+        } else {
+            SieveError::new(line, message)
+        }
+    }
+}
+
+impl SieveError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        SieveError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for SieveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// Consumes a "text:" string up to and including the line holding a single dot
+fn skip_multiline(script: &str, pos: &mut usize, line: &mut usize) -> Result<(), &'static str> {
+    let bytes = script.as_bytes();
+    while matches!(bytes.get(*pos), Some(b' ' | b'\t' | b'\r')) {
+        *pos += 1;
+    }
+    if bytes.get(*pos) == Some(&b'#') {
+        while *pos < bytes.len() && bytes[*pos] != b'\n' {
+            *pos += 1;
+        }
+    }
+    if bytes.get(*pos) != Some(&b'\n') {
+        return Err("expected a new line after 'text:'");
+    }
+    *pos += 1;
+    *line += 1;
+
+    loop {
+        let eol = script[*pos..].find('\n').map(|eol| *pos + eol);
+        let text = &script[*pos..eol.unwrap_or(script.len())];
+        let is_end = text.trim_end_matches('\r') == ".";
+        match eol {
+            Some(eol) => {
+                *pos = eol + 1;
+                *line += 1;
+                if is_end {
+                    return Ok(());
+                }
+            }
+            None => {
+                *pos = script.len();
+                return if is_end {
+                    Ok(())
+                } else {
+                    Err("unterminated multi-line string")
+                };
+            }
+        }
+    }
+}
+
+fn skip_identifier(bytes: &[u8], mut pos: usize) -> usize {
+    while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+        pos += 1;
+    }
+    pos
+}
+
+fn is_identifier_start(ch: u8) -> bool {
+    ch.is_ascii_alphabetic() || ch == b'_'
+}
+
+fn count_lines(text: &str) -> usize {
+    text.bytes().filter(|ch| *ch == b'\n').count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(script: &str) -> Vec<(TokenKind, &str)> {
+        let (tokens, error) = tokenize(script);
+        assert_eq!(error, None, "{script:?}");
+        tokens
+            .iter()
+            .map(|token| (token.kind, &script[token.start..token.end]))
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_script() {
+        assert_eq!(
+            kinds("if size :over 1M { # big\n  fileinto \"a\\\"b\"; }"),
+            vec![
+                (TokenKind::Identifier, "if"),
+                (TokenKind::Identifier, "size"),
+                (TokenKind::Tag, ":over"),
+                (TokenKind::Number, "1M"),
+                (TokenKind::Punctuation(b'{'), "{"),
+                (TokenKind::Comment, "# big"),
+                (TokenKind::Identifier, "fileinto"),
+                (TokenKind::String, "\"a\\\"b\""),
+                (TokenKind::Punctuation(b';'), ";"),
+                (TokenKind::Punctuation(b'}'), "}"),
+            ]
+        );
+        assert_eq!(
+            kinds("/* a\nb */ reject text:\nline\n.\n;"),
+            vec![
+                (TokenKind::Comment, "/* a\nb */"),
+                (TokenKind::Identifier, "reject"),
+                (TokenKind::String, "text:\nline\n.\n"),
+                (TokenKind::Punctuation(b';'), ";"),
+            ]
+        );
+
+        let (tokens, _) = tokenize("keep;\n\n  stop;");
+        assert_eq!(tokens[2].line, 3);
+    }
+
+    #[test]
+    fn tokenize_errors() {
+        for (script, line, message) in [
+            ("keep;\nfileinto \"a;", 2, "unterminated string"),
+            ("/* open", 1, "unterminated comment"),
+            ("reject text:\nno end", 1, "unterminated multi-line string"),
+            ("reject text: x", 1, "expected a new line after 'text:'"),
+            ("if header : \"a\"", 1, "expected a tag name after ':'"),
+            ("keep;\n\nkeep @", 3, "unexpected character '@'"),
+        ] {
+            assert_eq!(
+                tokenize(script).1,
+                Some(SieveError::new(line, message)),
+                "{script:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn valid_scripts() {
+        for script in [
+            "",
+            "# only a comment",
+            "keep;",
+            "require [\"fileinto\", \"reject\"];\nrequire \"body\";\nstop;",
+            concat!(
+                "require \"fileinto\";\n",
+                "if header :contains \"subject\" \"money\" {\n",
+                "  fileinto \"Spam\";\n",
+                "} elsif anyof (not exists \"from\", size :over 100K) {\n",
+                "  discard;\n",
+                "} else {\n",
+                "  keep;\n",
+                "}\n",
+            ),
+            "if true { if false { stop; } }",
+            "reject text:\nGo away\n.\n;",
+        ] {
+            assert_eq!(check_script(script), Ok(()), "{script:?}");
+        }
+    }
+
+    #[test]
+    fn invalid_scripts() {
+        for (script, line, message) in [
+            (
+                "fileinto \"a\" stop;",
+                1,
+                "unexpected 'stop' after arguments",
+            ),
+            (
+                "keep;\nfileinto \"a\"\nstop;",
+                3,
+                "unexpected 'stop' after arguments",
+            ),
+            ("keep", 1, "expected ';' or '{' after 'keep'"),
+            (
+                "if true { keep;",
+                1,
+                "unexpected end of script, missing '}'",
+            ),
+            ("if true { keep; } }", 1, "expected a command, found '}'"),
+            (
+                "if header :is [\"a\", \"b\" \"c\";",
+                1,
+                "expected ',' or ']' in string list",
+            ),
+            (
+                "if anyof (true, false] { }",
+                1,
+                "expected ',' or ')' in test list",
+            ),
+            ("if true;", 1, "'if' must be followed by a block"),
+            ("if { keep; }", 1, "'if' requires a test"),
+            ("else { keep; }", 1, "'else' without a preceding 'if'"),
+            (
+                "if true { } else \"a\" { }",
+                1,
+                "'else' does not take arguments",
+            ),
+            (
+                "keep;\nrequire \"fileinto\";",
+                2,
+                "'require' must appear before any other command",
+            ),
+            ("keep;\n\"unterminated", 2, "unterminated string"),
+        ] {
+            assert_eq!(
+                check_script(script),
+                Err(SieveError::new(line, message)),
+                "{script:?}"
+            );
+        }
+    }
+}
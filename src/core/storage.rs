@@ -0,0 +1,112 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::fmt;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+const PROBE_KEY: &str = "webadmin_storage_probe";
+
+// Drop-in replacement for gloo's storage wrappers that doesn't panic when the
+// browser denies access, private and locked-down modes then run in memory only
+pub trait Storage {
+    fn raw() -> Option<web_sys::Storage>;
+
+    fn get<T: DeserializeOwned>(key: impl AsRef<str>) -> Result<T, StorageError> {
+        let key = key.as_ref();
+        let item = Self::raw()
+            .ok_or(StorageError::Unavailable)?
+            .get_item(key)
+            .map_err(|err| StorageError::Js(format!("{err:?}")))?
+            .ok_or_else(|| StorageError::KeyNotFound(key.to_string()))?;
+        serde_json::from_str(&item).map_err(StorageError::Serde)
+    }
+
+    fn set<T: Serialize>(key: impl AsRef<str>, value: T) -> Result<(), StorageError> {
+        let value = serde_json::to_string(&value).map_err(StorageError::Serde)?;
+        Self::raw()
+            .ok_or(StorageError::Unavailable)?
+            .set_item(key.as_ref(), &value)
+            .map_err(|err| StorageError::Js(format!("{err:?}")))
+    }
+
+    fn delete(key: impl AsRef<str>) {
+        if let Some(storage) = Self::raw() {
+            let _ = storage.remove_item(key.as_ref());
+        }
+    }
+
+    fn contains(key: impl AsRef<str>) -> bool {
+        Self::raw()
+            .and_then(|storage| storage.get_item(key.as_ref()).ok().flatten())
+            .is_some()
+    }
+
+    // Some browsers expose the storage but refuse every write
+    fn is_writable() -> bool {
+        Self::raw().is_some_and(|storage| {
+            storage
+                .set_item(PROBE_KEY, PROBE_KEY)
+                .and_then(|_| storage.remove_item(PROBE_KEY))
+                .is_ok()
+        })
+    }
+}
+
+pub struct LocalStorage;
+pub struct SessionStorage;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Unavailable,
+    KeyNotFound(String),
+    Serde(serde_json::Error),
+    Js(String),
+}
+
+impl Storage for LocalStorage {
+    fn raw() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok().flatten()
+    }
+}
+
+impl Storage for SessionStorage {
+    fn raw() -> Option<web_sys::Storage> {
+        web_sys::window()?.session_storage().ok().flatten()
+    }
+}
+
+pub fn is_storage_available() -> bool {
+    LocalStorage::is_writable() && SessionStorage::is_writable()
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Unavailable => f.write_str("storage is not available in this browser"),
+            StorageError::KeyNotFound(key) => write!(f, "key {key:?} not found"),
+            StorageError::Serde(err) => write!(f, "serialization error: {err}"),
+            StorageError::Js(err) => write!(f, "browser error: {err}"),
+        }
+    }
+}
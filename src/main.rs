@@ -28,12 +28,11 @@ use std::{sync::Arc, time::Duration};
 use components::{
     icon::{
         IconAdjustmentsHorizontal, IconDocumentChartBar, IconDocumentText, IconKey, IconLockClosed,
-        IconQueueList, IconShieldCheck, IconUserGroup, IconWrench,
+        IconQueueList, IconShieldCheck, IconSquares2x2, IconUserCircle, IconUserGroup, IconWrench,
     },
     layout::MenuItem,
 };
-use gloo_storage::{SessionStorage, Storage};
-use leptos::*;
+use leptos::{leptos_dom::helpers::TimeoutHandle, *};
 use leptos_meta::*;
 use leptos_router::*;
 use pages::{
@@ -41,30 +40,72 @@ use pages::{
     manage::spam::{SpamTest, SpamTrain},
 };
 
-pub static VERSION_NAME: &str = concat!("Stalwart Management UI v", env!("CARGO_PKG_VERSION"),);
+pub static VERSION: &str = env!("CARGO_PKG_VERSION");
+pub static PRODUCT_NAME: &str = match option_env!("WEBADMIN_PRODUCT_NAME") {
+    Some(name) => name,
+    None => "Stalwart Management UI",
+};
+pub static LOGO_URL: &str = match option_env!("WEBADMIN_LOGO_URL") {
+    Some(url) => url,
+    None => "/logo.svg",
+};
 
 use crate::{
     components::{
-        layout::{Layout, LayoutBuilder},
-        messages::{alert::init_alerts, modal::init_modals},
+        layout::{GuardedLayout, LayoutBuilder},
+        messages::{
+            alert::{defer_alert, init_alerts, start_countdown, Alert},
+            banner::StorageBanner,
+            console::LogConsole,
+            error::init_panic_handler,
+            modal::{init_modals, use_modals, Modal},
+        },
+        progress::LoadingBar,
+    },
+    core::{
+        a11y::init_high_contrast,
+        clock::init_clock_check,
+        loading::init_loading_indicator,
+        logging::{init_log_console, init_logging},
+        oauth::{
+            init_refresh_timer, init_session_check, oauth_refresh_token, update_token_claims,
+            use_refresh_timer, validate_session, AuthToken, RefreshError,
+        },
+        prefs::{init_preferences, init_preferences_sync, use_preferences},
+        rdns::init_reverse_dns,
+        recent::init_recently_viewed,
+        storage::{SessionStorage, Storage},
     },
-    core::oauth::{oauth_refresh_token, AuthToken},
     pages::{
-        account::{crypto::ManageCrypto, password::ChangePassword},
+        account::{
+            crypto::ManageCrypto, password::ChangePassword, preferences::ManagePreferences,
+            session::SessionInfo, totp::ManageTotp,
+        },
         authorize::Authorize,
-        config::{edit::SettingsEdit, list::SettingsList, search::SettingsSearch},
+        config::{
+            edit::SettingsEdit, import::SettingsImport, list::SettingsList, search::SettingsSearch,
+        },
         directory::{
             domains::{display::DomainDisplay, edit::DomainCreate, list::DomainList},
             principals::{edit::PrincipalEdit, list::PrincipalList},
         },
-        login::Login,
-        manage::{logs::Logs, maintenance::Maintenance},
+        login::{
+            clear_session, forget_remembered_session, forget_sliding_session,
+            restore_remembered_session, restore_sliding_session, sign_out,
+            update_remembered_session, update_sliding_session, Login,
+        },
+        manage::{
+            dashboard::Dashboard, logs::Logs, maintenance::Maintenance, restore::RestoreConfig,
+        },
         notfound::NotFound,
         queue::{
             messages::{list::QueueList, manage::QueueManage},
             reports::{display::ReportDisplay, list::ReportList},
         },
-        reports::{display::IncomingReportDisplay, list::IncomingReportList},
+        reports::{
+            display::IncomingReportDisplay, init_report_sequence, inspect::ReportInspect,
+            list::IncomingReportList,
+        },
     },
 };
 
@@ -74,10 +115,29 @@ pub mod pages;
 
 pub const STATE_STORAGE_KEY: &str = "webadmin_state";
 pub const STATE_LOGIN_NAME_KEY: &str = "webadmin_login_name";
+pub const STATE_REMEMBERED_SESSION_KEY: &str = "webadmin_remembered_session";
+pub const STATE_SLIDING_SESSION_KEY: &str = "webadmin_sliding_session";
+pub const STATE_SLIDING_SESSION_PREF_KEY: &str = "webadmin_sliding_session_pref";
+pub const STATE_PREFERENCES_KEY: &str = "webadmin_preferences";
+pub const STATE_LOG_SETTINGS_KEY: &str = "webadmin_log_settings";
+pub const STATE_CUSTOM_HEADERS_KEY: &str = "webadmin_custom_headers";
+pub const STATE_RECENTLY_VIEWED_KEY: &str = "webadmin_recently_viewed";
+
+pub fn version_name() -> String {
+    format!("{PRODUCT_NAME} v{VERSION}")
+}
+
+pub fn page_title(text: String) -> String {
+    if !text.is_empty() {
+        format!("{text} — {PRODUCT_NAME}")
+    } else {
+        PRODUCT_NAME.to_string()
+    }
+}
 
 fn main() {
-    _ = console_log::init_with_level(log::Level::Debug);
-    console_error_panic_hook::set_once();
+    init_logging();
+    init_panic_handler();
     leptos::mount_to_body(|| view! { <App/> })
 }
 
@@ -85,6 +145,9 @@ fn main() {
 pub fn App() -> impl IntoView {
     let auth_token = create_rw_signal(
         SessionStorage::get::<AuthToken>(STATE_STORAGE_KEY)
+            .ok()
+            .or_else(restore_remembered_session)
+            .or_else(restore_sliding_session)
             .map(|mut t| {
                 // Force token refresh on reload
                 t.is_valid = false;
@@ -97,215 +160,215 @@ pub fn App() -> impl IntoView {
     provide_context(build_schemas());
     init_alerts();
     init_modals();
+    let loading = init_loading_indicator();
+    init_preferences();
+    init_preferences_sync(auth_token);
+    let prefs = use_preferences();
+    init_recently_viewed(auth_token);
+    init_clock_check(auth_token);
+    init_reverse_dns(auth_token);
+    init_high_contrast();
+    init_log_console();
+    init_report_sequence();
+    init_refresh_timer();
+    let refresh_timer = use_refresh_timer();
+    let session_check = auth_token.with_untracked(|auth_token| {
+        init_session_check(auth_token.is_logged_in() || !auth_token.refresh_token.is_empty())
+    });
+
+    // Contexts are not reachable from the refresh future once it has been suspended
+    let owner = Owner::current();
+    let modal = use_modals();
+    // Bumped to retry a failed refresh while the access token is still accepted
+    let refresh_attempt = create_rw_signal(0u32);
+    // Persisted sessions are kept, they are only forgotten once the server rejects them
+    let end_session = move |expired: bool| {
+        SessionStorage::delete(STATE_STORAGE_KEY);
+        auth_token.set(AuthToken::default());
+        if expired {
+            if let Some(owner) = owner {
+                with_owner(owner, || {
+                    defer_alert(Alert::warning("Session expired").with_details(
+                        "Your session could not be renewed, sign in again to continue.",
+                    ))
+                });
+            }
+        }
+    };
+    // Gives the user a chance to retry the refresh before unsaved changes are lost. Failed
+    // retries reopen the same warning, the deadline does not move so neither does the timer.
+    let remaining = create_rw_signal(0u64);
+    let expiry = store_value(None::<TimeoutHandle>);
+    let cancel_expiry = move || {
+        if let Some(handle) = expiry.try_update_value(|handle| handle.take()).flatten() {
+            handle.clear();
+        }
+        remaining.set(0);
+    };
+    let warn_expiry = move |seconds_left: u64| {
+        let Some(owner) = owner else {
+            return end_session(true);
+        };
+        if expiry.with_value(Option::is_none) {
+            start_countdown(remaining, Duration::from_secs(seconds_left));
+            expiry.set_value(
+                set_timeout_with_handle(
+                    move || {
+                        expiry.set_value(None);
+                        if auth_token.with_untracked(|auth| auth.is_logged_in() && !auth.is_valid) {
+                            modal.update(|modal| modal.is_open = false);
+                            end_session(true);
+                        }
+                    },
+                    Duration::from_secs(seconds_left),
+                )
+                .ok(),
+            );
+        }
+        with_owner(owner, || {
+            modal.set(
+                Modal::with_title("Session about to expire")
+                    .with_message(
+                        "Your session could not be renewed. Any unsaved changes will be lost once it expires.",
+                    )
+                    .with_countdown(remaining)
+                    .with_button("Stay signed in")
+                    .with_callback(move || refresh_attempt.update(|attempt| *attempt += 1))
+                    .with_secondary_button("Sign out", move || {
+                        cancel_expiry();
+                        spawn_local(sign_out(auth_token, refresh_timer));
+                    }),
+            );
+        });
+    };
 
     // Create a resource to refresh the OAuth token
     let _refresh_token_resource = create_resource(
-        move || auth_token.get(),
-        move |changed_auth_token| {
+        move || (auth_token.get(), refresh_attempt.get()),
+        move |(changed_auth_token, _)| {
             let changed_auth_token = changed_auth_token.clone();
 
             async move {
                 if !changed_auth_token.is_valid && !changed_auth_token.refresh_token.is_empty() {
-                    if let Some(grant) = oauth_refresh_token(
+                    match oauth_refresh_token(
                         &changed_auth_token.base_url,
                         &changed_auth_token.refresh_token,
                     )
                     .await
                     {
-                        let refresh_token = grant.refresh_token.unwrap_or_default();
-                        auth_token.update(|auth_token| {
-                            auth_token.access_token = grant.access_token.into();
-                            auth_token.refresh_token = refresh_token.clone().into();
-                            auth_token.is_valid = true;
+                        Ok(grant) => {
+                            // A successful refresh proves the restored session is still good
+                            session_check.finish();
+                            cancel_expiry();
+                            let refresh_token = grant.refresh_token.clone().unwrap_or_default();
+                            auth_token.update(|auth_token| {
+                                auth_token.access_token = grant.access_token.clone().into();
+                                auth_token.refresh_token = refresh_token.clone().into();
+                                auth_token.is_valid = true;
+                                auth_token.claims = None;
 
-                            if let Err(err) =
-                                SessionStorage::set(STATE_STORAGE_KEY, auth_token.clone())
-                            {
-                                log::error!(
-                                    "Failed to save authorization token to session storage: {}",
-                                    err
-                                );
+                                if let Err(err) =
+                                    SessionStorage::set(STATE_STORAGE_KEY, auth_token.clone())
+                                {
+                                    log::error!(
+                                        "Failed to save authorization token to session storage: {}",
+                                        err
+                                    );
+                                }
+                                update_remembered_session(auth_token);
+                                update_sliding_session(auth_token, &prefs.get_untracked());
+                            });
+                            // Set timer to refresh token
+                            if grant.expires_in > 0 && !refresh_token.is_empty() {
+                                refresh_timer.schedule(auth_token, grant.expires_in);
+                            }
+                            update_token_claims(auth_token, &grant).await;
+                        }
+                        Err(err) => {
+                            // Restoring a persisted session would be rejected the same way
+                            if err == RefreshError::Rejected {
+                                forget_remembered_session();
+                                forget_sliding_session();
+                            }
+                            // Once the access token expires the guarded layout sends the
+                            // user to the login page
+                            if !session_check.is_pending_untracked() {
+                                match refresh_timer.seconds_left() {
+                                    0 => end_session(true),
+                                    seconds_left => warn_expiry(seconds_left),
+                                }
                             }
-                        });
-                        // Set timer to refresh token
-                        if grant.expires_in > 0 && !refresh_token.is_empty() {
-                            log::debug!(
-                                "Next OAuth token refresh in {} seconds.",
-                                grant.expires_in
-                            );
-                            set_timeout(
-                                move || {
-                                    auth_token.update(|auth_token| {
-                                        auth_token.is_valid = false;
-                                    });
-                                },
-                                Duration::from_secs(grant.expires_in),
-                            );
                         }
                     }
                 }
+
+                // Validate restored sessions before rendering protected content
+                if session_check.is_pending_untracked() {
+                    if !validate_session(&changed_auth_token).await {
+                        log::debug!("Restored session is no longer valid");
+                        clear_session(auth_token);
+                    }
+                    session_check.finish();
+                }
             }
         },
     );
 
-    let is_logged_in = create_memo(move |_| auth_token.get().is_logged_in());
-    let is_admin = create_memo(move |_| auth_token.get().is_admin());
-
     view! {
-        <Router>
+        <Title formatter=page_title text=""/>
+        <Meta name="application-name" content=PRODUCT_NAME/>
+        <Router set_is_routing=SignalSetter::map(move |routing| loading.set_routing(routing))>
+            <LoadingBar/>
+            <StorageBanner/>
             <Routes>
-                <ProtectedRoute
+                <Route
                     path="/manage"
-                    view=move || {
-                        view! { <Layout menu_items=LayoutBuilder::manage() is_admin=is_admin/> }
+                    view=|| {
+                        view! { <GuardedLayout menu_items=LayoutBuilder::manage admin_only=true/> }
                     }
-
-                    redirect_path="/login"
-                    condition=move || is_logged_in.get()
                 >
-                    <ProtectedRoute
-                        path="/directory/domains"
-                        view=DomainList
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/directory/domains/edit"
-                        view=DomainCreate
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/directory/domains/:id/view"
-                        view=DomainDisplay
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
+                    <Route path="/dashboard" view=Dashboard/>
+                    <Route path="/directory/domains" view=DomainList/>
+                    <Route path="/directory/domains/edit" view=DomainCreate/>
+                    <Route path="/directory/domains/:id/view" view=DomainDisplay/>
 
-                    <ProtectedRoute
-                        path="/directory/:object"
-                        view=PrincipalList
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/directory/:object/:id?/edit"
-                        view=PrincipalEdit
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/queue/messages"
-                        view=QueueList
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/queue/message/:id"
-                        view=QueueManage
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/queue/reports"
-                        view=ReportList
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/queue/report/:id"
-                        view=ReportDisplay
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/reports/:object"
-                        view=IncomingReportList
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/reports/:object/:id"
-                        view=IncomingReportDisplay
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/logs"
-                        view=Logs
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/spam/train"
-                        view=SpamTrain
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/spam/test"
-                        view=SpamTest
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/maintenance"
-                        view=Maintenance
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                </ProtectedRoute>
-                <ProtectedRoute
+                    <Route path="/directory/:object" view=PrincipalList/>
+                    <Route path="/directory/:object/:id?/edit" view=PrincipalEdit/>
+                    <Route path="/queue/messages" view=QueueList/>
+                    <Route path="/queue/message/:id" view=QueueManage/>
+                    <Route path="/queue/reports" view=ReportList/>
+                    <Route path="/queue/report/:id" view=ReportDisplay/>
+                    <Route path="/reports/inspect" view=ReportInspect/>
+                    <Route path="/reports/:object" view=IncomingReportList/>
+                    <Route path="/reports/:object/:id" view=IncomingReportDisplay/>
+                    <Route path="/logs" view=Logs/>
+                    <Route path="/spam/train" view=SpamTrain/>
+                    <Route path="/spam/test" view=SpamTest/>
+                    <Route path="/maintenance" view=Maintenance/>
+                    <Route path="/maintenance/restore" view=RestoreConfig/>
+                </Route>
+                <Route
                     path="/settings"
-                    view=move || {
-                        view! { <Layout menu_items=LayoutBuilder::settings() is_admin=is_admin/> }
+                    view=|| {
+                        view! { <GuardedLayout menu_items=LayoutBuilder::settings admin_only=true/> }
                     }
-
-                    redirect_path="/login"
-                    condition=move || is_admin.get()
                 >
-                    <ProtectedRoute
-                        path="/:object"
-                        view=SettingsList
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/:object/:id?/edit"
-                        view=SettingsEdit
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                    <ProtectedRoute
-                        path="/search"
-                        view=SettingsSearch
-                        redirect_path="/login"
-                        condition=move || is_admin.get()
-                    />
-                </ProtectedRoute>
-                <ProtectedRoute
+                    <Route path="/:object" view=SettingsList/>
+                    <Route path="/:object/:id?/edit" view=SettingsEdit/>
+                    <Route path="/:object/import" view=SettingsImport/>
+                    <Route path="/search" view=SettingsSearch/>
+                </Route>
+                <Route
                     path="/account"
-                    view=move || {
-                        view! { <Layout menu_items=LayoutBuilder::account() is_admin=is_admin/> }
-                    }
-
-                    redirect_path="/login"
-                    condition=move || is_logged_in.get()
+                    view=|| view! { <GuardedLayout menu_items=LayoutBuilder::account/> }
                 >
-                    <ProtectedRoute
-                        path="/crypto"
-                        view=ManageCrypto
-                        redirect_path="/login"
-                        condition=move || is_logged_in.get()
-                    />
-                    <ProtectedRoute
-                        path="/password"
-                        view=ChangePassword
-                        redirect_path="/login"
-                        condition=move || is_logged_in.get()
-                    />
+                    <Route path="/crypto" view=ManageCrypto/>
+                    <Route path="/password" view=ChangePassword/>
+                    <Route path="/preferences" view=ManagePreferences/>
+                    <Route path="/session" view=SessionInfo/>
+                    <Route path="/two-factor" view=ManageTotp/>
 
-                </ProtectedRoute>
+                </Route>
 
                 <Route path="/" view=Login/>
                 <Route path="/login" view=Login/>
@@ -313,6 +376,7 @@ pub fn App() -> impl IntoView {
                 <Route path="/*any" view=NotFound/>
             </Routes>
         </Router>
+        <LogConsole/>
         <div id="portal_root"></div>
     }
 }
@@ -320,6 +384,10 @@ pub fn App() -> impl IntoView {
 impl LayoutBuilder {
     pub fn manage() -> Vec<MenuItem> {
         LayoutBuilder::new("/manage")
+            .create("Dashboard")
+            .icon(view! { <IconSquares2x2/> })
+            .route("/dashboard")
+            .insert()
             .create("Directory")
             .icon(view! { <IconUserGroup/> })
             .create("Accounts")
@@ -390,6 +458,18 @@ impl LayoutBuilder {
             .icon(view! { <IconKey/> })
             .route("/password")
             .insert()
+            .create("Two-factor Auth")
+            .icon(view! { <IconShieldCheck/> })
+            .route("/two-factor")
+            .insert()
+            .create("Preferences")
+            .icon(view! { <IconAdjustmentsHorizontal/> })
+            .route("/preferences")
+            .insert()
+            .create("Session")
+            .icon(view! { <IconUserCircle/> })
+            .route("/session")
+            .insert()
             .menu_items
     }
 }
@@ -416,6 +496,8 @@ pub fn build_schemas() -> Arc<Schemas> {
         .build_spam_lists()
         .build_spam_manage()
         .build_password_change()
+        .build_totp()
+        .build_preferences()
         .build_crypto()
         .build_authorize()
         .build()
@@ -30,10 +30,8 @@ use serde::{Deserialize, Serialize};
 use crate::{
     components::{
         form::{
-            button::Button,
-            input::{InputPassword, TextArea},
-            select::Select,
-            Form, FormButtonBar, FormElement, FormItem, FormSection,
+            button::Button, file::InputFile, input::InputPassword, select::Select, Form,
+            FormButtonBar, FormElement, FormItem, FormSection,
         },
         messages::alert::{use_alerts, Alert},
         skeleton::Skeleton,
@@ -42,7 +40,7 @@ use crate::{
     core::{
         form::FormData,
         http::{self, Error, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
         schema::{Builder, Schemas, Source, Type, Validator},
     },
 };
@@ -149,7 +147,7 @@ pub fn ManageCrypto() -> impl IntoView {
                 {move || match fetch_crypto.get() {
                     None => None,
                     Some(Err(http::Error::Unauthorized)) => {
-                        use_navigate()("/login", Default::default());
+                        use_navigate()(&login_redirect_url(), Default::default());
                         Some(view! { <div></div> }.into_view())
                     }
                     Some(Err(err)) => {
@@ -190,7 +188,7 @@ pub fn ManageCrypto() -> impl IntoView {
                                         tooltip="The armored OpenPGP certificate or S/MIME certificate in PEM format."
                                         hide=has_no_crypto
                                     >
-                                        <TextArea element=FormElement::new("certs", data)/>
+                                        <InputFile element=FormElement::new("certs", data)/>
                                     </FormItem>
 
                                 </FormSection>
@@ -298,8 +296,22 @@ impl Builder<Schemas, ()> {
             )
             .build()
             .new_field("certs")
-            .typ(Type::Text)
-            .input_check([], [Validator::Required])
+            .typ(Type::File)
+            .input_check(
+                [],
+                [
+                    Validator::Required,
+                    Validator::MaxFileSize(1024 * 1024),
+                    Validator::FileType(&[
+                        ".pem",
+                        ".asc",
+                        ".crt",
+                        ".cer",
+                        "application/pgp-keys",
+                        "text/*",
+                    ]),
+                ],
+            )
             .display_if_eq(
                 "type",
                 [
@@ -23,3 +23,6 @@
 
 pub mod crypto;
 pub mod password;
+pub mod preferences;
+pub mod session;
+pub mod totp;
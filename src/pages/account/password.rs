@@ -87,6 +87,9 @@ pub fn ChangePassword() -> impl IntoView {
                 <FormItem label="New Password">
                     <InputPassword element=FormElement::new("new-password", data)/>
                 </FormItem>
+                <FormItem label="Confirm Password">
+                    <InputPassword element=FormElement::new("new-password-confirm", data)/>
+                </FormItem>
 
             </FormSection>
 
@@ -125,6 +128,7 @@ impl Builder<Schemas, ()> {
             .typ(Type::Secret)
             .input_check([], [Validator::Required])
             .new_field("new-password")
+            .new_confirm_field("new-password-confirm", "new-password")
             .build()
             .build()
     }
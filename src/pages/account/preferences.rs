@@ -0,0 +1,342 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::sync::Arc;
+
+use leptos::*;
+
+use crate::{
+    components::{
+        form::{
+            button::Button,
+            input::{InputSwitch, InputText, TextArea},
+            select::Select,
+            Form, FormButtonBar, FormElement, FormItem, FormSection,
+        },
+        messages::alert::{use_alerts, Alert},
+        Color,
+    },
+    core::{
+        a11y::HIGH_CONTRAST_OPTIONS,
+        headers::CustomHeaders,
+        logging::{use_log_state, LOG_LEVELS},
+        oauth::use_authorization,
+        prefs::{use_preferences, LANDING_PAGES, PAGE_SIZE_OPTIONS, SESSION_SCOPE_OPTIONS},
+        schema::{Builder, Schemas, Source, Transformer, Type, Validator},
+    },
+    pages::login::update_sliding_session,
+};
+
+#[component]
+pub fn ManagePreferences() -> impl IntoView {
+    let alert = use_alerts();
+    let auth = use_authorization();
+    let prefs = use_preferences();
+    let log_state = use_log_state();
+
+    let data = expect_context::<Arc<Schemas>>()
+        .build_form("preferences")
+        .with_value(
+            "landing-page",
+            prefs.get_untracked().landing_page.unwrap_or_default(),
+        )
+        .with_value(
+            "cert-expiry-days",
+            prefs.get_untracked().cert_expiry_days().to_string(),
+        )
+        .with_value(
+            "queue-warning-threshold",
+            prefs.get_untracked().queue_warning_threshold().to_string(),
+        )
+        .with_value(
+            "queue-critical-threshold",
+            prefs.get_untracked().queue_critical_threshold().to_string(),
+        )
+        .with_value("page-size", prefs.get_untracked().page_size().to_string())
+        .with_value(
+            "high-contrast",
+            prefs
+                .get_untracked()
+                .high_contrast
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+        )
+        .with_value(
+            "input-masks",
+            prefs.get_untracked().input_masks().to_string(),
+        )
+        .with_value(
+            "strict-search",
+            (!prefs.get_untracked().fuzzy_search()).to_string(),
+        )
+        .with_value(
+            "sliding-session",
+            prefs.get_untracked().sliding_session().to_string(),
+        )
+        .with_value("custom-headers", CustomHeaders::current().to_text())
+        .with_value("log-level", log_state.settings.get_untracked().level)
+        .with_value(
+            "log-console",
+            log_state.settings.get_untracked().console.to_string(),
+        )
+        .into_signal();
+
+    view! {
+        <Form title="Preferences" subtitle="Customize the behavior of the web admin on this device.">
+            <FormSection>
+                <FormItem label="Landing page">
+                    <Select element=FormElement::new("landing-page", data)/>
+                </FormItem>
+                <FormItem
+                    label="Certificate expiry warning"
+                    tooltip="Number of days before a TLS certificate expires to start showing warnings"
+                >
+                    <InputText element=FormElement::new("cert-expiry-days", data)/>
+                </FormItem>
+                <FormItem
+                    label="Queue warning threshold"
+                    tooltip="Number of queued messages at which the queue indicator turns amber"
+                >
+                    <InputText element=FormElement::new("queue-warning-threshold", data)/>
+                </FormItem>
+                <FormItem
+                    label="Queue critical threshold"
+                    tooltip="Number of queued messages at which the queue indicator turns red"
+                >
+                    <InputText element=FormElement::new("queue-critical-threshold", data)/>
+                </FormItem>
+                <FormItem
+                    label="Results per page"
+                    tooltip="Default number of results shown in lists, unless a size was picked on the list itself"
+                >
+                    <Select element=FormElement::new("page-size", data)/>
+                </FormItem>
+                <FormItem
+                    label="High contrast"
+                    tooltip="Use a stronger color palette, by default this follows the system setting"
+                >
+                    <Select element=FormElement::new("high-contrast", data)/>
+                </FormItem>
+
+            </FormSection>
+
+            <FormSection title="Advanced".to_string()>
+                <FormItem
+                    label="Keep session"
+                    tooltip="By default closing the tab signs you out, which is the safest choice on shared computers. Keeping the session lets tabs reopened within 8 hours of closing the web admin continue without signing in, but anyone using this browser in that time can too. Activity in an open tab does not matter, the 8 hours start once it is closed. \"Keep me signed in\" on the login page overrides both."
+                >
+                    <Select element=FormElement::new("sliding-session", data)/>
+                </FormItem>
+                <FormItem label="Input masking">
+                    <InputSwitch
+                        label="Guide the format of addresses and ports while typing"
+                        element=FormElement::new("input-masks", data)
+                    />
+                </FormItem>
+                <FormItem label="Strict search">
+                    <InputSwitch
+                        label="Only match the exact search text instead of similar spellings"
+                        element=FormElement::new("strict-search", data)
+                    />
+                </FormItem>
+                <FormItem
+                    label="Custom headers"
+                    tooltip="Sent with every request to the server, one \"Name: Value\" per line. Use this when the web admin sits behind a gateway that requires its own credentials. Stored on this device only."
+                >
+                    <TextArea
+                        placeholder="X-Proxy-Token: secret"
+                        element=FormElement::new("custom-headers", data)
+                    />
+                </FormItem>
+                <FormItem
+                    label="Log level"
+                    tooltip="Verbosity of the browser console log, applies to this session only"
+                >
+                    <Select element=FormElement::new("log-level", data)/>
+                </FormItem>
+                <FormItem label="Log console">
+                    <InputSwitch
+                        label="Show log messages in an overlay console"
+                        element=FormElement::new("log-console", data)
+                    />
+                </FormItem>
+
+            </FormSection>
+
+            <FormButtonBar>
+
+                <Button
+                    text="Save changes"
+                    color=Color::Blue
+                    on_click=Callback::new(move |_| {
+                        data.update(|data| {
+                            let custom_headers = match CustomHeaders::parse(
+                                data.value::<String>("custom-headers").unwrap_or_default().as_str(),
+                            ) {
+                                Ok(custom_headers) => Some(custom_headers),
+                                Err(err) => {
+                                    data.new_error("custom-headers", err);
+                                    None
+                                }
+                            };
+                            if let (Some(warning), Some(critical)) = (
+                                data.value::<u64>("queue-warning-threshold"),
+                                data.value::<u64>("queue-critical-threshold"),
+                            ) {
+                                if warning >= critical {
+                                    data.new_error(
+                                        "queue-critical-threshold",
+                                        "must be greater than the warning threshold",
+                                    );
+                                }
+                            }
+                            if data.validate_form() && custom_headers.is_some() {
+                                let landing_page = data
+                                    .value::<String>("landing-page")
+                                    .filter(|v| !v.is_empty());
+                                let cert_expiry_days = data.value::<u32>("cert-expiry-days");
+                                let queue_warning_threshold = data
+                                    .value::<u64>("queue-warning-threshold");
+                                let queue_critical_threshold = data
+                                    .value::<u64>("queue-critical-threshold");
+                                let input_masks = data.value::<bool>("input-masks");
+                                let strict_search = data.value::<bool>("strict-search");
+                                let high_contrast = data.value::<bool>("high-contrast");
+                                let page_size = data.value::<u32>("page-size");
+                                let sliding_session = data.value::<bool>("sliding-session");
+                                prefs
+                                    .update(|prefs| {
+                                        prefs.landing_page = landing_page;
+                                        prefs.cert_expiry_days = cert_expiry_days;
+                                        prefs.queue_warning_threshold = queue_warning_threshold;
+                                        prefs.queue_critical_threshold = queue_critical_threshold;
+                                        prefs.input_masks = input_masks;
+                                        prefs.strict_search = strict_search;
+                                        prefs.high_contrast = high_contrast;
+                                        prefs.page_size = page_size;
+                                        prefs.sliding_session = sliding_session;
+                                        prefs.save();
+                                    });
+                                update_sliding_session(&auth.get_untracked(), &prefs.get_untracked());
+                                let level = data
+                                    .value::<String>("log-level")
+                                    .unwrap_or_default();
+                                let console = data.value::<bool>("log-console").unwrap_or_default();
+                                log_state
+                                    .settings
+                                    .update(|settings| {
+                                        settings.level = level;
+                                        settings.console = console;
+                                        settings.apply();
+                                        settings.save();
+                                    });
+                                if let Some(custom_headers) = custom_headers {
+                                    custom_headers.save();
+                                    custom_headers.apply();
+                                }
+                                alert.set(Alert::success("Preferences saved"));
+                            }
+                        });
+                    })
+                />
+
+            </FormButtonBar>
+
+        </Form>
+    }
+}
+
+impl Builder<Schemas, ()> {
+    pub fn build_preferences(self) -> Self {
+        self.new_schema("preferences")
+            .new_field("landing-page")
+            .typ(Type::Select {
+                multi: false,
+                source: Source::Static(LANDING_PAGES),
+            })
+            .help("The page to open after signing in")
+            .build()
+            .new_field("cert-expiry-days")
+            .typ(Type::Input)
+            .input_check(
+                [Transformer::Trim],
+                [
+                    Validator::Required,
+                    Validator::MinValue(1.into()),
+                    Validator::MaxValue(365.into()),
+                ],
+            )
+            .build()
+            .new_field("queue-warning-threshold")
+            .typ(Type::Input)
+            .input_check(
+                [Transformer::Trim],
+                [Validator::Required, Validator::MinValue(1.into())],
+            )
+            .build()
+            .new_field("queue-critical-threshold")
+            .typ(Type::Input)
+            .input_check(
+                [Transformer::Trim],
+                [Validator::Required, Validator::MinValue(1.into())],
+            )
+            .build()
+            .new_field("page-size")
+            .typ(Type::Select {
+                multi: false,
+                source: Source::Static(PAGE_SIZE_OPTIONS),
+            })
+            .build()
+            .new_field("high-contrast")
+            .typ(Type::Select {
+                multi: false,
+                source: Source::Static(HIGH_CONTRAST_OPTIONS),
+            })
+            .build()
+            .new_field("sliding-session")
+            .typ(Type::Select {
+                multi: false,
+                source: Source::Static(SESSION_SCOPE_OPTIONS),
+            })
+            .build()
+            .new_field("input-masks")
+            .typ(Type::Boolean)
+            .build()
+            .new_field("strict-search")
+            .typ(Type::Boolean)
+            .build()
+            .new_field("custom-headers")
+            .typ(Type::Text)
+            .build()
+            .new_field("log-level")
+            .typ(Type::Select {
+                multi: false,
+                source: Source::Static(LOG_LEVELS),
+            })
+            .build()
+            .new_field("log-console")
+            .typ(Type::Boolean)
+            .build()
+            .build()
+    }
+}
@@ -0,0 +1,122 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use chrono::DateTime;
+use leptos::*;
+
+use crate::{
+    components::{
+        badge::Badge,
+        form::{Form, FormItem, FormSection},
+        Color,
+    },
+    core::oauth::{use_authorization, ClaimsSource},
+    pages::FormatDateTime,
+};
+
+#[component]
+pub fn SessionInfo() -> impl IntoView {
+    let auth = use_authorization();
+    let claims = create_memo(move |_| auth.get().claims);
+
+    let text = |value: String| {
+        view! { <p class="mt-2 text-sm text-gray-800 dark:text-gray-200 break-all">{value}</p> }
+    };
+    let badges = |values: Vec<String>, color: Color| {
+        if values.is_empty() {
+            view! { <p class="mt-2 text-sm text-gray-500">None</p> }.into_view()
+        } else {
+            view! {
+                <div class="mt-2 flex flex-wrap gap-1">
+                    {values
+                        .into_iter()
+                        .map(|value| view! { <Badge color=color>{value}</Badge> })
+                        .collect_view()}
+                </div>
+            }
+            .into_view()
+        }
+    };
+
+    view! {
+        <Form
+            title="Session"
+            subtitle="Details of the access token used by this session."
+        >
+            <FormSection>
+                <FormItem label="Account">
+                    {move || text(auth.get().username.to_string())}
+                </FormItem>
+                <FormItem label="Server">
+                    {move || {
+                        let base_url = auth.get().base_url.to_string();
+                        text(
+                            if base_url.is_empty() {
+                                window().location().origin().unwrap_or_default()
+                            } else {
+                                base_url
+                            },
+                        )
+                    }}
+
+                </FormItem>
+                <FormItem
+                    label="Administrator"
+                    tooltip="Determined by the server when the session was created."
+                >
+                    {move || text(if auth.get().is_admin() { "Yes" } else { "No" }.to_string())}
+                </FormItem>
+            </FormSection>
+
+            {move || match claims.get() {
+                Some(claims) => {
+                    let source = match claims.source {
+                        ClaimsSource::Jwt => "Decoded from the access token",
+                        ClaimsSource::Introspection => "Reported by the token introspection endpoint",
+                        ClaimsSource::Grant => "Scope granted at sign in, the token itself is opaque",
+                    };
+                    view! {
+                        <FormSection title="Permissions".to_string()>
+                            <FormItem label="Scopes">{badges(claims.scopes, Color::Blue)}</FormItem>
+                            <FormItem label="Roles">{badges(claims.roles, Color::Gray)}</FormItem>
+                            <FormItem label="Expires" hide=claims.expires_at.is_none()>
+                                {text(
+                                    claims
+                                        .expires_at
+                                        .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                                        .map(|date| date.format_date_time())
+                                        .unwrap_or_default(),
+                                )}
+                            </FormItem>
+                            <FormItem label="Source">{text(source.to_string())}</FormItem>
+                        </FormSection>
+                    }
+                        .into_view()
+                }
+                // Opaque tokens the server would not describe
+                None => ().into_view(),
+            }}
+
+        </Form>
+    }
+}
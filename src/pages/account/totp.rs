@@ -0,0 +1,211 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::sync::Arc;
+
+use leptos::*;
+use serde::Serialize;
+
+use crate::{
+    components::{
+        form::{
+            button::Button, input::InputPassword, Form, FormButtonBar, FormElement, FormItem,
+            FormSection,
+        },
+        icon::IconClipboard,
+        messages::alert::{use_alerts, Alert},
+        qr::QrCode,
+        Color,
+    },
+    core::{
+        clipboard::copy_to_clipboard,
+        http::{Error, HttpRequest},
+        oauth::use_authorization,
+        otp::{base32_encode, otpauth_url},
+        schema::{Builder, Schemas, Type, Validator},
+    },
+};
+
+// The server stores whatever secret the client enrolls, so it is generated here
+const SECRET_LEN: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum AccountAuthRequest {
+    EnableOtpAuth { url: String },
+}
+
+#[component]
+pub fn ManageTotp() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let (pending, set_pending) = create_signal(false);
+
+    let data = expect_context::<Arc<Schemas>>()
+        .build_form("enable-totp")
+        .into_signal();
+
+    let secret = generate_secret();
+    if secret.is_none() {
+        alert.set(
+            Alert::error("Two-factor authentication unavailable")
+                .with_details("This browser could not generate a secure random secret")
+                .without_timeout(),
+        );
+    }
+    let issuer = window()
+        .location()
+        .hostname()
+        .ok()
+        .filter(|hostname| !hostname.is_empty())
+        .unwrap_or_else(|| "Stalwart Mail".to_string());
+    let url = secret
+        .as_ref()
+        .map(|secret| otpauth_url(&issuer, auth.get_untracked().username.as_str(), secret));
+
+    let enable_totp = create_action(move |(password, url): &(String, String)| {
+        let password = password.clone();
+        let url = url.clone();
+        let auth = auth.get();
+
+        async move {
+            set_pending.set(true);
+            let result = HttpRequest::post("/api/account/auth")
+                .with_basic_authorization(auth.username.as_str(), &password)
+                .with_base_url(&auth)
+                .with_body(vec![AccountAuthRequest::EnableOtpAuth { url }])
+                .unwrap()
+                .send::<()>()
+                .await;
+            set_pending.set(false);
+
+            alert.set(match result {
+                Ok(_) => Alert::success("Two-factor authentication enabled")
+                    .with_details(
+                        "You will be asked for a code from your authenticator app when signing in",
+                    )
+                    .without_timeout(),
+                Err(Error::Unauthorized) => Alert::warning("Incorrect password")
+                    .with_details("The password you entered is incorrect"),
+                Err(err) => Alert::from(err),
+            });
+        }
+    });
+
+    view! {
+        <Form
+            title="Two-factor authentication"
+            subtitle="Scan the code with an authenticator app or enter the secret manually, then confirm with your password."
+        >
+
+            {secret
+                .zip(url.clone())
+                .map(|(secret, url)| {
+                    let secret_ = secret.clone();
+                    view! {
+                        <FormSection>
+                            <FormItem label="QR code">
+                                <QrCode data=url label="QR code for your authenticator app"/>
+                            </FormItem>
+                            <FormItem
+                                label="Secret"
+                                tooltip="Enter this key in your authenticator app if you cannot scan the code."
+                            >
+                                <div class="flex items-center gap-x-2">
+                                    <code class="py-2 px-3 rounded-lg bg-gray-100 text-sm font-mono break-all text-gray-800 dark:bg-slate-800 dark:text-gray-200">
+                                        {secret}
+                                    </code>
+                                    <Button
+                                        text="Copy"
+                                        color=Color::Gray
+                                        on_click=move |_| {
+                                            let secret = secret_.clone();
+                                            spawn_local(async move {
+                                                alert
+                                                    .set(
+                                                        if copy_to_clipboard(&secret).await {
+                                                            Alert::success("Copied secret to clipboard")
+                                                        } else {
+                                                            Alert::error("Failed to copy to clipboard")
+                                                        },
+                                                    );
+                                            });
+                                        }
+                                    >
+
+                                        <IconClipboard/>
+                                    </Button>
+                                </div>
+                            </FormItem>
+                            <FormItem label="Current Password">
+                                <InputPassword element=FormElement::new("password", data)/>
+                            </FormItem>
+                        </FormSection>
+                    }
+                })}
+
+            <FormButtonBar>
+
+                <Button
+                    text="Enable"
+                    color=Color::Blue
+                    on_click=Callback::new(move |_| {
+                        let Some(url) = url.clone() else {
+                            return;
+                        };
+                        data.update(|data| {
+                            if data.validate_form() {
+                                enable_totp
+                                    .dispatch((data.value::<String>("password").unwrap(), url));
+                            }
+                        });
+                    })
+
+                    disabled=pending
+                />
+            </FormButtonBar>
+
+        </Form>
+    }
+}
+
+fn generate_secret() -> Option<String> {
+    let mut bytes = [0u8; SECRET_LEN];
+    window()
+        .crypto()
+        .ok()?
+        .get_random_values_with_u8_array(&mut bytes)
+        .ok()?;
+    Some(base32_encode(&bytes))
+}
+
+impl Builder<Schemas, ()> {
+    pub fn build_totp(self) -> Self {
+        self.new_schema("enable-totp")
+            .new_field("password")
+            .typ(Type::Secret)
+            .input_check([], [Validator::Required])
+            .build()
+            .build()
+    }
+}
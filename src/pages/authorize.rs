@@ -38,7 +38,9 @@ use crate::{
     core::{
         oauth::{oauth_device_authentication, oauth_user_authentication, OAuthCodeRequest},
         schema::{Builder, Schemas, Transformer, Type, Validator},
+        secret::SecretString,
     },
+    LOGO_URL, PRODUCT_NAME,
 };
 
 //const BASE_URL: &str = "https://127.0.0.1";
@@ -55,7 +57,7 @@ pub fn Authorize() -> impl IntoView {
     let client_id = create_memo(move |_| query.get().get("client_id").cloned());
 
     let login_action = create_action(
-        move |(username, password, request): &(String, String, OAuthCodeRequest)| {
+        move |(username, password, request): &(String, SecretString, OAuthCodeRequest)| {
             let username = username.clone();
             let password = password.clone();
             let request = request.clone();
@@ -124,12 +126,13 @@ pub fn Authorize() -> impl IntoView {
         .into_signal();
 
     view! {
+        <Title text="Authorize"/>
         <Body class="dark:bg-slate-900 bg-gray-100 flex h-full items-center py-16"/>
         <main class="w-full max-w-md mx-auto p-6">
             <div class="mt-7 bg-white border border-gray-200 rounded-xl shadow-sm dark:bg-gray-800 dark:border-gray-700">
                 <div class="p-4 sm:p-7">
                     <div class="text-center p-6">
-                        <img src="/logo.svg"/>
+                        <img src=LOGO_URL alt=PRODUCT_NAME/>
 
                     </div>
 
@@ -205,7 +208,7 @@ pub fn Authorize() -> impl IntoView {
                                                     .value::<String>("login")
                                                     .unwrap_or_default();
                                                 let password = data
-                                                    .value::<String>("password")
+                                                    .secret("password")
                                                     .unwrap_or_default();
                                                 let request = if let Some(client_id) = client_id {
                                                     OAuthCodeRequest::Code {
@@ -0,0 +1,162 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use chrono::Duration;
+use leptos::*;
+
+use crate::{
+    components::{
+        messages::alert::{use_alerts, Alert},
+        report::{ReportItem, ReportSection, ReportTextValue},
+    },
+    core::{
+        cert::{parse_certificate, private_key_matches},
+        clock::server_now,
+        form::FormData,
+        prefs::use_preferences,
+    },
+    pages::FormatDateTime,
+};
+
+// Values such as "%{file:/path}%" are resolved by the server and cannot be inspected here
+fn pem_value(data: &FormData, id: &str) -> Option<String> {
+    data.value::<String>(id)
+        .filter(|value| value.contains("-----BEGIN "))
+}
+
+// A certificate the server cannot load, or paired with the wrong key, would only fail once
+// a listener tries to use it
+pub fn certificate_error(data: &FormData) -> Option<&'static str> {
+    let cert = match parse_certificate(&pem_value(data, "cert")?) {
+        Ok(cert) => cert,
+        Err(err) => return Some(err),
+    };
+    match pem_value(data, "private-key").map(|pem| private_key_matches(&cert, &pem)) {
+        Some(Ok(Some(false))) => Some("The private key does not belong to this certificate."),
+        _ => None,
+    }
+}
+
+#[component]
+pub fn CertificateSummary(data: RwSignal<FormData>) -> impl IntoView {
+    let alert = use_alerts();
+    let expiry_days = use_preferences().get_untracked().cert_expiry_days();
+
+    let pem_value = move |id: &str| data.with(|data| pem_value(data, id));
+    let certificate = create_memo(move |_| pem_value("cert").map(|pem| parse_certificate(&pem)));
+    let key_match = create_memo(move |_| match certificate.get() {
+        Some(Ok(cert)) => pem_value("private-key").map(|pem| private_key_matches(&cert, &pem)),
+        _ => None,
+    });
+
+    create_effect(move |_| {
+        if let Some(Err(err)) = certificate.get() {
+            alert.set(Alert::error("Invalid certificate").with_details(err));
+        }
+    });
+    create_effect(move |_| match key_match.get() {
+        Some(Err(err)) => {
+            alert.set(Alert::error("Invalid private key").with_details(err));
+        }
+        Some(Ok(Some(false))) => {
+            alert.set(
+                Alert::error("Private key mismatch")
+                    .with_details("The private key does not belong to this certificate."),
+            );
+        }
+        _ => (),
+    });
+
+    move || {
+        let cert = certificate.get()?.ok()?;
+        let now = server_now();
+        let (validity, validity_class) = if cert.not_after <= now {
+            (
+                format!("Expired on {}", cert.not_after.format_date()),
+                "text-red-600",
+            )
+        } else if cert.not_before > now {
+            (
+                format!("Not valid until {}", cert.not_before.format_date()),
+                "text-red-600",
+            )
+        } else if cert.not_after - now <= Duration::days(expiry_days as i64) {
+            (
+                format!(
+                    "Expires on {} ({} days)",
+                    cert.not_after.format_date(),
+                    (cert.not_after - now).num_days()
+                ),
+                "text-yellow-600",
+            )
+        } else {
+            (
+                format!(
+                    "Valid from {} to {}",
+                    cert.not_before.format_date(),
+                    cert.not_after.format_date()
+                ),
+                "text-gray-500",
+            )
+        };
+        let key_status = match key_match.get() {
+            Some(Ok(Some(true))) => "Matches the certificate",
+            Some(Ok(Some(false))) => "Does not match the certificate",
+            Some(Ok(None)) => "Could not be verified (encrypted or unsupported key)",
+            Some(Err(_)) => "Invalid",
+            None => "Not provided",
+        };
+        let sans = if cert.subject_alt_names.is_empty() {
+            "None".to_string()
+        } else {
+            cert.subject_alt_names.join(", ")
+        };
+
+        Some(view! {
+            <div class="mt-5 p-4 border border-gray-200 rounded-xl dark:border-gray-700">
+                <ReportSection title="Certificate details">
+                    <ReportItem label="Subject">
+                        <ReportTextValue value=cert.subject/>
+                    </ReportItem>
+                    <ReportItem label="Alternative names">
+                        <ReportTextValue value=sans/>
+                    </ReportItem>
+                    <ReportItem label="Issuer">
+                        <ReportTextValue value=cert.issuer/>
+                    </ReportItem>
+                    <ReportItem label="Validity">
+                        <label class=format!(
+                            "inline-block text-sm font-semibold mt-2.5 {validity_class}",
+                        )>{validity}</label>
+                    </ReportItem>
+                    <ReportItem label="Key type">
+                        <ReportTextValue value=cert.key_type/>
+                    </ReportItem>
+                    <ReportItem label="Private key">
+                        <ReportTextValue value=key_status.to_string()/>
+                    </ReportItem>
+                </ReportSection>
+            </div>
+        })
+    }
+}
@@ -21,41 +21,57 @@
  * for more details.
 */
 
-use std::sync::Arc;
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+};
 
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
+use gloo_net::http::Method;
 use leptos::*;
-use leptos_router::{use_navigate, use_params_map};
+use leptos_router::{use_navigate, use_params_map, use_query_map};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     components::{
         form::{
             button::Button,
+            checkbox::Checkbox,
             expression::InputExpression,
+            file::InputFile,
             input::{
-                InputDuration, InputPassword, InputRate, InputSize, InputSwitch, InputText,
-                TextArea,
+                InputDate, InputDuration, InputPassword, InputRate, InputSize, InputSwitch,
+                InputText, TextArea,
             },
             select::{CheckboxGroup, Select, SelectCron},
+            sieve::SieveEditor,
             stacked_input::StackedInput,
-            Form, FormButtonBar, FormElement, FormItem, FormSection,
+            undo::FieldUndo,
+            Form, FormButtonBar, FormElement, FormItem, FormItemGroup, FormSection,
         },
-        icon::IconRefresh,
+        icon::{IconClipboard, IconRefresh},
         messages::{
-            alert::{use_alerts, Alert},
+            alert::{defer_alert, use_alerts, Alert},
             modal::{use_modals, Modal},
         },
         skeleton::Skeleton,
         Color,
     },
     core::{
+        api::ApiClient,
+        clipboard::copy_to_clipboard,
         form::{ExternalSources, FormData},
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization, AuthToken},
+        recent::use_recently_viewed,
     },
     pages::{
-        config::{ReloadSettings, Schema, SchemaType, Schemas, Settings, Type, UpdateSettings},
+        config::{
+            certificate::{certificate_error, CertificateSummary},
+            metrics::ListenerMetricsPanel,
+            probe::ListenerTestPanel,
+            Field, ReloadSettings, Schema, SchemaType, Schemas, Settings, Type, UpdateSettings,
+        },
         List,
     },
 };
@@ -75,6 +91,10 @@ enum FetchResult {
     Create {
         external_sources: ExternalSources,
     },
+    Duplicate {
+        settings: Settings,
+        external_sources: ExternalSources,
+    },
     NotFound,
 }
 
@@ -85,6 +105,7 @@ pub fn SettingsEdit() -> impl IntoView {
     let auth = use_authorization();
     let alert = use_alerts();
     let params = use_params_map();
+    let recent = use_recently_viewed();
     let modal = use_modals();
 
     let schemas = expect_context::<Arc<Schemas>>();
@@ -101,9 +122,15 @@ pub fn SettingsEdit() -> impl IntoView {
         }
     });
 
+    let query = use_query_map();
     let fetch_settings = create_resource(
-        move || params.get().get("id").cloned().unwrap_or_default(),
-        move |name| {
+        move || {
+            (
+                params.get().get("id").cloned().unwrap_or_default(),
+                query.with(|query| query.get("copy").cloned()),
+            )
+        },
+        move |(name, copy_from)| {
             let auth = auth.get_untracked();
             let schema = current_schema.get();
             let is_create = name.is_empty();
@@ -146,21 +173,29 @@ pub fn SettingsEdit() -> impl IntoView {
                 match schema.typ {
                     SchemaType::Record { prefix, .. } => {
                         if !is_create {
-                            HttpRequest::get("/api/settings/list")
-                                .with_authorization(&auth)
-                                .with_parameter("prefix", format!("{prefix}.{name}"))
-                                .send::<FetchSettings>()
+                            fetch_record(&auth, prefix, &name).await.map(|settings| {
+                                if let Some(settings) = settings {
+                                    FetchResult::Update {
+                                        settings,
+                                        external_sources,
+                                    }
+                                } else {
+                                    FetchResult::NotFound
+                                }
+                            })
+                        } else if let Some(copy_from) = copy_from {
+                            // Prefill a new record with the settings of an existing one
+                            fetch_record(&auth, prefix, &copy_from)
                                 .await
-                                .map(|mut list| {
-                                    if !list.items.is_empty() {
-                                        list.items.insert("_id".to_string(), name.to_string());
-                                        FetchResult::Update {
-                                            settings: list.items,
+                                .map(|settings| match settings {
+                                    Some(mut settings) => {
+                                        settings.remove("_id");
+                                        FetchResult::Duplicate {
+                                            settings,
                                             external_sources,
                                         }
-                                    } else {
-                                        FetchResult::NotFound
                                     }
+                                    None => FetchResult::NotFound,
                                 })
                         } else {
                             Ok(FetchResult::Create { external_sources })
@@ -229,18 +264,113 @@ pub fn SettingsEdit() -> impl IntoView {
             }
         },
     );
+    // Only records loaded for editing are recorded, never new or duplicated ones
+    create_effect(move |_| {
+        if let Some(Ok(FetchResult::Update { .. })) = fetch_settings.get() {
+            let schema = current_schema.get_untracked();
+            if let (SchemaType::Record { .. }, Some(id)) = (
+                &schema.typ,
+                params.get_untracked().get("id").filter(|id| !id.is_empty()),
+            ) {
+                recent.record(
+                    schema.name_singular,
+                    id,
+                    id,
+                    format!("/settings/{}/{}/edit", schema.id, id),
+                );
+            }
+        }
+    });
     let (pending, set_pending) = create_signal(false);
+    let notify_saved = store_value(false);
+    let loaded_version = store_value(None::<u64>);
+    // Overwriting after a conflict goes through the same validation as a regular save
+    let overwrite = create_rw_signal(None::<(Arc<Vec<UpdateSettings>>, bool)>);
     let data = FormData::default().into_signal();
+    let advisories = create_memo(move |_| {
+        current_schema
+            .get()
+            .advisory
+            .map(|advisory| data.with(advisory))
+            .unwrap_or_default()
+    });
+    // Sections whose "*.override" switch replaces the inherited defaults
+    let overrides = create_memo(move |_| {
+        let schema = current_schema.get();
+        data.with(|data| {
+            schema
+                .form
+                .sections
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, section)| {
+                    let title = section.title?;
+                    section
+                        .fields
+                        .iter()
+                        .any(|field| {
+                            field.id.ends_with(".override")
+                                && matches!(field.typ_, Type::Boolean)
+                                && data.value::<bool>(field.id).unwrap_or_default()
+                        })
+                        .then_some((idx, title))
+                })
+                .collect::<Vec<_>>()
+        })
+    });
 
     let save_changes = create_action(
-        move |(changes, reload): &(Arc<Vec<UpdateSettings>>, bool)| {
+        move |(changes, reload, version): &(Arc<Vec<UpdateSettings>>, bool, Option<u64>)| {
             let changes = changes.clone();
             let reload = *reload;
+            let version = *version;
             let auth = auth.get();
             let schema = current_schema.get();
+            let record_id = params
+                .get_untracked()
+                .get("id")
+                .cloned()
+                .unwrap_or_default();
 
             async move {
                 set_pending.set(true);
+
+                // Refuse to silently overwrite changes made by someone else since loading
+                if let (SchemaType::Record { prefix, .. }, Some(version)) = (&schema.typ, version) {
+                    match check_version(&auth, prefix, &record_id, version).await {
+                        Ok(()) => (),
+                        Err(SaveError::Conflict) => {
+                            set_pending.set(false);
+                            modal.set(
+                                Modal::with_title("Settings changed")
+                                    .with_message(format!(
+                                        concat!(
+                                            "This {} was changed by someone else after you ",
+                                            "opened it. Reload to see their changes and discard ",
+                                            "yours, or overwrite them with your version."
+                                        ),
+                                        schema.name_singular
+                                    ))
+                                    .with_secondary_button("Reload", move || {
+                                        fetch_settings.refetch();
+                                    })
+                                    .with_button("Overwrite")
+                                    .with_dangerous_callback(move || {
+                                        overwrite.set(Some((changes.clone(), reload)));
+                                    }),
+                            );
+                            return;
+                        }
+                        Err(SaveError::Http(http::Error::Unauthorized)) => {
+                            use_navigate()(&login_redirect_url(), Default::default());
+                            return;
+                        }
+                        Err(SaveError::Http(err)) => {
+                            log::warn!("Failed to check for concurrent changes: {err:?}");
+                        }
+                    }
+                }
+
                 match HttpRequest::post("/api/settings")
                     .with_authorization(&auth)
                     .with_body(changes)
@@ -250,6 +380,17 @@ pub fn SettingsEdit() -> impl IntoView {
                     .map(|_| ())
                 {
                     Ok(_) => {
+                        data.update(|data| data.clear_history());
+
+                        // Track our own changes so staying on the page does not report a conflict
+                        if let (SchemaType::Record { prefix, .. }, true) =
+                            (&schema.typ, loaded_version.get_value().is_some())
+                        {
+                            if let Ok(current) = fetch_record(&auth, prefix, &record_id).await {
+                                loaded_version.set_value(current.as_ref().map(settings_version));
+                            }
+                        }
+
                         if reload {
                             match HttpRequest::get(format!(
                                 "/api/reload/{}",
@@ -282,7 +423,7 @@ pub fn SettingsEdit() -> impl IntoView {
                                     }
                                 }
                                 Err(http::Error::Unauthorized) => {
-                                    use_navigate()("/login", Default::default());
+                                    use_navigate()(&login_redirect_url(), Default::default());
                                 }
                                 Err(err) => {
                                     set_pending.set(false);
@@ -291,8 +432,17 @@ pub fn SettingsEdit() -> impl IntoView {
                             }
                         } else {
                             set_pending.set(false);
+                            let notify = notify_saved.get_value();
                             match schema.list_path() {
-                                Some(url) => use_navigate()(&url, Default::default()),
+                                Some(url) => {
+                                    if notify {
+                                        defer_alert(Alert::success("Settings saved"));
+                                    }
+                                    use_navigate()(&url, Default::default())
+                                }
+                                None if notify => {
+                                    alert.set(Alert::success("Settings saved"));
+                                }
                                 None => {
                                     modal.set(
                                         Modal::with_title("Settings saved")
@@ -311,7 +461,7 @@ pub fn SettingsEdit() -> impl IntoView {
                         set_pending.set(false);
                         match err {
                             http::Error::Unauthorized => {
-                                use_navigate()("/login", Default::default());
+                                use_navigate()(&login_redirect_url(), Default::default());
                             }
                             err => {
                                 alert.set(Alert::from(err));
@@ -323,18 +473,183 @@ pub fn SettingsEdit() -> impl IntoView {
         },
     );
 
+    let validate_save = create_action(
+        move |(changes, reload, check_version): &(Arc<Vec<UpdateSettings>>, bool, bool)| {
+            let changes = changes.clone();
+            let reload = *reload;
+            let auth = auth.get_untracked();
+            let version = loaded_version.get_value().filter(|_| *check_version);
+            let references = data.with_untracked(|data| {
+                data.schema
+                    .references()
+                    .filter(|(field, _)| field.display(data))
+                    .filter_map(|(field, schema)| {
+                        let values = data
+                            .array_value(field.id)
+                            .filter(|value| !value.is_empty())
+                            .map(|value| value.to_string())
+                            .collect::<Vec<_>>();
+                        (!values.is_empty()).then_some((field, schema, values))
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            async move {
+                match missing_references(&auth, references).await {
+                    Ok(missing) if !missing.is_empty() => {
+                        let details = missing
+                            .iter()
+                            .map(|(field, schema, value)| {
+                                format!(
+                                    "{}: no {} with id {value:?} exists",
+                                    field.label_form, schema.name_singular
+                                )
+                            })
+                            .collect::<Vec<_>>();
+                        data.update(|data| {
+                            for (field, schema, value) in &missing {
+                                data.new_error(
+                                    field.id,
+                                    format!("no {} with id {value:?} exists", schema.name_singular),
+                                );
+                            }
+                        });
+                        alert.set(
+                            Alert::warning("Referenced items not found")
+                                .with_details_list(details)
+                                .with_action("Save anyway", move |_| {
+                                    save_changes.dispatch((changes.clone(), reload, version));
+                                })
+                                .without_timeout(),
+                        );
+                    }
+                    Ok(_) => {
+                        save_changes.dispatch((changes, reload, version));
+                    }
+                    Err(http::Error::Unauthorized) => {
+                        use_navigate()(&login_redirect_url(), Default::default());
+                    }
+                    Err(err) => {
+                        // The server validates on reload, so a failed lookup should not block saving
+                        log::warn!("Failed to validate references: {err:?}");
+                        save_changes.dispatch((changes, reload, version));
+                    }
+                }
+            }
+        },
+    );
+    create_effect(move |_| {
+        if let Some((changes, reload)) = overwrite.get() {
+            validate_save.dispatch((changes, reload, false));
+        }
+    });
+    let is_saving = Signal::derive(move || pending.get() || validate_save.pending().get());
+    let save = move |reload: bool, notify: bool| {
+        data.update(|data| {
+            if data.validate_form() {
+                if current_schema.get_untracked().id == "certificate" {
+                    if let Some(error) = certificate_error(data) {
+                        alert.set(Alert::error("Certificate not saved").with_details(error));
+                        return;
+                    }
+                }
+                notify_saved.set_value(notify);
+                validate_save.dispatch((Arc::new(data.build_update()), reload, true));
+            } else if let Some(error) = data
+                .schema
+                .fields
+                .values()
+                .filter(|field| field.is_sieve_script())
+                .find_map(|field| data.error_string(field.id))
+            {
+                alert.set(
+                    Alert::error("Invalid Sieve script")
+                        .with_details(format!("The script could not be saved, {error}.")),
+                );
+            }
+        });
+    };
+
+    let copy_as_curl = move || {
+        data.update(|data| {
+            if data.validate_form() {
+                match ApiClient::new(auth.get_untracked()).curl(
+                    Method::POST,
+                    "/api/settings",
+                    Some(data.build_update()),
+                ) {
+                    Ok(command) => spawn_local(async move {
+                        alert.set(if copy_to_clipboard(&command).await {
+                            Alert::success("Copied curl command to clipboard")
+                        } else {
+                            Alert::error("Failed to copy to clipboard")
+                        });
+                    }),
+                    Err(err) => {
+                        alert.set(Alert::from(err));
+                    }
+                }
+            }
+        });
+    };
+
     view! {
         <Form
             title=Signal::derive(move || current_schema.get().form.title.to_string())
             subtitle=Signal::derive(move || current_schema.get().form.subtitle.to_string())
+            data=data
+            on_save=Callback::new(move |_| {
+                if !is_saving.get_untracked() {
+                    save(false, true);
+                }
+            })
         >
 
+            {move || {
+                params
+                    .get()
+                    .get("id")
+                    .filter(|_| current_schema.get().id == "listener")
+                    .map(|id| view! { <ListenerMetricsPanel id=id.clone()/> })
+            }}
+
+            <Show when=move || !overrides.get().is_empty()>
+                <div class="mb-6 flex flex-wrap items-center gap-2 text-sm text-gray-600 dark:text-gray-400">
+                    <span>Overriding:</span>
+                    {move || {
+                        overrides
+                            .get()
+                            .into_iter()
+                            .map(|(idx, title)| {
+                                view! {
+                                    <button
+                                        type="button"
+                                        class="py-1 px-2 inline-flex items-center text-xs font-medium rounded-full border border-blue-200 bg-blue-100 text-blue-800 hover:bg-blue-200 dark:bg-blue-800/30 dark:border-blue-900 dark:text-blue-500"
+                                        on:click=move |_| {
+                                            if let Some(section) = document()
+                                                .get_element_by_id(&section_id(idx))
+                                            {
+                                                section.scroll_into_view();
+                                            }
+                                        }
+                                    >
+
+                                        {title}
+                                    </button>
+                                }
+                            })
+                            .collect_view()
+                    }}
+
+                </div>
+            </Show>
+
             <Transition fallback=Skeleton set_pending>
 
                 {move || match fetch_settings.get() {
                     None => None,
                     Some(Err(http::Error::Unauthorized)) => {
-                        use_navigate()("/login", Default::default());
+                        use_navigate()(&login_redirect_url(), Default::default());
                         Some(view! { <div></div> }.into_view())
                     }
                     Some(Err(http::Error::NotFound) | Ok(FetchResult::NotFound)) => {
@@ -354,166 +669,270 @@ pub fn SettingsEdit() -> impl IntoView {
                             FetchResult::Create { external_sources } => {
                                 (true, None, external_sources)
                             }
+                            FetchResult::Duplicate { settings, external_sources } => {
+                                (true, Some(settings), external_sources)
+                            }
                             FetchResult::NotFound => unreachable!(),
                         };
                         let schema = current_schema.get();
                         let sections = schema.form.sections.iter().cloned();
-                        data.set(
-                            FormData::from_settings(schema.clone(), settings)
-                                .with_external_sources(external_sources),
-                        );
+                        loaded_version
+                            .set_value(
+                                settings
+                                    .as_ref()
+                                    .filter(|_| {
+                                        !is_create
+                                            && matches!(schema.typ, SchemaType::Record { .. })
+                                    })
+                                    .map(settings_version),
+                            );
+                        let mut form = FormData::from_settings(schema.clone(), settings)
+                            .with_external_sources(external_sources);
+                        form.is_update = !is_create;
+                        data.set(form);
                         Some(
                             sections
-                                .map(|section| {
+                                .enumerate()
+                                .map(|(idx, section)| {
                                     let title = section.title.map(|s| s.to_string());
                                     let section_ = section.clone();
                                     let hide_section = create_memo(move |_| {
                                         !section_.display(&data.get())
                                     });
-                                    let components = section
-                                        .fields
-                                        .iter()
-                                        .cloned()
-                                        .map(|field| {
-                                            let is_disabled = field.readonly && !is_create;
-                                            let field_label = field.label_form;
-                                            let help = field.help;
-                                            let field_ = field.clone();
-                                            let hide_label = create_memo(move |_| {
-                                                !field_.display(&data.get())
-                                            });
-                                            let field_ = field.clone();
-                                            let is_optional = create_memo(move |_| {
-                                                !field_.is_required(&data.get())
-                                            });
-                                            let is_switch = matches!(field.typ_, Type::Boolean);
-                                            let component = match field.typ_ {
-                                                Type::Input => {
-                                                    view! {
-                                                        <InputText
-                                                            element=FormElement::new(field.id, data)
-                                                            placeholder=create_memo(move |_| {
-                                                                field
-                                                                    .placeholder(&data.get())
-                                                                    .unwrap_or_default()
-                                                                    .to_string()
-                                                            })
-
-                                                            disabled=is_disabled
-                                                        />
-                                                    }
-                                                        .into_view()
+                                    let render_field = move |field: Arc<Field>, stacked: bool| {
+                                        let is_disabled = field.readonly && !is_create;
+                                        let field_label = field.label_form;
+                                        let help = field.help;
+                                        let deprecated = field.deprecated.map(|d| d.message);
+                                        let field_ = field.clone();
+                                        let hide_label = create_memo(move |_| {
+                                            !field_.display(&data.get())
+                                        });
+                                        let field_ = field.clone();
+                                        let is_optional = create_memo(move |_| {
+                                            !field_.is_required(&data.get())
+                                        });
+                                        let is_switch = matches!(field.typ_, Type::Boolean);
+                                        let field_id = field.id;
+                                        let component = match field.typ_ {
+                                            Type::Input => {
+                                                view! {
+                                                    <InputText
+                                                        element=FormElement::new(field.id, data)
+                                                        placeholder=create_memo(move |_| {
+                                                            field
+                                                                .placeholder(&data.get())
+                                                                .unwrap_or_default()
+                                                                .to_string()
+                                                        })
+
+                                                        disabled=is_disabled
+                                                    />
                                                 }
-                                                Type::Array => {
-                                                    view! {
-                                                        <StackedInput
-                                                            add_button_text="Add".to_string()
-                                                            element=FormElement::new(field.id, data)
-                                                            placeholder=create_memo(move |_| {
-                                                                field
-                                                                    .placeholder(&data.get())
-                                                                    .unwrap_or_default()
-                                                                    .to_string()
-                                                            })
-                                                        />
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Array => {
+                                                view! {
+                                                    <StackedInput
+                                                        add_button_text="Add".to_string()
+                                                        element=FormElement::new(field.id, data)
+                                                        placeholder=create_memo(move |_| {
+                                                            field
+                                                                .placeholder(&data.get())
+                                                                .unwrap_or_default()
+                                                                .to_string()
+                                                        })
+                                                    />
                                                 }
-                                                Type::Secret => {
-                                                    view! {
-                                                        <InputPassword element=FormElement::new(field.id, data)/>
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Secret => {
+                                                view! {
+                                                    <InputPassword
+                                                        element=FormElement::new(field.id, data)
+                                                        show_toggle=!field.sensitive
+                                                    />
                                                 }
-                                                Type::Select { multi: false, .. } => {
-                                                    view! {
-                                                        <Select
-                                                            element=FormElement::new(field.id, data)
-                                                            disabled=is_disabled
-                                                        />
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Select { multi: false, .. } => {
+                                                view! {
+                                                    <Select
+                                                        element=FormElement::new(field.id, data)
+                                                        disabled=is_disabled
+                                                    />
                                                 }
-                                                Type::Select { multi: true, .. } => {
-                                                    view! {
-                                                        <CheckboxGroup
-                                                            element=FormElement::new(field.id, data)
-                                                            disabled=is_disabled
-                                                        />
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Select { multi: true, .. } => {
+                                                view! {
+                                                    <CheckboxGroup
+                                                        element=FormElement::new(field.id, data)
+                                                        disabled=is_disabled
+                                                    />
                                                 }
-                                                Type::Size => {
-                                                    view! {
-                                                        <InputSize element=FormElement::new(field.id, data)/>
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Size => {
+                                                view! {
+                                                    <InputSize element=FormElement::new(field.id, data)/>
                                                 }
-                                                Type::Boolean => {
-                                                    view! {
-                                                        <InputSwitch
-                                                            label=field_label
-                                                            tooltip=help.unwrap_or_default()
-                                                            element=FormElement::new(field.id, data)
-                                                        />
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Boolean if field.checkbox => {
+                                                view! {
+                                                    <Checkbox
+                                                        label=field_label
+                                                        hint=help.unwrap_or_default()
+                                                        checked=Signal::derive(move || {
+                                                            data.get().value::<bool>(field_id).unwrap_or_default()
+                                                        })
+
+                                                        disabled=is_disabled
+                                                        on_change=move |checked: bool| {
+                                                            data.update(|data| {
+                                                                data.update(
+                                                                    field_id,
+                                                                    if checked { "true" } else { "false" },
+                                                                );
+                                                            });
+                                                        }
+                                                    />
                                                 }
-                                                Type::Duration => {
-                                                    view! {
-                                                        <InputDuration element=FormElement::new(field.id, data)/>
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Boolean => {
+                                                view! {
+                                                    <InputSwitch
+                                                        label=field_label
+                                                        tooltip=help.unwrap_or_default()
+                                                        element=FormElement::new(field.id, data)
+                                                        disabled=is_disabled
+                                                    />
                                                 }
-                                                Type::Rate => {
-                                                    view! {
-                                                        <InputRate element=FormElement::new(field.id, data)/>
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Duration => {
+                                                view! {
+                                                    <InputDuration element=FormElement::new(field.id, data)/>
                                                 }
-                                                Type::Expression => {
-                                                    view! {
-                                                        <InputExpression element=FormElement::new(field.id, data)/>
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Rate => {
+                                                view! {
+                                                    <InputRate element=FormElement::new(field.id, data)/>
                                                 }
-                                                Type::Cron => {
-                                                    view! {
-                                                        <SelectCron element=FormElement::new(field.id, data)/>
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Expression => {
+                                                view! {
+                                                    <InputExpression element=FormElement::new(field.id, data)/>
                                                 }
-                                                Type::Text => {
-                                                    view! {
-                                                        <TextArea element=FormElement::new(field.id, data)/>
-                                                    }
-                                                        .into_view()
+                                                    .into_view()
+                                            }
+                                            Type::Cron => {
+                                                view! {
+                                                    <SelectCron element=FormElement::new(field.id, data)/>
                                                 }
-                                            };
-                                            if !is_switch {
+                                                    .into_view()
+                                            }
+                                            Type::Date => {
                                                 view! {
-                                                    <FormItem
-                                                        label=field_label
-                                                        hide=hide_label
-                                                        is_optional=is_optional
-                                                        tooltip=help.unwrap_or_default()
-                                                    >
-                                                        {component}
-                                                    </FormItem>
+                                                    <InputDate
+                                                        element=FormElement::new(field.id, data)
+                                                        disabled=is_disabled
+                                                    />
+                                                }
+                                                    .into_view()
+                                            }
+                                            Type::Text if field.is_sieve_script() => {
+                                                view! {
+                                                    <SieveEditor
+                                                        element=FormElement::new(field.id, data)
+                                                        disabled=is_disabled
+                                                    />
+                                                }
+                                                    .into_view()
+                                            }
+                                            Type::Text => {
+                                                view! {
+                                                    <TextArea element=FormElement::new(field.id, data) auto_grow=true/>
                                                 }
-                                            } else {
+                                                    .into_view()
+                                            }
+                                            Type::File => {
                                                 view! {
-                                                    <FormItem label="" hide=hide_label is_optional=is_optional>
-                                                        {component}
-                                                    </FormItem>
+                                                    <InputFile
+                                                        element=FormElement::new(field.id, data)
+                                                        disabled=is_disabled
+                                                    />
                                                 }
+                                                    .into_view()
                                             }
+                                        };
+                                        let component = view! {
+                                            <FieldUndo element=FormElement::new(field_id, data)>
+                                                {component}
+                                            </FieldUndo>
+                                        };
+                                        if !is_switch {
+                                            view! {
+                                                <FormItem
+                                                    label=field_label
+                                                    hide=hide_label
+                                                    is_optional=is_optional
+                                                    tooltip=help.unwrap_or_default()
+                                                    deprecated=deprecated.unwrap_or_default()
+                                                    stacked=stacked
+                                                >
+                                                    {component}
+                                                </FormItem>
+                                            }
+                                        } else {
+                                            view! {
+                                                <FormItem
+                                                    label=""
+                                                    hide=hide_label
+                                                    is_optional=is_optional
+                                                    stacked=stacked
+                                                >
+                                                    {component}
+                                                </FormItem>
+                                            }
+                                        }
+                                    };
+                                    let components = section
+                                        .fields
+                                        .iter()
+                                        .filter_map(|field| match section.group(field.id) {
+                                            Some(group) if group[0] == field.id => {
+                                                let items = group
+                                                    .iter()
+                                                    .filter_map(|id| {
+                                                        section.fields.iter().find(|f| f.id == *id).cloned()
+                                                    })
+                                                    .map(|field| {
+                                                        view! { <div>{render_field(field, true)}</div> }
+                                                    })
+                                                    .collect_view();
+                                                Some(
+                                                    view! {
+                                                        <FormItemGroup columns=group.len()>
+                                                            {items}
+                                                        </FormItemGroup>
+                                                    }
+                                                        .into_view(),
+                                                )
+                                            }
+                                            Some(_) => None,
+                                            None => Some(render_field(field.clone(), false).into_view()),
                                         })
                                         .collect_view();
                                     view! {
                                         <FormSection
                                             title=title.unwrap_or_default()
                                             hide=hide_section
+                                            attr:id=section_id(idx)
                                         >
                                             {components}
                                         </FormSection>
@@ -527,7 +946,46 @@ pub fn SettingsEdit() -> impl IntoView {
 
             </Transition>
 
+            <Show when=move || !advisories.get().is_empty()>
+                <div
+                    class="mt-5 bg-yellow-50 border border-yellow-200 text-sm text-yellow-800 rounded-lg p-4 dark:bg-yellow-800/10 dark:border-yellow-900 dark:text-yellow-500"
+                    role="alert"
+                >
+                    <ul class="list-disc space-y-1 ps-5">
+                        {move || {
+                            advisories
+                                .get()
+                                .into_iter()
+                                .map(|advisory| view! { <li>{advisory}</li> })
+                                .collect_view()
+                        }}
+
+                    </ul>
+                </div>
+            </Show>
+
+            {move || {
+                params
+                    .get()
+                    .get("id")
+                    .filter(|_| current_schema.get().id == "listener")
+                    .map(|id| view! { <ListenerTestPanel id=id.clone()/> })
+            }}
+
+            <Show when=move || current_schema.get().id == "certificate">
+                <CertificateSummary data=data/>
+            </Show>
+
             <FormButtonBar>
+                <Button
+                    text="Copy as curl"
+                    color=Color::Gray
+                    on_click=Callback::new(move |_| copy_as_curl())
+                >
+
+                    <IconClipboard/>
+                </Button>
+
                 <Button
                     text="Cancel"
                     color=Color::Gray
@@ -542,15 +1000,9 @@ pub fn SettingsEdit() -> impl IntoView {
                 <Button
                     text="Save & Reload"
                     color=Color::Gray
-                    on_click=Callback::new(move |_| {
-                        data.update(|data| {
-                            if data.validate_form() {
-                                save_changes.dispatch((Arc::new(data.build_update()), true));
-                            }
-                        });
-                    })
+                    on_click=Callback::new(move |_| save(true, false))
 
-                    disabled=pending
+                    disabled=is_saving
                 >
 
                     <IconRefresh/>
@@ -559,15 +1011,9 @@ pub fn SettingsEdit() -> impl IntoView {
                 <Button
                     text="Save changes"
                     color=Color::Blue
-                    on_click=Callback::new(move |_| {
-                        data.update(|data| {
-                            if data.validate_form() {
-                                save_changes.dispatch((Arc::new(data.build_update()), false));
-                            }
-                        });
-                    })
+                    on_click=Callback::new(move |_| save(false, false))
 
-                    disabled=pending
+                    disabled=is_saving
                 />
             </FormButtonBar>
 
@@ -575,6 +1021,90 @@ pub fn SettingsEdit() -> impl IntoView {
     }
 }
 
+async fn fetch_record(auth: &AuthToken, prefix: &str, id: &str) -> http::Result<Option<Settings>> {
+    HttpRequest::get("/api/settings/list")
+        .with_authorization(auth)
+        .with_parameter("prefix", format!("{prefix}.{id}"))
+        .send::<FetchSettings>()
+        .await
+        .map(|mut list| {
+            if !list.items.is_empty() {
+                list.items.insert("_id".to_string(), id.to_string());
+                Some(list.items)
+            } else {
+                None
+            }
+        })
+}
+
+enum SaveError {
+    // The record no longer matches the version the form was loaded from
+    Conflict,
+    Http(http::Error),
+}
+
+// The settings API does not accept a version with updates, so the check runs right before
+// each save to keep the window in which a concurrent write goes unnoticed short
+async fn check_version(
+    auth: &AuthToken,
+    prefix: &str,
+    id: &str,
+    version: u64,
+) -> Result<(), SaveError> {
+    match fetch_record(auth, prefix, id).await {
+        Ok(current) if current.as_ref().map_or(0, settings_version) != version => {
+            Err(SaveError::Conflict)
+        }
+        Ok(_) => Ok(()),
+        Err(err) => Err(SaveError::Http(err)),
+    }
+}
+
+fn settings_version(settings: &Settings) -> u64 {
+    let mut entries = settings.iter().collect::<Vec<_>>();
+    entries.sort_unstable();
+    let mut hasher = DefaultHasher::new();
+    entries.hash(&mut hasher);
+    hasher.finish()
+}
+
+async fn missing_references(
+    auth: &AuthToken,
+    references: Vec<(Arc<Field>, Arc<Schema>, Vec<String>)>,
+) -> http::Result<Vec<(Arc<Field>, Arc<Schema>, String)>> {
+    let mut record_ids: AHashMap<&'static str, AHashSet<String>> = AHashMap::new();
+    let mut missing = Vec::new();
+
+    for (field, schema, values) in references {
+        if !matches!(schema.typ, SchemaType::Record { .. }) {
+            continue;
+        }
+        if !record_ids.contains_key(schema.id) {
+            let ids = HttpRequest::get("/api/settings/group")
+                .with_authorization(auth)
+                .with_parameter("prefix", schema.unwrap_prefix())
+                .with_parameter("suffix", schema.try_unwrap_suffix().unwrap_or_default())
+                .send::<List<Settings>>()
+                .await?
+                .items
+                .into_iter()
+                .filter_map(|mut item| item.remove("_id"))
+                .collect();
+            record_ids.insert(schema.id, ids);
+        }
+
+        let ids = &record_ids[schema.id];
+        missing.extend(
+            values
+                .into_iter()
+                .filter(|value| !ids.contains(value))
+                .map(|value| (field.clone(), schema.clone(), value)),
+        );
+    }
+
+    Ok(missing)
+}
+
 impl Schema {
     fn list_path(&self) -> Option<String> {
         if !matches!(self.typ, SchemaType::List) {
@@ -589,3 +1119,7 @@ impl Schema {
             .unwrap_or_else(|| DEFAULT_SETTINGS_URL.to_string())
     }
 }
+
+fn section_id(idx: usize) -> String {
+    format!("form-section-{idx}")
+}
@@ -0,0 +1,171 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::collections::BTreeMap;
+
+use chrono::Local;
+use serde::Deserialize;
+
+use crate::core::{
+    api::{ApiClient, ApiResult},
+    oauth::AuthToken,
+    schema::Schemas,
+    url::UrlBuilder,
+};
+
+use super::Settings;
+
+pub const REDACTED: &str = "<redacted>";
+
+// Settings without a schema, or whose schema field is missing the sensitive flag, are
+// still redacted when their name gives them away
+const SECRET_KEY_NAMES: &[&str] = &["private-key", "secret", "password"];
+
+#[derive(Deserialize)]
+struct FetchSettings {
+    items: Settings,
+}
+
+pub struct ConfigExport {
+    pub filename: String,
+    pub contents: String,
+    pub redacted: usize,
+}
+
+pub async fn export_config(
+    auth: &AuthToken,
+    schemas: &Schemas,
+    include_secrets: bool,
+) -> ApiResult<ConfigExport> {
+    let settings = ApiClient::new(auth)
+        .get::<FetchSettings>(UrlBuilder::new("/api/settings/list").with_parameter("prefix", ""))
+        .await?
+        .items;
+
+    let mut redacted = 0;
+    let settings = settings
+        .into_iter()
+        .map(|(key, value)| {
+            if !include_secrets && (schemas.is_sensitive_key(&key) || is_secret_key(&key)) {
+                redacted += 1;
+                (key, REDACTED.to_string())
+            } else {
+                (key, value)
+            }
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let now = Local::now();
+    let mut contents = format!(
+        "# Stalwart Mail Server configuration exported on {}\n",
+        now.to_rfc2822()
+    );
+    if redacted > 0 {
+        contents.push_str("# Secrets have been redacted and must be restored before use\n");
+    }
+    contents.push('\n');
+    contents.push_str(&to_toml(&settings));
+
+    Ok(ConfigExport {
+        filename: format!("stalwart-config-{}.toml", now.format("%Y%m%d-%H%M%S")),
+        contents,
+        redacted,
+    })
+}
+
+// Writes one dotted key per line, quoting keys that would otherwise clash with a nested table
+fn to_toml(settings: &BTreeMap<String, String>) -> String {
+    let mut contents = String::new();
+
+    for (key, value) in settings {
+        let table_prefix = format!("{key}.");
+        let is_table = settings
+            .range(table_prefix.clone()..)
+            .next()
+            .is_some_and(|(next_key, _)| next_key.starts_with(&table_prefix));
+        if is_table {
+            contents.push_str(&quote_key(key));
+        } else {
+            contents.push_str(
+                &key.split('.')
+                    .map(|part| {
+                        if !part.is_empty()
+                            && part
+                                .chars()
+                                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_'))
+                        {
+                            part.to_string()
+                        } else {
+                            quote_key(part)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("."),
+            );
+        }
+        contents.push_str(" = ");
+        contents.push_str(&toml::Value::String(value.to_string()).to_string());
+        contents.push('\n');
+    }
+
+    contents
+}
+
+fn quote_key(key: &str) -> String {
+    toml::Value::String(key.to_string()).to_string()
+}
+
+fn is_secret_key(key: &str) -> bool {
+    key.split('.').any(|part| {
+        SECRET_KEY_NAMES
+            .iter()
+            .any(|name| part.to_ascii_lowercase().contains(name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_secret_key;
+
+    #[test]
+    fn secret_key_names() {
+        for key in [
+            "signature.rsa.private-key",
+            "certificate.default.private-key",
+            "store.pg.password",
+            "directory.ldap.bind.secret",
+            "authentication.oauth.client-secret.0000",
+            "server.listener.smtp.Password",
+        ] {
+            assert!(is_secret_key(key), "{key:?}");
+        }
+
+        for key in [
+            "signature.rsa.selector",
+            "server.listener.smtp.bind",
+            "storage.data",
+        ] {
+            assert!(!is_secret_key(key), "{key:?}");
+        }
+    }
+}
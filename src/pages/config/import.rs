@@ -0,0 +1,522 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::sync::Arc;
+
+use leptos::*;
+use leptos_router::{use_navigate, use_params_map};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{
+    components::{
+        badge::Badge,
+        form::{button::Button, Form, FormButtonBar, FormItem, FormSection},
+        list::table::{Table, TableRow},
+        messages::alert::{use_alerts, Alert},
+        progress::ProgressBar,
+        Color,
+    },
+    core::{
+        api::{ApiClient, ApiError},
+        form::FormData,
+        oauth::{login_redirect_url, use_authorization},
+        schema::Type,
+        url::UrlBuilder,
+    },
+    pages::{
+        config::{Schema, SchemaType, Schemas, Settings, SettingsValues},
+        maybe_plural,
+    },
+};
+
+#[derive(Clone, PartialEq, Eq)]
+enum ImportStatus {
+    Pending,
+    Imported,
+    Failed(String),
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct ImportItem {
+    id: String,
+    settings: Settings,
+    data: FormData,
+    errors: Vec<String>,
+    status: ImportStatus,
+}
+
+#[component]
+pub fn SettingsImport() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let params = use_params_map();
+    let schemas = expect_context::<Arc<Schemas>>();
+    let current_schema = create_memo(move |_| {
+        if let Some(schema) = params
+            .get()
+            .get("object")
+            .and_then(|id| schemas.schemas.get(id.as_str()))
+            .filter(|schema| matches!(schema.typ, SchemaType::Record { .. }))
+        {
+            schema.clone()
+        } else {
+            use_navigate()("/404", Default::default());
+            Arc::new(Schema::default())
+        }
+    });
+
+    let contents = create_rw_signal(String::new());
+    let items = create_rw_signal(Vec::<ImportItem>::new());
+    let importable = create_memo(move |_| {
+        items.with(|items| items.iter().filter(|item| item.is_importable()).count())
+    });
+
+    let preview_action = create_action(move |contents: &String| {
+        let schema = current_schema.get_untracked();
+        let client = ApiClient::new(auth.get_untracked());
+        let records = parse_records(&schema, contents);
+
+        async move {
+            let records = match records {
+                Ok(records) if !records.is_empty() => records,
+                Ok(_) => {
+                    items.set(Vec::new());
+                    alert.set(Alert::warning(format!(
+                        "No {} found in the configuration",
+                        schema.name_plural
+                    )));
+                    return;
+                }
+                Err(err) => {
+                    items.set(Vec::new());
+                    alert.set(Alert::error("Failed to parse configuration").with_details(err));
+                    return;
+                }
+            };
+
+            // Fetch existing records to tell creates apart from updates
+            let prefix = schema.unwrap_prefix();
+            let existing = match client
+                .get::<Settings>(
+                    UrlBuilder::new("/api/settings/keys").with_parameter(
+                        "prefixes",
+                        records
+                            .iter()
+                            .map(|(id, _)| format!("{prefix}.{id}"))
+                            .collect::<Vec<_>>()
+                            .join(","),
+                    ),
+                )
+                .await
+            {
+                Ok(existing) => existing,
+                Err(ApiError::Unauthorized) => {
+                    use_navigate()(&login_redirect_url(), Default::default());
+                    return;
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                    return;
+                }
+            };
+
+            items.set(
+                records
+                    .into_iter()
+                    .map(|(id, settings)| {
+                        let record_prefix = format!("{prefix}.{id}.");
+                        let is_update = existing.keys().any(|key| key.starts_with(&record_prefix));
+                        ImportItem::new(schema.clone(), id, settings, is_update)
+                    })
+                    .collect(),
+            );
+        }
+    });
+
+    let progress = create_rw_signal(None::<(usize, usize)>);
+    let import_action = create_action(move |_: &()| {
+        let schema = current_schema.get_untracked();
+        let client = ApiClient::new(auth.get_untracked());
+        let updates = items.with_untracked(|items| {
+            items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| item.is_importable())
+                .map(|(idx, item)| (idx, item.data.build_update()))
+                .collect::<Vec<_>>()
+        });
+
+        async move {
+            let mut imported = 0;
+            let mut failed = 0;
+            let total = updates.len();
+            progress.set(Some((0, total)));
+
+            for (done, (idx, update)) in updates.into_iter().enumerate() {
+                let status = match client
+                    .create::<_, Option<String>>("/api/settings", update)
+                    .await
+                {
+                    Ok(_) => {
+                        imported += 1;
+                        ImportStatus::Imported
+                    }
+                    Err(ApiError::Unauthorized) => {
+                        progress.set(None);
+                        use_navigate()(&login_redirect_url(), Default::default());
+                        return;
+                    }
+                    Err(err) => {
+                        failed += 1;
+                        ImportStatus::Failed(err.to_string())
+                    }
+                };
+                items.update(|items| items[idx].status = status);
+                progress.set(Some((done + 1, total)));
+            }
+            progress.set(None);
+
+            let text = maybe_plural(imported, schema.name_singular, schema.name_plural);
+            alert.set(if failed == 0 {
+                Alert::success(format!("Imported {text}"))
+            } else {
+                Alert::warning(format!("Imported {text}, {failed} failed"))
+                    .with_details("Review the failed items below and try again.")
+            });
+        }
+    });
+    let pending =
+        Signal::derive(move || preview_action.pending().get() || import_action.pending().get());
+
+    let headers = Signal::derive(move || {
+        let schema = current_schema.get();
+        schema
+            .list
+            .fields
+            .iter()
+            .map(|field| field.label_column.to_string())
+            .chain(["Action".to_string(), "Status".to_string()])
+            .collect::<Vec<_>>()
+    });
+
+    view! {
+        <Form
+            title=Signal::derive(move || {
+                format!("Import {}", current_schema.get().name_plural)
+            })
+
+            subtitle=Signal::derive(move || {
+                format!(
+                    "Create or update multiple {} from a TOML configuration file",
+                    current_schema.get().name_plural,
+                )
+            })
+        >
+
+            <FormSection>
+                <FormItem label="Configuration">
+                    <textarea
+                        class="py-3 px-4 block w-full font-mono border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                        rows="12"
+                        spellcheck="false"
+                        placeholder=move || {
+                            format!(
+                                "[{}.<id>]\nkey = \"value\"",
+                                current_schema.get().unwrap_prefix(),
+                            )
+                        }
+
+                        prop:value=move || contents.get()
+                        on:input=move |ev| {
+                            contents.set(event_target_value(&ev));
+                        }
+                    >
+                    </textarea>
+                    <input
+                        type="file"
+                        accept=".toml,text/plain"
+                        class="mt-2 block w-full text-sm text-gray-500 file:me-4 file:py-2 file:px-4 file:rounded-lg file:border-0 file:text-sm file:font-semibold file:bg-blue-600 file:text-white hover:file:bg-blue-700 dark:text-gray-400"
+                        on:change=move |ev| {
+                            let Some(file) = event_target::<web_sys::HtmlInputElement>(&ev)
+                                .files()
+                                .and_then(|files| files.get(0)) else {
+                                return;
+                            };
+                            spawn_local(async move {
+                                match JsFuture::from(file.text()).await {
+                                    Ok(text) => {
+                                        contents.set(text.as_string().unwrap_or_default());
+                                    }
+                                    Err(_) => {
+                                        alert.set(Alert::error("Failed to read file"));
+                                    }
+                                }
+                            });
+                        }
+                    />
+
+                </FormItem>
+            </FormSection>
+
+            <Show when=move || items.with(|items| !items.is_empty())>
+                <div class="mt-5">
+                    <Table headers=headers>
+                        {move || {
+                            let schema = current_schema.get();
+                            items
+                                .get()
+                                .into_iter()
+                                .map(|item| {
+                                    let columns = schema
+                                        .list
+                                        .fields
+                                        .iter()
+                                        .map(|field| {
+                                            let value = if field.id == "_id" {
+                                                item.id.clone()
+                                            } else {
+                                                item.settings.format(field)
+                                            };
+                                            view! { <span>{value}</span> }
+                                        })
+                                        .collect_view();
+                                    let action = if item.data.is_update {
+                                        view! { <Badge color=Color::Yellow>"Update"</Badge> }
+                                    } else {
+                                        view! { <Badge color=Color::Blue>"Create"</Badge> }
+                                    };
+                                    let status = item.status_view();
+                                    view! {
+                                        <TableRow>
+                                            {columns}
+                                            {action}
+                                            {status}
+                                        </TableRow>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+
+                    </Table>
+                </div>
+            </Show>
+
+            <Show when=move || progress.get().is_some()>
+                <FormSection>
+                    <ProgressBar
+                        value=Signal::derive(move || {
+                            progress
+                                .get()
+                                .filter(|(_, total)| *total > 0)
+                                .map_or(0.0, |(done, total)| done as f64 * 100.0 / total as f64)
+                        })
+
+                        label=Signal::derive(move || {
+                            let (done, total) = progress.get().unwrap_or_default();
+                            format!("Imported {done} of {total}")
+                        })
+                    />
+
+                </FormSection>
+            </Show>
+
+            <FormButtonBar>
+                <Button
+                    text="Cancel"
+                    color=Color::Gray
+                    on_click=move |_| {
+                        use_navigate()(
+                            &format!("/settings/{}", current_schema.get().id),
+                            Default::default(),
+                        );
+                    }
+                />
+
+                <Button
+                    text="Preview"
+                    color=Color::Gray
+                    disabled=Signal::derive(move || {
+                        pending.get() || contents.with(|contents| contents.trim().is_empty())
+                    })
+
+                    on_click=move |_| {
+                        preview_action.dispatch(contents.get());
+                    }
+                />
+
+                <Button
+                    text=Signal::derive(move || {
+                        let schema = current_schema.get();
+                        format!(
+                            "Import {}",
+                            maybe_plural(
+                                importable.get(),
+                                schema.name_singular,
+                                schema.name_plural,
+                            ),
+                        )
+                    })
+
+                    color=Color::Blue
+                    disabled=Signal::derive(move || pending.get() || importable.get() == 0)
+                    on_click=move |_| {
+                        import_action.dispatch(());
+                    }
+                />
+
+            </FormButtonBar>
+        </Form>
+    }
+}
+
+impl ImportItem {
+    fn new(schema: Arc<Schema>, id: String, mut settings: Settings, is_update: bool) -> Self {
+        let mut errors = settings
+            .keys()
+            .filter(|key| !is_known_key(&schema, key))
+            .map(|key| format!("{key}: Unknown setting"))
+            .collect::<Vec<_>>();
+
+        settings.insert("_id".to_string(), id.clone());
+        let mut data = FormData::from_settings(schema, Some(settings.clone()));
+        data.is_update = is_update;
+        if !data.validate_form() {
+            errors.extend(data.errors.iter().map(|(key, err)| {
+                if key == "_id" {
+                    format!("ID: {}", err.error)
+                } else {
+                    format!("{key}: {}", err.error)
+                }
+            }));
+        }
+        errors.sort();
+
+        ImportItem {
+            id,
+            settings,
+            data,
+            errors,
+            status: ImportStatus::Pending,
+        }
+    }
+
+    fn is_importable(&self) -> bool {
+        self.errors.is_empty() && self.status != ImportStatus::Imported
+    }
+
+    fn status_view(&self) -> View {
+        match &self.status {
+            ImportStatus::Imported => {
+                view! { <Badge color=Color::Green>"Imported"</Badge> }.into_view()
+            }
+            ImportStatus::Failed(error) => view! {
+                <div class="flex flex-col gap-y-1">
+                    <Badge color=Color::Red>"Failed"</Badge>
+                    <span class="text-xs text-red-600 whitespace-normal">{error.clone()}</span>
+                </div>
+            }
+            .into_view(),
+            ImportStatus::Pending if !self.errors.is_empty() => {
+                let errors = self
+                    .errors
+                    .iter()
+                    .map(|error| view! { <li>{error.clone()}</li> })
+                    .collect_view();
+                view! {
+                    <div class="flex flex-col gap-y-1">
+                        <Badge color=Color::Red>"Invalid"</Badge>
+                        <ul class="text-xs text-red-600 whitespace-normal list-disc list-inside">
+                            {errors}
+                        </ul>
+                    </div>
+                }
+                .into_view()
+            }
+            ImportStatus::Pending => view! { <Badge color=Color::Gray>"Ready"</Badge> }.into_view(),
+        }
+    }
+}
+
+// Accepts either the full configuration layout or bare tables keyed by record id
+fn parse_records(schema: &Schema, contents: &str) -> Result<Vec<(String, Settings)>, String> {
+    let root = contents
+        .parse::<toml::Table>()
+        .map_err(|err| err.message().to_string())?;
+    let table = schema
+        .unwrap_prefix()
+        .split('.')
+        .try_fold(&root, |table, key| table.get(key)?.as_table())
+        .unwrap_or(&root);
+
+    let mut records = Vec::with_capacity(table.len());
+    for (id, value) in table {
+        let Some(record) = value.as_table() else {
+            return Err(format!("Expected a table for {id:?}"));
+        };
+        let mut settings = Settings::new();
+        for (key, value) in record {
+            flatten_value(key, value, &mut settings);
+        }
+        records.push((id.to_string(), settings));
+    }
+
+    Ok(records)
+}
+
+pub fn flatten_value(key: &str, value: &toml::Value, settings: &mut Settings) {
+    match value {
+        toml::Value::String(value) => {
+            settings.insert(key.to_string(), value.to_string());
+        }
+        toml::Value::Table(table) => {
+            for (subkey, value) in table {
+                flatten_value(&format!("{key}.{subkey}"), value, settings);
+            }
+        }
+        toml::Value::Array(values) => match values.as_slice() {
+            [] => (),
+            [value] => flatten_value(key, value, settings),
+            values => {
+                let pad_len = (values.len() - 1).to_string().len();
+                for (idx, value) in values.iter().enumerate() {
+                    flatten_value(&format!("{key}.{idx:0>pad_len$}"), value, settings);
+                }
+            }
+        },
+        value => {
+            settings.insert(key.to_string(), value.to_string());
+        }
+    }
+}
+
+fn is_known_key(schema: &Schema, key: &str) -> bool {
+    schema.fields.values().any(|field| {
+        key == field.id
+            || (matches!(
+                field.typ_,
+                Type::Array | Type::Select { multi: true, .. } | Type::Expression
+            ) && key
+                .strip_prefix(field.id)
+                .is_some_and(|key| key.starts_with('.')))
+    })
+}
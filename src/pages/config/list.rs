@@ -21,31 +21,36 @@
  * for more details.
 */
 
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 
-use leptos::*;
+use leptos::{wasm_bindgen::JsCast, *};
 use leptos_router::*;
 
 use crate::{
     components::{
-        icon::{IconAdd, IconRefresh, IconTrash},
+        form::{input::InputText, FormElement},
+        highlight::{filter_terms, Highlight},
+        icon::{IconAdd, IconArrowUturnLeft, IconDocumentText, IconRefresh, IconTrash},
         list::{
             header::ColumnList,
             pagination::Pagination,
-            row::SelectItem,
+            row::{use_row_menu, RowAction, RowActions, SelectItem},
             toolbar::{SearchBox, ToolbarButton},
             Footer, ListItem, ListSection, ListTable, ListTextItem, Toolbar, ZeroResults,
         },
         messages::{
-            alert::{use_alerts, Alert},
+            alert::{use_alerts, Alert, UNDO_TIMEOUT},
             modal::{use_modals, Modal},
         },
         skeleton::Skeleton,
         Color,
     },
     core::{
+        form::FormData,
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
+        prefs::{use_preferences, ListSort, ListView},
+        schema::{Field, Type},
         url::UrlBuilder,
     },
     pages::{
@@ -97,11 +102,32 @@ pub fn SettingsList() -> impl IntoView {
     let auth = use_authorization();
     let alert = use_alerts();
     let modal = use_modals();
+    let prefs = use_preferences();
     provide_context(selected);
 
+    let list_view =
+        create_memo(move |_| prefs.with(|prefs| prefs.list_view(current_schema.get().id)));
+    let page_size = create_memo(move |_| {
+        list_view
+            .get()
+            .page_size
+            .or(current_schema.get().list.page_size)
+            .unwrap_or_else(|| prefs.with(|prefs| prefs.page_size()))
+    });
+    let sort = Signal::derive(move || list_view.get().sort);
+    let update_view = move |update: &dyn Fn(&mut ListView)| {
+        let id = current_schema.get_untracked().id;
+        prefs.update(|prefs| {
+            let mut view = prefs.list_view(id);
+            update(&mut view);
+            prefs.set_list_view(id, view);
+            prefs.save();
+        });
+    };
+
     let settings = create_resource(
-        move || (page.get(), filter.get()),
-        move |(page, filter)| {
+        move || (page.get(), filter.get(), page_size.get()),
+        move |(page, filter, page_size)| {
             let auth = auth.get_untracked();
             let schema = current_schema.get();
 
@@ -109,7 +135,7 @@ pub fn SettingsList() -> impl IntoView {
                 HttpRequest::get("/api/settings/group")
                     .with_authorization(&auth)
                     .with_parameter("page", page.to_string())
-                    .with_parameter("limit", schema.list.page_size.to_string())
+                    .with_parameter("limit", page_size.to_string())
                     .with_parameter("prefix", schema.unwrap_prefix())
                     .with_parameter("suffix", schema.try_unwrap_suffix().unwrap_or_default())
                     .with_optional_parameter("filter", filter)
@@ -136,7 +162,37 @@ pub fn SettingsList() -> impl IntoView {
                     alert.set(Alert::from(result));
                 }
                 Err(http::Error::Unauthorized) => {
-                    use_navigate()("/login", Default::default());
+                    use_navigate()(&login_redirect_url(), Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    let restore_action = create_action(move |updates: &Arc<Vec<UpdateSettings>>| {
+        let updates = updates.clone();
+        let auth = auth.get();
+        let schema = current_schema.get();
+
+        async move {
+            match HttpRequest::post("/api/settings")
+                .with_authorization(&auth)
+                .with_body(updates.clone())
+                .unwrap()
+                .send::<Option<String>>()
+                .await
+            {
+                Ok(_) => {
+                    settings.refetch();
+                    alert.set(Alert::success(format!(
+                        "Restored {}.",
+                        maybe_plural(updates.len(), schema.name_singular, schema.name_plural,)
+                    )));
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()(&login_redirect_url(), Default::default());
                 }
                 Err(err) => {
                     alert.set(Alert::from(err));
@@ -149,6 +205,20 @@ pub fn SettingsList() -> impl IntoView {
         let items = items.clone();
         let auth = auth.get();
         let schema = current_schema.get();
+        // Keep a copy of the deleted items so they can be restored
+        let restore = Arc::new(
+            untrack(|| settings.get())
+                .and_then(|settings| settings.ok())
+                .map(|settings| {
+                    settings
+                        .items
+                        .into_iter()
+                        .filter(|item| item.get("_id").is_some_and(|id| items.contains(id)))
+                        .filter_map(|item| restore_settings(&schema, item))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default(),
+        );
 
         async move {
             let mut updates = Vec::with_capacity(items.len());
@@ -179,10 +249,19 @@ pub fn SettingsList() -> impl IntoView {
             {
                 Ok(_) => {
                     settings.refetch();
-                    alert.set(Alert::success(format!(
+                    let mut message = Alert::success(format!(
                         "Deleted {}.",
                         maybe_plural(items.len(), schema.name_singular, schema.name_plural,)
-                    )));
+                    ));
+                    if !restore.is_empty() {
+                        message = message
+                            .with_action(
+                                "Undo",
+                                Callback::new(move |_| restore_action.dispatch(restore.clone())),
+                            )
+                            .with_timeout(UNDO_TIMEOUT);
+                    }
+                    alert.set(message);
                 }
                 Err(err) => {
                     alert.set(Alert::from(err));
@@ -191,6 +270,10 @@ pub fn SettingsList() -> impl IntoView {
         }
     });
 
+    let on_update = Callback::new(move |_| settings.refetch());
+    let on_delete = Callback::new(move |id: String| {
+        delete_action.dispatch(Arc::new(HashSet::from([id])));
+    });
     let total_results = create_rw_signal(None::<u32>);
     view! {
         <ListSection>
@@ -233,7 +316,7 @@ pub fn SettingsList() -> impl IntoView {
                                         Modal::with_title("Confirm deletion")
                                             .with_message(
                                                 format!(
-                                                    "Are you sure you want to delete {text}? This action cannot be undone.",
+                                                    "Are you sure you want to delete {text}?",
                                                 ),
                                             )
                                             .with_button(format!("Delete {text}"))
@@ -253,6 +336,19 @@ pub fn SettingsList() -> impl IntoView {
                         <IconTrash/>
                     </ToolbarButton>
 
+                    <Show when=move || list_view.get() != ListView::default()>
+                        <ToolbarButton
+                            text="Reset view"
+                            color=Color::Gray
+                            on_click=Callback::new(move |_| {
+                                update_view(&|view| *view = ListView::default());
+                            })
+                        >
+
+                            <IconArrowUturnLeft/>
+                        </ToolbarButton>
+                    </Show>
+
                     <ToolbarButton
                         text="Reload config"
 
@@ -265,6 +361,22 @@ pub fn SettingsList() -> impl IntoView {
                         <IconRefresh/>
                     </ToolbarButton>
 
+                    <Show when=move || current_schema.get().id == "listener">
+                        <ToolbarButton
+                            text="Import"
+                            color=Color::Gray
+                            on_click=move |_| {
+                                use_navigate()(
+                                    &format!("/settings/{}/import", current_schema.get().id),
+                                    Default::default(),
+                                );
+                            }
+                        >
+
+                            <IconDocumentText/>
+                        </ToolbarButton>
+                    </Show>
+
                     <ToolbarButton
                         text=Signal::derive(move || {
                             format!("Create {}", current_schema.get().name_singular)
@@ -288,7 +400,7 @@ pub fn SettingsList() -> impl IntoView {
                     {move || match settings.get() {
                         None => None,
                         Some(Err(http::Error::Unauthorized)) => {
-                            use_navigate()("/login", Default::default());
+                            use_navigate()(&login_redirect_url(), Default::default());
                             Some(view! { <div></div> }.into_view())
                         }
                         Some(Err(err)) => {
@@ -299,6 +411,13 @@ pub fn SettingsList() -> impl IntoView {
                         Some(Ok(settings)) if !settings.items.is_empty() => {
                             total_results.set(Some(settings.total as u32));
                             let schema = current_schema.get();
+                            let mut settings = settings;
+                            // Sorting happens in the browser, with more than one page it
+                            // would only order the current page
+                            let is_sortable = settings.total <= settings.items.len() as u64;
+                            if let Some(sort) = sort.get().filter(|_| is_sortable) {
+                                sort_settings(&mut settings.items, &schema, sort);
+                            }
                             let settings_ = settings.clone();
                             let mut headers = schema
                                 .list
@@ -313,6 +432,11 @@ pub fn SettingsList() -> impl IntoView {
                                 view! {
                                     <ColumnList
                                         headers=headers
+                                        sort=sort
+                                        on_sort=is_sortable
+                                            .then(|| Callback::new(move |sort| {
+                                                update_view(&|view| view.sort = Some(sort));
+                                            }))
 
                                         select_all=Callback::new(move |_| {
                                             settings_
@@ -334,7 +458,13 @@ pub fn SettingsList() -> impl IntoView {
 
                                             let:settings
                                         >
-                                            <SettingsItem settings schema=schema.clone()/>
+                                            <SettingsItem
+                                                settings
+                                                schema=schema.clone()
+                                                filter=filter
+                                                on_update=on_update
+                                                on_delete=on_delete
+                                            />
                                         </For>
 
                                     </ColumnList>
@@ -376,7 +506,18 @@ pub fn SettingsList() -> impl IntoView {
                     <Pagination
                         current_page=page
                         total_results=total_results.read_only()
-                        page_size=Signal::derive(move || current_schema.get().list.page_size)
+                        page_size=page_size
+                        on_page_size_change=move |size| {
+                            update_view(&|view| view.page_size = Some(size));
+                            use_navigate()(
+                                &UrlBuilder::new("/settings")
+                                    .with_subpath(current_schema.get().id)
+                                    .with_optional_parameter("filter", filter.get())
+                                    .finish(),
+                                Default::default(),
+                            );
+                        }
+
                         on_page_change=move |page: u32| {
                             use_navigate()(
                                 &UrlBuilder::new("/settings")
@@ -395,31 +536,111 @@ pub fn SettingsList() -> impl IntoView {
     }
 }
 
+fn restore_settings(schema: &Schema, mut item: Settings) -> Option<UpdateSettings> {
+    let id = item.remove("_id")?;
+    match schema.typ {
+        SchemaType::Record { prefix, .. } => Some(UpdateSettings::Insert {
+            prefix: Some(format!("{prefix}.{id}")),
+            values: item
+                .into_iter()
+                .filter(|(key, _)| !key.starts_with('_'))
+                .collect(),
+            assert_empty: true,
+        }),
+        SchemaType::Entry { prefix } => Some(UpdateSettings::Insert {
+            prefix: None,
+            values: vec![(format!("{prefix}.{id}"), item.remove("_value")?)],
+            assert_empty: true,
+        }),
+        SchemaType::List => None,
+    }
+}
+
+fn sort_settings(items: &mut [Settings], schema: &Schema, sort: ListSort) {
+    let Some(field) = schema.list.fields.get(sort.column) else {
+        return;
+    };
+    items.sort_by(|a, b| {
+        let (a, b) = (a.format(field), b.format(field));
+        let ordering = match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.total_cmp(&b),
+            _ => a.to_lowercase().cmp(&b.to_lowercase()),
+        };
+        if sort.ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+}
+
 #[component]
-fn SettingsItem(settings: Settings, schema: Arc<Schema>) -> impl IntoView {
+fn SettingsItem(
+    settings: Settings,
+    schema: Arc<Schema>,
+    #[prop(into)] filter: Signal<Option<String>>,
+    #[prop(into)] on_update: Callback<(), ()>,
+    #[prop(into)] on_delete: Callback<String, ()>,
+) -> impl IntoView {
+    let terms = filter_terms(filter);
+    let row_menu = use_row_menu();
     let columns = schema
         .list
         .fields
         .iter()
         .map(|field| {
-            let value = settings.format(field);
-            view! { <ListTextItem>{value}</ListTextItem> }
+            if field.inline_edit {
+                view! {
+                    <InlineEditItem
+                        settings=settings.clone()
+                        schema=schema.clone()
+                        field=field.clone()
+                        on_update=on_update
+                    />
+                }
+            } else {
+                let value = settings.format(field);
+                view! {
+                    <ListTextItem>
+                        <Highlight text=value query=terms/>
+                    </ListTextItem>
+                }
+            }
         })
         .collect_view();
     let setting_id = settings
         .get("_id")
         .map(|s| s.to_string())
         .unwrap_or_default();
-    let edit_link = if schema.can_edit() {
-        let edit_url = format!("/settings/{}/{}/edit", schema.id, setting_id);
+    let row_actions = if schema.can_edit() {
+        let mut actions = vec![RowAction::link(
+            "Edit",
+            format!("/settings/{}/{}/edit", schema.id, setting_id),
+        )];
+        if matches!(schema.typ, SchemaType::Record { .. }) {
+            actions.push(RowAction::link(
+                "Duplicate",
+                UrlBuilder::new(format!("/settings/{}/edit", schema.id))
+                    .with_parameter("copy", setting_id.clone())
+                    .finish(),
+            ));
+        }
+        let delete_id = setting_id.clone();
+        actions.push(RowAction::dangerous(
+            "Delete",
+            Modal::with_title("Confirm deletion")
+                .with_message(format!(
+                    "Are you sure you want to delete {} {setting_id:?}?",
+                    schema.name_singular
+                ))
+                .with_button(format!("Delete {}", schema.name_singular))
+                .with_dangerous_callback(move || on_delete.call(delete_id.clone())),
+        ));
+
+        let item_id = setting_id.clone();
         Some(view! {
-            <ListItem subclass="px-6 py-1.5">
-                <a
-                    class="inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
-                    href=edit_url
-                >
-                    Edit
-                </a>
+            <ListItem subclass="px-6 py-1.5 text-end">
+                <RowActions item_id=item_id actions=actions/>
             </ListItem>
         })
     } else {
@@ -427,7 +648,11 @@ fn SettingsItem(settings: Settings, schema: Arc<Schema>) -> impl IntoView {
     };
 
     view! {
-        <tr>
+        <tr
+            tabindex="0"
+            class="focus:outline-none focus:bg-gray-50 dark:focus:bg-slate-800"
+            on:keydown=row_menu.on_row_keydown(setting_id.clone())
+        >
             <ListItem>
                 <label class="flex">
                     <SelectItem item_id=setting_id/>
@@ -436,8 +661,200 @@ fn SettingsItem(settings: Settings, schema: Arc<Schema>) -> impl IntoView {
                 </label>
             </ListItem>
             {columns}
-            {edit_link}
+            {row_actions}
 
         </tr>
     }
 }
+
+#[component]
+fn InlineEditItem(
+    settings: Settings,
+    schema: Arc<Schema>,
+    field: Arc<Field>,
+    #[prop(into)] on_update: Callback<(), ()>,
+) -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let editing = create_rw_signal(false);
+    let display_value = create_rw_signal(settings.format(&field));
+    let row = store_value(settings.clone());
+    let data = FormData::from_settings(schema, Some(settings)).into_signal();
+    let original = store_value(
+        data.get_untracked()
+            .value::<String>(field.id)
+            .unwrap_or_default(),
+    );
+    let element = FormElement { id: field.id, data };
+    let input_ref = create_node_ref::<html::Div>();
+    input_ref.on_load(|div| {
+        request_animation_frame(move || {
+            if let Some(input) = div
+                .query_selector("input")
+                .ok()
+                .flatten()
+                .and_then(|input| input.dyn_into::<web_sys::HtmlElement>().ok())
+            {
+                let _ = input.focus();
+            }
+        });
+    });
+    let label = field.label_column;
+    let is_boolean = matches!(field.typ_, Type::Boolean);
+    let field = store_value(field);
+
+    // Changes are shown right away and rolled back if the server rejects them
+    let apply_value = move |value: String| {
+        data.update(|data| {
+            data.update(element.id, value.clone());
+        });
+        row.update_value(|row| {
+            row.insert(element.id.to_string(), value);
+        });
+        display_value.set(row.with_value(|row| field.with_value(|field| row.format(field))));
+    };
+
+    let save_action = create_action(
+        move |(updates, value): &(Arc<Vec<UpdateSettings>>, String)| {
+            let updates = updates.clone();
+            let value = value.clone();
+            let auth = auth.get();
+
+            async move {
+                match HttpRequest::post("/api/settings")
+                    .with_authorization(&auth)
+                    .with_body(updates)
+                    .unwrap()
+                    .send::<Option<String>>()
+                    .await
+                {
+                    Ok(_) => {
+                        original.set_value(value);
+                        alert.set(
+                            Alert::success(format!("Updated {}.", label.to_lowercase()))
+                                .with_timeout(Duration::from_secs(3)),
+                        );
+                        on_update.call(());
+                    }
+                    Err(http::Error::Unauthorized) => {
+                        use_navigate()(&login_redirect_url(), Default::default());
+                    }
+                    Err(err) => {
+                        apply_value(original.get_value());
+                        alert.set(Alert::from(err));
+                    }
+                }
+            }
+        },
+    );
+    let pending = save_action.pending();
+
+    let commit = move |value: String| {
+        if pending.get_untracked() || (!editing.get_untracked() && !is_boolean) {
+            return;
+        }
+        if original.with_value(|original| *original == value) {
+            editing.set(false);
+            return;
+        }
+
+        let mut updates = None;
+        data.update(|data| {
+            data.update(element.id, value);
+            if data.validate_form() {
+                updates = Some((
+                    data.build_update(),
+                    data.value::<String>(element.id).unwrap_or_default(),
+                ));
+            }
+        });
+        match updates {
+            Some((updates, value)) => {
+                editing.set(false);
+                apply_value(value.clone());
+                save_action.dispatch((Arc::new(updates), value));
+            }
+            None if !data.with_untracked(|data| data.has_errors(element.id)) => {
+                // The rest of the record is invalid, only the full form can fix it
+                alert.set(Alert::error(
+                    "This item has errors in other fields, use the edit form to fix them.",
+                ));
+            }
+            None => {}
+        }
+    };
+
+    if is_boolean {
+        let checked =
+            create_memo(move |_| data.get().value::<bool>(element.id).unwrap_or_default());
+
+        view! {
+            <ListItem>
+                <input
+                    type="checkbox"
+                    class="shrink-0 border-gray-300 rounded text-blue-600 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-600 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
+                    title=format!("Toggle {}", label.to_lowercase())
+                    prop:checked=move || checked.get()
+                    disabled=move || pending.get()
+                    on:change=move |ev| {
+                        commit(event_target_checked(&ev).to_string());
+                    }
+                />
+
+            </ListItem>
+        }
+        .into_view()
+    } else {
+        view! {
+            <ListItem subclass="ps-6 py-1.5">
+                <Show
+                    when=move || editing.get()
+                    fallback=move || {
+                        view! {
+                            <button
+                                type="button"
+                                class="text-sm text-gray-500 border-b border-dashed border-gray-300 hover:text-blue-600 dark:border-gray-600"
+                                title="Click to edit"
+                                on:click=move |_| editing.set(true)
+                            >
+                                {move || {
+                                    let value = display_value.get();
+                                    if value.is_empty() { "-".to_string() } else { value }
+                                }}
+
+                            </button>
+                        }
+                    }
+                >
+
+                    <div
+                        class="min-w-40"
+                        node_ref=input_ref
+                        on:keydown=move |ev| {
+                            match ev.key().as_str() {
+                                "Enter" => {
+                                    ev.prevent_default();
+                                    commit(event_target_value(&ev));
+                                }
+                                "Escape" => {
+                                    editing.set(false);
+                                    data.update(|data| {
+                                        data.update(element.id, original.get_value());
+                                    });
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        on:focusout=move |ev| {
+                            commit(event_target_value(&ev));
+                        }
+                    >
+                        <InputText element=element disabled=pending/>
+                    </div>
+                </Show>
+            </ListItem>
+        }
+        .into_view()
+    }
+}
@@ -0,0 +1,163 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::time::Duration;
+
+use chrono::Utc;
+use gloo_net::http::Method;
+use humansize::{format_size, DECIMAL};
+use leptos::{leptos_dom::helpers::IntervalHandle, *};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{chart::Sparkline, Color},
+    core::{api::ApiClient, oauth::use_authorization},
+};
+
+const METRICS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const METRICS_HISTORY: usize = 30;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ListenerMetrics {
+    pub connections: u64,
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct MetricsSample {
+    connections: u64,
+    throughput: f64,
+}
+
+#[component]
+pub fn ListenerMetricsPanel(id: String) -> impl IntoView {
+    let auth = use_authorization();
+    let refresh = create_rw_signal(0u32);
+    let available = create_rw_signal(true);
+    let samples = create_rw_signal(Vec::<MetricsSample>::new());
+    let last_counters = store_value(None::<(i64, u64)>);
+    let handle = store_value(None::<IntervalHandle>);
+    let clear = move || {
+        if let Some(handle) = handle.try_update_value(|handle| handle.take()).flatten() {
+            handle.clear();
+        }
+    };
+
+    let metrics = create_resource(
+        move || refresh.get(),
+        move |_| {
+            let auth = auth.get_untracked();
+            let id = id.clone();
+
+            async move {
+                ApiClient::new(&auth)
+                    .request(Method::GET, ("/api/telemetry/listener", &id))
+                    .in_background()
+                    .send::<ListenerMetrics>()
+                    .await
+            }
+        },
+    );
+
+    create_effect(move |_| match metrics.get() {
+        Some(Ok(metrics)) => {
+            let now = Utc::now().timestamp_millis();
+            let total = metrics.bytes_received + metrics.bytes_sent;
+            let throughput = match last_counters.get_value() {
+                Some((last_time, last_total)) if now > last_time && total >= last_total => {
+                    (total - last_total) as f64 * 1000.0 / (now - last_time) as f64
+                }
+                _ => 0.0,
+            };
+            last_counters.set_value(Some((now, total)));
+            samples.update(|samples| {
+                if samples.len() >= METRICS_HISTORY {
+                    samples.remove(0);
+                }
+                samples.push(MetricsSample {
+                    connections: metrics.connections,
+                    throughput,
+                });
+            });
+        }
+        Some(Err(err)) => {
+            // Older servers do not expose listener metrics
+            log::debug!("Listener metrics unavailable: {err:?}");
+            available.set(false);
+            clear();
+        }
+        None => (),
+    });
+
+    match set_interval_with_handle(
+        move || refresh.update(|refresh| *refresh += 1),
+        METRICS_REFRESH_INTERVAL,
+    ) {
+        Ok(new_handle) => handle.set_value(Some(new_handle)),
+        Err(err) => log::error!("Failed to start metrics refresh: {err:?}"),
+    }
+    on_cleanup(clear);
+
+    let latest = move || samples.with(|samples| samples.last().copied());
+    let connections = Signal::derive(move || {
+        samples.with(|samples| samples.iter().map(|s| s.connections as f64).collect())
+    });
+    let throughput = Signal::derive(move || {
+        samples.with(|samples| samples.iter().map(|s| s.throughput).collect())
+    });
+
+    view! {
+        <Show when=move || available.get() && latest().is_some()>
+            <div class="grid sm:grid-cols-2 gap-4 mb-8">
+                <div class="flex justify-between items-center p-4 border border-gray-200 rounded-xl dark:border-gray-700">
+                    <div>
+                        <p class="text-xs uppercase tracking-wide text-gray-500">Connections</p>
+                        <p class="text-xl font-medium text-gray-800 dark:text-gray-200">
+                            {move || latest().map(|s| s.connections).unwrap_or_default()}
+                        </p>
+                    </div>
+                    <Sparkline values=connections/>
+                </div>
+                <div class="flex justify-between items-center p-4 border border-gray-200 rounded-xl dark:border-gray-700">
+                    <div>
+                        <p class="text-xs uppercase tracking-wide text-gray-500">Throughput</p>
+                        <p class="text-xl font-medium text-gray-800 dark:text-gray-200">
+                            {move || {
+                                format!(
+                                    "{}/s",
+                                    format_size(
+                                        latest().map(|s| s.throughput).unwrap_or_default() as u64,
+                                        DECIMAL,
+                                    ),
+                                )
+                            }}
+
+                        </p>
+                    </div>
+                    <Sparkline values=throughput color=Color::Green markers=true/>
+                </div>
+            </div>
+        </Show>
+    }
+}
@@ -21,8 +21,13 @@
  * for more details.
 */
 
+pub mod certificate;
 pub mod edit;
+pub mod export;
+pub mod import;
 pub mod list;
+pub mod metrics;
+pub mod probe;
 pub mod schema;
 pub mod search;
 
@@ -146,6 +151,9 @@ impl FormData {
                 FormValue::Value(value) if !value.is_empty() => {
                     key_values.push((key.to_string(), value.to_string()));
                 }
+                FormValue::Secret(value) if !value.is_empty() => {
+                    key_values.push((key.to_string(), value.expose().to_string()));
+                }
                 FormValue::Array(values) if !values.is_empty() => {
                     let total_values = values.len();
                     if total_values > 1 {
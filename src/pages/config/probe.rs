@@ -0,0 +1,242 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::time::Duration;
+
+use chrono::DateTime;
+use gloo_net::http::Method;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{
+        form::button::Button,
+        icon::{IconCheckCircle, IconExclamationCircle, IconServer},
+        Color,
+    },
+    core::{
+        api::{ApiClient, ApiError},
+        oauth::use_authorization,
+        url::UrlBuilder,
+    },
+    pages::FormatDateTime,
+};
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ListenerTestResult {
+    pub success: bool,
+    pub address: Option<String>,
+    pub elapsed_ms: Option<u64>,
+    pub greeting: Option<String>,
+    pub tls: Option<TlsHandshake>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TlsHandshake {
+    pub version: String,
+    pub cipher: String,
+    pub certificates: Vec<TestCertificate>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TestCertificate {
+    pub subject: String,
+    pub issuer: String,
+    pub not_after: Option<i64>,
+}
+
+// The server connects to the saved listener through the loopback interface, so the
+// test exercises the configured bind address, protocol and TLS settings
+#[component]
+pub fn ListenerTestPanel(id: String) -> impl IntoView {
+    let auth = use_authorization();
+    let id = store_value(id);
+    let available = create_rw_signal(false);
+    let result = create_rw_signal(None::<ListenerTestResult>);
+
+    // Servers without the endpoint answer 404, otherwise the last result is returned
+    spawn_local(async move {
+        match ApiClient::new(auth.get_untracked())
+            .get::<Option<ListenerTestResult>>(test_url(id))
+            .await
+        {
+            Ok(last_result) => {
+                result.set(last_result);
+                available.set(true);
+            }
+            Err(err) => {
+                log::debug!("Listener tests unavailable: {err:?}");
+            }
+        }
+    });
+
+    let run_test = create_action(move |_: &()| {
+        let client = ApiClient::new(auth.get_untracked());
+
+        async move {
+            let test_result = client
+                .request(Method::POST, test_url(id))
+                .with_timeout(TEST_TIMEOUT)
+                .send::<ListenerTestResult>()
+                .await
+                .unwrap_or_else(|err| ListenerTestResult {
+                    error: Some(match err {
+                        ApiError::Network(_) => {
+                            "The test timed out or the server could not be reached".to_string()
+                        }
+                        err => err.to_string(),
+                    }),
+                    ..Default::default()
+                });
+            result.set(Some(test_result));
+        }
+    });
+    let is_testing = run_test.pending();
+
+    view! {
+        <Show when=move || available.get()>
+            <div class="mt-5 p-4 border border-gray-200 rounded-xl dark:border-gray-700">
+                <div class="flex flex-wrap justify-between items-center gap-2">
+                    <div>
+                        <p class="text-sm font-semibold text-gray-800 dark:text-gray-200">
+                            Connection test
+                        </p>
+                        <p class="text-xs text-gray-500">
+                            Connects to the saved listener from the server and reports the handshake.
+                        </p>
+                    </div>
+                    <Button
+                        text=Signal::derive(move || {
+                            if is_testing.get() { "Testing..." } else { "Test connection" }.to_string()
+                        })
+
+                        color=Color::Gray
+                        disabled=is_testing
+                        on_click=Callback::new(move |_| run_test.dispatch(()))
+                    >
+                        <IconServer/>
+                    </Button>
+                </div>
+
+                {move || result.get().map(|result| view! { <ListenerTestDetails result/> })}
+            </div>
+        </Show>
+    }
+}
+
+#[component]
+fn ListenerTestDetails(result: ListenerTestResult) -> impl IntoView {
+    let (icon, summary) = if result.success {
+        (
+            view! { <IconCheckCircle attr:class="flex-shrink-0 size-4 text-green-600"/> },
+            "Connection succeeded",
+        )
+    } else {
+        (
+            view! { <IconExclamationCircle attr:class="flex-shrink-0 size-4 text-red-600"/> },
+            "Connection failed",
+        )
+    };
+    let summary = match result.elapsed_ms {
+        Some(elapsed) => format!("{summary} in {elapsed} ms"),
+        None => summary.to_string(),
+    };
+    let mut details = Vec::new();
+    if let Some(address) = result.address {
+        details.push(("Address", address));
+    }
+    if let Some(greeting) = result.greeting {
+        details.push(("Greeting", greeting));
+    }
+    if let Some(error) = result.error {
+        details.push(("Error", error));
+    }
+    let certificates = result.tls.map(|tls| {
+        details.push(("TLS", format!("{} {}", tls.version, tls.cipher)));
+        tls.certificates
+            .into_iter()
+            .enumerate()
+            .map(|(depth, cert)| {
+                let expires = cert
+                    .not_after
+                    .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                    .map(|date| format!("expires {}", date.format_date()))
+                    .unwrap_or_default();
+                view! {
+                    <li class="ps-3 border-s border-gray-200 dark:border-gray-700">
+                        <p class="text-sm text-gray-800 dark:text-gray-200">
+                            {format!("#{depth} {}", cert.subject)}
+                        </p>
+                        <p class="text-xs text-gray-500">
+                            {format!("Issued by {} {expires}", cert.issuer)}
+                        </p>
+                    </li>
+                }
+            })
+            .collect_view()
+    });
+
+    view! {
+        <div class="mt-4 space-y-2" role="status">
+            <p class="flex items-center gap-x-2 text-sm font-medium text-gray-800 dark:text-gray-200">
+                {icon}
+                {summary}
+            </p>
+            <dl class="grid sm:grid-cols-4 gap-x-4 gap-y-1 text-sm">
+                {details
+                    .into_iter()
+                    .map(|(label, value)| {
+                        view! {
+                            <dt class="text-gray-500">{label}</dt>
+                            <dd class="sm:col-span-3 font-mono text-xs text-gray-800 break-all dark:text-gray-200">
+                                {value}
+                            </dd>
+                        }
+                    })
+                    .collect_view()}
+            </dl>
+            {certificates
+                .map(|certificates| {
+                    view! {
+                        <p class="text-xs uppercase tracking-wide text-gray-500">
+                            Certificate chain
+                        </p>
+                        <ul class="space-y-2">{certificates}</ul>
+                    }
+                })}
+
+        </div>
+    }
+}
+
+fn test_url(id: StoredValue<String>) -> UrlBuilder {
+    UrlBuilder::new("/api/listener")
+        .with_subpath(id.get_value())
+        .with_subpath("test")
+}
@@ -58,7 +58,9 @@ impl Builder<Schemas, ()> {
                 "Contents of the private key PEM used to sign messages"
             ))
             .typ(Type::Text)
+            .rows(10)
             .input_check([], [Validator::Required])
+            .sensitive()
             .build()
             .new_field("domain")
             .label("Domain Name")
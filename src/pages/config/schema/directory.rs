@@ -141,6 +141,7 @@ impl Builder<Schemas, ()> {
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsPort],
             )
+            .mask(InputMask::Numeric)
             .build()
             // TLS
             .new_field("tls.enable")
@@ -137,12 +137,14 @@ impl Builder<Schemas, ()> {
             .help("Whether to create the inbox folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.inbox.subscribe")
             .label("Subscribe automatically")
             .help("Whether to subscribe to the inbox folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.trash.name")
             .label("Name")
@@ -156,12 +158,14 @@ impl Builder<Schemas, ()> {
             .help("Whether to create the trash folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.trash.subscribe")
             .label("Subscribe automatically")
             .help("Whether to subscribe to the trash folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.junk.name")
             .label("Name")
@@ -175,12 +179,14 @@ impl Builder<Schemas, ()> {
             .help("Whether to create the junk folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.junk.subscribe")
             .label("Subscribe automatically")
             .help("Whether to subscribe to the junk folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.drafts.name")
             .label("Name")
@@ -194,12 +200,14 @@ impl Builder<Schemas, ()> {
             .help("Whether to create the drafts folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.drafts.subscribe")
             .label("Subscribe automatically")
             .help("Whether to subscribe to the drafts folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.sent.name")
             .label("Name")
@@ -213,12 +221,14 @@ impl Builder<Schemas, ()> {
             .help("Whether to create the sent folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.sent.subscribe")
             .label("Subscribe automatically")
             .help("Whether to subscribe to the sent folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.archive.name")
             .label("Name")
@@ -232,12 +242,14 @@ impl Builder<Schemas, ()> {
             .help("Whether to create the archive folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.archive.subscribe")
             .label("Subscribe automatically")
             .help("Whether to subscribe to the archive folder automatically")
             .default("true")
             .typ(Type::Boolean)
+            .checkbox()
             .build()
             .new_field("jmap.folders.shared.name")
             .label("Name")
@@ -21,7 +21,36 @@
  * for more details.
 */
 
-use crate::core::schema::*;
+use std::net::SocketAddr;
+
+use crate::core::{form::FormData, schema::*};
+
+use super::server::proxy_networks_advisory;
+
+// Well-known ports and whether they are normally served over implicit TLS
+const WELL_KNOWN_PORTS: &[(&str, u16, bool)] = &[
+    ("smtp", 25, false),
+    ("smtp", 587, false),
+    ("smtp", 465, true),
+    ("lmtp", 24, false),
+    ("imap", 143, false),
+    ("imap", 993, true),
+    ("pop3", 110, false),
+    ("pop3", 995, true),
+    ("http", 80, false),
+    ("http", 8080, false),
+    ("http", 443, true),
+    ("managesieve", 4190, false),
+];
+
+const DEFAULT_BIND_ADDRESSES: &[(&str, &str)] = &[
+    ("smtp", "[::]:25"),
+    ("lmtp", "[::]:24"),
+    ("http", "[::]:8080"),
+    ("imap", "[::]:143"),
+    ("pop3", "[::]:110"),
+    ("managesieve", "[::]:4190"),
+];
 
 impl Builder<Schemas, ()> {
     pub fn build_listener(self) -> Self {
@@ -51,6 +80,7 @@ impl Builder<Schemas, ()> {
             .help("The protocol used by the listener")
             .input_check([], [Validator::Required])
             .default("smtp")
+            .on_change_set("bind", DEFAULT_BIND_ADDRESSES)
             .build()
             // Bind addresses
             .new_field("bind")
@@ -58,9 +88,10 @@ impl Builder<Schemas, ()> {
             .help("The addresses the listener will bind to")
             .typ(Type::Array)
             .input_check(
-                [Transformer::Trim],
+                [Transformer::NormalizeSocketAddr],
                 [Validator::Required, Validator::IsSocketAddr],
             )
+            .mask(InputMask::SocketAddr)
             .build()
             // Override proxy protocol
             .new_field("proxy.override")
@@ -88,6 +119,19 @@ impl Builder<Schemas, ()> {
             .help("Whether to use implicit TLS")
             .typ(Type::Boolean)
             .default("false")
+            .inline_edit()
+            .build()
+            // Legacy per-listener certificate
+            .new_field("tls.certificate")
+            .label("Certificate")
+            .help("The certificate to use for this listener")
+            .typ(Type::Input)
+            .input_check([Transformer::Trim], [])
+            .references("certificate")
+            .deprecated_hidden(concat!(
+                "Certificates are now selected by SNI, ",
+                "configure them under TLS certificates instead"
+            ))
             .build()
             // Add common fields
             .add_network_fields(true)
@@ -101,6 +145,7 @@ impl Builder<Schemas, ()> {
             .title("TLS options")
             .fields([
                 "tls.implicit",
+                "tls.certificate",
                 "tls.override",
                 "tls.disable-protocols",
                 "tls.disable-ciphers",
@@ -126,10 +171,59 @@ impl Builder<Schemas, ()> {
                 "socket.reuse-addr",
                 "socket.reuse-port",
             ])
+            .group(["socket.send-buffer-size", "socket.recv-buffer-size"])
+            .group(["socket.ttl", "socket.tos"])
             .build()
+            .advisory(listener_advisory)
             .list_title("Listeners")
             .list_subtitle("Manage SMTP, IMAP, HTTP, and other listeners")
             .list_fields(["_id", "protocol", "bind", "tls.implicit"])
             .build()
     }
 }
+
+fn listener_advisory(data: &FormData) -> Vec<String> {
+    let protocol = data.value::<String>("protocol").unwrap_or_default();
+    let implicit_tls = data.value::<bool>("tls.implicit").unwrap_or_default();
+    let mut ports = data
+        .array_value("bind")
+        .filter_map(|addr| addr.trim().parse::<SocketAddr>().ok())
+        .map(|addr| addr.port())
+        .collect::<Vec<_>>();
+    ports.sort_unstable();
+    ports.dedup();
+
+    let mut warnings = Vec::new();
+    if data.value::<bool>("proxy.override").unwrap_or_default() {
+        warnings.extend(proxy_networks_advisory(data, "proxy.trusted-networks"));
+    }
+    for port in ports {
+        if let Some((_, _, uses_tls)) = WELL_KNOWN_PORTS
+            .iter()
+            .find(|(proto, p, _)| *proto == protocol && *p == port)
+        {
+            if *uses_tls && !implicit_tls {
+                warnings.push(format!(
+                    "Port {port} usually uses implicit TLS, consider enabling it."
+                ));
+            } else if !*uses_tls && implicit_tls {
+                warnings.push(format!(
+                    "Port {port} usually does not use implicit TLS, clients will expect STARTTLS or plain text."
+                ));
+            }
+        } else if let Some((proto, _, _)) = WELL_KNOWN_PORTS.iter().find(|(_, p, _)| *p == port) {
+            let label = |id| {
+                data.schema
+                    .fields
+                    .get("protocol")
+                    .map_or(id, |field| field.typ_.label(id))
+            };
+            warnings.push(format!(
+                "Port {port} is usually used by {}, not {}.",
+                label(proto),
+                label(&protocol)
+            ));
+        }
+    }
+    warnings
+}
@@ -21,7 +21,15 @@
  * for more details.
 */
 
-use crate::core::schema::*;
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use crate::core::{
+    form::{parse_ip_or_mask, FormData},
+    schema::*,
+};
 
 use super::CONNECTION_VARS;
 
@@ -113,6 +121,7 @@ impl Builder<Schemas, ()> {
                 "server.proxy.trusted-networks",
             ])
             .build()
+            .advisory(|data| proxy_networks_advisory(data, "server.proxy.trusted-networks"))
             .new_form_section()
             .title("HTTP Settings")
             .fields([
@@ -136,6 +145,11 @@ impl Builder<Schemas, ()> {
                 "server.socket.reuse-addr",
                 "server.socket.reuse-port",
             ])
+            .group([
+                "server.socket.send-buffer-size",
+                "server.socket.recv-buffer-size",
+            ])
+            .group(["server.socket.ttl", "server.socket.tos"])
             .build()
             .build()
             // Common settings
@@ -269,6 +283,7 @@ impl Builder<Schemas, ()> {
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsIpOrMask],
             )
+            .mask(InputMask::IpOrMask)
             .build()
             .new_form_section()
             .field("_id")
@@ -290,6 +305,7 @@ impl Builder<Schemas, ()> {
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsIpOrMask],
             )
+            .mask(InputMask::IpOrMask)
             .build()
             .new_form_section()
             .field("_id")
@@ -330,6 +346,7 @@ impl Builder<Schemas, ()> {
             .placeholder("10.0.0.1")
             .typ(Type::Input)
             .input_check([Transformer::Trim], [Validator::IsIpOrMask])
+            .mask(InputMask::IpOrMask)
             .build()
             // Bind port
             .new_field("cluster.bind-port")
@@ -341,6 +358,7 @@ impl Builder<Schemas, ()> {
             .default("1179")
             .typ(Type::Input)
             .input_check([Transformer::Trim], [Validator::IsPort])
+            .mask(InputMask::Numeric)
             .build()
             // Seed nodes
             .new_field("cluster.seed-nodes")
@@ -348,6 +366,7 @@ impl Builder<Schemas, ()> {
             .help(concat!("The initial nodes to connect to in the cluster"))
             .typ(Type::Array)
             .input_check([Transformer::Trim], [Validator::IsIpOrMask])
+            .mask(InputMask::IpOrMask)
             .build()
             // Heartbeat interval
             .new_field("cluster.heartbeat")
@@ -521,6 +540,7 @@ impl Builder<Schemas, Schema> {
         .help("Enable proxy protocol for connections from these networks")
         .typ(Type::Array)
         .input_check([Transformer::Trim], [Validator::IsIpOrMask])
+        .mask(InputMask::IpOrMask)
         .display_if_eq("proxy.override", do_override.iter().copied())
         .build()
         // Socket options
@@ -567,6 +587,7 @@ impl Builder<Schemas, Schema> {
             "there is still unsent data"
         ))
         .typ(Type::Duration)
+        .input_check([Transformer::SecondsToDuration], [Validator::IsDuration])
         .display_if_eq("socket.override", do_override.iter().copied())
         .build()
         // ToS
@@ -653,3 +674,100 @@ impl Builder<Schemas, Schema> {
         .build()
     }
 }
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Network {
+    addr: u128,
+    prefix: u8,
+    is_v4: bool,
+}
+
+// Informational only: overlapping entries still work but make the list harder to review
+pub fn proxy_networks_advisory(data: &FormData, field: &str) -> Vec<String> {
+    let networks = data
+        .array_value(field)
+        .map(str::trim)
+        .filter_map(|value| Some((value, Network::parse(value)?)))
+        .collect::<Vec<_>>();
+
+    let mut warnings = Vec::new();
+    let mut effective = Vec::new();
+    for (idx, (value, network)) in networks.iter().enumerate() {
+        // Equal networks are only reported on their second occurrence
+        let covered_by = networks
+            .iter()
+            .enumerate()
+            .find(|(other_idx, (_, other))| {
+                *other_idx != idx
+                    && other.contains(network)
+                    && (other != network || *other_idx < idx)
+            })
+            .map(|(_, other)| other);
+
+        match covered_by {
+            Some((other_value, _)) if other_value == value => {
+                warnings.push(format!("Proxy network {value} is listed more than once."));
+            }
+            Some((other_value, other)) if other == network => {
+                warnings.push(format!(
+                    "Proxy network {value} is the same network as {other_value}."
+                ));
+            }
+            Some((other_value, _)) => {
+                warnings.push(format!(
+                    "Proxy network {value} is already covered by {other_value}."
+                ));
+            }
+            None => effective.push(network.to_string()),
+        }
+    }
+
+    if !warnings.is_empty() {
+        warnings.push(format!(
+            "Effective proxy networks: {}.",
+            effective.join(", ")
+        ));
+    }
+    warnings
+}
+
+impl Network {
+    fn parse(value: &str) -> Option<Self> {
+        let (ip, prefix) = parse_ip_or_mask(value).ok()?;
+        let (addr, bits, is_v4) = match ip {
+            IpAddr::V4(ip) => (u32::from(ip) as u128, 32, true),
+            IpAddr::V6(ip) => (u128::from(ip), 128, false),
+        };
+        let prefix = prefix.unwrap_or(bits).min(bits);
+        Some(Network {
+            addr: addr & Self::mask(prefix, bits),
+            prefix,
+            is_v4,
+        })
+    }
+
+    fn mask(prefix: u8, bits: u8) -> u128 {
+        if prefix == 0 {
+            0
+        } else {
+            (u128::MAX << (128 - prefix)) >> (128 - bits)
+        }
+    }
+
+    fn contains(&self, other: &Network) -> bool {
+        let bits = if self.is_v4 { 32 } else { 128 };
+        self.is_v4 == other.is_v4
+            && self.prefix <= other.prefix
+            && other.addr & Self::mask(self.prefix, bits) == self.addr
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_v4 {
+            write!(f, "{}/{}", Ipv4Addr::from(self.addr as u32), self.prefix)
+        } else {
+            write!(f, "{}/{}", Ipv6Addr::from(self.addr), self.prefix)
+        }
+    }
+}
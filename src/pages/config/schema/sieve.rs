@@ -348,7 +348,8 @@ impl Builder<Schemas, ()> {
             .label("Contents")
             .help("Contents of the Sieve script")
             .typ(Type::Text)
-            .input_check([], [Validator::Required])
+            .rows(15)
+            .input_check([], [Validator::Required, Validator::IsSieveScript])
             .build()
             .new_form_section()
             .title("Sieve Script")
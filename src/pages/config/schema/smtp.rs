@@ -580,6 +580,7 @@ impl Builder<Schemas, ()> {
             ))
             .typ(Type::Input)
             .input_check([], [Validator::Required, Validator::IsPort])
+            .mask(InputMask::Numeric)
             .placeholder("25")
             .build()
             .new_field("protocol")
@@ -655,6 +656,7 @@ impl Builder<Schemas, ()> {
             .help("Whether to enable this throttle")
             .typ(Type::Boolean)
             .default("true")
+            .inline_edit()
             .build()
             .new_field("key")
             .label("Keys")
@@ -700,6 +702,7 @@ impl Builder<Schemas, ()> {
             ))
             .typ(Type::Input)
             .input_check([Transformer::Trim], [Validator::MinValue(1.into())])
+            .inline_edit()
             .build()
             .new_field("rate")
             .label("Rate limit")
@@ -731,6 +734,7 @@ impl Builder<Schemas, ()> {
             .help("Whether to enable this quota")
             .typ(Type::Boolean)
             .default("true")
+            .inline_edit()
             .build()
             .new_field("key")
             .label("Keys")
@@ -774,6 +778,7 @@ impl Builder<Schemas, ()> {
             ))
             .typ(Type::Input)
             .input_check([Transformer::Trim], [Validator::MinValue(1.into())])
+            .inline_edit()
             .build()
             .new_field("size")
             .label("Max Size")
@@ -1349,6 +1354,7 @@ impl Builder<Schemas, ()> {
             .help("Whether to enable this throttle")
             .typ(Type::Boolean)
             .default("true")
+            .inline_edit()
             .build()
             .new_field("key")
             .label("Keys")
@@ -1394,6 +1400,7 @@ impl Builder<Schemas, ()> {
             ))
             .typ(Type::Input)
             .input_check([Transformer::Trim], [Validator::MinValue(1.into())])
+            .inline_edit()
             .build()
             .new_field("rate")
             .label("Rate limit")
@@ -1455,6 +1462,7 @@ impl Builder<Schemas, ()> {
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsPort],
             )
+            .mask(InputMask::Numeric)
             .build()
             .new_field("tls")
             .label("Enable TLS")
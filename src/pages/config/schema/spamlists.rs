@@ -419,6 +419,7 @@ impl Builder<Schemas, ()> {
                 [Transformer::RemoveSpaces],
                 [Validator::Required, Validator::IsId],
             )
+            .hide_transform_hint()
             .build()
             .new_value_field()
             .label("Rule")
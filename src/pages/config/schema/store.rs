@@ -128,6 +128,7 @@ impl Builder<Schemas, ()> {
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsPort],
             )
+            .mask(InputMask::Numeric)
             .build()
             // Database name
             .new_field("database")
@@ -23,6 +23,15 @@
 
 use crate::core::schema::*;
 
+const PEM_FILE_TYPES: &[&str] = &[
+    ".pem",
+    ".crt",
+    ".cer",
+    ".key",
+    "application/x-pem-file",
+    "text/*",
+];
+
 impl Builder<Schemas, ()> {
     pub fn build_tls(self) -> Self {
         self.new_schema("acme")
@@ -185,6 +194,7 @@ impl Builder<Schemas, ()> {
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsPort],
             )
+            .mask(InputMask::Numeric)
             .default("53")
             // Host
             .new_field("host")
@@ -195,6 +205,7 @@ impl Builder<Schemas, ()> {
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsIpOrMask],
             )
+            .mask(InputMask::IpOrMask)
             // Key
             .new_field("key")
             .label("Key")
@@ -281,16 +292,31 @@ impl Builder<Schemas, ()> {
             // Cert
             .new_field("cert")
             .label("Certificate")
-            .typ(Type::Text)
+            .typ(Type::File)
             .help("TLS certificate in PEM format")
-            .input_check([Transformer::Trim], [Validator::Required])
+            .input_check(
+                [Transformer::Trim],
+                [
+                    Validator::Required,
+                    Validator::MaxFileSize(1024 * 1024),
+                    Validator::FileType(PEM_FILE_TYPES),
+                ],
+            )
             .build()
             // PK
             .new_field("private-key")
             .label("Private Key")
-            .typ(Type::Text)
+            .typ(Type::File)
             .help("Private key in PEM format")
-            .input_check([Transformer::Trim], [Validator::Required])
+            .input_check(
+                [Transformer::Trim],
+                [
+                    Validator::Required,
+                    Validator::MaxFileSize(1024 * 1024),
+                    Validator::FileType(PEM_FILE_TYPES),
+                ],
+            )
+            .sensitive()
             .build()
             .new_field("subjects")
             .typ(Type::Array)
@@ -350,6 +376,7 @@ impl Builder<Schemas, Schema> {
         .label("Handshake Timeout")
         .help("TLS handshake timeout")
         .typ(Type::Duration)
+        .input_check([Transformer::SecondsToDuration], [Validator::IsDuration])
         .default("1m")
         .display_if_eq("tls.override", do_override.iter().copied())
         .build()
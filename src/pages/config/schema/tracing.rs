@@ -79,6 +79,7 @@ impl Builder<Schemas, ()> {
             .label("Enable this tracer")
             .help("Enable or disable the tracer")
             .default("true")
+            .inline_edit()
             .build()
             // Enable
             .new_field("ansi")
@@ -27,7 +27,12 @@ use leptos::*;
 use leptos_router::use_query_map;
 
 use crate::{
-    components::{list::ZeroResults, report::ReportView},
+    components::{
+        highlight::{search_terms, Highlight},
+        list::ZeroResults,
+        report::ReportView,
+    },
+    core::{fuzzy::fuzzy_score, prefs::use_preferences},
     pages::config::Schemas,
 };
 
@@ -37,26 +42,29 @@ use super::{Field, Form, Section};
 pub fn SettingsSearch() -> impl IntoView {
     let query = use_query_map();
     let schemas = expect_context::<Arc<Schemas>>();
+    let prefs = use_preferences();
+    let fuzzy = Signal::derive(move || prefs.with(|prefs| prefs.fuzzy_search()));
 
+    let terms = create_memo(move |_| {
+        query.with(|q| q.get("query").map(|s| search_terms(s)).unwrap_or_default())
+    });
     let results = create_memo(move |_| {
-        let params = query.with(|q| {
-            q.get("query")
-                .map(|s| {
-                    s.split_whitespace()
-                        .map(|s| s.to_lowercase())
-                        .collect::<Vec<_>>()
-                })
-                .unwrap_or_default()
-        });
+        let params = terms.get();
+        let fuzzy = fuzzy.get();
 
-        schemas
+        let mut results = schemas
             .schemas
             .values()
             .filter_map(|s| {
-                let (title, matches) = s.form.contains_string(&params)?;
+                let (title, matches, score) = s.form.search(&params, fuzzy)?;
 
-                Some((s.id, title, matches))
+                Some((s.id, title, matches, score))
             })
+            .collect::<Vec<_>>();
+        results.sort_by(|a, b| b.3.cmp(&a.3).then_with(|| a.1.cmp(b.1)));
+        results
+            .into_iter()
+            .map(|(id, title, matches, _)| (id, title, matches))
             .collect::<Vec<_>>()
     });
 
@@ -77,9 +85,11 @@ pub fn SettingsSearch() -> impl IntoView {
                                 <div class="flex justify-between items-center">
                                     <div>
                                         <h3 class="group-hover:text-blue-600 font-semibold text-gray-800 dark:group-hover:text-gray-400 dark:text-gray-200">
-                                            {title}
+                                            <Highlight text=title query=terms fuzzy=fuzzy/>
                                         </h3>
-                                        <p class="text-sm text-gray-500">{matches}</p>
+                                        <p class="text-sm text-gray-500">
+                                            <Highlight text=matches query=terms fuzzy=fuzzy/>
+                                        </p>
                                     </div>
                                     <div class="ps-3">
                                         <svg
@@ -129,50 +139,80 @@ pub fn SettingsSearch() -> impl IntoView {
     }
 }
 
-trait ContainsString {
-    fn contains_string(&self, query: &[String]) -> Option<&'static str>;
+// Matches on a form title rank above matches on its fields
+const TITLE_BONUS: i32 = 10;
+
+type Match = (&'static str, i32);
+
+trait SearchMatch {
+    fn search(&self, query: &[String], fuzzy: bool) -> Option<Match>;
 }
 
 impl Form {
-    fn contains_string(&self, query: &[String]) -> Option<(&'static str, &'static str)> {
-        self.title
-            .contains_string(query)
-            .or_else(|| self.subtitle.contains_string(query))
-            .map(|matches| (self.title, matches))
-            .or_else(|| {
-                self.sections.iter().find_map(|s| {
-                    s.contains_string(query)
-                        .map(|m| (s.title.as_ref().copied().unwrap_or(self.title), m))
-                })
+    fn search(&self, query: &[String], fuzzy: bool) -> Option<(&'static str, &'static str, i32)> {
+        let title = self
+            .title
+            .search(query, fuzzy)
+            .map(|(matches, score)| (self.title, matches, score + TITLE_BONUS));
+        let subtitle = self
+            .subtitle
+            .search(query, fuzzy)
+            .map(|(matches, score)| (self.title, matches, score));
+        let sections = self.sections.iter().filter_map(|s| {
+            s.search(query, fuzzy).map(|(matches, score)| {
+                (
+                    s.title.as_ref().copied().unwrap_or(self.title),
+                    matches,
+                    score,
+                )
             })
+        });
+
+        title
+            .into_iter()
+            .chain(subtitle)
+            .chain(sections)
+            .reduce(|best, m| if m.2 > best.2 { m } else { best })
     }
 }
 
-impl ContainsString for Section {
-    fn contains_string(&self, query: &[String]) -> Option<&'static str> {
+impl SearchMatch for Section {
+    fn search(&self, query: &[String], fuzzy: bool) -> Option<Match> {
         self.title
             .as_ref()
-            .and_then(|t| t.contains_string(query))
-            .or_else(|| self.fields.iter().find_map(|f| f.contains_string(query)))
+            .and_then(|t| t.search(query, fuzzy))
+            .into_iter()
+            .chain(self.fields.iter().filter_map(|f| f.search(query, fuzzy)))
+            .reduce(best_match)
     }
 }
 
-impl ContainsString for Field {
-    fn contains_string(&self, query: &[String]) -> Option<&'static str> {
+impl SearchMatch for Field {
+    fn search(&self, query: &[String], fuzzy: bool) -> Option<Match> {
         self.label_form
-            .contains_string(query)
-            .or_else(|| self.help.as_ref().and_then(|h| h.contains_string(query)))
-            .or_else(|| self.id.contains_string(query))
+            .search(query, fuzzy)
+            .into_iter()
+            .chain(self.help.as_ref().and_then(|h| h.search(query, fuzzy)))
+            .chain(self.id.search(query, fuzzy))
+            .reduce(best_match)
     }
 }
 
-impl ContainsString for &'static str {
-    fn contains_string(&self, query: &[String]) -> Option<&'static str> {
-        let s = self.to_ascii_lowercase();
-        if query.iter().all(|q| s.contains(q)) {
-            Some(self)
+impl SearchMatch for &'static str {
+    fn search(&self, query: &[String], fuzzy: bool) -> Option<Match> {
+        if fuzzy {
+            fuzzy_score(self, query).map(|score| (*self, score))
         } else {
-            None
+            let s = self.to_ascii_lowercase();
+            query.iter().all(|q| s.contains(q)).then_some((*self, 0))
         }
     }
 }
+
+fn best_match(best: Match, m: Match) -> Match {
+    if m.1 > best.1 {
+        m
+    } else {
+        best
+    }
+}
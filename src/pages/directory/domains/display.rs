@@ -38,7 +38,7 @@ use crate::{
     },
     core::{
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
     },
     pages::List,
 };
@@ -87,7 +87,7 @@ pub fn DomainDisplay() -> impl IntoView {
             {move || match domain_details.get() {
                 None => None,
                 Some(Err(http::Error::Unauthorized)) => {
-                    use_navigate()("/login", Default::default());
+                    use_navigate()(&login_redirect_url(), Default::default());
                     Some(view! { <div></div> }.into_view())
                 }
                 Some(Err(http::Error::NotFound)) => {
@@ -29,11 +29,12 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     components::{
+        highlight::{filter_terms, Highlight},
         icon::{IconAdd, IconTrash},
         list::{
             header::ColumnList,
             pagination::Pagination,
-            row::SelectItem,
+            row::{use_row_menu, RowAction, RowActions, SelectItem},
             toolbar::{SearchBox, ToolbarButton},
             Footer, ListItem, ListSection, ListTable, Toolbar, ZeroResults,
         },
@@ -46,7 +47,8 @@ use crate::{
     },
     core::{
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
+        prefs::use_preferences,
         url::UrlBuilder,
     },
     pages::{maybe_plural, List},
@@ -58,8 +60,6 @@ struct Domain {
     addresses: u32,
 }
 
-const PAGE_SIZE: u32 = 10;
-
 #[component]
 pub fn DomainList() -> impl IntoView {
     let query = use_query_map();
@@ -83,21 +83,23 @@ pub fn DomainList() -> impl IntoView {
     });
 
     let auth = use_authorization();
+    let prefs = use_preferences();
+    let page_size = create_memo(move |_| prefs.with(|prefs| prefs.page_size()));
     let alert = use_alerts();
     let modal = use_modals();
     let selected = create_rw_signal::<HashSet<String>>(HashSet::new());
     provide_context(selected);
 
     let domains = create_resource(
-        move || (page.get(), filter.get()),
-        move |(page, filter)| {
+        move || (page.get(), filter.get(), page_size.get()),
+        move |(page, filter, page_size)| {
             let auth = auth.get_untracked();
 
             async move {
                 let domain_names = HttpRequest::get("/api/domain")
                     .with_authorization(&auth)
                     .with_parameter("page", page.to_string())
-                    .with_parameter("limit", PAGE_SIZE.to_string())
+                    .with_parameter("limit", page_size.to_string())
                     .with_optional_parameter("filter", filter)
                     .send::<List<String>>()
                     .await?;
@@ -146,6 +148,9 @@ pub fn DomainList() -> impl IntoView {
         }
     });
 
+    let on_delete = Callback::new(move |id: String| {
+        delete_action.dispatch(Arc::new(HashSet::from([id])));
+    });
     let total_results = create_rw_signal(None::<u32>);
 
     view! {
@@ -217,7 +222,7 @@ pub fn DomainList() -> impl IntoView {
                     {move || match domains.get() {
                         None => None,
                         Some(Err(http::Error::Unauthorized)) => {
-                            use_navigate()("/login", Default::default());
+                            use_navigate()(&login_redirect_url(), Default::default());
                             Some(view! { <div></div> }.into_view())
                         }
                         Some(Err(err)) => {
@@ -251,7 +256,7 @@ pub fn DomainList() -> impl IntoView {
                                             key=|domain| domain.name.clone()
                                             let:domain
                                         >
-                                            <DomainItem domain/>
+                                            <DomainItem domain filter=filter on_delete=on_delete/>
                                         </For>
 
                                     </ColumnList>
@@ -288,7 +293,6 @@ pub fn DomainList() -> impl IntoView {
                     <Pagination
                         current_page=page
                         total_results=total_results.read_only()
-                        page_size=PAGE_SIZE
                         on_page_change=move |page: u32| {
                             use_navigate()(
                                 &UrlBuilder::new("/manage/directory/domains")
@@ -307,13 +311,38 @@ pub fn DomainList() -> impl IntoView {
 }
 
 #[component]
-fn DomainItem(domain: Domain) -> impl IntoView {
+fn DomainItem(
+    domain: Domain,
+    #[prop(into)] filter: Signal<Option<String>>,
+    #[prop(into)] on_delete: Callback<String, ()>,
+) -> impl IntoView {
+    let terms = filter_terms(filter);
+    let row_menu = use_row_menu();
     let action_url = format!("/manage/directory/accounts?filter={}", domain.name);
     let domain_id = domain.name.clone();
     let manage_url = format!("/manage/directory/domains/{domain_id}/view",);
+    let delete_id = domain_id.clone();
+    let actions = vec![
+        RowAction::link("DNS Records", manage_url),
+        RowAction::link("Accounts", action_url.clone()),
+        RowAction::dangerous(
+            "Delete",
+            Modal::with_title("Confirm deletion")
+                .with_message(format!(
+                    "Are you sure you want to delete domain {domain_id:?}?"
+                ))
+                .with_button("Delete domain")
+                .with_dangerous_callback(move || on_delete.call(delete_id.clone())),
+        ),
+    ];
+    let item_id = domain_id.clone();
 
     view! {
-        <tr>
+        <tr
+            tabindex="0"
+            class="focus:outline-none focus:bg-gray-50 dark:focus:bg-slate-800"
+            on:keydown=row_menu.on_row_keydown(domain_id.clone())
+        >
             <ListItem>
                 <label class="flex">
                     <SelectItem item_id=domain_id/>
@@ -325,7 +354,7 @@ fn DomainItem(domain: Domain) -> impl IntoView {
             <ListItem subclass="ps-6 lg:ps-3 xl:ps-0 pe-6 py-3">
                 <div class="flex items-center gap-x-3">
                     <span class="block text-sm font-semibold text-gray-800 dark:text-gray-200">
-                        {domain.name}
+                        <Highlight text=domain.name query=terms/>
                     </span>
                 </div>
             </ListItem>
@@ -339,13 +368,8 @@ fn DomainItem(domain: Domain) -> impl IntoView {
                 </a>
             </ListItem>
 
-            <ListItem subclass="px-6 py-1.5">
-                <a
-                    class="inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
-                    href=manage_url
-                >
-                    DNS Records
-                </a>
+            <ListItem subclass="px-6 py-1.5 text-end">
+                <RowActions item_id=item_id actions=actions/>
             </ListItem>
         </tr>
     }
@@ -45,7 +45,8 @@ use crate::{
     core::{
         form::FormData,
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
+        recent::use_recently_viewed,
         schema::{Builder, Schemas, Source, Transformer, Type, Validator},
     },
     pages::directory::{Principal, PrincipalType},
@@ -56,6 +57,7 @@ pub fn PrincipalEdit() -> impl IntoView {
     let auth = use_authorization();
     let alert = use_alerts();
     let params = use_params_map();
+    let recent = use_recently_viewed();
     let fetch_principal = create_resource(
         move || params.get().get("id").cloned().unwrap_or_default(),
         move |name| {
@@ -86,6 +88,23 @@ pub fn PrincipalEdit() -> impl IntoView {
             _ => PrincipalType::Individual,
         }
     });
+    create_effect(move |_| {
+        if let Some(Ok(principal)) = fetch_principal.get() {
+            if let Some(name) = principal.name.as_deref().filter(|name| !name.is_empty()) {
+                let typ = selected_type.get_untracked();
+                recent.record(
+                    typ.item_name(false),
+                    name,
+                    principal
+                        .description
+                        .as_deref()
+                        .filter(|description| !description.is_empty())
+                        .unwrap_or(name),
+                    format!("/manage/directory/{}/{name}/edit", typ.resource_name()),
+                );
+            }
+        }
+    });
     let (pending, set_pending) = create_signal(false);
 
     let current_principal = create_rw_signal(Principal::default());
@@ -128,7 +147,7 @@ pub fn PrincipalEdit() -> impl IntoView {
                     )),
                     Err(http::Error::NotFound) => Err("Principal does not exist".to_string()),
                     Err(http::Error::Unauthorized) => {
-                        use_navigate()("/login", Default::default());
+                        use_navigate()(&login_redirect_url(), Default::default());
                         Err("Unauthorized".to_string())
                     }
                     Err(err) => Err(format!("Request failed: {err:?}")),
@@ -227,7 +246,7 @@ pub fn PrincipalEdit() -> impl IntoView {
                 {move || match fetch_principal.get() {
                     None => None,
                     Some(Err(http::Error::Unauthorized)) => {
-                        use_navigate()("/login", Default::default());
+                        use_navigate()(&login_redirect_url(), Default::default());
                         Some(view! { <div></div> }.into_view())
                     }
                     Some(Err(http::Error::NotFound)) => {
@@ -304,6 +323,13 @@ pub fn PrincipalEdit() -> impl IntoView {
                                         <FormItem label="Password">
                                             <InputPassword element=FormElement::new("password", data)/>
                                         </FormItem>
+
+                                        <FormItem label="Confirm Password">
+                                            <InputPassword element=FormElement::new(
+                                                "password-confirm",
+                                                data,
+                                            )/>
+                                        </FormItem>
                                     </Show>
 
                                     <FormItem label="Email">
@@ -545,6 +571,10 @@ impl Builder<Schemas, ()> {
             .typ(Type::Input)
             .input_check([Transformer::Trim], [])
             .build()
+            .new_field("password")
+            .typ(Type::Secret)
+            .new_confirm_field("password-confirm", "password")
+            .build()
             .new_field("type")
             .typ(Type::Select {
                 source: Source::Static(IDS),
@@ -30,11 +30,12 @@ use leptos_router::*;
 use crate::{
     components::{
         badge::Badge,
+        highlight::{filter_terms, matches_terms, Highlight},
         icon::{IconAdd, IconTrash},
         list::{
             header::ColumnList,
             pagination::Pagination,
-            row::SelectItem,
+            row::{use_row_menu, RowAction, RowActions, SelectItem},
             toolbar::{SearchBox, ToolbarButton},
             Footer, ListItem, ListSection, ListTable, ListTextItem, Toolbar, ZeroResults,
         },
@@ -47,7 +48,8 @@ use crate::{
     },
     core::{
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
+        prefs::use_preferences,
         url::UrlBuilder,
     },
     pages::{
@@ -56,8 +58,6 @@ use crate::{
     },
 };
 
-const PAGE_SIZE: u32 = 10;
-
 #[component]
 pub fn PrincipalList() -> impl IntoView {
     let selected = create_rw_signal::<HashSet<String>>(HashSet::new());
@@ -100,12 +100,14 @@ pub fn PrincipalList() -> impl IntoView {
     });
 
     let auth = use_authorization();
+    let prefs = use_preferences();
+    let page_size = create_memo(move |_| prefs.with(|prefs| prefs.page_size()));
     let alert = use_alerts();
     let modal = use_modals();
 
     let principals = create_resource(
-        move || (page.get(), filter.get()),
-        move |(page, filter)| {
+        move || (page.get(), filter.get(), page_size.get()),
+        move |(page, filter, page_size)| {
             let auth = auth.get_untracked();
             let selected_type = selected_type.get();
 
@@ -113,7 +115,7 @@ pub fn PrincipalList() -> impl IntoView {
                 let principal_names = HttpRequest::get("/api/principal")
                     .with_authorization(&auth)
                     .with_parameter("page", page.to_string())
-                    .with_parameter("limit", PAGE_SIZE.to_string())
+                    .with_parameter("limit", page_size.to_string())
                     .with_parameter("type", selected_type.id())
                     .with_optional_parameter("filter", filter)
                     .send::<List<String>>()
@@ -171,6 +173,9 @@ pub fn PrincipalList() -> impl IntoView {
         }
     });
 
+    let on_delete = Callback::new(move |id: String| {
+        delete_action.dispatch(Arc::new(HashSet::from([id])));
+    });
     let total_results = create_rw_signal(None::<u32>);
     let title = Signal::derive(move || {
         match selected_type.get() {
@@ -198,6 +203,13 @@ pub fn PrincipalList() -> impl IntoView {
                 <Toolbar slot>
                     <SearchBox
                         value=filter
+                        placeholder=Signal::derive(move || {
+                            match selected_type.get() {
+                                PrincipalType::Individual => "Search by name, e-mail or alias",
+                                _ => "Search by name or e-mail",
+                            }
+                                .to_string()
+                        })
                         on_search=move |value| {
                             use_navigate()(
                                 &UrlBuilder::new(
@@ -279,7 +291,7 @@ pub fn PrincipalList() -> impl IntoView {
                     {move || match principals.get() {
                         None => None,
                         Some(Err(http::Error::Unauthorized)) => {
-                            use_navigate()("/login", Default::default());
+                            use_navigate()(&login_redirect_url(), Default::default());
                             Some(view! { <div></div> }.into_view())
                         }
                         Some(Err(err)) => {
@@ -340,7 +352,12 @@ pub fn PrincipalList() -> impl IntoView {
                                             key=|principal| principal.name.clone().unwrap_or_default()
                                             let:principal
                                         >
-                                            <PrincipalItem principal selected_type=selected_type.get()/>
+                                            <PrincipalItem
+                                                principal
+                                                selected_type=selected_type.get()
+                                                filter=filter
+                                                on_delete=on_delete
+                                            />
                                         </For>
                                     </ColumnList>
                                 }
@@ -382,7 +399,6 @@ pub fn PrincipalList() -> impl IntoView {
                     <Pagination
                         current_page=page
                         total_results=total_results.read_only()
-                        page_size=PAGE_SIZE
                         on_page_change=move |page: u32| {
                             use_navigate()(
                                 &UrlBuilder::new(
@@ -406,7 +422,14 @@ pub fn PrincipalList() -> impl IntoView {
 }
 
 #[component]
-fn PrincipalItem(principal: Principal, selected_type: PrincipalType) -> impl IntoView {
+fn PrincipalItem(
+    principal: Principal,
+    selected_type: PrincipalType,
+    #[prop(into)] filter: Signal<Option<String>>,
+    #[prop(into)] on_delete: Callback<String, ()>,
+) -> impl IntoView {
+    let terms = filter_terms(filter);
+    let row_menu = use_row_menu();
     let name = principal.name.as_deref().unwrap_or("unknown").to_string();
     let display_name = principal
         .description
@@ -426,10 +449,46 @@ fn PrincipalItem(principal: Principal, selected_type: PrincipalType) -> impl Int
         principal_id
     );
     let num_members = principal.members.len();
+    let primary_email = principal.emails.first().cloned().unwrap_or_default();
+    let aliases = principal.emails.iter().skip(1).cloned().collect::<Vec<_>>();
+    let num_aliases = aliases.len();
+
+    // Show the alias that matched the search when the primary address did not
+    let matched_alias = Signal::derive(move || {
+        terms.with(|terms| {
+            if terms.is_empty() || matches_terms(&primary_email, terms) {
+                None
+            } else {
+                aliases
+                    .iter()
+                    .find(|alias| matches_terms(alias, terms))
+                    .cloned()
+            }
+        })
+    });
     let num_member_of = principal.member_of.len();
+    let delete_id = principal_id.clone();
+    let actions = vec![
+        RowAction::link("Edit", manage_url),
+        RowAction::dangerous(
+            "Delete",
+            Modal::with_title("Confirm deletion")
+                .with_message(format!(
+                    "Are you sure you want to delete {} {principal_id:?}?",
+                    selected_type.item_name(false)
+                ))
+                .with_button(format!("Delete {}", selected_type.item_name(false)))
+                .with_dangerous_callback(move || on_delete.call(delete_id.clone())),
+        ),
+    ];
+    let item_id = principal_id.clone();
 
     view! {
-        <tr>
+        <tr
+            tabindex="0"
+            class="focus:outline-none focus:bg-gray-50 dark:focus:bg-slate-800"
+            on:keydown=row_menu.on_row_keydown(principal_id.clone())
+        >
             <ListItem>
                 <label class="flex">
                     <SelectItem item_id=principal_id/>
@@ -446,19 +505,36 @@ fn PrincipalItem(principal: Principal, selected_type: PrincipalType) -> impl Int
                     </span>
                     <div class="grow">
                         <span class="block text-sm font-semibold text-gray-800 dark:text-gray-200">
-                            {display_name}
+                            <Highlight text=display_name query=terms/>
+                        </span>
+                        <span class="block text-sm text-gray-500">
+                            <Highlight text=name query=terms/>
                         </span>
-                        <span class="block text-sm text-gray-500">{name}</span>
                     </div>
                 </div>
             </ListItem>
 
             <ListItem class="h-px w-72 whitespace-nowrap">
                 <span class="block text-sm font-semibold text-gray-800 dark:text-gray-200">
-                    {principal.emails.first().cloned().unwrap_or_default()}
+                    <Highlight
+                        text=principal.emails.first().cloned().unwrap_or_default()
+                        query=terms
+                    />
                 </span>
                 <span class="block text-sm text-gray-500">
-                    {maybe_plural(principal.emails.len().saturating_sub(1), "alias", "aliases")}
+                    {move || match matched_alias.get() {
+                        Some(alias) => {
+                            let others = num_aliases - 1;
+                            view! {
+                                <Highlight text=alias query=terms/>
+                                {(others > 0)
+                                    .then(|| format!(" (+{})", maybe_plural(others, "alias", "aliases")))}
+                            }
+                                .into_view()
+                        }
+                        None => maybe_plural(num_aliases, "alias", "aliases").into_view(),
+                    }}
+
                 </span>
             </ListItem>
 
@@ -501,13 +577,8 @@ fn PrincipalItem(principal: Principal, selected_type: PrincipalType) -> impl Int
             }>
                 <ListTextItem>{maybe_plural(num_member_of, "group", "groups")}</ListTextItem>
             </Show>
-            <ListItem subclass="px-6 py-1.5">
-                <a
-                    class="inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
-                    href=manage_url
-                >
-                    Edit
-                </a>
+            <ListItem subclass="px-6 py-1.5 text-end">
+                <RowActions item_id=item_id actions=actions/>
             </ListItem>
 
         </tr>
@@ -21,93 +21,333 @@
  * for more details.
 */
 
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
-use gloo_storage::{LocalStorage, SessionStorage, Storage};
-use leptos::*;
+use leptos::{html::Div, *};
 use leptos_meta::*;
-use leptos_router::{use_navigate, use_query_map};
+use leptos_router::use_navigate;
+use leptos_use::on_click_outside;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     components::{
         form::{
+            checkbox::Checkbox,
             input::{InputPassword, InputText},
             FormElement,
         },
-        messages::alert::{use_alerts, Alerts},
+        icon::{IconUserCircle, IconXMark},
+        layout::footer::Footer,
+        messages::alert::{start_countdown, use_alerts, Alert, Alerts},
     },
     core::{
-        oauth::{oauth_authenticate, AuthToken},
+        oauth::{
+            now_secs, oauth_authenticate, oauth_probe_server, oauth_revoke, update_token_claims,
+            use_refresh_timer, AuthToken, OAuthLogin, RefreshTimer,
+        },
+        prefs::{use_preferences, Preferences, SLIDING_SESSION_IDLE_SECS},
+        query::use_query_param,
         schema::{Builder, Schemas, Transformer, Type, Validator},
+        secret::SecretString,
+        storage::{LocalStorage, SessionStorage, Storage},
     },
-    STATE_LOGIN_NAME_KEY, STATE_STORAGE_KEY,
+    LOGO_URL, PRODUCT_NAME, STATE_LOGIN_NAME_KEY, STATE_REMEMBERED_SESSION_KEY,
+    STATE_SLIDING_SESSION_KEY, STATE_STORAGE_KEY,
 };
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+const MAX_SAVED_LOGINS: usize = 10;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 struct SavedSession {
     login: String,
     base_url: String,
+    #[serde(default)]
+    keep_signed_in: bool,
+}
+
+// Older versions stored a single login instead of one per server
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedSessions {
+    List(Vec<SavedSession>),
+    Single(SavedSession),
+}
+
+// Most recently used first, at most one entry per server
+fn saved_logins() -> Vec<SavedSession> {
+    match LocalStorage::get::<SavedSessions>(STATE_LOGIN_NAME_KEY) {
+        Ok(SavedSessions::List(sessions)) => sessions,
+        Ok(SavedSessions::Single(session)) => {
+            let sessions = vec![session];
+            store_logins(&sessions);
+            sessions
+        }
+        Err(_) => vec![],
+    }
+}
+
+fn store_logins(sessions: &[SavedSession]) {
+    if sessions.is_empty() {
+        LocalStorage::delete(STATE_LOGIN_NAME_KEY);
+    } else if let Err(err) = LocalStorage::set(STATE_LOGIN_NAME_KEY, sessions) {
+        log::error!("Failed to save login name to local storage: {}", err);
+    }
+}
+
+// Applies the "Remember me" choice, saved logins for other servers are left untouched
+fn stage_saved_login(
+    mut sessions: Vec<SavedSession>,
+    session: SavedSession,
+    remember_me: bool,
+) -> Vec<SavedSession> {
+    sessions.retain(|saved| saved.base_url != session.base_url);
+    if remember_me {
+        sessions.insert(0, session);
+        sessions.truncate(MAX_SAVED_LOGINS);
+    }
+    sessions
+}
+
+fn update_saved_logins(session: SavedSession, remember_me: bool) -> Vec<SavedSession> {
+    let sessions = stage_saved_login(saved_logins(), session, remember_me);
+    store_logins(&sessions);
+    sessions
+}
+
+pub fn forget_saved_login(base_url: &str) {
+    let mut sessions = saved_logins();
+    sessions.retain(|saved| saved.base_url != base_url);
+    store_logins(&sessions);
+}
+
+// Only the refresh token is kept across browser restarts, the access token is
+// obtained again on startup
+pub fn remember_session(auth_token: &AuthToken) {
+    if let Err(err) = LocalStorage::set(
+        STATE_REMEMBERED_SESSION_KEY,
+        AuthToken {
+            access_token: Default::default(),
+            is_valid: false,
+            ..auth_token.clone()
+        },
+    ) {
+        log::error!("Failed to save session to local storage: {}", err);
+    }
+}
+
+// Keeps a remembered session in step with rotated refresh tokens
+pub fn update_remembered_session(auth_token: &AuthToken) {
+    if LocalStorage::contains(STATE_REMEMBERED_SESSION_KEY) {
+        remember_session(auth_token);
+    }
+}
+
+pub fn restore_remembered_session() -> Option<AuthToken> {
+    LocalStorage::get::<AuthToken>(STATE_REMEMBERED_SESSION_KEY)
+        .ok()
+        .filter(|auth_token| !auth_token.refresh_token.is_empty())
+}
+
+pub fn forget_remembered_session() {
+    LocalStorage::delete(STATE_REMEMBERED_SESSION_KEY);
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SlidingSession {
+    auth_token: AuthToken,
+    expires_at: u64,
+}
+
+// Session storage dies with the tab, so users who opted in also get a copy in local
+// storage that expires once idle. Every token refresh pushes the deadline back, at the
+// cost of anyone reaching the browser before then being signed in as well.
+pub fn update_sliding_session(auth_token: &AuthToken, prefs: &Preferences) {
+    if !prefs.sliding_session() || auth_token.refresh_token.is_empty() {
+        forget_sliding_session();
+        return;
+    }
+    if let Err(err) = LocalStorage::set(
+        STATE_SLIDING_SESSION_KEY,
+        SlidingSession {
+            auth_token: AuthToken {
+                access_token: Default::default(),
+                is_valid: false,
+                ..auth_token.clone()
+            },
+            expires_at: now_secs() + SLIDING_SESSION_IDLE_SECS,
+        },
+    ) {
+        log::error!("Failed to save session to local storage: {}", err);
+    }
+}
+
+pub fn restore_sliding_session() -> Option<AuthToken> {
+    let session = LocalStorage::get::<SlidingSession>(STATE_SLIDING_SESSION_KEY).ok()?;
+    if session.expires_at > now_secs() && !session.auth_token.refresh_token.is_empty() {
+        Some(session.auth_token)
+    } else {
+        forget_sliding_session();
+        None
+    }
+}
+
+pub fn forget_sliding_session() {
+    LocalStorage::delete(STATE_SLIDING_SESSION_KEY);
+}
+
+// Drops every copy of the session kept by this browser
+pub fn clear_session(auth_token: RwSignal<AuthToken>) {
+    SessionStorage::delete(STATE_STORAGE_KEY);
+    forget_remembered_session();
+    forget_sliding_session();
+    auth_token.set(AuthToken::default());
+}
+
+// Revoking is best effort, local state is cleared even when the server can't be reached.
+// The refresh token goes first as revoking the access token may invalidate the request.
+pub async fn sign_out(auth_token: RwSignal<AuthToken>, refresh_timer: RefreshTimer) {
+    let auth = auth_token.get_untracked();
+    refresh_timer.cancel();
+
+    if !auth.access_token.is_empty() {
+        if !auth.refresh_token.is_empty() {
+            oauth_revoke(
+                &auth.base_url,
+                &auth.access_token,
+                &auth.refresh_token,
+                "refresh_token",
+            )
+            .await;
+        }
+        oauth_revoke(
+            &auth.base_url,
+            &auth.access_token,
+            &auth.access_token,
+            "access_token",
+        )
+        .await;
+    }
+    clear_session(auth_token);
 }
 
 #[component]
 pub fn Login() -> impl IntoView {
-    let stored_data: Option<SavedSession> = LocalStorage::get(STATE_LOGIN_NAME_KEY).ok();
+    let saved_sessions = create_rw_signal(saved_logins());
+    let stored_data = saved_sessions.get_untracked().into_iter().next();
     let remember_me = create_rw_signal(stored_data.is_some());
+    // Nothing could be remembered, the storage banner explains why
+    let can_remember = LocalStorage::is_writable();
+    let keep_signed_in = create_rw_signal(
+        stored_data
+            .as_ref()
+            .is_some_and(|session| session.keep_signed_in),
+    );
     let alert = use_alerts();
     let auth_token = use_context::<RwSignal<AuthToken>>().unwrap();
-    let query = use_query_map();
+    let redirect = use_query_param::<String>("redirect");
+    let remote = use_query_param::<String>("remote");
+    let prefs = use_preferences();
+    let refresh_timer = use_refresh_timer();
+    let probed_host = create_rw_signal::<Option<String>>(None);
+    // Seconds until the server accepts another attempt after throttling sign-ins
+    let retry_in = create_rw_signal(0u64);
+    // Second step, the password was accepted and a one-time code is needed
+    let mfa_required = create_rw_signal(false);
+    let (login, base_url) = stored_data.map_or_else(
+        || (String::new(), String::new()),
+        |session| (session.login, session.base_url),
+    );
+    let data = expect_context::<Arc<Schemas>>()
+        .build_form("login")
+        .with_value("base-url", base_url)
+        .with_value("login", login)
+        .into_signal();
 
     let login_action = create_action(
-        move |(username, password, base_url): &(String, String, String)| {
+        move |(username, password, base_url, keep_signed_in, totp_code): &(
+            String,
+            SecretString,
+            String,
+            bool,
+            Option<String>,
+        )| {
             let username = username.clone();
             let password = password.clone();
             let base_url = base_url.clone();
+            let keep_signed_in = *keep_signed_in;
+            let totp_code = totp_code.clone();
+
+            // Discard any timer left over from a previous session
+            refresh_timer.cancel();
 
             async move {
-                match oauth_authenticate(&base_url, &username, &password).await {
-                    Ok((grant, is_admin)) => {
-                        let refresh_token = grant.refresh_token.unwrap_or_default();
+                // Warn once per host when it doesn't look like a Stalwart server
+                if !base_url.is_empty()
+                    && probed_host.get_untracked().as_ref() != Some(&base_url)
+                    && !oauth_probe_server(&base_url).await
+                {
+                    probed_host.set(Some(base_url.clone()));
+                    alert.set(
+                        Alert::warning("Server not recognized")
+                            .with_details(format!(
+                                "{base_url} does not appear to be a Stalwart server. Check the host and try again, or select Sign in to continue anyway."
+                            ))
+                            .without_timeout(),
+                    );
+                    return;
+                }
+                probed_host.set(Some(base_url.clone()));
+
+                match oauth_authenticate(&base_url, &username, &password, totp_code.as_deref())
+                    .await
+                {
+                    Ok(OAuthLogin::MfaRequired) => {
+                        mfa_required.set(true);
+                    }
+                    Ok(OAuthLogin::Granted { grant, is_admin }) => {
+                        let refresh_token = grant.refresh_token.clone().unwrap_or_default();
                         auth_token.update(|auth_token| {
-                            auth_token.access_token = grant.access_token.into();
+                            auth_token.access_token = grant.access_token.clone().into();
                             auth_token.refresh_token = refresh_token.clone().into();
                             auth_token.base_url = base_url.clone().into();
                             auth_token.username = username.into();
                             auth_token.is_valid = true;
                             auth_token.is_admin = is_admin;
+                            auth_token.claims = None;
 
                             if let Err(err) =
                                 SessionStorage::set(STATE_STORAGE_KEY, auth_token.clone())
                             {
                                 log::error!("Failed to save state to session storage: {}", err);
                             }
+                            if keep_signed_in && !refresh_token.is_empty() {
+                                remember_session(auth_token);
+                            } else {
+                                forget_remembered_session();
+                            }
+                            update_sliding_session(auth_token, &prefs.get_untracked());
                         });
 
                         // Set timer to refresh token
                         if grant.expires_in > 0 && !refresh_token.is_empty() {
-                            log::debug!(
-                                "Next OAuth token refresh in {} seconds.",
-                                grant.expires_in
-                            );
-
-                            set_timeout(
-                                move || {
-                                    auth_token.update(|auth_token| {
-                                        auth_token.is_valid = false;
-                                    });
-                                },
-                                Duration::from_secs(grant.expires_in),
-                            );
+                            refresh_timer.schedule(auth_token, grant.expires_in);
                         }
 
-                        let url = if is_admin {
-                            "/manage/directory/accounts"
-                        } else {
-                            "/account/crypto"
-                        };
-                        use_navigate()(url, Default::default());
+                        let url = auth_token.get_untracked().landing_route(
+                            redirect.get_untracked().as_deref(),
+                            &prefs.get_untracked(),
+                        );
+                        use_navigate()(&url, Default::default());
+                        update_token_claims(auth_token, &grant).await;
                     }
                     Err(err) => {
+                        if let Some(retry_after) = err.retry_after {
+                            start_countdown(retry_in, retry_after);
+                        }
+                        // Codes are single use, the credentials are kept for the next try
+                        if totp_code.is_some() {
+                            data.update(|data| data.set("totp-code", String::new()));
+                        }
                         alert.set(err);
                     }
                 }
@@ -115,31 +355,46 @@ pub fn Login() -> impl IntoView {
         },
     );
 
-    let (login, base_url) = stored_data.map_or_else(
-        || (String::new(), String::new()),
-        |session| (session.login, session.base_url),
-    );
-    let data = expect_context::<Arc<Schemas>>()
-        .build_form("login")
-        .with_value("base-url", base_url)
-        .with_value("login", login)
-        .into_signal();
-    let has_remote = create_memo(move |_| {
-        query.get().get("remote").is_some()
-            || data
-                .get()
-                .get("base-url")
-                .filter(|v| !v.is_empty())
-                .is_some()
+    // The stored login always reflects the checkboxes, not only the last submit
+    let stage_login = move || {
+        let session = data.with_untracked(|data| SavedSession {
+            login: data.value::<String>("login").unwrap_or_default(),
+            base_url: data.value::<String>("base-url").unwrap_or_default(),
+            keep_signed_in: keep_signed_in.get_untracked(),
+        });
+        saved_sessions.set(update_saved_logins(session, remember_me.get_untracked()));
+    };
+    let show_saved = create_rw_signal(false);
+    let saved_switcher = create_node_ref::<Div>();
+    on_cleanup(on_click_outside(saved_switcher, move |_| {
+        show_saved.set(false);
+    }));
+    let select_session = move |session: SavedSession| {
+        remember_me.set(true);
+        keep_signed_in.set(session.keep_signed_in);
+        data.update(|data| {
+            data.set("base-url", session.base_url);
+            data.set("login", session.login);
+        });
+    };
+    let has_base_url =
+        create_memo(move |_| data.with(|data| data.get("base-url").is_some_and(|v| !v.is_empty())));
+    let has_remote = create_memo(move |_| remote.is_set() || has_base_url.get());
+    // Keeps the host field visible when the page is refreshed or the link shared
+    create_effect(move |_| {
+        if has_base_url.get() && !remote.is_set() {
+            remote.set(Some(String::new()));
+        }
     });
 
     view! {
+        <Title text="Sign in"/>
         <Body class="dark:bg-slate-900 bg-gray-100 flex h-full items-center py-16"/>
         <main class="w-full max-w-md mx-auto p-6">
             <div class="mt-7 bg-white border border-gray-200 rounded-xl shadow-sm dark:bg-gray-800 dark:border-gray-700">
                 <div class="p-4 sm:p-7">
                     <div class="text-center p-6">
-                        <img src="/logo.svg"/>
+                        <img src=LOGO_URL alt=PRODUCT_NAME/>
 
                     </div>
 
@@ -147,6 +402,78 @@ pub fn Login() -> impl IntoView {
                         <Alerts/>
                         <form on:submit=|ev| ev.prevent_default()>
                             <div class="grid gap-y-4">
+                                <Show when=move || !saved_sessions.get().is_empty()>
+                                    <div class="relative" node_ref=saved_switcher>
+                                        <button
+                                            type="button"
+                                            class="w-full py-2 px-3 inline-flex items-center gap-x-2 text-sm rounded-lg border border-gray-200 bg-white text-gray-800 shadow-sm hover:bg-gray-50 dark:bg-slate-900 dark:border-gray-700 dark:text-white dark:hover:bg-gray-800 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                                            aria-haspopup="true"
+                                            aria-expanded=move || show_saved.get().to_string()
+                                            on:click=move |_| show_saved.update(|show| *show = !*show)
+                                        >
+                                            <IconUserCircle attr:class="flex-shrink-0 size-4"/>
+                                            Saved accounts
+                                        </button>
+                                        <Show when=move || show_saved.get()>
+                                            <ul class="absolute z-10 mt-1 w-full p-1 bg-white border border-gray-200 rounded-lg shadow-md dark:bg-gray-800 dark:border-gray-700">
+                                                <For
+                                                    each=move || saved_sessions.get()
+                                                    key=|session| session.base_url.clone()
+                                                    let:session
+                                                >
+
+                                                    {
+                                                        let base_url = session.base_url.clone();
+                                                        let host = if session.base_url.is_empty() {
+                                                            "This server".to_string()
+                                                        } else {
+                                                            session.base_url.clone()
+                                                        };
+                                                        let remove_label = format!(
+                                                            "Remove {} on {host}",
+                                                            session.login,
+                                                        );
+                                                        let login = session.login.clone();
+                                                        view! {
+                                                            <li class="flex items-center gap-x-2 rounded-lg hover:bg-gray-100 dark:hover:bg-gray-700">
+                                                                <button
+                                                                    type="button"
+                                                                    class="grow py-2 px-3 text-start focus:outline-none focus:bg-gray-100 dark:focus:bg-gray-700"
+                                                                    on:click=move |_| {
+                                                                        show_saved.set(false);
+                                                                        select_session(session.clone());
+                                                                    }
+                                                                >
+
+                                                                    <span class="block text-sm text-gray-800 dark:text-gray-200">
+                                                                        {login}
+                                                                    </span>
+                                                                    <span class="block text-xs text-gray-500 break-all">
+                                                                        {host}
+                                                                    </span>
+                                                                </button>
+                                                                <button
+                                                                    type="button"
+                                                                    class="p-2 me-1 rounded-full text-gray-500 hover:text-red-600 hover:bg-gray-200 dark:hover:bg-gray-600"
+                                                                    aria-label=remove_label.clone()
+                                                                    title=remove_label
+                                                                    on:click=move |_| {
+                                                                        forget_saved_login(&base_url);
+                                                                        saved_sessions.set(saved_logins());
+                                                                    }
+                                                                >
+
+                                                                    <IconXMark attr:class="flex-shrink-0 size-3.5"/>
+                                                                </button>
+                                                            </li>
+                                                        }
+                                                    }
+
+                                                </For>
+                                            </ul>
+                                        </Show>
+                                    </div>
+                                </Show>
                                 <Show when=move || has_remote.get()>
                                     <div>
                                         <label class="block text-sm mb-2 dark:text-white">
@@ -174,73 +501,105 @@ pub fn Login() -> impl IntoView {
                                     </div>
                                     <InputPassword element=FormElement::new("password", data)/>
                                 </div>
-                                <div class="flex items-center">
-                                    <div class="flex">
-                                        <input
-                                            id="remember-me"
-                                            name="remember-me"
-                                            type="checkbox"
-                                            class="shrink-0 mt-0.5 border-gray-200 rounded text-blue-600 focus:ring-blue-500 dark:bg-gray-800 dark:border-gray-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
-                                            prop:checked=remember_me
-                                            on:input=move |_| {
-                                                remember_me
-                                                    .update(|v| {
-                                                        *v = !*v;
-                                                    })
-                                            }
-                                        />
-
-                                    </div>
-                                    <div class="ms-3">
-                                        <label for="remember-me" class="text-sm dark:text-white">
-                                            Remember me
+                                <Show when=move || mfa_required.get()>
+                                    <div>
+                                        <label class="block text-sm mb-2 dark:text-white">
+                                            Verification code
                                         </label>
+                                        <InputText
+                                            placeholder="123456"
+                                            element=FormElement::new("totp-code", data)
+                                            attr:inputmode="numeric"
+                                            attr:autocomplete="one-time-code"
+                                            attr:maxlength="6"
+                                        />
+                                        <p class="text-xs text-gray-500 mt-2">
+                                            Enter the 6-digit code from your authenticator app.
+                                        </p>
                                     </div>
-                                </div>
+                                </Show>
+                                <Checkbox
+                                    label="Remember me"
+                                    checked=remember_me
+                                    disabled=!can_remember
+                                    on_change=move |checked| {
+                                        remember_me.set(checked);
+                                        stage_login();
+                                    }
+                                />
+
+                                <Show when=move || remember_me.get()>
+                                    <Checkbox
+                                        label="Keep me signed in"
+                                        hint="Stores a refresh token on this device so you stay signed in after closing the browser. Anyone with access to this browser profile can use your account, only enable it on devices you trust."
+                                        checked=keep_signed_in
+                                        on_change=move |checked| {
+                                            keep_signed_in.set(checked);
+                                            stage_login();
+                                        }
+                                    />
+
+                                </Show>
 
                                 <button
                                     type="submit"
                                     class="w-full py-3 px-4 inline-flex justify-center items-center gap-x-2 text-sm font-semibold rounded-lg border border-transparent bg-blue-600 text-white hover:bg-blue-700 disabled:opacity-50 disabled:pointer-events-none dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                                    disabled=move || retry_in.get() > 0
                                     on:click=move |_| {
                                         data.update(|data| {
                                             if data.validate_form() {
+                                                if mfa_required.get()
+                                                    && data.value_is_empty("totp-code")
+                                                {
+                                                    data.new_error("totp-code", "required");
+                                                    return;
+                                                }
                                                 let login = data
                                                     .value::<String>("login")
                                                     .unwrap_or_default();
                                                 let password = data
-                                                    .value::<String>("password")
+                                                    .secret("password")
                                                     .unwrap_or_default();
                                                 let base_url = data
                                                     .value::<String>("base-url")
                                                     .unwrap_or_default();
-                                                if remember_me.get() {
-                                                    if let Err(err) = LocalStorage::set(
-                                                        STATE_LOGIN_NAME_KEY,
-                                                        SavedSession {
-                                                            login: login.clone(),
-                                                            base_url: base_url.clone(),
-                                                        },
-                                                    ) {
-                                                        log::error!(
-                                                            "Failed to save login name to local storage: {}", err
-                                                        );
-                                                    }
-                                                } else {
-                                                    LocalStorage::delete(STATE_LOGIN_NAME_KEY);
-                                                }
-                                                login_action.dispatch((login, password, base_url));
+                                                let keep_signed_in = remember_me.get()
+                                                    && keep_signed_in.get();
+                                                update_saved_logins(
+                                                    SavedSession {
+                                                        login: login.clone(),
+                                                        base_url: base_url.clone(),
+                                                        keep_signed_in,
+                                                    },
+                                                    remember_me.get(),
+                                                );
+                                                login_action
+                                                    .dispatch((
+                                                        login,
+                                                        password,
+                                                        base_url,
+                                                        keep_signed_in,
+                                                        data
+                                                            .value::<String>("totp-code")
+                                                            .filter(|_| mfa_required.get()),
+                                                    ));
                                             }
                                         });
                                     }
                                 >
 
-                                    Sign in
+                                    {move || match retry_in.get() {
+                                        0 => "Sign in".to_string(),
+                                        secs => format!("Try again in {secs}s"),
+                                    }}
+
                                 </button>
                             </div>
                         </form>
                     </div>
                 </div>
             </div>
+            <Footer/>
         </main>
     }
 }
@@ -262,6 +621,99 @@ impl Builder<Schemas, ()> {
             .new_field("base-url")
             .input_check([Transformer::Trim], [Validator::IsUrl])
             .build()
+            .new_field("totp-code")
+            .input_check([Transformer::RemoveSpaces], [Validator::IsOtpCode])
             .build()
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stage_saved_login, SavedSession, SavedSessions, MAX_SAVED_LOGINS};
+
+    fn session(login: &str, base_url: &str, keep_signed_in: bool) -> SavedSession {
+        SavedSession {
+            login: login.to_string(),
+            base_url: base_url.to_string(),
+            keep_signed_in,
+        }
+    }
+
+    #[test]
+    fn stage_saved_login_toggles() {
+        let other = session("admin", "https://other.example.org", false);
+        let current = session("john", "https://mail.example.org", false);
+
+        // "Remember me" adds the server as the most recent entry
+        let sessions = stage_saved_login(vec![other.clone()], current.clone(), true);
+        assert_eq!(sessions, vec![current.clone(), other.clone()]);
+
+        // "Keep me signed in" replaces the previous entry for the same server
+        let kept = session("john", "https://mail.example.org", true);
+        let sessions = stage_saved_login(sessions, kept.clone(), true);
+        assert_eq!(sessions, vec![kept.clone(), other.clone()]);
+
+        // Unchecking "Remember me" only forgets the current server
+        let sessions = stage_saved_login(sessions, kept, false);
+        assert_eq!(sessions, vec![other.clone()]);
+
+        // Forgetting a server that was never saved leaves the list alone
+        let sessions = stage_saved_login(sessions, current, false);
+        assert_eq!(sessions, vec![other]);
+    }
+
+    #[test]
+    fn saved_sessions_formats() {
+        // Older versions stored a single login without the keep signed in flag
+        let legacy = serde_json::from_str::<SavedSessions>(
+            r#"{"login":"john","base_url":"https://mail.example.org"}"#,
+        );
+        assert!(matches!(
+            legacy,
+            Ok(SavedSessions::Single(saved))
+                if saved == session("john", "https://mail.example.org", false)
+        ));
+
+        let sessions = vec![
+            session("john", "https://mail.example.org", true),
+            session("admin", "", false),
+        ];
+        let stored = serde_json::to_string(&sessions).unwrap();
+        assert!(matches!(
+            serde_json::from_str::<SavedSessions>(&stored),
+            Ok(SavedSessions::List(saved)) if saved == sessions
+        ));
+        assert!(matches!(
+            serde_json::from_str::<SavedSessions>(
+                r#"[{"login":"john","base_url":"https://mail.example.org"}]"#
+            ),
+            Ok(SavedSessions::List(saved))
+                if saved == [session("john", "https://mail.example.org", false)]
+        ));
+        assert!(matches!(
+            serde_json::from_str::<SavedSessions>("[]"),
+            Ok(SavedSessions::List(saved)) if saved.is_empty()
+        ));
+
+        for invalid in [r#""john""#, r#"{"login":"john"}"#, "null"] {
+            assert!(
+                serde_json::from_str::<SavedSessions>(invalid).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn stage_saved_login_limit() {
+        let sessions = (0..MAX_SAVED_LOGINS)
+            .map(|idx| session("admin", &format!("https://mx{idx}.example.org"), false))
+            .collect::<Vec<_>>();
+        let current = session("admin", "https://mail.example.org", false);
+
+        let staged = stage_saved_login(sessions.clone(), current.clone(), true);
+        assert_eq!(staged.len(), MAX_SAVED_LOGINS);
+        assert_eq!(staged.first(), Some(&current));
+        assert_eq!(staged.last(), sessions.get(MAX_SAVED_LOGINS - 2));
     }
 }
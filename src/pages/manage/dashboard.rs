@@ -0,0 +1,251 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use chrono::{DateTime, Duration, Utc};
+use leptos::*;
+use leptos_router::use_navigate;
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+
+use crate::{
+    components::{
+        card::{Card, CardItem},
+        icon::{IconDocumentChartBar, IconLockClosed, IconQueueList, IconServerStack},
+        messages::alert::{use_alerts, Alert, Alerts},
+        skeleton::Skeleton,
+    },
+    core::{
+        cert::certificate_expiry,
+        clock::server_now,
+        http::{self, HttpRequest},
+        oauth::{login_redirect_url, use_authorization, AuthToken},
+        prefs::use_preferences,
+    },
+    pages::{
+        config::Settings, format_count, queue::messages::fetch_queue_size, FormatDateTime, List,
+    },
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DashboardStats {
+    queue_size: u64,
+    listeners: u64,
+    reports: u64,
+    certificates: Vec<CertificateExpiry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CertificateExpiry {
+    id: String,
+    expires: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FetchSettings {
+    items: Settings,
+}
+
+#[component]
+pub fn Dashboard() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let expiry_days = use_preferences().get_untracked().cert_expiry_days();
+
+    let stats = create_resource(
+        || (),
+        move |_| {
+            let auth = auth.get_untracked();
+
+            async move {
+                let mut reports = 0;
+                for report_type in ["dmarc", "tls", "arf"] {
+                    reports += HttpRequest::get(format!("/api/reports/{report_type}"))
+                        .with_authorization(&auth)
+                        .with_parameter("limit", "1")
+                        .send::<List<IgnoredAny>>()
+                        .await?
+                        .total;
+                }
+
+                Ok(DashboardStats {
+                    queue_size: fetch_queue_size(&auth).await?,
+                    listeners: count_settings(&auth, "server.listener", "protocol").await?,
+                    reports,
+                    certificates: fetch_certificates(&auth).await?,
+                })
+            }
+        },
+    );
+
+    view! {
+        <div class="max-w-[85rem] px-4 py-10 sm:px-6 lg:px-8 lg:py-14 mx-auto">
+            <Alerts/>
+            <Transition fallback=Skeleton>
+                {move || match stats.get() {
+                    None => None,
+                    Some(Err(http::Error::Unauthorized)) => {
+                        use_navigate()(&login_redirect_url(), Default::default());
+                        Some(view! { <div></div> }.into_view())
+                    }
+                    Some(Err(err)) => {
+                        alert.set(Alert::from(err));
+                        Some(view! { <div></div> }.into_view())
+                    }
+                    Some(Ok(stats)) => {
+                        let now = server_now();
+                        let mut expiring = stats
+                            .certificates
+                            .iter()
+                            .filter_map(|cert| {
+                                cert.expires
+                                    .filter(|expires| {
+                                        *expires - now <= Duration::days(expiry_days as i64)
+                                    })
+                                    .map(|expires| (cert.id.clone(), expires))
+                            })
+                            .collect::<Vec<_>>();
+                        expiring.sort_by_key(|(_, expires)| *expires);
+                        let expiring_count = if expiring.is_empty() {
+                            String::new()
+                        } else {
+                            format!("{} expiring soon", expiring.len())
+                        };
+                        let warnings = (!expiring.is_empty())
+                            .then(|| {
+                                let items = expiring
+                                    .into_iter()
+                                    .map(|(id, expires)| {
+                                        let message = if expires <= now {
+                                            format!("expired on {}", expires.format_date())
+                                        } else {
+                                            format!(
+                                                "expires on {} ({} days)",
+                                                expires.format_date(),
+                                                (expires - now).num_days(),
+                                            )
+                                        };
+                                        view! {
+                                            <li>
+                                                <a
+                                                    class="font-semibold hover:underline"
+                                                    href=format!("/settings/certificate/{id}/edit")
+                                                >
+                                                    {id}
+                                                </a>
+                                                {format!(" {message}")}
+                                            </li>
+                                        }
+                                    })
+                                    .collect_view();
+                                view! {
+                                    <div
+                                        class="mx-4 sm:mx-6 lg:mx-8 mb-4 bg-yellow-50 border border-yellow-200 text-sm text-yellow-800 rounded-lg p-4 dark:bg-yellow-800/10 dark:border-yellow-900 dark:text-yellow-500"
+                                        role="alert"
+                                    >
+                                        <h3 class="font-semibold">TLS certificates expiring soon</h3>
+                                        <ul class="mt-2 list-disc ps-5 space-y-1">{items}</ul>
+                                    </div>
+                                }
+                            });
+                        Some(
+                            view! {
+                                {warnings}
+                                <Card links=vec![
+                                    "/manage/queue/messages".to_string(),
+                                    "/settings/listener".to_string(),
+                                    "/manage/reports/dmarc".to_string(),
+                                    "/settings/certificate".to_string(),
+                                ]>
+                                    <CardItem
+                                        title="Queued Messages"
+                                        contents=format_count(stats.queue_size)
+                                    >
+
+                                        <IconQueueList attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
+
+                                    </CardItem>
+                                    <CardItem title="Listeners" contents=format_count(stats.listeners)>
+
+                                        <IconServerStack attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
+
+                                    </CardItem>
+                                    <CardItem
+                                        title="Incoming Reports"
+                                        contents=format_count(stats.reports)
+                                    >
+
+                                        <IconDocumentChartBar attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
+
+                                    </CardItem>
+                                    <CardItem
+                                        title="TLS Certificates"
+                                        contents=format_count(stats.certificates.len() as u64)
+                                        subcontents=expiring_count
+                                    >
+
+                                        <IconLockClosed attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
+
+                                    </CardItem>
+                                </Card>
+                            }
+                                .into_view(),
+                        )
+                    }
+                }}
+
+            </Transition>
+        </div>
+    }
+}
+
+async fn fetch_certificates(auth: &AuthToken) -> http::Result<Vec<CertificateExpiry>> {
+    let settings = HttpRequest::get("/api/settings/list")
+        .with_authorization(auth)
+        .with_parameter("prefix", "certificate")
+        .send::<FetchSettings>()
+        .await?
+        .items;
+
+    let mut certificates = settings
+        .iter()
+        .filter_map(|(key, value)| {
+            key.strip_suffix(".cert").map(|id| CertificateExpiry {
+                id: id.to_string(),
+                expires: certificate_expiry(value),
+            })
+        })
+        .collect::<Vec<_>>();
+    certificates.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(certificates)
+}
+
+async fn count_settings(auth: &AuthToken, prefix: &str, suffix: &str) -> http::Result<u64> {
+    HttpRequest::get("/api/settings/group")
+        .with_authorization(auth)
+        .with_parameter("limit", "1")
+        .with_parameter("prefix", prefix.to_string())
+        .with_parameter("suffix", suffix.to_string())
+        .send::<List<Settings>>()
+        .await
+        .map(|list| list.total)
+}
@@ -41,7 +41,7 @@ use crate::{
     },
     core::{
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
         url::UrlBuilder,
     },
     pages::{FormatDateTime, List},
@@ -122,7 +122,7 @@ pub fn Logs() -> impl IntoView {
                     {move || match logs.get() {
                         None => None,
                         Some(Err(http::Error::Unauthorized)) => {
-                            use_navigate()("/login", Default::default());
+                            use_navigate()(&login_redirect_url(), Default::default());
                             Some(view! { <div></div> }.into_view())
                         }
                         Some(Err(err)) => {
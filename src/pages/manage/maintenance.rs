@@ -21,21 +21,36 @@
  * for more details.
 */
 
+use std::sync::Arc;
+
+use gloo_net::http::Method;
 use leptos::*;
 use leptos_router::use_navigate;
 
 use crate::{
     components::{
-        icon::{IconCheckCircle, IconComputerDesktop, IconPower, IconRefresh, IconShieldCheck},
-        messages::alert::{use_alerts, Alert, Alerts},
+        icon::{
+            IconArrowUturnLeft, IconCheckCircle, IconClipboard, IconComputerDesktop,
+            IconDocumentText, IconPower, IconRefresh, IconShieldCheck,
+        },
+        messages::{
+            alert::{use_alerts, Alert, Alerts},
+            modal::{use_modals, Modal},
+        },
     },
     core::{
+        api::ApiClient,
+        clipboard::copy_to_clipboard,
+        download::download_file,
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
+        schema::Schemas,
     },
-    pages::config::ReloadSettings,
+    pages::config::{export::export_config, ReloadSettings},
 };
 
+const ICON_CLASS: &str = "mt-1 flex-shrink-0 size-5 text-gray-800 dark:text-gray-200";
+
 #[derive(Debug, Clone, Copy)]
 struct Action {
     title: &'static str,
@@ -81,7 +96,6 @@ const ACTIONS: &[Action] = &[
         url: "/api/update/webadmin",
         success_message: "Successfully updated the web admin to the latest version",
     },
-
 ];
 
 #[component]
@@ -131,7 +145,7 @@ pub fn Maintenance() -> impl IntoView {
 
             match err {
                 http::Error::Unauthorized => {
-                    use_navigate()("/login", Default::default());
+                    use_navigate()(&login_redirect_url(), Default::default());
                 }
                 err => {
                     set_pending.set(false);
@@ -142,40 +156,51 @@ pub fn Maintenance() -> impl IntoView {
     });
 
     let actions = ACTIONS.iter().enumerate().map(|(idx, action)| {
-        let icon_class = "mt-1 flex-shrink-0 size-5 text-gray-800 dark:text-gray-200";
         let icon = match action.icon {
-            "refresh" => view! { <IconRefresh attr:class=icon_class/> },
-            "check_circle" => view! { <IconCheckCircle attr:class=icon_class/> },
-            "power" => view! { <IconPower attr:class=icon_class/> },
-            "shield_check" => view! { <IconShieldCheck attr:class=icon_class/> },
-            "computer_desktop" => view! { <IconComputerDesktop attr:class=icon_class/> },
+            "refresh" => view! { <IconRefresh attr:class=ICON_CLASS/> },
+            "check_circle" => view! { <IconCheckCircle attr:class=ICON_CLASS/> },
+            "power" => view! { <IconPower attr:class=ICON_CLASS/> },
+            "shield_check" => view! { <IconShieldCheck attr:class=ICON_CLASS/> },
+            "computer_desktop" => view! { <IconComputerDesktop attr:class=ICON_CLASS/> },
             _ => unreachable!("No icon specified"),
         };
 
         view! {
-            <a
-                class="group flex flex-col bg-white border shadow-sm rounded-xl hover:shadow-md transition dark:bg-slate-900 dark:border-gray-800"
-                href="#"
-                on:click=move |_| {
-                    execute.dispatch(idx);
-                }
-
-                disabled=move || pending.get()
+            <ActionCard
+                title=action.title
+                description=action.description
+                icon=icon.into_view()
+                on_click=move |_| execute.dispatch(idx)
+                disabled=pending
             >
+                <div class="mt-auto border-t border-gray-200 px-4 py-2 md:px-5 dark:border-gray-800">
+                    <button
+                        type="button"
+                        class="inline-flex items-center gap-x-1 text-xs text-gray-500 hover:text-blue-600 dark:text-gray-400 dark:hover:text-gray-300"
+                        on:click=move |_| {
+                            let command = ApiClient::new(auth.get_untracked())
+                                .curl(Method::GET, action.url, None::<()>);
+                            match command {
+                                Ok(command) => spawn_local(async move {
+                                    alert.set(if copy_to_clipboard(&command).await {
+                                        Alert::success("Copied curl command to clipboard")
+                                    } else {
+                                        Alert::error("Failed to copy to clipboard")
+                                    });
+                                }),
+                                Err(err) => {
+                                    alert.set(Alert::from(err));
+                                }
+                            }
+                        }
+                    >
 
-                <div class="p-4 md:p-5">
-                    <div class="flex">
-                        {icon} <div class="grow ms-5">
-                            <h3 class="group-hover:text-blue-600 font-semibold text-gray-800 dark:group-hover:text-gray-400 dark:text-gray-200">
-                                {action.title}
-                            </h3>
-                            <p class="text-sm text-gray-500">{action.description}</p>
-                        </div>
-                    </div>
+                        <IconClipboard attr:class="flex-shrink-0 size-3"/>
+                        Copy as curl
+                    </button>
                 </div>
-            </a>
+            </ActionCard>
         }
-
     }).collect_view();
 
     view! {
@@ -184,9 +209,119 @@ pub fn Maintenance() -> impl IntoView {
             <div class="grid sm:grid-cols-2 lg:grid-cols-3 gap-3 sm:gap-6">
 
                 {actions}
+                <ExportConfig pending=pending set_pending=set_pending/>
+                <ActionCard
+                    title="Restore configuration"
+                    description="Reviews and applies the settings from a configuration backup."
+                    icon=view! { <IconArrowUturnLeft attr:class=ICON_CLASS/> }.into_view()
+                    on_click=move |_| use_navigate()("/manage/maintenance/restore", Default::default())
+                    disabled=pending
+                />
 
             </div>
 
         </div>
     }
 }
+
+#[component]
+fn ExportConfig(pending: ReadSignal<bool>, set_pending: WriteSignal<bool>) -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let modal = use_modals();
+    let schemas = expect_context::<Arc<Schemas>>();
+
+    let export = create_action(move |include_secrets: &bool| {
+        let auth = auth.get();
+        let schemas = schemas.clone();
+        let include_secrets = *include_secrets;
+
+        async move {
+            set_pending.set(true);
+            let result = export_config(&auth, &schemas, include_secrets).await;
+            set_pending.set(false);
+
+            match result {
+                Ok(export) => {
+                    if download_file(&export.filename, "application/toml", &export.contents) {
+                        alert.set(if export.redacted > 0 {
+                            Alert::success(format!(
+                                "Configuration exported with {} secrets redacted",
+                                export.redacted
+                            ))
+                        } else {
+                            Alert::success("Configuration exported")
+                        });
+                    } else {
+                        alert.set(Alert::error("Failed to download configuration"));
+                    }
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()(&login_redirect_url(), Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    view! {
+        <ActionCard
+            title="Export configuration"
+            description="Downloads a backup of the server configuration as a TOML file."
+            icon=view! { <IconDocumentText attr:class=ICON_CLASS/> }.into_view()
+            on_click=move |_| {
+                let include_secrets = create_rw_signal(false);
+                modal
+                    .set(
+                        Modal::with_title("Export configuration")
+                            .with_message(
+                                "Secrets such as passwords and private keys are redacted unless you choose to include them.",
+                            )
+                            .with_button("Export")
+                            .with_checkbox("Include secrets", include_secrets)
+                            .with_callback(move || {
+                                export.dispatch(include_secrets.get_untracked());
+                            }),
+                    );
+            }
+
+            disabled=pending
+        />
+    }
+}
+
+#[component]
+fn ActionCard(
+    title: &'static str,
+    description: &'static str,
+    icon: View,
+    #[prop(into)] on_click: Callback<()>,
+    disabled: ReadSignal<bool>,
+    #[prop(optional)] children: Option<Children>,
+) -> impl IntoView {
+    view! {
+        <div class="group flex flex-col bg-white border shadow-sm rounded-xl hover:shadow-md transition dark:bg-slate-900 dark:border-gray-800">
+            <a
+                class="block"
+                href="#"
+                on:click=move |_| on_click.call(())
+                disabled=move || disabled.get()
+            >
+
+                <div class="p-4 md:p-5">
+                    <div class="flex">
+                        {icon} <div class="grow ms-5">
+                            <h3 class="group-hover:text-blue-600 font-semibold text-gray-800 dark:group-hover:text-gray-400 dark:text-gray-200">
+                                {title}
+                            </h3>
+                            <p class="text-sm text-gray-500">{description}</p>
+                        </div>
+                    </div>
+                </div>
+            </a>
+            {children.map(|children| children())}
+        </div>
+    }
+}
@@ -21,6 +21,8 @@
  * for more details.
 */
 
+pub mod dashboard;
 pub mod logs;
 pub mod maintenance;
+pub mod restore;
 pub mod spam;
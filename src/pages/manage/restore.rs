@@ -0,0 +1,465 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use leptos::*;
+use leptos_router::use_navigate;
+use serde::Deserialize;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{
+    components::{
+        badge::Badge,
+        form::{button::Button, Form, FormButtonBar, FormItem, FormSection},
+        list::table::{Table, TableRow},
+        messages::{
+            alert::{use_alerts, Alert},
+            modal::{use_modals, Modal},
+        },
+        Color,
+    },
+    core::{
+        api::{ApiClient, ApiError},
+        oauth::{login_redirect_url, use_authorization},
+        schema::Schemas,
+        url::UrlBuilder,
+    },
+    pages::{
+        config::{export::REDACTED, import::flatten_value, Settings, UpdateSettings},
+        maybe_plural,
+    },
+};
+
+const MAX_VALUE_LEN: usize = 80;
+
+#[derive(Deserialize)]
+struct FetchSettings {
+    items: Settings,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ChangeType {
+    Added,
+    Changed,
+    Removed,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct SettingChange {
+    key: String,
+    current: Option<String>,
+    backup: Option<String>,
+    value: Option<String>,
+    typ: ChangeType,
+}
+
+#[derive(Clone, Default, PartialEq, Eq)]
+struct RestorePlan {
+    changes: Vec<SettingChange>,
+    unknown_keys: Vec<String>,
+    redacted_keys: Vec<String>,
+}
+
+#[component]
+pub fn RestoreConfig() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let modal = use_modals();
+    let schemas = expect_context::<Arc<Schemas>>();
+
+    let contents = create_rw_signal(String::new());
+    let remove_missing = create_rw_signal(false);
+    let plan = create_rw_signal(None::<RestorePlan>);
+
+    let preview_action = create_action(move |(contents, remove_missing): &(String, bool)| {
+        let client = ApiClient::new(auth.get_untracked());
+        let schemas = schemas.clone();
+        let backup = parse_backup(contents);
+        let remove_missing = *remove_missing;
+
+        async move {
+            let backup = match backup {
+                Ok(backup) if !backup.is_empty() => backup,
+                Ok(_) => {
+                    plan.set(None);
+                    alert.set(Alert::warning("The backup file contains no settings"));
+                    return;
+                }
+                Err(err) => {
+                    plan.set(None);
+                    alert.set(Alert::error("Invalid backup file").with_details(err));
+                    return;
+                }
+            };
+
+            match client
+                .get::<FetchSettings>(
+                    UrlBuilder::new("/api/settings/list").with_parameter("prefix", ""),
+                )
+                .await
+            {
+                Ok(current) => {
+                    plan.set(Some(RestorePlan::new(
+                        &schemas,
+                        current.items,
+                        backup,
+                        remove_missing,
+                    )));
+                }
+                Err(ApiError::Unauthorized) => {
+                    use_navigate()(&login_redirect_url(), Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    let apply_action = create_action(move |updates: &Arc<Vec<UpdateSettings>>| {
+        let client = ApiClient::new(auth.get_untracked());
+        let updates = updates.clone();
+
+        async move {
+            match client
+                .create::<_, Option<String>>("/api/settings", updates)
+                .await
+            {
+                Ok(_) => {
+                    plan.set(None);
+                    contents.set(String::new());
+                    alert.set(
+                        Alert::success("Configuration restored")
+                            .with_details("Reload the configuration or restart the server to apply the changes.")
+                            .without_timeout(),
+                    );
+                }
+                Err(ApiError::Unauthorized) => {
+                    use_navigate()(&login_redirect_url(), Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+    let pending =
+        Signal::derive(move || preview_action.pending().get() || apply_action.pending().get());
+
+    view! {
+        <Form
+            title="Restore configuration"
+            subtitle="Apply settings from a configuration backup"
+        >
+
+            <FormSection>
+                <FormItem label="Backup file">
+                    <input
+                        type="file"
+                        accept=".toml,text/plain"
+                        class="block w-full text-sm text-gray-500 file:me-4 file:py-2 file:px-4 file:rounded-lg file:border-0 file:text-sm file:font-semibold file:bg-blue-600 file:text-white hover:file:bg-blue-700 dark:text-gray-400"
+                        on:change=move |ev| {
+                            let Some(file) = event_target::<web_sys::HtmlInputElement>(&ev)
+                                .files()
+                                .and_then(|files| files.get(0)) else {
+                                return;
+                            };
+                            plan.set(None);
+                            spawn_local(async move {
+                                match JsFuture::from(file.text()).await {
+                                    Ok(text) => {
+                                        contents.set(text.as_string().unwrap_or_default());
+                                    }
+                                    Err(_) => {
+                                        alert.set(Alert::error("Failed to read file"));
+                                    }
+                                }
+                            });
+                        }
+                    />
+
+                </FormItem>
+                <FormItem label="Remove other settings">
+                    <label class="flex items-center gap-x-3 mt-2.5 text-sm text-gray-800 dark:text-gray-400">
+                        <input
+                            type="checkbox"
+                            class="shrink-0 border-gray-200 rounded text-blue-600 focus:ring-blue-500 dark:bg-gray-800 dark:border-gray-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
+                            prop:checked=move || remove_missing.get()
+                            on:input=move |_| {
+                                remove_missing.update(|v| *v = !*v);
+                                plan.set(None);
+                            }
+                        />
+
+                        "Delete settings that are not present in the backup"
+                    </label>
+                </FormItem>
+            </FormSection>
+
+            {move || {
+                plan.get()
+                    .map(|plan| {
+                        let warnings = plan.warnings();
+                        let summary = plan.summary();
+                        let rows = plan
+                            .changes
+                            .into_iter()
+                            .map(|change| {
+                                let (color, label) = match change.typ {
+                                    ChangeType::Added => (Color::Green, "Added"),
+                                    ChangeType::Changed => (Color::Yellow, "Changed"),
+                                    ChangeType::Removed => (Color::Red, "Removed"),
+                                };
+                                view! {
+                                    <TableRow>
+                                        <span class="font-mono">{change.key}</span>
+                                        <span class="font-mono">
+                                            {change.current.unwrap_or_default()}
+                                        </span>
+                                        <span class="font-mono">
+                                            {change.backup.unwrap_or_default()}
+                                        </span>
+                                        <Badge color=color>{label}</Badge>
+                                    </TableRow>
+                                }
+                            })
+                            .collect_view();
+                        view! {
+                            <div class="mt-5 space-y-3">
+                                <p class="text-sm text-gray-800 dark:text-gray-200">{summary}</p>
+                                {warnings
+                                    .into_iter()
+                                    .map(|(title, items)| {
+                                        let items = items
+                                            .into_iter()
+                                            .map(|item| view! { <li class="font-mono">{item}</li> })
+                                            .collect_view();
+                                        view! {
+                                            <details class="text-sm text-yellow-800 bg-yellow-50 border border-yellow-200 rounded-lg p-3 dark:bg-yellow-800/10 dark:border-yellow-900 dark:text-yellow-500">
+                                                <summary class="cursor-pointer">{title}</summary>
+                                                <ul class="mt-2 list-disc ps-5 text-xs space-y-1">
+                                                    {items}
+                                                </ul>
+                                            </details>
+                                        }
+                                    })
+                                    .collect_view()}
+                                <Table headers=vec![
+                                    "Key".to_string(),
+                                    "Current".to_string(),
+                                    "Backup".to_string(),
+                                    "Change".to_string(),
+                                ]>{rows}</Table>
+                            </div>
+                        }
+                    })
+            }}
+
+            <FormButtonBar>
+                <Button
+                    text="Cancel"
+                    color=Color::Gray
+                    on_click=move |_| {
+                        use_navigate()("/manage/maintenance", Default::default());
+                    }
+                />
+
+                <Button
+                    text="Preview changes"
+                    color=Color::Gray
+                    disabled=Signal::derive(move || {
+                        pending.get() || contents.with(|contents| contents.trim().is_empty())
+                    })
+
+                    on_click=move |_| {
+                        preview_action.dispatch((contents.get(), remove_missing.get()));
+                    }
+                />
+
+                <Button
+                    text="Restore"
+                    color=Color::Red
+                    disabled=Signal::derive(move || {
+                        pending.get()
+                            || plan.with(|plan| plan.as_ref().is_none_or(|plan| plan.changes.is_empty()))
+                    })
+
+                    on_click=move |_| {
+                        let Some(plan) = plan.get() else {
+                            return;
+                        };
+                        let updates = Arc::new(plan.build_update());
+                        modal
+                            .set(
+                                Modal::with_title("Restore configuration")
+                                    .with_message(
+                                        format!(
+                                            "{} This cannot be undone, consider exporting the current configuration first.",
+                                            plan.summary(),
+                                        ),
+                                    )
+                                    .with_button("Restore configuration")
+                                    .with_dangerous_callback(move || {
+                                        apply_action.dispatch(updates.clone());
+                                    }),
+                            );
+                    }
+                />
+
+            </FormButtonBar>
+        </Form>
+    }
+}
+
+impl RestorePlan {
+    fn new(schemas: &Schemas, current: Settings, backup: Settings, remove_missing: bool) -> Self {
+        let current = current.into_iter().collect::<BTreeMap<_, _>>();
+        let backup = backup.into_iter().collect::<BTreeMap<_, _>>();
+        let display = |key: &str, value: &str| {
+            if schemas.is_sensitive_key(key) {
+                "********".to_string()
+            } else if value.chars().count() > MAX_VALUE_LEN {
+                format!("{}…", value.chars().take(MAX_VALUE_LEN).collect::<String>())
+            } else {
+                value.to_string()
+            }
+        };
+
+        let mut plan = RestorePlan::default();
+        for (key, value) in &backup {
+            if value == REDACTED {
+                plan.redacted_keys.push(key.clone());
+                continue;
+            }
+            if !schemas.is_known_key(key) {
+                plan.unknown_keys.push(key.clone());
+            }
+            let typ = match current.get(key) {
+                Some(current) if current == value => continue,
+                Some(_) => ChangeType::Changed,
+                None => ChangeType::Added,
+            };
+            plan.changes.push(SettingChange {
+                key: key.clone(),
+                current: current.get(key).map(|current| display(key, current)),
+                backup: Some(display(key, value)),
+                value: Some(value.clone()),
+                typ,
+            });
+        }
+
+        if remove_missing {
+            for (key, value) in &current {
+                if !backup.contains_key(key) {
+                    plan.changes.push(SettingChange {
+                        key: key.clone(),
+                        current: Some(display(key, value)),
+                        backup: None,
+                        value: None,
+                        typ: ChangeType::Removed,
+                    });
+                }
+            }
+            plan.changes.sort_by(|a, b| a.key.cmp(&b.key));
+        }
+
+        plan
+    }
+
+    fn summary(&self) -> String {
+        let count = |typ| self.changes.iter().filter(|c| c.typ == typ).count();
+        if self.changes.is_empty() {
+            "The current configuration already matches the backup.".to_string()
+        } else {
+            format!(
+                "{} will be added, {} changed and {} removed.",
+                maybe_plural(count(ChangeType::Added), "setting", "settings"),
+                count(ChangeType::Changed),
+                count(ChangeType::Removed)
+            )
+        }
+    }
+
+    fn warnings(&self) -> Vec<(String, Vec<String>)> {
+        let mut warnings = Vec::new();
+        if !self.unknown_keys.is_empty() {
+            warnings.push((
+                format!(
+                    "{} not recognized by this version of the web admin",
+                    maybe_plural(self.unknown_keys.len(), "key is", "keys are")
+                ),
+                self.unknown_keys.clone(),
+            ));
+        }
+        if !self.redacted_keys.is_empty() {
+            warnings.push((
+                format!(
+                    "{} redacted in the backup and will keep their current values",
+                    maybe_plural(self.redacted_keys.len(), "secret was", "secrets were")
+                ),
+                self.redacted_keys.clone(),
+            ));
+        }
+        warnings
+    }
+
+    fn build_update(&self) -> Vec<UpdateSettings> {
+        let mut updates = Vec::new();
+        let removed = self
+            .changes
+            .iter()
+            .filter(|change| change.typ == ChangeType::Removed)
+            .map(|change| change.key.clone())
+            .collect::<Vec<_>>();
+        if !removed.is_empty() {
+            updates.push(UpdateSettings::Delete { keys: removed });
+        }
+
+        let values = self
+            .changes
+            .iter()
+            .filter_map(|change| Some((change.key.clone(), change.value.clone()?)))
+            .collect::<Vec<_>>();
+        if !values.is_empty() {
+            updates.push(UpdateSettings::Insert {
+                prefix: None,
+                values,
+                assert_empty: false,
+            });
+        }
+
+        updates
+    }
+}
+
+fn parse_backup(contents: &str) -> Result<Settings, String> {
+    let root = contents
+        .parse::<toml::Table>()
+        .map_err(|err| err.message().to_string())?;
+    let mut settings = Settings::new();
+    for (key, value) in &root {
+        flatten_value(key, value, &mut settings);
+    }
+    Ok(settings)
+}
@@ -43,7 +43,7 @@ use crate::{
     core::{
         form::FormValue,
         http::{Error, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
         schema::{Builder, Schemas, Source, Transformer, Type, Validator},
     },
 };
@@ -144,7 +144,7 @@ pub fn SpamTest() -> impl IntoView {
                         alert.set(Alert::warning("Message discarded by filter"));
                     }
                     Err(Error::Unauthorized) => {
-                        use_navigate()("/login", Default::default());
+                        use_navigate()(&login_redirect_url(), Default::default());
                     }
                     Err(err) => {
                         alert.set(Alert::from(err));
@@ -321,7 +321,7 @@ pub fn SpamTrain() -> impl IntoView {
                     alert.set(Alert::error("Unexpected server response"));
                 }
                 Err(Error::Unauthorized) => {
-                    use_navigate()("/login", Default::default());
+                    use_navigate()(&login_redirect_url(), Default::default());
                 }
                 Err(err) => {
                     alert.set(Alert::from(err));
@@ -22,8 +22,11 @@
 */
 
 use chrono::{DateTime, Local, Utc};
+use chrono_humanize::HumanTime;
 use serde::{Deserialize, Serialize};
 
+use crate::core::clock::server_now;
+
 pub mod account;
 pub mod authorize;
 pub mod config;
@@ -48,10 +51,23 @@ pub fn maybe_plural(items: usize, singular: &str, plural: &str) -> String {
     }
 }
 
+pub fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (pos, ch) in digits.chars().enumerate() {
+        if pos > 0 && (digits.len() - pos).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(ch);
+    }
+    result
+}
+
 pub trait FormatDateTime {
     fn format_date_time(&self) -> String;
     fn format_date(&self) -> String;
     fn format_time(&self) -> String;
+    fn format_relative(&self) -> String;
 }
 
 impl FormatDateTime for DateTime<Utc> {
@@ -70,4 +86,8 @@ impl FormatDateTime for DateTime<Utc> {
     fn format_time(&self) -> String {
         self.with_timezone(&Local).format("%H:%M:%S").to_string()
     }
+
+    fn format_relative(&self) -> String {
+        HumanTime::from(*self - server_now()).to_string()
+    }
 }
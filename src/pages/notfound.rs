@@ -29,6 +29,7 @@ use crate::components::icon::IconArrowLeft;
 #[component]
 pub fn NotFound() -> impl IntoView {
     view! {
+        <Title text="Page not found"/>
         <Html lang="en" class="h-full"/>
         <Body class="dark:bg-slate-900 bg-gray-100 flex h-full items-center py-16"/>
         <div class="max-w-[50rem] flex flex-col mx-auto size-full">
@@ -21,7 +21,7 @@
  * for more details.
 */
 
-use leptos::*;
+use leptos::{leptos_dom::helpers::TimeoutHandle, *};
 use leptos_router::*;
 use std::collections::HashSet;
 
@@ -35,11 +35,11 @@ use crate::{
             header::ColumnList,
             pagination::Pagination,
             row::SelectItem,
-            toolbar::{SearchBox, ToolbarButton},
+            toolbar::{AutoRefresh, SearchBox, ToolbarButton},
             Footer, ListItem, ListSection, ListTable, Toolbar, ZeroResults,
         },
         messages::{
-            alert::{use_alerts, Alert},
+            alert::{use_alerts, Alert, UNDO_TIMEOUT},
             modal::{use_modals, Modal},
         },
         skeleton::Skeleton,
@@ -47,20 +47,17 @@ use crate::{
     },
     core::{
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization, AuthToken},
+        prefs::use_preferences,
         url::UrlBuilder,
     },
     pages::{
         maybe_plural,
         queue::messages::{Message, Status},
-        List,
+        FormatDateTime, List,
     },
 };
 
-use chrono_humanize::HumanTime;
-
-const PAGE_SIZE: u32 = 10;
-
 #[component]
 pub fn QueueList() -> impl IntoView {
     let query = use_query_map();
@@ -84,21 +81,23 @@ pub fn QueueList() -> impl IntoView {
     });
 
     let auth = use_authorization();
+    let prefs = use_preferences();
+    let page_size = create_memo(move |_| prefs.with(|prefs| prefs.page_size()));
     let alert = use_alerts();
     let modal = use_modals();
     let selected = create_rw_signal::<HashSet<String>>(HashSet::new());
     provide_context(selected);
 
     let messages = create_resource(
-        move || (page.get(), filter.get()),
-        move |(page, filter)| {
+        move || (page.get(), filter.get(), page_size.get()),
+        move |(page, filter, page_size)| {
             let auth = auth.get_untracked();
 
             async move {
                 HttpRequest::get("/api/queue/messages")
                     .with_authorization(&auth)
                     .with_parameter("page", page.to_string())
-                    .with_parameter("limit", PAGE_SIZE.to_string())
+                    .with_parameter("limit", page_size.to_string())
                     .with_parameter("values", "1")
                     .with_parameter("max-total", "100")
                     .with_optional_parameter("text", filter)
@@ -113,30 +112,67 @@ pub fn QueueList() -> impl IntoView {
         let auth = auth.get();
 
         async move {
-            let mut total_deleted = 0;
-            for id in items {
-                match HttpRequest::delete(("/api/queue/messages", &id))
-                    .with_authorization(&auth)
-                    .send::<bool>()
-                    .await
-                {
-                    Ok(true) => {
-                        total_deleted += 1;
-                    }
-                    Ok(false) | Err(http::Error::NotFound) => {}
-                    Err(err) => {
-                        alert.set(Alert::from(err));
-                        return;
+            match cancel_messages(&auth, items).await {
+                Ok(total_deleted) => {
+                    messages.refetch();
+                    if total_deleted > 0 {
+                        alert.set(Alert::success(format!(
+                            "Removed {} from queue.",
+                            maybe_plural(total_deleted, "message", "messages")
+                        )));
                     }
                 }
+                Err(err) => {
+                    messages.refetch();
+                    alert.set(Alert::from(err));
+                }
             }
+        }
+    });
 
-            if total_deleted > 0 {
-                messages.refetch();
-                alert.set(Alert::success(format!(
-                    "Removed {} from queue.",
-                    maybe_plural(total_deleted, "message", "messages")
-                )));
+    // Cancellations are held back for a few seconds so they can be undone
+    let pending_cancel = create_rw_signal(HashSet::<String>::new());
+    let cancel_timer = store_value(None::<TimeoutHandle>);
+    let flush_cancel = move || {
+        if let Some(handle) = cancel_timer.try_update_value(|h| h.take()).flatten() {
+            handle.clear();
+        }
+        let items = pending_cancel
+            .try_update(std::mem::take)
+            .unwrap_or_default();
+        if !items.is_empty() {
+            cancel_action.dispatch(items);
+        }
+    };
+    let undo_cancel = move || {
+        if let Some(handle) = cancel_timer.try_update_value(|h| h.take()).flatten() {
+            handle.clear();
+        }
+        pending_cancel.try_set(HashSet::new());
+    };
+    let schedule_cancel = move |items: HashSet<String>| {
+        flush_cancel();
+        let text = maybe_plural(items.len(), "message", "messages");
+        pending_cancel.set(items);
+        cancel_timer.set_value(set_timeout_with_handle(flush_cancel, UNDO_TIMEOUT).ok());
+        alert.set(
+            Alert::success(format!("Cancelling delivery of {text}."))
+                .with_action("Undo", Callback::new(move |_| undo_cancel()))
+                .with_timeout(UNDO_TIMEOUT),
+        );
+    };
+    on_cleanup(move || {
+        if let Some(handle) = cancel_timer.try_update_value(|h| h.take()).flatten() {
+            handle.clear();
+            let items = pending_cancel
+                .try_update(std::mem::take)
+                .unwrap_or_default();
+            if let Some(auth) = auth.try_get_untracked().filter(|_| !items.is_empty()) {
+                spawn_local(async move {
+                    if let Err(err) = cancel_messages(&auth, items).await {
+                        log::warn!("Failed to cancel queued messages: {err:?}");
+                    }
+                });
             }
         }
     });
@@ -191,6 +227,19 @@ pub fn QueueList() -> impl IntoView {
                         }
                     />
 
+                    <AutoRefresh
+                        on_refresh=move |_| {
+                            if !messages.loading().get_untracked() {
+                                messages.refetch();
+                            }
+                        }
+
+                        paused=Signal::derive(move || {
+                            modal.get().is_open || cancel_action.pending().get()
+                                || retry_action.pending().get()
+                        })
+                    />
+
                     <ToolbarButton
                         text="Refresh"
 
@@ -240,15 +289,14 @@ pub fn QueueList() -> impl IntoView {
                                         Modal::with_title("Confirm deletion")
                                             .with_message(
                                                 format!(
-                                                    "Are you sure you want to cancel delivery of {text}? This action cannot be undone.",
+                                                    "Are you sure you want to cancel delivery of {text}?",
                                                 ),
                                             )
                                             .with_button(format!("Delete {text}"))
                                             .with_dangerous_callback(move || {
-                                                cancel_action
-                                                    .dispatch(
-                                                        selected.try_update(std::mem::take).unwrap_or_default(),
-                                                    );
+                                                schedule_cancel(
+                                                    selected.try_update(std::mem::take).unwrap_or_default(),
+                                                );
                                             }),
                                     )
                             }
@@ -264,7 +312,7 @@ pub fn QueueList() -> impl IntoView {
                     {move || match messages.get() {
                         None => None,
                         Some(Err(http::Error::Unauthorized)) => {
-                            use_navigate()("/login", Default::default());
+                            use_navigate()(&login_redirect_url(), Default::default());
                             Some(view! { <div></div> }.into_view())
                         }
                         Some(Err(err)) => {
@@ -296,7 +344,17 @@ pub fn QueueList() -> impl IntoView {
                                     >
 
                                         <For
-                                            each=move || messages.items.clone()
+                                            each=move || {
+                                                let pending = pending_cancel.get();
+                                                messages
+                                                    .items
+                                                    .iter()
+                                                    .filter(|message| {
+                                                        !pending.contains(&message.id.to_string())
+                                                    })
+                                                    .cloned()
+                                                    .collect::<Vec<_>>()
+                                            }
                                             key=|message| message.id
                                             let:message
                                         >
@@ -329,7 +387,6 @@ pub fn QueueList() -> impl IntoView {
                     <Pagination
                         current_page=page
                         total_results=total_results.read_only()
-                        page_size=PAGE_SIZE
                         on_page_change=move |page: u32| {
                             use_navigate()(
                                 &UrlBuilder::new("/manage/queue/messages")
@@ -347,6 +404,24 @@ pub fn QueueList() -> impl IntoView {
     }
 }
 
+async fn cancel_messages(auth: &AuthToken, items: HashSet<String>) -> http::Result<usize> {
+    let mut total_deleted = 0;
+    for id in items {
+        match HttpRequest::delete(("/api/queue/messages", &id))
+            .with_authorization(auth)
+            .send::<bool>()
+            .await
+        {
+            Ok(true) => {
+                total_deleted += 1;
+            }
+            Ok(false) | Err(http::Error::NotFound) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(total_deleted)
+}
+
 #[component]
 fn QueueItem(message: Message) -> impl IntoView {
     let mut total_success = 0;
@@ -376,10 +451,8 @@ fn QueueItem(message: Message) -> impl IntoView {
         }
     }
 
-    let next_retry = message
-        .next_retry()
-        .map(|dt| HumanTime::from(dt).to_string());
-    let next_dsn = message.next_dsn().map(|dt| HumanTime::from(dt).to_string());
+    let next_retry = message.next_retry().map(|dt| dt.format_relative());
+    let next_dsn = message.next_dsn().map(|dt| dt.format_relative());
     let return_path = message.return_path().to_string();
     let recipients = if total_recipients > 0 {
         format!("{first_recipient} and {total_recipients} more",)
@@ -23,8 +23,6 @@
 
 use std::{collections::HashSet, vec};
 
-use chrono::Utc;
-use chrono_humanize::HumanTime;
 use humansize::{format_size, DECIMAL};
 use leptos::*;
 use leptos_router::{use_navigate, use_params_map};
@@ -49,8 +47,10 @@ use crate::{
         Color,
     },
     core::{
+        clock::server_now,
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
+        resource::use_item_resource,
     },
     pages::{
         maybe_plural,
@@ -67,20 +67,9 @@ pub fn QueueManage() -> impl IntoView {
     let params = use_params_map();
     let blob_hash = RwSignal::new(String::new());
     let fetch_headers = RwSignal::new(true);
-    let fetch_message = create_resource(
-        move || params.get().get("id").cloned().unwrap_or_default(),
-        move |id| {
-            let auth = auth.get_untracked();
-            let id = id.clone();
-
-            async move {
-                HttpRequest::get(("/api/queue/messages", &id))
-                    .with_authorization(&auth)
-                    .send::<Message>()
-                    .await
-            }
-        },
-    );
+    let fetch_message = use_item_resource::<Message>("/api/queue/messages", move || {
+        params.get().get("id").cloned().unwrap_or_default()
+    });
     let fetch_contents = create_resource(
         move || (blob_hash.get(), fetch_headers.get()),
         move |(blob_hash, fetch_headers)| {
@@ -187,10 +176,7 @@ pub fn QueueManage() -> impl IntoView {
 
             {move || match fetch_message.get() {
                 None => None,
-                Some(Err(http::Error::Unauthorized)) => {
-                    use_navigate()("/login", Default::default());
-                    Some(view! { <div></div> }.into_view())
-                }
+                Some(Err(http::Error::Unauthorized)) => Some(view! { <div></div> }.into_view()),
                 Some(Err(http::Error::NotFound)) => {
                     use_navigate()("/manage/queue/messages", Default::default());
                     Some(view! { <div></div> }.into_view())
@@ -243,7 +229,7 @@ pub fn QueueManage() -> impl IntoView {
                                 </CardItem>
                                 <CardItem
                                     title="Sent"
-                                    contents=HumanTime::from(message.created).to_string()
+                                    contents=message.created.format_relative()
                                     subcontents=message.created.format_date_time()
                                 >
 
@@ -275,7 +261,7 @@ pub fn QueueManage() -> impl IntoView {
                                 <CardItem
                                     title="Next Retry"
                                     contents=next_retry
-                                        .map(|dt| HumanTime::from(dt).to_string())
+                                        .map(|dt| dt.format_relative())
                                         .unwrap_or("N/A".to_string())
                                     subcontents=next_retry
                                         .map(|dt| { dt.format_date_time() })
@@ -288,7 +274,7 @@ pub fn QueueManage() -> impl IntoView {
                                 <CardItem
                                     title="Next notification"
                                     contents=next_dsn
-                                        .map(|dt| HumanTime::from(dt).to_string())
+                                        .map(|dt| dt.format_relative())
                                         .unwrap_or("N/A".to_string())
                                     subcontents=next_dsn
                                         .map(|dt| { dt.format_date_time() })
@@ -301,7 +287,7 @@ pub fn QueueManage() -> impl IntoView {
                                 <CardItem
                                     title="Last attempt"
                                     contents=expires
-                                        .map(|dt| HumanTime::from(dt).to_string())
+                                        .map(|dt| dt.format_relative())
                                         .unwrap_or("N/A".to_string())
                                     subcontents=expires
                                         .map(|dt| { dt.format_date_time() })
@@ -456,11 +442,11 @@ pub fn QueueManage() -> impl IntoView {
                                                 let next_retry = next_retry
                                                     .map(|dt| {
                                                         if !matches!(recipient.status, Status::Completed(_))
-                                                            || dt < Utc::now()
+                                                            || dt < server_now()
                                                         {
                                                             format!(
                                                                 "{} ({})",
-                                                                HumanTime::from(dt),
+                                                                dt.format_relative(),
                                                                 dt.format_date_time(),
                                                             )
                                                         } else {
@@ -540,7 +526,7 @@ pub fn QueueManage() -> impl IntoView {
             {move || match fetch_contents.get() {
                 None | Some(Err(http::Error::NotFound)) => None,
                 Some(Err(http::Error::Unauthorized)) => {
-                    use_navigate()("/login", Default::default());
+                    use_navigate()(&login_redirect_url(), Default::default());
                     Some(view! { <div></div> }.into_view())
                 }
                 Some(Err(err)) => {
@@ -25,7 +25,25 @@ pub mod list;
 pub mod manage;
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{de::IgnoredAny, Deserialize, Deserializer, Serialize};
+
+use crate::{
+    core::{
+        http::{self, HttpRequest},
+        oauth::AuthToken,
+    },
+    pages::List,
+};
+
+pub async fn fetch_queue_size(auth: &AuthToken) -> http::Result<u64> {
+    HttpRequest::get("/api/queue/messages")
+        .with_authorization(auth)
+        .with_parameter("limit", "1")
+        .in_background()
+        .send::<List<IgnoredAny>>()
+        .await
+        .map(|list| list.total)
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Message {
@@ -32,16 +32,22 @@ use crate::{
         card::{Card, CardItem},
         form::button::Button,
         icon::{IconAlertTriangle, IconClock, IconDocumentChartBar},
-        report::{ReportItem, ReportSection, ReportTextValue, ReportView},
+        report::{
+            ReportExtraValue, ReportIpValue, ReportItem, ReportLabelValue, ReportNavigation,
+            ReportNavigationButtons, ReportSection, ReportTextValue, ReportView,
+        },
         Color,
     },
     pages::{
-        queue::reports::{DeliveryResult, FeedbackType, IdentityAlignment},
+        format_count,
+        queue::reports::{DeliveryResult, FeedbackType, IdentityAlignment, ReportLabel},
         FormatDateTime,
     },
 };
 
-use super::Feedback;
+use super::{auth::parse_authentication_results, Feedback};
+
+const TRUNCATE_AT: usize = 200;
 
 #[component]
 #[allow(unused_parens)]
@@ -50,23 +56,33 @@ pub fn ArfReportDisplay(
     received: DateTime<Utc>,
     extra: Vec<(String, String)>,
     back_url: String,
+    #[prop(default = None)] navigation: Option<ReportNavigation>,
 ) -> impl IntoView {
     let received_date = received.format_date();
     let received_time = received.format_time();
-    let arrival_date = report
-        .arrival_date
-        .and_then(|date| DateTime::from_timestamp(date, 0))
-        .unwrap_or(received);
-    let arrival_time = arrival_date.format_time();
-    let arrival_date = arrival_date.format_date();
-    let has_port = report.source_port > 0;
+    // Never substitute the received time for a missing or invalid arrival date
+    let (arrival_date, arrival_time) = match report.arrival_date {
+        Some(date) => match DateTime::from_timestamp(date, 0) {
+            Some(date) => (date.format_date(), date.format_time()),
+            None => (
+                "Unavailable".to_string(),
+                format!("Reported time unavailable (invalid timestamp {date})"),
+            ),
+        },
+        None => (
+            "Unavailable".to_string(),
+            "Reported time unavailable".to_string(),
+        ),
+    };
+    let source_port = report.reported_source_port();
+    let incidents = format_count(report.incident_count() as u64);
     let extra = extra
         .into_iter()
         .filter_map(|(k, v)| {
             if !v.is_empty() {
                 Some(view! {
                     <ReportItem label=k>
-                        <ReportTextValue value=v/>
+                        <ReportExtraValue value=v/>
                     </ReportItem>
                 })
             } else {
@@ -75,46 +91,75 @@ pub fn ArfReportDisplay(
         })
         .collect_view();
 
+    let show_raw_auth = create_rw_signal(false);
+    let raw_auth_results = report
+        .authentication_results
+        .iter()
+        .map(|result| result.trim())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let reported_uri = report
+        .reported_uri
+        .iter()
+        .map(|uri| {
+            let uri = uri.trim().to_string();
+            if is_web_url(&uri) {
+                view! {
+                    <a
+                        class="inline-block text-sm font-semibold text-blue-600 hover:underline mt-2.5 break-all dark:text-blue-500"
+                        href=uri.clone()
+                        target="_blank"
+                        rel="noopener noreferrer"
+                    >
+                        {uri}
+                    </a>
+                }
+                    .into_view()
+            } else {
+                view! { <ReportTextValue value=uri truncate_at=TRUNCATE_AT/> }.into_view()
+            }
+        })
+        .collect_view();
+
     let auth_failure = if report.feedback_type == FeedbackType::AuthFailure {
+        let label = |value: &dyn ReportLabel| {
+            view! { <ReportLabelValue label=value.label() description=value.description()/> }
+        };
+        let text = |value: Option<String>| {
+            value.map(|value| view! { <ReportTextValue value=value truncate_at=TRUNCATE_AT/> })
+        };
         let items = [
-            ("Failure Type", Some(report.auth_failure.to_string())),
+            ("Failure Type", Some(label(&report.auth_failure))),
             (
                 "Delivery Result",
-                if report.delivery_result != DeliveryResult::Unspecified {
-                    Some(report.delivery_result.to_string())
-                } else {
-                    None
-                },
+                (report.delivery_result != DeliveryResult::Unspecified)
+                    .then(|| label(&report.delivery_result)),
+            ),
+            ("DKIM ADSP DNS", text(report.dkim_adsp_dns)),
+            (
+                "DKIM Canonicalized Body",
+                text(report.dkim_canonicalized_body),
             ),
-            ("DKIM ADSP DNS", report.dkim_adsp_dns),
-            ("DKIM Canonicalized Body", report.dkim_canonicalized_body),
             (
                 "DKIM Canonicalized Header",
-                report.dkim_canonicalized_header,
+                text(report.dkim_canonicalized_header),
             ),
-            ("DKIM Domain", report.dkim_domain),
-            ("DKIM Identity", report.dkim_identity),
-            ("DKIM Selector", report.dkim_selector),
-            ("DKIM Selector DNS", report.dkim_selector_dns),
-            ("SPF DNS", report.spf_dns),
+            ("DKIM Domain", text(report.dkim_domain)),
+            ("DKIM Identity", text(report.dkim_identity)),
+            ("DKIM Selector", text(report.dkim_selector)),
+            ("DKIM Selector DNS", text(report.dkim_selector_dns)),
+            ("SPF DNS", text(report.spf_dns)),
             (
                 "Identity Alignment",
-                if report.identity_alignment != IdentityAlignment::Unspecified {
-                    Some(report.identity_alignment.to_string())
-                } else {
-                    None
-                },
+                (report.identity_alignment != IdentityAlignment::Unspecified)
+                    .then(|| label(&report.identity_alignment)),
             ),
         ];
         let items = items
             .into_iter()
             .filter_map(|(k, v)| {
                 let v = v?;
-                Some(view! {
-                    <ReportItem label=k.to_string()>
-                        <ReportTextValue value=v/>
-                    </ReportItem>
-                })
+                Some(view! { <ReportItem label=k.to_string()>{v}</ReportItem> })
             })
             .collect_view();
 
@@ -133,7 +178,7 @@ pub fn ArfReportDisplay(
                 <IconDocumentChartBar attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
 
             </CardItem>
-            <CardItem title="Incidents" contents=std::cmp::max(report.incidents, 1).to_string()>
+            <CardItem title="Incidents" contents=incidents>
 
                 <IconAlertTriangle attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
 
@@ -157,14 +202,32 @@ pub fn ArfReportDisplay(
                     <ReportTextValue value=report.reported_domain.join(",")/>
                 </ReportItem>
                 <ReportItem label="Reported URI" hide=report.reported_uri.is_empty()>
-                    <ReportTextValue value=report.reported_uri.join(",")/>
+                    <div class="flex flex-col">{reported_uri}</div>
                 </ReportItem>
 
                 <ReportItem
                     label="Authentication Results"
                     hide=report.authentication_results.is_empty()
                 >
-                    <ReportTextValue value=report.authentication_results.join(", ")/>
+                    <div class="flex flex-wrap items-center gap-2 mt-2.5">
+                        {report
+                            .authentication_results
+                            .iter()
+                            .flat_map(|result| parse_authentication_results(result))
+                            .collect_view()}
+                    </div>
+                    <button
+                        type="button"
+                        class="ms-2 text-xs font-semibold text-blue-600 hover:underline dark:text-blue-500"
+                        on:click=move |_| show_raw_auth.update(|v| *v = !*v)
+                    >
+                        {move || if show_raw_auth.get() { "hide raw" } else { "show raw" }}
+                    </button>
+                    <Show when=move || show_raw_auth.get()>
+                        <pre class="mt-2 p-3 overflow-x-auto text-xs font-mono text-gray-700 bg-gray-50 border border-gray-200 rounded-lg dark:bg-slate-800 dark:border-gray-700 dark:text-gray-300">
+                            {raw_auth_results.clone()}
+                        </pre>
+                    </Show>
                 </ReportItem>
                 <ReportItem label="Original Mail From" hide=report.original_mail_from.is_none()>
                     <ReportTextValue value=report.original_mail_from.unwrap_or_default()/>
@@ -179,13 +242,10 @@ pub fn ArfReportDisplay(
                     <ReportTextValue value=report.reporting_mta.unwrap_or_default()/>
                 </ReportItem>
                 <ReportItem label="Source IP" hide=report.source_ip.is_none()>
-                    <ReportTextValue value=report
-                        .source_ip
-                        .map(|ip| ip.to_string())
-                        .unwrap_or_default()/>
+                    {report.source_ip.map(|ip| view! { <ReportIpValue ip=ip/> })}
                 </ReportItem>
-                <ReportItem label="Source Port" hide=has_port>
-                    <ReportTextValue value=report.source_port.to_string()/>
+                <ReportItem label="Source Port" hide=source_port.is_none()>
+                    <ReportTextValue value=source_port.map(|port| port.to_string()).unwrap_or_default()/>
                 </ReportItem>
                 <ReportItem label="User Agent" hide=report.user_agent.is_none()>
                     <ReportTextValue value=report.user_agent.unwrap_or_default()/>
@@ -195,7 +255,12 @@ pub fn ArfReportDisplay(
             </ReportSection>
             {auth_failure}
 
-            <div class="flex justify-end">
+            <div class="flex justify-end gap-x-2">
+
+                {navigation
+                    .map(|navigation| {
+                        view! { <ReportNavigationButtons navigation=navigation/> }
+                    })}
 
                 <Button
                     text="Close"
@@ -209,3 +274,11 @@ pub fn ArfReportDisplay(
         </ReportView>
     }
 }
+
+fn is_web_url(uri: &str) -> bool {
+    ["http://", "https://"].iter().any(|scheme| {
+        uri.get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+            && uri.len() > scheme.len()
+    })
+}
@@ -0,0 +1,236 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use leptos::*;
+
+use crate::components::{badge::Badge, Color};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthResult {
+    Method {
+        method: String,
+        result: String,
+        properties: String,
+    },
+    Text(String),
+}
+
+const KNOWN_METHODS: &[&str] = &[
+    "arc",
+    "auth",
+    "bimi",
+    "dkim",
+    "dkim-adsp",
+    "dkim-atps",
+    "dmarc",
+    "domainkeys",
+    "iprev",
+    "rrvs",
+    "sender-id",
+    "smime",
+    "spf",
+    "vbr",
+];
+
+pub fn parse_authentication_results(value: &str) -> Vec<AuthResult> {
+    let mut results = Vec::new();
+    let mut depth = 0u32;
+    let mut in_quotes = false;
+    let mut start = 0;
+
+    for (pos, ch) in value.char_indices() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            '(' if !in_quotes => depth += 1,
+            ')' if !in_quotes => depth = depth.saturating_sub(1),
+            ';' if !in_quotes && depth == 0 => {
+                push_result(&mut results, &value[start..pos], start == 0);
+                start = pos + 1;
+            }
+            _ => {}
+        }
+    }
+    push_result(&mut results, &value[start..], start == 0);
+
+    results
+}
+
+fn push_result(results: &mut Vec<AuthResult>, segment: &str, is_first: bool) {
+    let segment = segment.trim();
+    if segment.is_empty() {
+        return;
+    }
+
+    if let Some(result) = parse_method(segment) {
+        results.push(result);
+    } else if !is_first {
+        // The first segment is usually the authserv-id, which is not a result
+        results.push(AuthResult::Text(segment.to_string()));
+    }
+}
+
+fn parse_method(segment: &str) -> Option<AuthResult> {
+    let (method, rest) = segment.split_once('=')?;
+    let method = method.trim().to_ascii_lowercase();
+    if !KNOWN_METHODS.contains(&method.as_str()) {
+        return None;
+    }
+
+    let rest = rest.trim_start();
+    let end = rest
+        .find(|ch: char| ch.is_whitespace() || ch == '(')
+        .unwrap_or(rest.len());
+    let result = rest[..end].to_ascii_lowercase();
+    if result.is_empty() {
+        return None;
+    }
+
+    Some(AuthResult::Method {
+        method,
+        result,
+        properties: rest[end..].trim().to_string(),
+    })
+}
+
+// Free-form text such as the details of a DMARC aggregate record only becomes badges when it
+// holds results, so plain explanations are still shown as they are
+pub fn auth_results_view(value: &str) -> View {
+    let results = parse_authentication_results(value);
+    if results
+        .iter()
+        .any(|result| matches!(result, AuthResult::Method { .. }))
+    {
+        view! { <div class="flex flex-wrap items-center gap-2">{results}</div> }.into_view()
+    } else {
+        value.to_string().into_view()
+    }
+}
+
+impl AuthResult {
+    pub fn color(&self) -> Color {
+        match self {
+            AuthResult::Method { result, .. } => match result.as_str() {
+                "pass" => Color::Green,
+                "fail" | "hardfail" | "permerror" => Color::Red,
+                "softfail" | "temperror" | "neutral" | "policy" => Color::Yellow,
+                "none" => Color::Gray,
+                _ => Color::Blue,
+            },
+            AuthResult::Text(_) => Color::Gray,
+        }
+    }
+}
+
+impl IntoView for AuthResult {
+    fn into_view(self) -> View {
+        let color = self.color();
+        match self {
+            AuthResult::Method {
+                method,
+                result,
+                properties,
+            } => view! {
+                <Badge color=color attr:title=properties>
+                    {format!("{method}={result}")}
+                </Badge>
+            }
+            .into_view(),
+            AuthResult::Text(text) => view! {
+                <span class="text-sm text-gray-500 break-all">{text}</span>
+            }
+            .into_view(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_authentication_results, AuthResult};
+
+    fn method(method: &str, result: &str, properties: &str) -> AuthResult {
+        AuthResult::Method {
+            method: method.to_string(),
+            result: result.to_string(),
+            properties: properties.to_string(),
+        }
+    }
+
+    fn text(text: &str) -> AuthResult {
+        AuthResult::Text(text.to_string())
+    }
+
+    #[test]
+    fn parse_results() {
+        for (value, expected) in [
+            // The authserv-id is not a result
+            ("mx.example.org; none", vec![text("none")]),
+            (
+                "mx.example.org; spf=pass smtp.mailfrom=example.com",
+                vec![method("spf", "pass", "smtp.mailfrom=example.com")],
+            ),
+            // Multiple methods, mixed case
+            (
+                "mx.example.org; DKIM=Pass header.d=example.com header.s=sel; spf=softfail smtp.mailfrom=example.com; dmarc=none header.from=example.com",
+                vec![
+                    method("dkim", "pass", "header.d=example.com header.s=sel"),
+                    method("spf", "softfail", "smtp.mailfrom=example.com"),
+                    method("dmarc", "none", "header.from=example.com"),
+                ],
+            ),
+            // Comments, including nested ones, may contain semicolons
+            (
+                "mx.example.org (version 1; build 2); spf=fail (sender not permitted; (really)) smtp.mailfrom=example.com",
+                vec![method(
+                    "spf",
+                    "fail",
+                    "(sender not permitted; (really)) smtp.mailfrom=example.com",
+                )],
+            ),
+            // A comment right after the result ends it
+            (
+                "mx.example.org; iprev=pass(ok) policy.iprev=192.0.2.1",
+                vec![method("iprev", "pass", "(ok) policy.iprev=192.0.2.1")],
+            ),
+            // Quoted values may contain semicolons and parentheses
+            (
+                r#"mx.example.org; auth=pass smtp.auth="user;(x)"; arc=none"#,
+                vec![
+                    method("auth", "pass", r#"smtp.auth="user;(x)""#),
+                    method("arc", "none", ""),
+                ],
+            ),
+            // Unknown methods fall through as text
+            (
+                "mx.example.org; x-custom=pass; dkim=fail",
+                vec![text("x-custom=pass"), method("dkim", "fail", "")],
+            ),
+            // Results without an authserv-id
+            ("spf=pass", vec![method("spf", "pass", "")]),
+            ("dkim=", vec![]),
+            ("", vec![]),
+            (" ; ;", vec![]),
+        ] {
+            assert_eq!(parse_authentication_results(value), expected, "{value}");
+        }
+    }
+}
@@ -33,7 +33,7 @@ use crate::{
     },
     core::{
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
     },
     pages::queue::reports::{dmarc::DmarcReportDisplay, tls::TlsReportDisplay, AggregateReport},
 };
@@ -97,7 +97,7 @@ pub fn ReportDisplay() -> impl IntoView {
             {move || match fetch_report.get() {
                 None => None,
                 Some(Err(http::Error::Unauthorized)) => {
-                    use_navigate()("/login", Default::default());
+                    use_navigate()(&login_redirect_url(), Default::default());
                     Some(view! { <div></div> }.into_view())
                 }
                 Some(Err(http::Error::NotFound)) => {
@@ -46,16 +46,20 @@ use crate::{
             toolbar::SearchBox,
             Footer, ListItem, ListTable, ListTextItem, Toolbar,
         },
-        report::{ReportItem, ReportSection, ReportTextValue, ReportView},
+        report::{
+            ReportExtraValue, ReportIpValue, ReportItem, ReportNavigation, ReportNavigationButtons,
+            ReportSection, ReportTextValue, ReportView, ReverseDnsName,
+        },
         Color,
     },
     pages::{
+        format_count,
         queue::reports::{display::PAGE_SIZE, ActionDisposition, Report},
         FormatDateTime,
     },
 };
 
-use super::{DkimResult, DmarcResult, Record, SpfResult};
+use super::{auth::auth_results_view, DkimResult, DmarcResult, Record, SpfResult};
 
 #[component]
 #[allow(unused_parens)]
@@ -63,6 +67,7 @@ pub fn DmarcReportDisplay(
     report: Report,
     extra: Vec<(String, String)>,
     back_url: String,
+    #[prop(default = None)] navigation: Option<ReportNavigation>,
 ) -> impl IntoView {
     let report_start_date =
         DateTime::<Utc>::from_timestamp(report.report_metadata.date_range.begin as i64, 0)
@@ -149,7 +154,7 @@ pub fn DmarcReportDisplay(
             if !v.is_empty() {
                 Some(view! {
                     <ReportItem label=k>
-                        <ReportTextValue value=v/>
+                        <ReportExtraValue value=v/>
                     </ReportItem>
                 })
             } else {
@@ -183,22 +188,22 @@ pub fn DmarcReportDisplay(
 
         </Card>
         <Card>
-            <CardItem title="Passed" contents=total_pass.to_string()>
+            <CardItem title="Passed" contents=format_count(total_pass as u64)>
 
                 <IconCheckCircle attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
 
             </CardItem>
-            <CardItem title="Rejected" contents=total_reject.to_string()>
+            <CardItem title="Rejected" contents=format_count(total_reject as u64)>
 
                 <IconCancel attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
 
             </CardItem>
-            <CardItem title="Quarantines" contents=total_quarantine.to_string()>
+            <CardItem title="Quarantines" contents=format_count(total_quarantine as u64)>
 
                 <IconAlertTriangle attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
 
             </CardItem>
-            <CardItem title="No Action" contents=total_none.to_string()>
+            <CardItem title="No Action" contents=format_count(total_none as u64)>
 
                 <IconArrowRightCircle attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
 
@@ -293,8 +298,12 @@ pub fn DmarcReportDisplay(
                                             {record
                                                 .row
                                                 .source_ip
-                                                .map(|ip| ip.to_string())
-                                                .unwrap_or_default()}
+                                                .map(|ip| {
+                                                    view! {
+                                                        {ip.to_string()}
+                                                        <ReverseDnsName ip=ip/>
+                                                    }
+                                                })}
                                         </ListTextItem>
                                         <ListTextItem>
                                             {record.row.policy_evaluated.disposition}
@@ -336,7 +345,12 @@ pub fn DmarcReportDisplay(
 
             </div>
 
-            <div class="flex justify-end">
+            <div class="flex justify-end gap-x-2">
+
+                {navigation
+                    .map(|navigation| {
+                        view! { <ReportNavigationButtons navigation=navigation/> }
+                    })}
 
                 <Button
                     text="Close"
@@ -387,14 +401,10 @@ pub fn DmarcReportDisplay(
                                         .unwrap_or_default()/>
                                 </ReportItem>
                                 <ReportItem label="Source IP" hide=record.row.source_ip.is_none()>
-                                    <ReportTextValue value=record
-                                        .row
-                                        .source_ip
-                                        .map(|ip| ip.to_string())
-                                        .unwrap_or_default()/>
+                                    {record.row.source_ip.map(|ip| view! { <ReportIpValue ip=ip/> })}
                                 </ReportItem>
                                 <ReportItem label="Count" hide=record.row.count == 0>
-                                    <ReportTextValue value=record.row.count.to_string()/>
+                                    <ReportTextValue value=format_count(record.row.count as u64)/>
                                 </ReportItem>
                                 <ReportItem
                                     label="Override Reasons"
@@ -437,7 +447,11 @@ pub fn DmarcReportDisplay(
                                                                 <span>{dkim.domain}</span>
                                                                 <span>{dkim.selector}</span>
                                                                 <span>{dkim.result}</span>
-                                                                <span>{dkim.human_result.unwrap_or_default()}</span>
+                                                                <span>
+                                                                    {dkim
+                                                                        .human_result
+                                                                        .map(|details| auth_results_view(&details))}
+                                                                </span>
 
                                                             </TableRow>
                                                         }
@@ -478,7 +492,11 @@ pub fn DmarcReportDisplay(
                                                                 <span>{spf.domain}</span>
                                                                 <span>{spf.scope.to_string()}</span>
                                                                 <span>{spf.result}</span>
-                                                                <span>{spf.human_result.unwrap_or_default()}</span>
+                                                                <span>
+                                                                    {spf
+                                                                        .human_result
+                                                                        .map(|details| auth_results_view(&details))}
+                                                                </span>
 
                                                             </TableRow>
                                                         }
@@ -44,8 +44,11 @@ use crate::{
         Color,
     },
     core::{
-        http::{self, HttpRequest},
+        api::{ApiClient, ApiError},
+        http,
         oauth::use_authorization,
+        prefs::use_preferences,
+        resource::{use_list_resource, ListQuery},
         url::UrlBuilder,
     },
     pages::{
@@ -57,8 +60,6 @@ use crate::{
 
 use chrono_humanize::{Accuracy, HumanTime, Tense};
 
-const PAGE_SIZE: u32 = 10;
-
 #[component]
 pub fn ReportList() -> impl IntoView {
     let query = use_query_map();
@@ -82,59 +83,31 @@ pub fn ReportList() -> impl IntoView {
     });
 
     let auth = use_authorization();
+    let prefs = use_preferences();
+    let page_size = create_memo(move |_| prefs.with(|prefs| prefs.page_size()));
     let alert = use_alerts();
     let modal = use_modals();
     let selected = create_rw_signal::<HashSet<String>>(HashSet::new());
     provide_context(selected);
 
-    let reports = create_resource(
-        move || (page.get(), filter.get()),
-        move |(page, filter)| {
-            let auth = auth.get_untracked();
-
-            async move {
-                HttpRequest::get("/api/queue/reports")
-                    .with_authorization(&auth)
-                    .with_parameter("page", page.to_string())
-                    .with_parameter("limit", PAGE_SIZE.to_string())
-                    .with_parameter("max-total", "100")
-                    .with_optional_parameter("domain", filter)
-                    .send::<List<String>>()
-                    .await
-                    .map(|list| {
-                        let mut response = List {
-                            items: Vec::with_capacity(list.items.len()),
-                            total: list.total,
-                        };
-                        for item in list.items {
-                            if let Some(item) = AggregateReportId::parse(item.clone()) {
-                                response.items.push(item);
-                            } else {
-                                log::warn!("Invalid report id: {item}");
-                            }
-                        }
-                        response
-                    })
-            }
-        },
-    );
+    let reports = use_list_resource::<String>("/api/queue/reports", move || {
+        ListQuery::new(page.get(), page_size.get())
+            .with_parameter("max-total", "100")
+            .with_optional_parameter("domain", filter.get())
+    });
 
     let cancel_action = create_action(move |items: &HashSet<String>| {
         let items = items.clone();
-        let auth = auth.get();
+        let client = ApiClient::new(auth.get());
 
         async move {
             let mut total_deleted = 0;
             for id in items {
-                match HttpRequest::delete(("/api/queue/reports", &id))
-                    .with_authorization(&auth)
-                    .send::<bool>()
-                    .await
-                {
+                match client.delete::<bool>(("/api/queue/reports", &id)).await {
                     Ok(true) => {
                         total_deleted += 1;
                     }
-                    Ok(false) | Err(http::Error::NotFound) => {}
+                    Ok(false) | Err(ApiError::NotFound) => {}
                     Err(err) => {
                         alert.set(Alert::from(err));
                         return;
@@ -225,7 +198,6 @@ pub fn ReportList() -> impl IntoView {
                     {move || match reports.get() {
                         None => None,
                         Some(Err(http::Error::Unauthorized)) => {
-                            use_navigate()("/login", Default::default());
                             Some(view! { <div></div> }.into_view())
                         }
                         Some(Err(err)) => {
@@ -235,6 +207,7 @@ pub fn ReportList() -> impl IntoView {
                         }
                         Some(Ok(reports)) if !reports.items.is_empty() => {
                             total_results.set(Some(reports.total as u32));
+                            let reports = parse_report_ids(reports);
                             let reports_ = reports.clone();
                             Some(
                                 view! {
@@ -290,7 +263,6 @@ pub fn ReportList() -> impl IntoView {
                     <Pagination
                         current_page=page
                         total_results=total_results.read_only()
-                        page_size=PAGE_SIZE
                         on_page_change=move |page: u32| {
                             use_navigate()(
                                 &UrlBuilder::new("/manage/queue/reports")
@@ -308,6 +280,23 @@ pub fn ReportList() -> impl IntoView {
     }
 }
 
+fn parse_report_ids(list: List<String>) -> List<AggregateReportId> {
+    List {
+        items: list
+            .items
+            .into_iter()
+            .filter_map(|item| {
+                let id = AggregateReportId::parse(item.clone());
+                if id.is_none() {
+                    log::warn!("Invalid report id: {item}");
+                }
+                id
+            })
+            .collect(),
+        total: list.total,
+    }
+}
+
 #[component]
 fn ReportItem(report: AggregateReportId) -> impl IntoView {
     let show_url = format!("/manage/queue/report/{}", report.id);
@@ -361,7 +350,7 @@ fn ReportItem(report: AggregateReportId) -> impl IntoView {
             </td>
 
             <ListTextItem>
-                {format!("{} ({})", HumanTime::from(report.due), report.due.format_date_time())}
+                {format!("{} ({})", report.due.format_relative(), report.due.format_date_time())}
 
             </ListTextItem>
 
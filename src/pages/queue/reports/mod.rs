@@ -22,6 +22,7 @@
 */
 
 pub mod arf;
+pub mod auth;
 pub mod display;
 pub mod dmarc;
 pub mod list;
@@ -438,6 +439,7 @@ pub enum ResultType {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Feedback {
     pub feedback_type: FeedbackType,
     pub arrival_date: Option<i64>,
@@ -665,58 +667,184 @@ impl Display for ResultType {
     }
 }
 
-impl Display for FeedbackType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Feedback {
+    // Reports without an Incidents header describe a single incident
+    pub fn incident_count(&self) -> u32 {
+        std::cmp::max(self.incidents, 1)
+    }
+
+    // Port zero is what reports without a Source-Port header deserialize to
+    pub fn reported_source_port(&self) -> Option<u32> {
+        (self.source_port > 0).then_some(self.source_port)
+    }
+}
+
+impl FeedbackType {
+    pub const ALL: [FeedbackType; 6] = [
+        FeedbackType::Abuse,
+        FeedbackType::AuthFailure,
+        FeedbackType::Fraud,
+        FeedbackType::NotSpam,
+        FeedbackType::Other,
+        FeedbackType::Virus,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
         match self {
-            FeedbackType::Abuse => f.write_str("Abuse"),
-            FeedbackType::AuthFailure => f.write_str("Authentication Failure"),
-            FeedbackType::Fraud => f.write_str("Fraud"),
-            FeedbackType::NotSpam => f.write_str("Not Spam"),
-            FeedbackType::Other => f.write_str("Other"),
-            FeedbackType::Virus => f.write_str("Virus"),
+            FeedbackType::Abuse => "abuse",
+            FeedbackType::AuthFailure => "auth-failure",
+            FeedbackType::Fraud => "fraud",
+            FeedbackType::NotSpam => "not-spam",
+            FeedbackType::Other => "other",
+            FeedbackType::Virus => "virus",
         }
     }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        FeedbackType::ALL
+            .into_iter()
+            .find(|typ| typ.as_str() == value)
+    }
 }
 
-impl Display for AuthFailureType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+// Friendly labels for report enums, with the RFC term as a description.
+pub trait ReportLabel {
+    fn label(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+}
+
+impl ReportLabel for FeedbackType {
+    fn label(&self) -> &'static str {
+        match self {
+            FeedbackType::Abuse => "Abuse",
+            FeedbackType::AuthFailure => "Authentication Failure",
+            FeedbackType::Fraud => "Fraud",
+            FeedbackType::NotSpam => "Not Spam",
+            FeedbackType::Other => "Other",
+            FeedbackType::Virus => "Virus",
+        }
+    }
+
+    fn description(&self) -> &'static str {
         match self {
-            AuthFailureType::Adsp => f.write_str("ADSP"),
-            AuthFailureType::BodyHash => f.write_str("Body Hash"),
-            AuthFailureType::Revoked => f.write_str("Revoked"),
-            AuthFailureType::Signature => f.write_str("Signature"),
-            AuthFailureType::Spf => f.write_str("SPF"),
-            AuthFailureType::Dmarc => f.write_str("DMARC"),
-            AuthFailureType::Unspecified => f.write_str("Unspecified"),
+            FeedbackType::Abuse => "abuse: unsolicited or otherwise abusive email (RFC 5965)",
+            FeedbackType::AuthFailure => {
+                "auth-failure: the message failed an authentication check (RFC 6591)"
+            }
+            FeedbackType::Fraud => "fraud: fraud or phishing activity (RFC 5965)",
+            FeedbackType::NotSpam => {
+                "not-spam: the message was incorrectly classified as spam (RFC 5965)"
+            }
+            FeedbackType::Other => "other: any other feedback (RFC 5965)",
+            FeedbackType::Virus => "virus: a virus was found in the message (RFC 5965)",
         }
     }
 }
 
-impl Display for IdentityAlignment {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl ReportLabel for AuthFailureType {
+    fn label(&self) -> &'static str {
+        match self {
+            AuthFailureType::Adsp => "ADSP Policy Violation",
+            AuthFailureType::BodyHash => "Body Hash Mismatch",
+            AuthFailureType::Revoked => "Revoked Key",
+            AuthFailureType::Signature => "Invalid Signature",
+            AuthFailureType::Spf => "SPF Failure",
+            AuthFailureType::Dmarc => "DMARC Failure",
+            AuthFailureType::Unspecified => "Unspecified",
+        }
+    }
+
+    fn description(&self) -> &'static str {
         match self {
-            IdentityAlignment::None => f.write_str("None"),
-            IdentityAlignment::Spf => f.write_str("SPF"),
-            IdentityAlignment::Dkim => f.write_str("DKIM"),
-            IdentityAlignment::DkimSpf => f.write_str("DKIM+SPF"),
-            IdentityAlignment::Unspecified => f.write_str("Unspecified"),
+            AuthFailureType::Adsp => "adsp: the message violated the ADSP policy (RFC 6591)",
+            AuthFailureType::BodyHash => {
+                "bodyhash: the DKIM body hash did not match the message body (RFC 6591)"
+            }
+            AuthFailureType::Revoked => "revoked: the DKIM key has been revoked (RFC 6591)",
+            AuthFailureType::Signature => {
+                "signature: the DKIM signature could not be verified (RFC 6591)"
+            }
+            AuthFailureType::Spf => "spf: the message failed SPF evaluation (RFC 6591)",
+            AuthFailureType::Dmarc => "dmarc: the message failed DMARC evaluation (RFC 7489)",
+            AuthFailureType::Unspecified => "The report did not include a failure type",
         }
     }
 }
 
-impl Display for DeliveryResult {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl ReportLabel for IdentityAlignment {
+    fn label(&self) -> &'static str {
+        match self {
+            IdentityAlignment::None => "Not Aligned",
+            IdentityAlignment::Spf => "SPF Aligned",
+            IdentityAlignment::Dkim => "DKIM Aligned",
+            IdentityAlignment::DkimSpf => "DKIM and SPF Aligned",
+            IdentityAlignment::Unspecified => "Unspecified",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            IdentityAlignment::None => "none: no identifier was aligned (RFC 7489)",
+            IdentityAlignment::Spf => "spf: the SPF identifier was aligned (RFC 7489)",
+            IdentityAlignment::Dkim => "dkim: the DKIM identifier was aligned (RFC 7489)",
+            IdentityAlignment::DkimSpf => {
+                "dkim,spf: both DKIM and SPF identifiers were aligned (RFC 7489)"
+            }
+            IdentityAlignment::Unspecified => "The report did not include an identity alignment",
+        }
+    }
+}
+
+impl ReportLabel for DeliveryResult {
+    fn label(&self) -> &'static str {
+        match self {
+            DeliveryResult::Delivered => "Delivered",
+            DeliveryResult::Spam => "Delivered to Spam",
+            DeliveryResult::Policy => "Blocked by Policy",
+            DeliveryResult::Reject => "Rejected",
+            DeliveryResult::Other => "Other",
+            DeliveryResult::Unspecified => "Unspecified",
+        }
+    }
+
+    fn description(&self) -> &'static str {
         match self {
-            DeliveryResult::Delivered => f.write_str("Delivered"),
-            DeliveryResult::Spam => f.write_str("Spam"),
-            DeliveryResult::Policy => f.write_str("Policy"),
-            DeliveryResult::Reject => f.write_str("Reject"),
-            DeliveryResult::Other => f.write_str("Other"),
-            DeliveryResult::Unspecified => f.write_str("Unspecified"),
+            DeliveryResult::Delivered => "delivered: the message was delivered (RFC 6591)",
+            DeliveryResult::Spam => "spam: the message was delivered to a spam folder (RFC 6591)",
+            DeliveryResult::Policy => {
+                "policy: the message was not delivered due to local policy (RFC 6591)"
+            }
+            DeliveryResult::Reject => "reject: the message was rejected (RFC 6591)",
+            DeliveryResult::Other => "other: any other delivery result (RFC 6591)",
+            DeliveryResult::Unspecified => "The report did not include a delivery result",
         }
     }
 }
 
+impl Display for FeedbackType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl Display for AuthFailureType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl Display for IdentityAlignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl Display for DeliveryResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
 #[cfg(feature = "demo")]
 pub fn test_dmarc_report() -> Report {
     Report {
@@ -1053,3 +1181,19 @@ fn test_tls_failure(result_type: ResultType, domain: &str, id: usize) -> Failure
         failure_reason_code: Some(format!("Some reason code for {id}")),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Feedback;
+
+    #[test]
+    fn reported_source_port() {
+        let report = |source_port| Feedback {
+            source_port,
+            ..Default::default()
+        };
+
+        assert_eq!(report(25).reported_source_port(), Some(25));
+        assert_eq!(report(0).reported_source_port(), None);
+    }
+}
@@ -39,10 +39,14 @@ use crate::{
             header::ColumnList, pagination::Pagination, toolbar::SearchBox, Footer, ListItem,
             ListTable, ListTextItem, Toolbar,
         },
-        report::{ReportItem, ReportSection, ReportTextValue, ReportView},
+        report::{
+            ReportExtraValue, ReportItem, ReportNavigation, ReportNavigationButtons, ReportSection,
+            ReportTextValue, ReportView,
+        },
         Color,
     },
     pages::{
+        format_count,
         queue::reports::{display::PAGE_SIZE, Policy},
         FormatDateTime,
     },
@@ -63,6 +67,7 @@ pub fn TlsReportDisplay(
     report: TlsReport,
     extra: Vec<(String, String)>,
     back_url: String,
+    #[prop(default = None)] navigation: Option<ReportNavigation>,
 ) -> impl IntoView {
     let report_start_date = report.date_range.start_datetime.format_date_time();
     let report_start_time = report.date_range.start_datetime.format_time();
@@ -120,7 +125,7 @@ pub fn TlsReportDisplay(
             if !v.is_empty() {
                 Some(view! {
                     <ReportItem label=k>
-                        <ReportTextValue value=v/>
+                        <ReportExtraValue value=v/>
                     </ReportItem>
                 })
             } else {
@@ -141,12 +146,12 @@ pub fn TlsReportDisplay(
                 <IconClock attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
 
             </CardItem>
-            <CardItem title="Successes" contents=total_success.to_string()>
+            <CardItem title="Successes" contents=format_count(total_success as u64)>
 
                 <IconCheckCircle attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
 
             </CardItem>
-            <CardItem title="Failures" contents=total_fail.to_string()>
+            <CardItem title="Failures" contents=format_count(total_fail as u64)>
 
                 <IconCancel attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
 
@@ -247,7 +252,12 @@ pub fn TlsReportDisplay(
                 None
             }}
 
-            <div class="flex justify-end">
+            <div class="flex justify-end gap-x-2">
+
+                {navigation
+                    .map(|navigation| {
+                        view! { <ReportNavigationButtons navigation=navigation/> }
+                    })}
 
                 <Button
                     text="Close"
@@ -34,14 +34,15 @@ use crate::{
     },
     core::{
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization},
+        recent::use_recently_viewed,
     },
     pages::{
         queue::reports::{
             arf::ArfReportDisplay, dmarc::DmarcReportDisplay, tls::TlsReportDisplay, Feedback,
             Report, TlsReport,
         },
-        reports::IncomingReport,
+        reports::{use_report_sequence, IncomingReport},
     },
 };
 
@@ -55,11 +56,27 @@ enum ReportWrapper {
     Arf(IncomingReport<Feedback>),
 }
 
+impl ReportWrapper {
+    fn subject(&self) -> &str {
+        let (subject, from) = match self {
+            ReportWrapper::Dmarc(report) => (&report.subject, &report.from),
+            ReportWrapper::Tls(report) => (&report.subject, &report.from),
+            ReportWrapper::Arf(report) => (&report.subject, &report.from),
+        };
+        if !subject.is_empty() {
+            subject
+        } else {
+            from
+        }
+    }
+}
+
 #[component]
 pub fn IncomingReportDisplay() -> impl IntoView {
     let auth = use_authorization();
     let alert = use_alerts();
     let params = use_params_map();
+    let recent = use_recently_viewed();
     let report_type = create_memo(move |_| {
         match params
             .get()
@@ -101,7 +118,24 @@ pub fn IncomingReportDisplay() -> impl IntoView {
             }
         },
     );
+    create_effect(move |_| {
+        if let Some(Ok(report)) = fetch_report.get() {
+            let report_id = params
+                .get_untracked()
+                .get("id")
+                .cloned()
+                .unwrap_or_default();
+            let report_type = report_type.get_untracked();
+            recent.record(
+                report_type.item_name(),
+                report_id.as_str(),
+                report.subject(),
+                format!("/manage/reports/{}/{report_id}", report_type.as_str()),
+            );
+        }
+    });
 
+    let sequence = use_report_sequence();
     let selected = create_rw_signal::<HashSet<String>>(HashSet::new());
     provide_context(selected);
 
@@ -112,7 +146,7 @@ pub fn IncomingReportDisplay() -> impl IntoView {
             {move || match fetch_report.get() {
                 None => None,
                 Some(Err(http::Error::Unauthorized)) => {
-                    use_navigate()("/login", Default::default());
+                    use_navigate()(&login_redirect_url(), Default::default());
                     Some(view! { <div></div> }.into_view())
                 }
                 Some(Err(http::Error::NotFound)) => {
@@ -127,6 +161,7 @@ pub fn IncomingReportDisplay() -> impl IntoView {
                     Some(view! { <div></div> }.into_view())
                 }
                 Some(Ok(report)) => {
+                    let report_id = params.get().get("id").cloned().unwrap_or_default();
                     match report {
                         ReportWrapper::Tls(report) => {
                             let (report, extra) = report.unwrap_report();
@@ -135,7 +170,10 @@ pub fn IncomingReportDisplay() -> impl IntoView {
                                     <TlsReportDisplay
                                         report=report
                                         extra=extra
-                                        back_url="/manage/reports/tls".to_string()
+                                        back_url=sequence.get_untracked().back_url(ReportType::Tls)
+                                        navigation=sequence
+                                            .get_untracked()
+                                            .navigation(ReportType::Tls, &report_id)
                                     />
                                 }
                                     .into_view(),
@@ -148,7 +186,10 @@ pub fn IncomingReportDisplay() -> impl IntoView {
                                     <DmarcReportDisplay
                                         report=report
                                         extra=extra
-                                        back_url="/manage/reports/dmarc".to_string()
+                                        back_url=sequence.get_untracked().back_url(ReportType::Dmarc)
+                                        navigation=sequence
+                                            .get_untracked()
+                                            .navigation(ReportType::Dmarc, &report_id)
                                     />
                                 }
                                     .into_view(),
@@ -156,16 +197,17 @@ pub fn IncomingReportDisplay() -> impl IntoView {
                         }
                         ReportWrapper::Arf(report) => {
                             let (report, extra) = report.unwrap_report();
-                            let received = parse_report_date(
-                                &params.get().get("id").cloned().unwrap_or_default(),
-                            );
+                            let received = parse_report_date(&report_id);
                             Some(
                                 view! {
                                     <ArfReportDisplay
                                         report=report
                                         received=received
                                         extra=extra
-                                        back_url="/manage/reports/arf".to_string()
+                                        back_url=sequence.get_untracked().back_url(ReportType::Arf)
+                                        navigation=sequence
+                                            .get_untracked()
+                                            .navigation(ReportType::Arf, &report_id)
                                     />
                                 }
                                     .into_view(),
@@ -0,0 +1,655 @@
+/*
+ * Copyright (c) 2024, Stalwart Labs Ltd.
+ *
+ * This file is part of Stalwart Mail Web-based Admin.
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of
+ * the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * in the LICENSE file at the top-level directory of this distribution.
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * You can be released from the requirements of the AGPLv3 license by
+ * purchasing a commercial license. Please contact licensing@stalw.art
+ * for more details.
+*/
+
+use std::net::IpAddr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, FixedOffset, Utc};
+use leptos::*;
+use leptos_router::use_navigate;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{
+    components::{
+        form::{button::Button, Form, FormButtonBar, FormItem, FormSection},
+        messages::alert::{use_alerts, Alert, Alerts},
+        Color,
+    },
+    pages::queue::reports::{
+        arf::ArfReportDisplay, AuthFailureType, DeliveryResult, Feedback, FeedbackType,
+        IdentityAlignment,
+    },
+};
+
+#[derive(Clone)]
+struct ParsedReport {
+    report: Feedback,
+    received: DateTime<Utc>,
+    extra: Vec<(String, String)>,
+}
+
+type Headers = Vec<(String, String)>;
+
+// Renders an ARF report received out-of-band, parsing is done locally so nothing
+// is submitted to the server. DMARC aggregate reports are not supported.
+#[component]
+pub fn ReportInspect() -> impl IntoView {
+    let alert = use_alerts();
+    let contents = create_rw_signal(String::new());
+    let error = create_rw_signal(None::<String>);
+    let parsed = create_rw_signal(None::<ParsedReport>);
+
+    let inspect = move || match parse_arf(&contents.get_untracked()) {
+        Ok(report) => {
+            error.set(None);
+            parsed.set(Some(report));
+        }
+        Err(err) => {
+            parsed.set(None);
+            error.set(Some(err));
+        }
+    };
+
+    view! {
+        <Alerts/>
+        {move || match parsed.get() {
+            Some(ParsedReport { report, received, extra }) => {
+                view! {
+                    <ArfReportDisplay
+                        report=report
+                        received=received
+                        extra=extra
+                        back_url="/manage/reports/arf".to_string()
+                    />
+                    <div class="max-w-[85rem] px-4 sm:px-6 lg:px-8 mx-auto">
+                        <Button
+                            text="Inspect another report"
+                            color=Color::Gray
+                            on_click=move |_| parsed.set(None)
+                        />
+                    </div>
+                }
+                    .into_view()
+            }
+            None => {
+                view! {
+                    <Form
+                        title="View Pasted ARF Report"
+                        subtitle="Display a raw ARF feedback report (RFC 5965) received outside of the server"
+                    >
+
+                        <FormSection>
+                            <FormItem label="Report">
+                                <textarea
+                                    class="py-3 px-4 block w-full font-mono border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                                    class:border-red-500=move || error.with(Option::is_some)
+                                    rows="16"
+                                    spellcheck="false"
+                                    placeholder="Content-Type: multipart/report; report-type=feedback-report; ..."
+                                    aria-invalid=move || error.with(Option::is_some).to_string()
+                                    aria-describedby=move || {
+                                        error.with(Option::is_some).then_some("report-error")
+                                    }
+
+                                    prop:value=move || contents.get()
+                                    on:input=move |ev| {
+                                        contents.set(event_target_value(&ev));
+                                        error.set(None);
+                                    }
+                                >
+                                </textarea>
+                                <input
+                                    type="file"
+                                    accept=".eml,message/rfc822,text/plain"
+                                    class="mt-2 block w-full text-sm text-gray-500 file:me-4 file:py-2 file:px-4 file:rounded-lg file:border-0 file:text-sm file:font-semibold file:bg-blue-600 file:text-white hover:file:bg-blue-700 dark:text-gray-400"
+                                    on:change=move |ev| {
+                                        let Some(file) = event_target::<web_sys::HtmlInputElement>(&ev)
+                                            .files()
+                                            .and_then(|files| files.get(0)) else {
+                                            return;
+                                        };
+                                        spawn_local(async move {
+                                            match JsFuture::from(file.text()).await {
+                                                Ok(text) => {
+                                                    contents.set(text.as_string().unwrap_or_default());
+                                                    error.set(None);
+                                                }
+                                                Err(_) => {
+                                                    alert.set(Alert::error("Failed to read file"));
+                                                }
+                                            }
+                                        });
+                                    }
+                                />
+
+                                <Show when=move || error.with(Option::is_some)>
+                                    <p class="text-sm text-red-600 mt-2" id="report-error">
+                                        {move || error.get()}
+                                    </p>
+                                </Show>
+                            </FormItem>
+                        </FormSection>
+
+                        <FormButtonBar>
+                            <Button
+                                text="Cancel"
+                                color=Color::Gray
+                                on_click=move |_| {
+                                    use_navigate()("/manage/reports/arf", Default::default());
+                                }
+                            />
+
+                            <Button
+                                text="View Report"
+                                color=Color::Blue
+                                disabled=Signal::derive(move || {
+                                    contents.with(|contents| contents.trim().is_empty())
+                                })
+
+                                on_click=move |_| inspect()
+                            />
+
+                        </FormButtonBar>
+                    </Form>
+                }
+                    .into_view()
+            }
+        }}
+    }
+}
+
+fn parse_arf(raw: &str) -> Result<ParsedReport, String> {
+    let raw = raw.replace("\r\n", "\n");
+    let raw = raw.trim();
+    if raw.starts_with('<') {
+        return Err(
+            "This looks like a DMARC aggregate report, which is not supported. Only ARF feedback reports can be viewed here."
+                .to_string(),
+        );
+    }
+
+    let (headers, body) = split_headers(raw);
+    let content_type = header(&headers, "content-type").unwrap_or_default();
+    let mut report = if content_type.to_ascii_lowercase().starts_with("multipart/") {
+        let boundary = content_type_param(content_type, "boundary")
+            .ok_or("The Content-Type header is missing the multipart boundary.")?;
+        let mut feedback = None;
+        let mut message = None;
+        let mut original_headers = None;
+        for (part_headers, part_body) in split_multipart(body, &boundary) {
+            let part_body = decode_body(&part_headers, part_body)?;
+            let part_type = header(&part_headers, "content-type")
+                .unwrap_or("text/plain")
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase();
+            match part_type.as_str() {
+                "message/feedback-report" => {
+                    feedback = Some(parse_feedback(&split_headers(&part_body).0)?);
+                }
+                "message/rfc822" => {
+                    original_headers = Some(header_section(&part_body).to_string());
+                    message = Some(part_body);
+                }
+                "text/rfc822-headers" => original_headers = Some(part_body),
+                _ => (),
+            }
+        }
+
+        let mut report = feedback.ok_or(
+            "The message does not contain a message/feedback-report part, it is not an ARF report.",
+        )?;
+        report.message = message;
+        report.headers = original_headers;
+        report
+    } else if header(&headers, "feedback-type").is_some() {
+        // Only the feedback-report part was pasted
+        parse_feedback(&headers)?
+    } else {
+        return Err(
+            "Not an ARF report: expected a multipart/report message or a Feedback-Type field."
+                .to_string(),
+        );
+    };
+    if report.version == 0 {
+        report.version = 1;
+    }
+
+    Ok(ParsedReport {
+        report,
+        received: header(&headers, "date")
+            .and_then(parse_date)
+            .map_or_else(Utc::now, |date| date.with_timezone(&Utc)),
+        extra: ["from", "to", "subject"]
+            .into_iter()
+            .zip(["Received From", "Recipients", "Subject"])
+            .map(|(name, label)| {
+                (
+                    label.to_string(),
+                    header(&headers, name).unwrap_or_default().to_string(),
+                )
+            })
+            .collect(),
+    })
+}
+
+fn parse_feedback(fields: &Headers) -> Result<Feedback, String> {
+    let mut report = Feedback::default();
+    let mut has_type = false;
+    let invalid = |name: &str, value: &str| format!("Invalid {name} value {value:?}.");
+
+    for (name, value) in fields {
+        let value = value.trim();
+        match name.as_str() {
+            "feedback-type" => {
+                has_type = true;
+                report.feedback_type = match value.to_ascii_lowercase().as_str() {
+                    "abuse" => FeedbackType::Abuse,
+                    "auth-failure" => FeedbackType::AuthFailure,
+                    "fraud" => FeedbackType::Fraud,
+                    "not-spam" => FeedbackType::NotSpam,
+                    "virus" => FeedbackType::Virus,
+                    _ => FeedbackType::Other,
+                };
+            }
+            "version" => {
+                report.version = value.parse().map_err(|_| invalid("Version", value))?;
+            }
+            "user-agent" => report.user_agent = Some(value.to_string()),
+            "arrival-date" | "received-date" => {
+                report.arrival_date = Some(
+                    parse_date(value)
+                        .ok_or_else(|| invalid("Arrival-Date", value))?
+                        .timestamp(),
+                );
+            }
+            "authentication-results" => report.authentication_results.push(value.to_string()),
+            "incidents" => {
+                report.incidents = value.parse().map_err(|_| invalid("Incidents", value))?;
+            }
+            "original-envelope-id" => report.original_envelope_id = Some(value.to_string()),
+            "original-mail-from" => report.original_mail_from = Some(value.to_string()),
+            "original-rcpt-to" => report.original_rcpt_to = Some(value.to_string()),
+            "reported-domain" => report.reported_domain.push(value.to_string()),
+            "reported-uri" => report.reported_uri.push(value.to_string()),
+            "reporting-mta" => {
+                // Strip the MTA name type, e.g. "dns; mx.example.org"
+                report.reporting_mta = Some(
+                    value
+                        .split_once(';')
+                        .map_or(value, |(_, name)| name)
+                        .trim()
+                        .to_string(),
+                );
+            }
+            "source-ip" => {
+                report.source_ip = Some(
+                    value
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .parse::<IpAddr>()
+                        .map_err(|_| invalid("Source-IP", value))?,
+                );
+            }
+            "source-port" => {
+                report.source_port = value.parse().map_err(|_| invalid("Source-Port", value))?;
+            }
+            "auth-failure" => {
+                report.auth_failure = match value.to_ascii_lowercase().as_str() {
+                    "adsp" => AuthFailureType::Adsp,
+                    "bodyhash" => AuthFailureType::BodyHash,
+                    "revoked" => AuthFailureType::Revoked,
+                    "signature" => AuthFailureType::Signature,
+                    "spf" => AuthFailureType::Spf,
+                    "dmarc" => AuthFailureType::Dmarc,
+                    _ => AuthFailureType::Unspecified,
+                };
+            }
+            "delivery-result" => {
+                report.delivery_result = match value.to_ascii_lowercase().as_str() {
+                    "delivered" => DeliveryResult::Delivered,
+                    "spam" => DeliveryResult::Spam,
+                    "policy" => DeliveryResult::Policy,
+                    "reject" => DeliveryResult::Reject,
+                    "other" => DeliveryResult::Other,
+                    _ => DeliveryResult::Unspecified,
+                };
+            }
+            "identity-alignment" => {
+                let value = value.to_ascii_lowercase();
+                let (dkim, spf) = value
+                    .split(',')
+                    .map(str::trim)
+                    .fold((false, false), |(dkim, spf), method| {
+                        (dkim || method == "dkim", spf || method == "spf")
+                    });
+                report.identity_alignment = match (dkim, spf) {
+                    (true, true) => IdentityAlignment::DkimSpf,
+                    (true, false) => IdentityAlignment::Dkim,
+                    (false, true) => IdentityAlignment::Spf,
+                    _ if value.trim() == "none" => IdentityAlignment::None,
+                    _ => IdentityAlignment::Unspecified,
+                };
+            }
+            "dkim-adsp-dns" => report.dkim_adsp_dns = Some(value.to_string()),
+            "dkim-canonicalized-body" => report.dkim_canonicalized_body = Some(value.to_string()),
+            "dkim-canonicalized-header" => {
+                report.dkim_canonicalized_header = Some(value.to_string())
+            }
+            "dkim-domain" => report.dkim_domain = Some(value.to_string()),
+            "dkim-identity" => report.dkim_identity = Some(value.to_string()),
+            "dkim-selector" => report.dkim_selector = Some(value.to_string()),
+            "dkim-selector-dns" => report.dkim_selector_dns = Some(value.to_string()),
+            "spf-dns" => report.spf_dns = Some(value.to_string()),
+            _ => (),
+        }
+    }
+
+    if has_type {
+        Ok(report)
+    } else {
+        Err("The feedback report is missing the required Feedback-Type field.".to_string())
+    }
+}
+
+// Unfolds header lines and lowercases their names, the body starts after the first empty line
+fn split_headers(raw: &str) -> (Headers, &str) {
+    let mut headers: Headers = Vec::new();
+    let mut offset = 0;
+    for line in raw.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim_end_matches('\n');
+        if line.trim().is_empty() {
+            return (headers, &raw[offset..]);
+        } else if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        } else {
+            return (headers, &raw[offset - line.len()..]);
+        }
+    }
+    (headers, "")
+}
+
+// Reporting agents often get the day of the week wrong, so it is not validated
+fn parse_date(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc2822(value).ok().or_else(|| {
+        value
+            .split_once(',')
+            .and_then(|(_, date)| DateTime::parse_from_rfc2822(date.trim()).ok())
+    })
+}
+
+fn header_section(message: &str) -> &str {
+    message
+        .split_once("\n\n")
+        .map_or(message, |(headers, _)| headers)
+}
+
+fn header<'x>(headers: &'x Headers, name: &str) -> Option<&'x str> {
+    headers
+        .iter()
+        .find(|(header, _)| header == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn content_type_param(content_type: &str, name: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+// Delimiters must take up a whole line, so boundaries that start with another boundary
+// are not confused. Parts after a missing close delimiter are kept.
+fn split_multipart<'x>(body: &'x str, boundary: &str) -> Vec<(Headers, &'x str)> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut part_start = None;
+    let mut offset = 0;
+    for line in body.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let Some(rest) = line.trim_end().strip_prefix(delimiter.as_str()) else {
+            continue;
+        };
+        if rest.is_empty() || rest == "--" {
+            if let Some(part_start) = part_start.take() {
+                parts.push(split_headers(&body[part_start..line_start]));
+            }
+            if rest == "--" {
+                return parts;
+            }
+            part_start = Some(offset);
+        }
+    }
+    if let Some(part_start) = part_start {
+        parts.push(split_headers(&body[part_start..]));
+    }
+    parts
+}
+
+fn decode_body(headers: &Headers, body: &str) -> Result<String, String> {
+    let body = body.trim_end();
+    match header(headers, "content-transfer-encoding")
+        .map(|encoding| encoding.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("base64") => STANDARD
+            .decode(
+                body.chars()
+                    .filter(|ch| !ch.is_ascii_whitespace())
+                    .collect::<String>(),
+            )
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .map_err(|_| "A report part has invalid base64 content.".to_string()),
+        Some("quoted-printable") => Ok(decode_quoted_printable(body)),
+        _ => Ok(body.to_string()),
+    }
+}
+
+// Malformed escapes are kept as they are, as most mail readers do
+fn decode_quoted_printable(body: &str) -> String {
+    let mut decoded = Vec::with_capacity(body.len());
+    let mut input = body.as_bytes();
+    while let Some((&byte, rest)) = input.split_first() {
+        input = rest;
+        if byte != b'=' {
+            decoded.push(byte);
+            continue;
+        }
+
+        // Soft line break, possibly followed by trailing whitespace
+        let padding = input
+            .iter()
+            .take_while(|&&ch| ch == b' ' || ch == b'\t')
+            .count();
+        if let Some(rest) = input[padding..].strip_prefix(b"\n") {
+            input = rest;
+        } else if let Some(hex) = input
+            .get(..2)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+        {
+            let hex = std::str::from_utf8(hex).unwrap_or_default();
+            decoded.push(u8::from_str_radix(hex, 16).unwrap_or_default());
+            input = &input[2..];
+        } else {
+            decoded.push(byte);
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEEDBACK: &str = "Feedback-Type: abuse\nUser-Agent: SomeGenerator/1.0\nVersion: 1\nSource-IP: 192.0.2.1\nReported-Domain: example.net\n";
+
+    fn multipart(parts: &[(&str, &str)]) -> String {
+        let mut raw = concat!(
+            "From: <abusedesk@example.com>\r\n",
+            "Subject: FW: Earn money\r\n",
+            "Date: Thu, 8 Mar 2005 17:40:36 EDT\r\n",
+            "Content-Type: multipart/report; report-type=feedback-report;\r\n",
+            "     boundary=\"part1_13d.2e68ed54\"\r\n",
+            "\r\n",
+            "This is a MIME preamble.\r\n",
+        )
+        .to_string();
+        for (headers, body) in parts {
+            raw.push_str(&format!(
+                "\r\n--part1_13d.2e68ed54\r\n{headers}\r\n\r\n{body}\r\n"
+            ));
+        }
+        raw.push_str("--part1_13d.2e68ed54--\r\nEpilogue\r\n");
+        raw
+    }
+
+    fn parse_error(raw: &str) -> String {
+        parse_arf(raw).err().expect("report should be rejected")
+    }
+
+    #[test]
+    fn multipart_boundaries() {
+        let parsed = parse_arf(&multipart(&[
+            (
+                "Content-Type: text/plain",
+                "--part1_13d.2e68ed54-not-a-delimiter\r\nThis is an email abuse report.",
+            ),
+            ("Content-Type: message/feedback-report", FEEDBACK),
+            (
+                "Content-Type: message/rfc822",
+                "From: <somespammer@example.net>\r\nSubject: Earn money\r\n\r\nSpam",
+            ),
+        ]))
+        .unwrap();
+
+        assert_eq!(parsed.report.feedback_type, FeedbackType::Abuse);
+        assert_eq!(
+            parsed.report.user_agent.as_deref(),
+            Some("SomeGenerator/1.0")
+        );
+        assert_eq!(parsed.report.source_ip, Some("192.0.2.1".parse().unwrap()));
+        assert_eq!(
+            parsed.report.reported_domain,
+            vec!["example.net".to_string()]
+        );
+        assert_eq!(
+            parsed.report.message.as_deref(),
+            Some("From: <somespammer@example.net>\nSubject: Earn money\n\nSpam")
+        );
+        assert_eq!(
+            parsed.report.headers.as_deref(),
+            Some("From: <somespammer@example.net>\nSubject: Earn money")
+        );
+        assert_eq!(parsed.received.timestamp(), 1110318036);
+        assert_eq!(
+            parsed.extra[2],
+            ("Subject".to_string(), "FW: Earn money".to_string())
+        );
+    }
+
+    #[test]
+    fn encoded_parts() {
+        let parsed = parse_arf(&multipart(&[
+            (
+                "Content-Type: message/feedback-report\r\nContent-Transfer-Encoding: base64",
+                "RmVlZGJhY2stVHlwZTogYXV0aC1mYWlsdXJlCkF1\r\ndGgtRmFpbHVyZTogYm9keWhhc2gKU291cmNlLVBvcnQ6IDI1Cg==",
+            ),
+            (
+                "Content-Type: text/rfc822-headers\r\nContent-Transfer-Encoding: Quoted-Printable",
+                "Subject: Caf=C3=A9 =\r\nmen=3Du \r\nX-Rate: 100=ZZ=",
+            ),
+        ]))
+        .unwrap();
+
+        assert_eq!(parsed.report.feedback_type, FeedbackType::AuthFailure);
+        assert_eq!(parsed.report.auth_failure, AuthFailureType::BodyHash);
+        assert_eq!(parsed.report.source_port, 25);
+        assert_eq!(
+            parsed.report.headers.as_deref(),
+            Some("Subject: Café men=u \nX-Rate: 100=ZZ=")
+        );
+    }
+
+    #[test]
+    fn feedback_part_only() {
+        let parsed = parse_arf(FEEDBACK).unwrap();
+        assert_eq!(parsed.report.feedback_type, FeedbackType::Abuse);
+        assert_eq!(parsed.report.version, 1);
+        assert!(parsed.report.message.is_none());
+    }
+
+    #[test]
+    fn malformed_reports() {
+        for (raw, error) in [
+            (
+                "<?xml version=\"1.0\"?>\n<feedback></feedback>".to_string(),
+                "DMARC aggregate report",
+            ),
+            ("Subject: Hello\n\nWorld".to_string(), "Not an ARF report"),
+            (
+                "Content-Type: multipart/report\n\n--x\n\n--x--".to_string(),
+                "missing the multipart boundary",
+            ),
+            (
+                multipart(&[("Content-Type: text/plain", "Nothing to see")]),
+                "does not contain a message/feedback-report part",
+            ),
+            (
+                multipart(&[(
+                    "Content-Type: message/feedback-report",
+                    "Feedback-Type: abuse\nSource-IP: 192.0.2.300",
+                )]),
+                "Invalid Source-IP",
+            ),
+            (
+                multipart(&[(
+                    "Content-Type: message/feedback-report",
+                    "User-Agent: SomeGenerator/1.0",
+                )]),
+                "missing the required Feedback-Type",
+            ),
+            (
+                multipart(&[(
+                    "Content-Type: message/feedback-report\nContent-Transfer-Encoding: base64",
+                    "not*base64",
+                )]),
+                "invalid base64",
+            ),
+        ] {
+            let message = parse_error(&raw);
+            assert!(message.contains(error), "{message:?} for {raw:?}");
+        }
+    }
+}
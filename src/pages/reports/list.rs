@@ -21,6 +21,7 @@
  * for more details.
 */
 
+use futures::future::join_all;
 use leptos::*;
 use leptos_router::*;
 use std::collections::HashSet;
@@ -28,13 +29,14 @@ use std::collections::HashSet;
 use super::ReportType;
 use crate::{
     components::{
-        icon::{IconCancel, IconRefresh},
+        form::date::{date_range, DateRange, DateRangePicker},
+        icon::{IconCancel, IconClipboard, IconRefresh},
         list::{
             header::ColumnList,
             pagination::Pagination,
             row::SelectItem,
             toolbar::{SearchBox, ToolbarButton},
-            Footer, ListItem, ListSection, ListTable, ListTextItem, Toolbar, ZeroResults,
+            Filters, Footer, ListItem, ListSection, ListTable, ListTextItem, Toolbar, ZeroResults,
         },
         messages::{
             alert::{use_alerts, Alert},
@@ -45,19 +47,26 @@ use crate::{
     },
     core::{
         http::{self, HttpRequest},
-        oauth::use_authorization,
+        oauth::{login_redirect_url, use_authorization, AuthToken},
+        prefs::use_preferences,
         url::UrlBuilder,
     },
     pages::{
-        maybe_plural,
-        queue::reports::{Feedback, Report, TlsReport},
-        reports::{IncomingReport, IncomingReportSummary},
+        format_count, maybe_plural,
+        queue::reports::{Feedback, FeedbackType, Report, ReportLabel, TlsReport},
+        reports::{
+            parse_report_date, use_report_sequence, IncomingReport, IncomingReportSummary,
+            ReportFilters, ReportSequence,
+        },
         FormatDateTime, List,
     },
 };
 use chrono_humanize::{Accuracy, HumanTime, Tense};
 
-const PAGE_SIZE: u32 = 10;
+const MAX_RESULTS: u32 = 100;
+// Reports fetched at once when filtering by type, which needs each report's contents
+const FETCH_CONCURRENCY: usize = 10;
+const LIST_ID: &str = "incoming-reports";
 
 #[component]
 pub fn IncomingReportList() -> impl IntoView {
@@ -80,6 +89,9 @@ pub fn IncomingReportList() -> impl IntoView {
             })
         })
     });
+    let filters = create_memo(move |_| {
+        query.with(|q| ReportFilters::parse(q.get("type"), q.get("from"), q.get("to")))
+    });
     let params = use_params_map();
     let report_type = create_memo(move |_| {
         match params
@@ -98,52 +110,79 @@ pub fn IncomingReportList() -> impl IntoView {
     let auth = use_authorization();
     let alert = use_alerts();
     let modal = use_modals();
+    let sequence = use_report_sequence();
+    let prefs = use_preferences();
+    let page_size = create_memo(move |_| prefs.with(|prefs| prefs.list_page_size(LIST_ID)));
     let selected = create_rw_signal::<HashSet<String>>(HashSet::new());
     provide_context(selected);
 
     let reports = create_resource(
-        move || (page.get(), filter.get()),
-        move |(page, filter)| {
+        move || (page.get(), filter.get(), filters.get(), page_size.get()),
+        move |(page, filter, filters, page_size)| {
             let auth = auth.get_untracked();
             let report_type = report_type.get();
 
             async move {
-                let ids = HttpRequest::get(format!("/api/reports/{}", report_type.as_str()))
+                let mut ids = HttpRequest::get(format!("/api/reports/{}", report_type.as_str()))
                     .with_authorization(&auth)
-                    .with_parameter("page", page.to_string())
-                    .with_parameter("limit", PAGE_SIZE.to_string())
-                    .with_parameter("max-total", "100")
+                    .with_parameter(
+                        "page",
+                        if filters.is_empty() { page } else { 1 }.to_string(),
+                    )
+                    .with_parameter(
+                        "limit",
+                        if filters.is_empty() {
+                            page_size
+                        } else {
+                            MAX_RESULTS
+                        }
+                        .to_string(),
+                    )
+                    .with_parameter("max-total", MAX_RESULTS.to_string())
                     .with_optional_parameter("filter", filter)
                     .send::<List<String>>()
                     .await?;
+
+                // Filters are applied locally on the first MAX_RESULTS results
+                let filter_locally = !filters.is_empty();
+                let filter_by_type =
+                    filter_locally && !filters.types.is_empty() && report_type == ReportType::Arf;
+                if filter_locally {
+                    ids.items
+                        .retain(|id| filters.matches_date(parse_report_date(id)));
+                    if !filter_by_type {
+                        ids.total = ids.items.len() as u64;
+                        ids.items = paginate(ids.items, page, page_size);
+                    }
+                }
+
                 let mut result = List {
                     items: Vec::with_capacity(ids.items.len()),
                     total: ids.total,
                 };
 
-                for id in ids.items {
-                    let report = match report_type {
-                        ReportType::Dmarc => HttpRequest::get(format!("/api/reports/dmarc/{id}"))
-                            .with_authorization(&auth)
-                            .try_send::<IncomingReport<Report>>()
-                            .await?
-                            .map(|report| IncomingReportSummary::dmarc(id, report)),
-                        ReportType::Tls => HttpRequest::get(format!("/api/reports/tls/{id}"))
-                            .with_authorization(&auth)
-                            .try_send::<IncomingReport<TlsReport>>()
-                            .await?
-                            .map(|report| IncomingReportSummary::tls(id, report)),
-                        ReportType::Arf => HttpRequest::get(format!("/api/reports/arf/{id}"))
-                            .with_authorization(&auth)
-                            .try_send::<IncomingReport<Feedback>>()
-                            .await?
-                            .map(|report| IncomingReportSummary::arf(id, report)),
-                    };
-                    if let Some(report) = report {
-                        result.items.push(report);
+                for ids in ids.items.chunks(FETCH_CONCURRENCY) {
+                    let reports = join_all(
+                        ids.iter()
+                            .map(|id| fetch_report_summary(&auth, report_type, id.clone())),
+                    )
+                    .await;
+                    for report in reports {
+                        if let Some(report) = report? {
+                            if !filter_by_type
+                                || matches!(report, IncomingReportSummary::Arf { typ, .. } if filters.matches_type(typ))
+                            {
+                                result.items.push(report);
+                            }
+                        }
                     }
                 }
 
+                if filter_by_type {
+                    result.total = result.items.len() as u64;
+                    result.items = paginate(result.items, page, page_size);
+                }
+
                 Ok(result)
             }
         },
@@ -184,6 +223,20 @@ pub fn IncomingReportList() -> impl IntoView {
     });
 
     let total_results = create_rw_signal(None::<u32>);
+    // The server stops counting at MAX_RESULTS, so the total is only known below it
+    let has_more = create_rw_signal(false);
+    let apply_filters = move |new_filters: ReportFilters| {
+        use_navigate()(
+            &new_filters
+                .with_parameters(UrlBuilder::new(format!(
+                    "/manage/reports/{}",
+                    report_type.get().as_str()
+                )))
+                .with_optional_parameter("filter", filter.get())
+                .finish(),
+            Default::default(),
+        );
+    };
 
     let title = create_memo(move |_| {
         match report_type.get() {
@@ -210,8 +263,12 @@ pub fn IncomingReportList() -> impl IntoView {
                         value=filter
                         on_search=move |value| {
                             use_navigate()(
-                                &UrlBuilder::new(
-                                        format!("/manage/reports/{}", report_type.get().as_str()),
+                                &filters
+                                    .get()
+                                    .with_parameters(
+                                        UrlBuilder::new(
+                                            format!("/manage/reports/{}", report_type.get().as_str()),
+                                        ),
                                     )
                                     .with_parameter("filter", value)
                                     .finish(),
@@ -232,6 +289,19 @@ pub fn IncomingReportList() -> impl IntoView {
                         <IconRefresh/>
                     </ToolbarButton>
 
+                    <Show when=move || report_type.get() == ReportType::Arf>
+                        <ToolbarButton
+                            text="View pasted ARF report"
+                            color=Color::Gray
+                            on_click=Callback::new(move |_| {
+                                use_navigate()("/manage/reports/inspect", Default::default());
+                            })
+                        >
+
+                            <IconClipboard/>
+                        </ToolbarButton>
+                    </Show>
+
                     <ToolbarButton
                         text=Signal::derive(move || {
                             let ns = selected.get().len();
@@ -268,20 +338,100 @@ pub fn IncomingReportList() -> impl IntoView {
 
                 </Toolbar>
 
+                <Filters slot>
+                    <label class="text-sm text-gray-600 dark:text-gray-400">Received</label>
+                    <DateRangePicker
+                        value=Signal::derive(move || {
+                            let filters = filters.get();
+                            Some(date_range(filters.from?, filters.to?))
+                        })
+                        on_change=move |range| {
+                            let range: Option<DateRange> = range;
+                            let mut new_filters = filters.get();
+                            new_filters.from = range.map(|(from, _)| from.date_naive());
+                            new_filters.to = range.map(|(_, to)| to.date_naive());
+                            apply_filters(new_filters);
+                        }
+                    />
+
+                    <Show when=move || report_type.get() == ReportType::Arf>
+                        <div class="flex flex-wrap items-center gap-2">
+                            {FeedbackType::ALL
+                                .into_iter()
+                                .map(|typ| {
+                                    let is_selected = create_memo(move |_| {
+                                        filters.get().types.contains(&typ)
+                                    });
+                                    view! {
+                                        <button
+                                            type="button"
+                                            class="py-1 px-2 inline-flex items-center text-xs font-medium rounded-full border"
+                                            class=("bg-blue-100", move || is_selected.get())
+                                            class=("text-blue-800", move || is_selected.get())
+                                            class=("border-blue-200", move || is_selected.get())
+                                            class=("bg-white", move || !is_selected.get())
+                                            class=("text-gray-800", move || !is_selected.get())
+                                            class=("border-gray-200", move || !is_selected.get())
+                                            attr:aria-pressed=move || is_selected.get().to_string()
+                                            title=typ.description()
+                                            on:click=move |_| {
+                                                apply_filters(filters.get().toggle_type(typ));
+                                            }
+                                        >
+                                            {typ.label()}
+                                        </button>
+                                    }
+                                })
+                                .collect_view()}
+                        </div>
+                    </Show>
+
+                    <Show when=move || !filters.get().is_empty()>
+                        <button
+                            type="button"
+                            class="text-sm font-semibold text-blue-600 hover:underline dark:text-blue-500"
+                            on:click=move |_| apply_filters(ReportFilters::default())
+                        >
+                            Clear filters
+                        </button>
+                        <p class="text-xs text-gray-500 dark:text-gray-400">
+                            {format!("Filters only apply to the {MAX_RESULTS} most recent reports.")}
+                        </p>
+                    </Show>
+                </Filters>
+
                 <Transition fallback=Skeleton>
                     {move || match reports.get() {
                         None => None,
                         Some(Err(http::Error::Unauthorized)) => {
-                            use_navigate()("/login", Default::default());
+                            use_navigate()(&login_redirect_url(), Default::default());
                             Some(view! { <div></div> }.into_view())
                         }
                         Some(Err(err)) => {
                             total_results.set(Some(0));
+                            has_more.set(false);
                             alert.set(Alert::from(err));
                             Some(view! { <Skeleton/> }.into_view())
                         }
                         Some(Ok(reports)) if !reports.items.is_empty() => {
                             total_results.set(Some(reports.total as u32));
+                            has_more
+                                .set(filters.get().is_empty() && reports.total >= MAX_RESULTS as u64);
+                            sequence
+                                .set(ReportSequence {
+                                    list_url: filters
+                                        .get()
+                                        .with_parameters(
+                                            UrlBuilder::new(
+                                                format!("/manage/reports/{}", report_type.get().as_str()),
+                                            ),
+                                        )
+                                        .with_parameter("page", page.get().to_string())
+                                        .with_optional_parameter("filter", filter.get())
+                                        .finish(),
+                                    report_type: Some(report_type.get()),
+                                    ids: reports.items.iter().map(|r| r.id().to_string()).collect(),
+                                });
                             let reports_ = reports.clone();
                             let headers = match report_type.get() {
                                 ReportType::Dmarc => {
@@ -345,6 +495,7 @@ pub fn IncomingReportList() -> impl IntoView {
                         }
                         Some(Ok(_)) => {
                             total_results.set(Some(0));
+                            has_more.set(false);
                             Some(
                                 view! {
                                     <ZeroResults
@@ -364,11 +515,38 @@ pub fn IncomingReportList() -> impl IntoView {
                     <Pagination
                         current_page=page
                         total_results=total_results.read_only()
-                        page_size=PAGE_SIZE
+                        has_more=has_more.read_only()
+                        page_size=page_size
+                        on_page_size_change=move |size| {
+                            prefs
+                                .update(|prefs| {
+                                    let mut view = prefs.list_view(LIST_ID);
+                                    view.page_size = Some(size);
+                                    prefs.set_list_view(LIST_ID, view);
+                                    prefs.save();
+                                });
+                            use_navigate()(
+                                &filters
+                                    .get()
+                                    .with_parameters(
+                                        UrlBuilder::new(
+                                            format!("/manage/reports/{}", report_type.get().as_str()),
+                                        ),
+                                    )
+                                    .with_optional_parameter("filter", filter.get())
+                                    .finish(),
+                                Default::default(),
+                            );
+                        }
+
                         on_page_change=move |page: u32| {
                             use_navigate()(
-                                &UrlBuilder::new(
-                                        format!("/manage/reports/{}", report_type.get().as_str()),
+                                &filters
+                                    .get()
+                                    .with_parameters(
+                                        UrlBuilder::new(
+                                            format!("/manage/reports/{}", report_type.get().as_str()),
+                                        ),
                                     )
                                     .with_parameter("page", page.to_string())
                                     .with_optional_parameter("filter", filter.get())
@@ -384,11 +562,43 @@ pub fn IncomingReportList() -> impl IntoView {
     }
 }
 
+fn paginate<T>(items: Vec<T>, page: u32, page_size: u32) -> Vec<T> {
+    items
+        .into_iter()
+        .skip(((page - 1) * page_size) as usize)
+        .take(page_size as usize)
+        .collect()
+}
+
 enum Item {
     Single(String),
     Double((String, String)),
 }
 
+async fn fetch_report_summary(
+    auth: &AuthToken,
+    report_type: ReportType,
+    id: String,
+) -> http::Result<Option<IncomingReportSummary>> {
+    Ok(match report_type {
+        ReportType::Dmarc => HttpRequest::get(format!("/api/reports/dmarc/{id}"))
+            .with_authorization(auth)
+            .try_send::<IncomingReport<Report>>()
+            .await?
+            .map(|report| IncomingReportSummary::dmarc(id, report)),
+        ReportType::Tls => HttpRequest::get(format!("/api/reports/tls/{id}"))
+            .with_authorization(auth)
+            .try_send::<IncomingReport<TlsReport>>()
+            .await?
+            .map(|report| IncomingReportSummary::tls(id, report)),
+        ReportType::Arf => HttpRequest::get(format!("/api/reports/arf/{id}"))
+            .with_authorization(auth)
+            .try_send::<IncomingReport<Feedback>>()
+            .await?
+            .map(|report| IncomingReportSummary::arf(id, report)),
+    })
+}
+
 #[component]
 fn ReportItem(report: IncomingReportSummary) -> impl IntoView {
     let (show_url, item_id, columns) = match report {
@@ -416,9 +626,9 @@ fn ReportItem(report: IncomingReportSummary) -> impl IntoView {
                     ),
                 )),
                 Item::Single(domains.join(", ")),
-                Item::Single(total_passes.to_string()),
-                Item::Single(total_rejects.to_string()),
-                Item::Single(total_quarantined.to_string()),
+                Item::Single(format_count(total_passes as u64)),
+                Item::Single(format_count(total_rejects as u64)),
+                Item::Single(format_count(total_quarantined as u64)),
             ],
         ),
         IncomingReportSummary::Tls {
@@ -459,8 +669,8 @@ fn ReportItem(report: IncomingReportSummary) -> impl IntoView {
                         )
                     }
                 },
-                Item::Single(total_success.to_string()),
-                Item::Single(total_failures.to_string()),
+                Item::Single(format_count(total_success as u64)),
+                Item::Single(format_count(total_failures as u64)),
             ],
         ),
         IncomingReportSummary::Arf {
@@ -470,14 +680,18 @@ fn ReportItem(report: IncomingReportSummary) -> impl IntoView {
             from,
             domains,
             total_incidents,
-            received,
+            ..
         } => (
             format!("/manage/reports/arf/{id}"),
             id,
             vec![
                 Item::Single(from),
                 Item::Single(typ.to_string()),
-                Item::Single(arrival_date.unwrap_or(received).format_date_time()),
+                Item::Single(
+                    arrival_date
+                        .map(|date| date.format_date_time())
+                        .unwrap_or_else(|| "Unavailable".to_string()),
+                ),
                 {
                     if domains.len() > 1 {
                         Item::Double((
@@ -494,7 +708,7 @@ fn ReportItem(report: IncomingReportSummary) -> impl IntoView {
                         )
                     }
                 },
-                Item::Single(total_incidents.to_string()),
+                Item::Single(format_count(total_incidents as u64)),
             ],
         ),
     };
@@ -21,12 +21,16 @@
  * for more details.
 */
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use leptos::{create_rw_signal, expect_context, provide_context, RwSignal};
 use serde::{Deserialize, Serialize};
 
+use crate::{components::report::ReportNavigation, core::url::UrlBuilder};
+
 use super::queue::reports::{ActionDisposition, Feedback, FeedbackType, Report, TlsReport};
 
 pub mod display;
+pub mod inspect;
 pub mod list;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -80,6 +84,20 @@ impl IncomingReportSummary {
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReportFilters {
+    pub types: Vec<FeedbackType>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReportSequence {
+    pub list_url: String,
+    pub report_type: Option<ReportType>,
+    pub ids: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IncomingReport<T> {
     pub from: String,
@@ -88,6 +106,94 @@ pub struct IncomingReport<T> {
     pub report: T,
 }
 
+pub fn init_report_sequence() {
+    provide_context(create_rw_signal(ReportSequence::default()));
+}
+
+pub fn use_report_sequence() -> RwSignal<ReportSequence> {
+    expect_context::<RwSignal<ReportSequence>>()
+}
+
+impl ReportSequence {
+    pub fn back_url(&self, report_type: ReportType) -> String {
+        if self.report_type == Some(report_type) {
+            self.list_url.clone()
+        } else {
+            format!("/manage/reports/{}", report_type.as_str())
+        }
+    }
+
+    pub fn navigation(&self, report_type: ReportType, id: &str) -> Option<ReportNavigation> {
+        if self.report_type != Some(report_type) {
+            return None;
+        }
+        let pos = self.ids.iter().position(|item| item == id)?;
+        let url = |id: &String| format!("/manage/reports/{}/{id}", report_type.as_str());
+
+        Some(ReportNavigation {
+            previous: pos
+                .checked_sub(1)
+                .and_then(|pos| self.ids.get(pos))
+                .map(url),
+            next: self.ids.get(pos + 1).map(url),
+        })
+    }
+}
+
+impl ReportFilters {
+    pub fn parse(types: Option<&String>, from: Option<&String>, to: Option<&String>) -> Self {
+        let parse_date = |date: Option<&String>| {
+            date.and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        };
+
+        ReportFilters {
+            types: types
+                .map(|types| types.split(',').filter_map(FeedbackType::parse).collect())
+                .unwrap_or_default(),
+            from: parse_date(from),
+            to: parse_date(to),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.from.is_none() && self.to.is_none()
+    }
+
+    pub fn matches_date(&self, date: DateTime<Utc>) -> bool {
+        let date = date.date_naive();
+        self.from.is_none_or(|from| date >= from) && self.to.is_none_or(|to| date <= to)
+    }
+
+    pub fn matches_type(&self, typ: FeedbackType) -> bool {
+        self.types.is_empty() || self.types.contains(&typ)
+    }
+
+    pub fn toggle_type(mut self, typ: FeedbackType) -> Self {
+        if let Some(pos) = self.types.iter().position(|t| *t == typ) {
+            self.types.remove(pos);
+        } else {
+            self.types.push(typ);
+        }
+        self
+    }
+
+    pub fn with_parameters(&self, url: UrlBuilder) -> UrlBuilder {
+        url.with_optional_parameter(
+            "type",
+            Some(
+                self.types
+                    .iter()
+                    .map(|t| t.as_str())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )
+            .filter(|types| !types.is_empty()),
+        )
+        .with_optional_parameter("from", self.from.map(|d| d.format("%Y-%m-%d").to_string()))
+        .with_optional_parameter("to", self.to.map(|d| d.format("%Y-%m-%d").to_string()))
+    }
+}
+
 impl ReportType {
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -96,6 +202,14 @@ impl ReportType {
             ReportType::Arf => "arf",
         }
     }
+
+    pub fn item_name(&self) -> &'static str {
+        match self {
+            ReportType::Dmarc => "DMARC report",
+            ReportType::Tls => "TLS report",
+            ReportType::Arf => "Failure report",
+        }
+    }
 }
 
 impl IncomingReportSummary {
@@ -162,6 +276,8 @@ impl IncomingReportSummary {
     }
 
     pub fn arf(id: String, report: IncomingReport<Feedback>) -> Self {
+        let total_incidents = report.report.incident_count();
+
         IncomingReportSummary::Arf {
             received: parse_report_date(&id),
             from: report.from,
@@ -172,7 +288,7 @@ impl IncomingReportSummary {
                 .arrival_date
                 .and_then(|date| DateTime::from_timestamp(date, 0)),
             typ: report.report.feedback_type,
-            total_incidents: std::cmp::max(report.report.incidents, 1),
+            total_incidents,
         }
     }
 }
@@ -16,6 +16,7 @@ module.exports = {
       //https://play.tailwindcss.com/VCZwwz1e3R
       animation: {
         text: 'text 5s ease infinite',
+        progress: 'progress 1.5s ease-in-out infinite',
       },
       keyframes: {
         text: {
@@ -28,6 +29,10 @@ module.exports = {
             'background-position': 'right center',
           },
         },
+        progress: {
+          '0%': { left: '-40%' },
+          '100%': { left: '100%' },
+        },
       },
     },
   },